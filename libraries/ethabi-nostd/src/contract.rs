@@ -0,0 +1,337 @@
+// Copyright 2019-2024 Ferrum Inc.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE or
+// http://www.apache.org/licenses/LICENSE-2.0>. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Contract descriptor loaded from a Solidity JSON ABI.
+
+use crate::{
+	signature::{canonical_signature, parse_function, parse_param},
+	Error, Event, Function, Param, ParamKind, Token,
+};
+use serde::Deserialize;
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+/// Functions and events of a contract, keyed by name. Overloads share a name and are kept in
+/// declaration order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Contract {
+	/// Contract functions.
+	pub functions: BTreeMap<String, Vec<Function>>,
+	/// Contract events.
+	pub events: BTreeMap<String, Vec<Event>>,
+}
+
+/// Either a bare ABI or a build artifact carrying it in its `abi` field, as written by
+/// Hardhat and Truffle.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AbiFile {
+	Abi(Vec<AbiEntry>),
+	Artifact { abi: Vec<AbiEntry> },
+}
+
+#[derive(Deserialize)]
+struct AbiEntry {
+	#[serde(rename = "type", default = "function_type")]
+	kind: String,
+	#[serde(default)]
+	name: String,
+	#[serde(default)]
+	inputs: Vec<AbiParam>,
+	#[serde(default)]
+	outputs: Vec<AbiParam>,
+	#[serde(default)]
+	anonymous: bool,
+}
+
+#[derive(Deserialize)]
+struct AbiParam {
+	#[serde(default)]
+	name: String,
+	#[serde(rename = "type")]
+	kind: String,
+	#[serde(default)]
+	components: Vec<AbiParam>,
+	#[serde(default)]
+	indexed: bool,
+}
+
+fn function_type() -> String {
+	"function".into()
+}
+
+impl AbiParam {
+	fn to_param(&self) -> Result<Param, Error> {
+		Ok(Param {
+			name: self.name.as_bytes().to_vec(),
+			kind: self.to_kind()?,
+			indexed: self.indexed,
+		})
+	}
+
+	/// Tuples are given as `tuple`, `tuple[]`, ... with the member types in `components`.
+	fn to_kind(&self) -> Result<ParamKind, Error> {
+		match self.kind.strip_prefix("tuple") {
+			Some(dimensions) => {
+				let components =
+					self.components.iter().map(|c| c.to_kind()).collect::<Result<Vec<_>, _>>()?;
+				let mut kind = canonical_signature("", &components);
+				kind.extend_from_slice(dimensions.as_bytes());
+				parse_param(sp_std::str::from_utf8(&kind).map_err(|_| Error::InvalidType)?)
+			},
+			None => parse_param(&self.kind),
+		}
+	}
+}
+
+impl Contract {
+	/// Loads a contract from a JSON ABI, or from a build artifact holding it. Constructors,
+	/// errors, fallback and receive functions are skipped.
+	pub fn load(json: &[u8]) -> Result<Contract, Error> {
+		let entries = match serde_json::from_slice(json).map_err(|_| Error::InvalidData)? {
+			AbiFile::Abi(entries) | AbiFile::Artifact { abi: entries } => entries,
+		};
+
+		let mut contract = Contract::default();
+		for entry in entries {
+			let inputs =
+				entry.inputs.iter().map(|p| p.to_param()).collect::<Result<Vec<_>, _>>()?;
+			match entry.kind.as_str() {
+				"function" => {
+					let outputs = entry
+						.outputs
+						.iter()
+						.map(|p| p.to_param())
+						.collect::<Result<Vec<_>, _>>()?;
+					let function =
+						Function { name: entry.name.as_bytes().to_vec(), inputs, outputs };
+					contract.functions.entry(entry.name).or_default().push(function);
+				},
+				"event" => {
+					let event = Event {
+						name: entry.name.as_bytes().to_vec(),
+						inputs,
+						anonymous: entry.anonymous,
+					};
+					contract.events.entry(entry.name).or_default().push(event);
+				},
+				_ => {},
+			}
+		}
+
+		Ok(contract)
+	}
+
+	/// Returns all the overloads of the function.
+	pub fn functions_by_name(&self, name: &str) -> Result<&[Function], Error> {
+		self.functions.get(name).map(|f| f.as_slice()).ok_or(Error::InvalidName)
+	}
+
+	/// Returns the function with the given name, the first declared one if it is overloaded.
+	pub fn function(&self, name: &str) -> Result<&Function, Error> {
+		self.functions_by_name(name)?.first().ok_or(Error::InvalidName)
+	}
+
+	/// Returns the overload matching the signature, e.g. `transfer(address,uint256)`.
+	pub fn function_by_signature(&self, signature: &str) -> Result<&Function, Error> {
+		let (name, params) = parse_function(signature)?;
+		self.functions_by_name(name)?
+			.iter()
+			.find(|f| f.input_kinds() == params)
+			.ok_or(Error::InvalidName)
+	}
+
+	/// Encodes a call of the named function. The overload is picked by type checking the
+	/// inputs, `Error::InvalidName` is returned if several of them match. A full signature can be
+	/// given instead of the name to pick the overload explicitly.
+	pub fn encode(&self, name: &str, inputs: &[Token]) -> Result<Vec<u8>, Error> {
+		if name.contains('(') {
+			return self.function_by_signature(name)?.encode_input(inputs);
+		}
+
+		let mut candidates = self
+			.functions_by_name(name)?
+			.iter()
+			.filter(|f| Token::types_check(inputs, &f.input_kinds()));
+		match (candidates.next(), candidates.next()) {
+			(Some(function), None) => function.encode_input(inputs),
+			(Some(_), Some(_)) => Err(Error::InvalidName),
+			(None, _) => Err(Error::TypeMismatch),
+		}
+	}
+
+	/// Returns all the overloads of the event.
+	pub fn events_by_name(&self, name: &str) -> Result<&[Event], Error> {
+		self.events.get(name).map(|e| e.as_slice()).ok_or(Error::InvalidName)
+	}
+
+	/// Returns the event with the given name, the first declared one if it is overloaded.
+	pub fn event(&self, name: &str) -> Result<&Event, Error> {
+		self.events_by_name(name)?.first().ok_or(Error::InvalidName)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{encode, Contract, Error, ParamKind, Token};
+	use hex_literal::hex;
+
+	const ABI: &str = r#"[
+		{
+			"type": "function",
+			"name": "balanceOf",
+			"stateMutability": "view",
+			"inputs": [{ "name": "owner", "type": "address", "internalType": "address" }],
+			"outputs": [{ "name": "balance", "type": "uint256", "internalType": "uint256" }]
+		},
+		{
+			"type": "function",
+			"name": "transfer",
+			"stateMutability": "nonpayable",
+			"inputs": [
+				{ "name": "to", "type": "address" },
+				{ "name": "amount", "type": "uint256" }
+			],
+			"outputs": [{ "name": "", "type": "bool" }]
+		},
+		{
+			"type": "function",
+			"name": "transfer",
+			"inputs": [
+				{ "name": "to", "type": "address" },
+				{ "name": "amount", "type": "uint256" },
+				{ "name": "data", "type": "bytes" }
+			],
+			"outputs": []
+		},
+		{
+			"type": "function",
+			"name": "lastLocalBlock",
+			"inputs": [{ "name": "chainId", "type": "uint256" }],
+			"outputs": [
+				{
+					"name": "",
+					"type": "tuple",
+					"components": [
+						{ "name": "chainId", "type": "uint64" },
+						{ "name": "nonce", "type": "uint64" },
+						{ "name": "timestamp", "type": "uint64" }
+					]
+				}
+			]
+		},
+		{
+			"type": "function",
+			"name": "batch",
+			"inputs": [
+				{
+					"name": "calls",
+					"type": "tuple[]",
+					"components": [
+						{ "name": "target", "type": "address" },
+						{ "name": "callData", "type": "bytes" }
+					]
+				}
+			],
+			"outputs": []
+		},
+		{
+			"type": "event",
+			"name": "Transfer",
+			"anonymous": false,
+			"inputs": [
+				{ "name": "from", "type": "address", "indexed": true },
+				{ "name": "to", "type": "address", "indexed": true },
+				{ "name": "value", "type": "uint256", "indexed": false }
+			]
+		},
+		{ "type": "constructor", "inputs": [] },
+		{ "type": "error", "name": "Unauthorized", "inputs": [] }
+	]"#;
+
+	#[test]
+	fn load_abi() {
+		let contract = Contract::load(ABI.as_bytes()).unwrap();
+		assert_eq!(contract.functions.len(), 4);
+		assert_eq!(contract.functions_by_name("transfer").unwrap().len(), 2);
+		assert_eq!(contract.events.len(), 1);
+
+		let last_block = contract.function("lastLocalBlock").unwrap();
+		assert_eq!(
+			last_block.outputs[0].kind,
+			ParamKind::Tuple(vec![
+				Box::new(ParamKind::Uint(64)),
+				Box::new(ParamKind::Uint(64)),
+				Box::new(ParamKind::Uint(64)),
+			])
+		);
+		assert_eq!(
+			contract.function("batch").unwrap().signature(),
+			b"batch((address,bytes)[])".to_vec()
+		);
+
+		let transfer = contract.event("Transfer").unwrap();
+		assert_eq!(
+			transfer.signature(),
+			hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").into()
+		);
+		assert!(transfer.inputs[0].indexed);
+
+		assert!(matches!(contract.function("Unauthorized"), Err(Error::InvalidName)));
+	}
+
+	#[test]
+	fn load_artifact() {
+		let artifact =
+			format!(r#"{{ "contractName": "Token", "abi": {}, "bytecode": "0x" }}"#, ABI);
+		assert_eq!(
+			Contract::load(artifact.as_bytes()).unwrap(),
+			Contract::load(ABI.as_bytes()).unwrap()
+		);
+		assert!(matches!(Contract::load(b"{}"), Err(Error::InvalidData)));
+	}
+
+	#[test]
+	fn encode_by_name_resolving_overloads() {
+		let contract = Contract::load(ABI.as_bytes()).unwrap();
+		let to = Token::Address([0x11u8; 20].into());
+		let amount = Token::Uint(0x45.into());
+
+		let call = contract.encode("transfer", &[to.clone(), amount.clone()]).unwrap();
+		assert_eq!(&call[..4], &hex!("a9059cbb"));
+
+		let call = contract
+			.encode("transfer", &[to.clone(), amount.clone(), Token::Bytes(vec![1])])
+			.unwrap();
+		assert_eq!(&call[..4], &contract.functions["transfer"][1].selector());
+
+		let call = contract
+			.encode("transfer(address,uint256)", &[to.clone(), amount.clone()])
+			.unwrap();
+		assert_eq!(&call[..4], &hex!("a9059cbb"));
+
+		assert!(matches!(contract.encode("transfer", &[to.clone()]), Err(Error::TypeMismatch)));
+		assert!(matches!(contract.encode("mint", &[to, amount]), Err(Error::InvalidName)));
+	}
+
+	#[test]
+	fn decode_named_outputs() {
+		let contract = Contract::load(ABI.as_bytes()).unwrap();
+		let block = Token::Tuple(vec![
+			Token::Uint(26000.into()),
+			Token::Uint(12.into()),
+			Token::Uint(1700000000.into()),
+		]);
+
+		let outputs = contract
+			.function("lastLocalBlock")
+			.unwrap()
+			.decode_output_named(&encode(&[block.clone()]))
+			.unwrap();
+		assert_eq!(outputs, vec![(vec![], block)]);
+	}
+}
@@ -46,6 +46,17 @@ fn as_bool(slice: &Word) -> Result<bool, Error> {
 
 /// Decodes ABI compliant vector of bytes into vector of tokens described by types param.
 pub fn decode(types: &[ParamKind], data: &[u8]) -> Result<Vec<Token>, Error> {
+	decode_with(types, data, false)
+}
+
+/// Same as `decode`, but rejects static values that are not encoded the way Solidity encodes
+/// them: bools other than 0 or 1, addresses and `uintN` with non zero high bytes, `intN` that are
+/// not sign extended and `bytesN` with non zero padding.
+pub fn decode_strict(types: &[ParamKind], data: &[u8]) -> Result<Vec<Token>, Error> {
+	decode_with(types, data, true)
+}
+
+fn decode_with(types: &[ParamKind], data: &[u8], strict: bool) -> Result<Vec<Token>, Error> {
 	let is_empty_bytes_valid_encoding = types.iter().all(|t| t.is_empty_bytes_valid_encoding());
 	if !is_empty_bytes_valid_encoding && data.is_empty() {
 		return Err(Error::InvalidName);
@@ -54,7 +65,7 @@ pub fn decode(types: &[ParamKind], data: &[u8]) -> Result<Vec<Token>, Error> {
 	let mut tokens = Vec::with_capacity(types.len());
 	let mut offset = 0;
 	for param in types {
-		let res = decode_param(param, &slices, offset)?;
+		let res = decode_param(param, &slices, offset, strict)?;
 		offset = res.new_offset;
 		tokens.push(res.token);
 	}
@@ -81,10 +92,18 @@ fn take_bytes(slices: &[Word], position: usize, len: usize) -> Result<BytesTaken
 	Ok(taken)
 }
 
-fn decode_param(param: &ParamKind, slices: &[Word], offset: usize) -> Result<DecodeResult, Error> {
+fn decode_param(
+	param: &ParamKind,
+	slices: &[Word],
+	offset: usize,
+	strict: bool,
+) -> Result<DecodeResult, Error> {
 	match *param {
 		ParamKind::Address => {
 			let slice = peek(slices, offset)?;
+			if strict && !slice[..12].iter().all(|x| *x == 0) {
+				return Err(Error::InvalidData);
+			}
 			let mut address = [0u8; 20];
 			address.copy_from_slice(&slice[12..]);
 
@@ -93,16 +112,27 @@ fn decode_param(param: &ParamKind, slices: &[Word], offset: usize) -> Result<Dec
 
 			Ok(result)
 		},
-		ParamKind::Int(_) => {
+		ParamKind::Int(size) => {
 			let slice = peek(slices, offset)?;
+			if strict {
+				// the bytes above the int have to be the sign extension of its top bit
+				let start = 32 - (size / 8).clamp(1, 32);
+				let sign = if slice[start] & 0x80 == 0 { 0x00 } else { 0xff };
+				if !slice[..start].iter().all(|x| *x == sign) {
+					return Err(Error::InvalidData);
+				}
+			}
 
 			let result =
 				DecodeResult { token: Token::Int((*slice).into()), new_offset: offset + 1 };
 
 			Ok(result)
 		},
-		ParamKind::Uint(_) => {
+		ParamKind::Uint(size) => {
 			let slice = peek(slices, offset)?;
+			if strict && !slice[..32 - (size / 8).clamp(1, 32)].iter().all(|x| *x == 0) {
+				return Err(Error::InvalidData);
+			}
 
 			let result =
 				DecodeResult { token: Token::Uint((*slice).into()), new_offset: offset + 1 };
@@ -111,6 +141,9 @@ fn decode_param(param: &ParamKind, slices: &[Word], offset: usize) -> Result<Dec
 		},
 		ParamKind::Bool => {
 			let slice = peek(slices, offset)?;
+			if strict && slice[31] > 1 {
+				return Err(Error::InvalidData);
+			}
 
 			let b = as_bool(slice)?;
 
@@ -121,6 +154,9 @@ fn decode_param(param: &ParamKind, slices: &[Word], offset: usize) -> Result<Dec
 			// FixedBytes is anything from bytes1 to bytes32. These values
 			// are padded with trailing zeros to fill 32 bytes.
 			let taken = take_bytes(slices, offset, len)?;
+			if strict && !peek(slices, offset)?[len.min(32)..].iter().all(|x| *x == 0) {
+				return Err(Error::InvalidData);
+			}
 			let result = DecodeResult {
 				token: Token::FixedBytes(taken.bytes),
 				new_offset: taken.new_offset,
@@ -162,7 +198,7 @@ fn decode_param(param: &ParamKind, slices: &[Word], offset: usize) -> Result<Dec
 			let mut new_offset = 0;
 
 			for _ in 0..len {
-				let res = decode_param(t, tail, new_offset)?;
+				let res = decode_param(t, tail, new_offset, strict)?;
 				new_offset = res.new_offset;
 				tokens.push(res.token);
 			}
@@ -182,7 +218,7 @@ fn decode_param(param: &ParamKind, slices: &[Word], offset: usize) -> Result<Dec
 			};
 
 			for _ in 0..len {
-				let res = decode_param(t, tail, new_offset)?;
+				let res = decode_param(t, tail, new_offset, strict)?;
 				new_offset = res.new_offset;
 				tokens.push(res.token);
 			}
@@ -208,7 +244,7 @@ fn decode_param(param: &ParamKind, slices: &[Word], offset: usize) -> Result<Dec
 			let len = t.len();
 			let mut tokens = Vec::with_capacity(len);
 			for i in 0..len {
-				let res = decode_param(&t[i], tail, new_offset)?;
+				let res = decode_param(&t[i], tail, new_offset, strict)?;
 				new_offset = res.new_offset;
 				tokens.push(res.token);
 			}
@@ -229,7 +265,10 @@ fn decode_param(param: &ParamKind, slices: &[Word], offset: usize) -> Result<Dec
 #[cfg(test)]
 mod tests {
 
-	use crate::{decoder::decode, ParamKind, Token};
+	use crate::{
+		decoder::{decode, decode_strict},
+		ParamKind, Token, U256,
+	};
 	use hex_literal::hex;
 
 	#[test]
@@ -541,4 +580,83 @@ mod tests {
 			]
 		);
 	}
+
+	#[test]
+	fn decode_strict_rejects_bad_padding() {
+		let word = |hex: &str| hex::decode(hex).unwrap();
+		let reject = |kind: ParamKind, hex: &str| {
+			assert!(decode(&[kind.clone()], &word(hex)).is_ok());
+			assert!(decode_strict(&[kind], &word(hex)).is_err());
+		};
+
+		reject(ParamKind::Bool, "0000000000000000000000000000000000000000000000000000000000000002");
+		reject(
+			ParamKind::Address,
+			"0000000000000000000000018497afefdc5ac170a664a231f6efb25526ef813f",
+		);
+		reject(
+			ParamKind::Uint(64),
+			"0000000000000000000000000000000000000000000000010000000000000000",
+		);
+		reject(
+			ParamKind::Int(8),
+			"0000000000000000000000000000000000000000000000000000000000000080",
+		);
+		reject(
+			ParamKind::Int(8),
+			"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+		);
+		reject(
+			ParamKind::FixedBytes(2),
+			"1234010000000000000000000000000000000000000000000000000000000000",
+		);
+	}
+
+	#[test]
+	fn decode_strict_accepts_valid_values() {
+		let encoded = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000008497afefdc5ac170a664a231f6efb25526ef813f
+			000000000000000000000000000000000000000000000000ffffffffffffffff
+			ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff80
+			1234000000000000000000000000000000000000000000000000000000000000
+		"
+		);
+		let kinds = [
+			ParamKind::Bool,
+			ParamKind::Address,
+			ParamKind::Uint(64),
+			ParamKind::Int(8),
+			ParamKind::FixedBytes(2),
+		];
+
+		assert_eq!(
+			decode_strict(&kinds, &encoded).unwrap(),
+			vec![
+				Token::Bool(true),
+				Token::Address(hex!("8497afefdc5ac170a664a231f6efb25526ef813f").into()),
+				Token::Uint(u64::MAX.into()),
+				Token::Int(U256::MAX - 127),
+				Token::FixedBytes(vec![0x12, 0x34]),
+			]
+		);
+		assert_eq!(decode_strict(&kinds, &encoded).unwrap()[3].clone().to_i128(), Some(-128));
+	}
+
+	#[test]
+	fn decode_strict_checks_nested_values() {
+		let encoded = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000003
+		"
+		);
+		let kind = ParamKind::Array(Box::new(ParamKind::Bool));
+
+		assert!(decode(&[kind.clone()], &encoded).is_ok());
+		assert!(decode_strict(&[kind], &encoded).is_err());
+	}
 }
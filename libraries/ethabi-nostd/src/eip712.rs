@@ -0,0 +1,463 @@
+// Copyright 2019-2024 Ferrum Inc.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE or
+// http://www.apache.org/licenses/LICENSE-2.0>. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! EIP-712 typed structured data hashing.
+//!
+//! Struct values are given as `Token::Tuple` holding the member values in declaration order,
+//! arrays as `Token::Array` or `Token::FixedArray`.
+
+use crate::{encode, encode_packed, signature::parse_param, Address, Error, Token, H256, U256};
+use sp_std::{
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	prelude::*,
+};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Struct member.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+	/// Member name.
+	pub name: Vec<u8>,
+	/// Member type, a Solidity type, a struct name or an array of either.
+	pub kind: Vec<u8>,
+}
+
+/// Struct type definitions, keyed by struct name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Types {
+	/// Struct members, in declaration order.
+	pub structs: BTreeMap<Vec<u8>, Vec<Member>>,
+}
+
+/// EIP-712 domain. Only the fields that are set are part of the `EIP712Domain` type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Domain {
+	/// Name of the signing domain, e.g. the DApp or protocol name.
+	pub name: Option<Vec<u8>>,
+	/// Current major version of the signing domain.
+	pub version: Option<Vec<u8>>,
+	/// EIP-155 chain id.
+	pub chain_id: Option<U256>,
+	/// Address of the contract verifying the signature.
+	pub verifying_contract: Option<Address>,
+	/// Disambiguating salt for the protocol.
+	pub salt: Option<H256>,
+}
+
+impl Types {
+	/// Parses struct definitions written as in `encodeType`, e.g.
+	/// `Mail(Person from,Person to,string contents)Person(string name,address wallet)`.
+	pub fn parse(definitions: &str) -> Result<Types, Error> {
+		let mut types = Types::default();
+		let mut rest = definitions.trim();
+		while !rest.is_empty() {
+			let open = rest.find('(').ok_or(Error::InvalidType)?;
+			let close = rest.find(')').ok_or(Error::InvalidType)?;
+			let name = rest[..open].trim();
+			if name.is_empty() || close < open {
+				return Err(Error::InvalidType);
+			}
+
+			let members = rest[open + 1..close]
+				.split(',')
+				.map(str::trim)
+				.filter(|member| !member.is_empty())
+				.map(|member| {
+					let mut words = member.split_whitespace();
+					match (words.next(), words.next(), words.next()) {
+						(Some(kind), Some(name), None) => Ok(Member {
+							name: name.as_bytes().to_vec(),
+							kind: kind.as_bytes().to_vec(),
+						}),
+						_ => Err(Error::InvalidType),
+					}
+				})
+				.collect::<Result<Vec<_>, _>>()?;
+			if types.structs.insert(name.as_bytes().to_vec(), members).is_some() {
+				return Err(Error::InvalidType);
+			}
+
+			rest = rest[close + 1..].trim_start();
+		}
+		Ok(types)
+	}
+
+	/// `encodeType`, the primary type followed by the structs it references sorted by name.
+	pub fn encode_type(&self, primary: &str) -> Result<Vec<u8>, Error> {
+		let primary = primary.as_bytes();
+		let mut dependencies = BTreeSet::new();
+		self.find_dependencies(primary, &mut dependencies)?;
+		dependencies.remove(primary);
+
+		let mut encoded = self.encode_struct_type(primary)?;
+		for dependency in dependencies {
+			encoded.extend(self.encode_struct_type(&dependency)?);
+		}
+		Ok(encoded)
+	}
+
+	/// `typeHash`, the hash of `encodeType`.
+	pub fn type_hash(&self, primary: &str) -> Result<H256, Error> {
+		Ok(keccak(&self.encode_type(primary)?).into())
+	}
+
+	/// `encodeData`, the type hash followed by the encoded member values.
+	pub fn encode_data(&self, primary: &str, value: &Token) -> Result<Vec<u8>, Error> {
+		let members = self.members(primary.as_bytes())?;
+		let values = match value {
+			Token::Tuple(values) if values.len() == members.len() => values,
+			_ => return Err(Error::TypeMismatch),
+		};
+
+		let mut encoded = self.type_hash(primary)?.as_bytes().to_vec();
+		for (member, value) in members.iter().zip(values) {
+			encoded.extend_from_slice(&self.encode_field(&member.kind, value)?);
+		}
+		Ok(encoded)
+	}
+
+	/// `hashStruct`, the hash of `encodeData`.
+	pub fn hash_struct(&self, primary: &str, value: &Token) -> Result<H256, Error> {
+		Ok(keccak(&self.encode_data(primary, value)?).into())
+	}
+
+	/// Returns the digest to sign for the message in the given domain.
+	pub fn digest(&self, domain: &Domain, primary: &str, message: &Token) -> Result<H256, Error> {
+		Ok(hash(&domain.separator(), &self.hash_struct(primary, message)?))
+	}
+
+	fn members(&self, name: &[u8]) -> Result<&Vec<Member>, Error> {
+		self.structs.get(name).ok_or(Error::InvalidName)
+	}
+
+	fn find_dependencies(&self, name: &[u8], found: &mut BTreeSet<Vec<u8>>) -> Result<(), Error> {
+		if found.contains(name) {
+			return Ok(());
+		}
+		found.insert(name.to_vec());
+		for member in self.members(name)? {
+			let kind = base_type(&member.kind);
+			if self.structs.contains_key(kind) {
+				self.find_dependencies(kind, found)?;
+			}
+		}
+		Ok(())
+	}
+
+	fn encode_struct_type(&self, name: &[u8]) -> Result<Vec<u8>, Error> {
+		let mut encoded = name.to_vec();
+		encoded.push(b'(');
+		for (i, member) in self.members(name)?.iter().enumerate() {
+			if i > 0 {
+				encoded.push(b',');
+			}
+			encoded.extend_from_slice(&member.kind);
+			encoded.push(b' ');
+			encoded.extend_from_slice(&member.name);
+		}
+		encoded.push(b')');
+		Ok(encoded)
+	}
+
+	/// Structs are encoded as their `hashStruct`, arrays, `bytes` and `string` as the hash of
+	/// their content and atomic values as in the standard ABI encoding.
+	fn encode_field(&self, kind: &[u8], value: &Token) -> Result<[u8; 32], Error> {
+		if let Some(array) = kind.strip_suffix(b"]") {
+			let open = array.iter().rposition(|b| *b == b'[').ok_or(Error::InvalidType)?;
+			let values = match value {
+				Token::Array(values) | Token::FixedArray(values) => values,
+				_ => return Err(Error::TypeMismatch),
+			};
+			if open + 1 < array.len() {
+				let size = sp_std::str::from_utf8(&array[open + 1..])
+					.ok()
+					.and_then(|size| size.parse::<usize>().ok())
+					.ok_or(Error::InvalidType)?;
+				if size != values.len() {
+					return Err(Error::TypeMismatch);
+				}
+			}
+
+			let mut encoded = vec![];
+			for value in values {
+				encoded.extend_from_slice(&self.encode_field(&array[..open], value)?);
+			}
+			return Ok(keccak(&encoded));
+		}
+
+		if self.structs.contains_key(kind) {
+			let name = sp_std::str::from_utf8(kind).map_err(|_| Error::InvalidType)?;
+			return Ok(self.hash_struct(name, value)?.into());
+		}
+
+		let kind = parse_param(sp_std::str::from_utf8(kind).map_err(|_| Error::InvalidType)?)?;
+		if !value.type_check(&kind) {
+			return Err(Error::TypeMismatch);
+		}
+		match value {
+			Token::Bytes(bytes) | Token::String(bytes) => Ok(keccak(bytes)),
+			// tuples are not EIP-712 types, structs are used instead
+			Token::Tuple(_) | Token::Array(_) | Token::FixedArray(_) => Err(Error::InvalidType),
+			value => {
+				let mut word = [0u8; 32];
+				word.copy_from_slice(&encode(&[value.clone()]));
+				Ok(word)
+			},
+		}
+	}
+}
+
+impl Domain {
+	/// Returns the domain separator, the `hashStruct` of the domain.
+	pub fn separator(&self) -> H256 {
+		let (types, value) = self.to_struct();
+		types
+			.hash_struct("EIP712Domain", &value)
+			.expect("domain values always match the domain type")
+	}
+
+	/// Returns the `EIP712Domain` type made of the fields that are set, and its value.
+	fn to_struct(&self) -> (Types, Token) {
+		let mut members = vec![];
+		let mut values = vec![];
+		let mut add = |name: &str, kind: &str, value: Token| {
+			members.push(Member { name: name.as_bytes().to_vec(), kind: kind.as_bytes().to_vec() });
+			values.push(value);
+		};
+
+		if let Some(name) = &self.name {
+			add("name", "string", Token::String(name.clone()));
+		}
+		if let Some(version) = &self.version {
+			add("version", "string", Token::String(version.clone()));
+		}
+		if let Some(chain_id) = self.chain_id {
+			add("chainId", "uint256", Token::Uint(chain_id));
+		}
+		if let Some(verifying_contract) = self.verifying_contract {
+			add("verifyingContract", "address", Token::Address(verifying_contract));
+		}
+		if let Some(salt) = self.salt {
+			add("salt", "bytes32", Token::FixedBytes(salt.as_bytes().to_vec()));
+		}
+
+		let mut types = Types::default();
+		types.structs.insert(b"EIP712Domain".to_vec(), members);
+		(types, Token::Tuple(values))
+	}
+}
+
+/// Returns the digest to sign, `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`.
+pub fn hash(domain_separator: &H256, struct_hash: &H256) -> H256 {
+	let packed = encode_packed(&[
+		Token::FixedBytes(b"\x19\x01".to_vec()),
+		Token::FixedBytes(domain_separator.as_bytes().to_vec()),
+		Token::FixedBytes(struct_hash.as_bytes().to_vec()),
+	])
+	.expect("fixed bytes can always be packed");
+	keccak(&packed).into()
+}
+
+/// The member type without its array dimensions, e.g. `Person` for `Person[][2]`.
+fn base_type(kind: &[u8]) -> &[u8] {
+	match kind.iter().position(|b| *b == b'[') {
+		Some(open) => &kind[..open],
+		None => kind,
+	}
+}
+
+fn keccak(data: &[u8]) -> [u8; 32] {
+	let mut hash = [0u8; 32];
+	let mut sponge = Keccak::v256();
+	sponge.update(data);
+	sponge.finalize(&mut hash);
+	hash
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{keccak, Domain, Types};
+	use crate::{encode, Address, Error, Token, H256, U256};
+	use hex_literal::hex;
+
+	fn address(value: [u8; 20]) -> Token {
+		Token::Address(Address::from(value))
+	}
+
+	fn person(name: &str, wallet: [u8; 20]) -> Token {
+		Token::Tuple(vec![Token::String(name.as_bytes().to_vec()), address(wallet)])
+	}
+
+	fn ether_mail_domain() -> Domain {
+		Domain {
+			name: Some(b"Ether Mail".to_vec()),
+			version: Some(b"1".to_vec()),
+			chain_id: Some(U256::from(1)),
+			verifying_contract: Some(hex!("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC").into()),
+			salt: None,
+		}
+	}
+
+	#[test]
+	fn eip712_reference_example() {
+		let types = Types::parse(
+			"Mail(Person from,Person to,string contents)Person(string name,address wallet)",
+		)
+		.unwrap();
+		let mail = Token::Tuple(vec![
+			person("Cow", hex!("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826")),
+			person("Bob", hex!("bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB")),
+			Token::String(b"Hello, Bob!".to_vec()),
+		]);
+
+		assert_eq!(
+			types.encode_type("Mail").unwrap(),
+			b"Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+				.to_vec()
+		);
+		assert_eq!(
+			types.type_hash("Mail").unwrap(),
+			hex!("a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2").into()
+		);
+		assert_eq!(
+			types.hash_struct("Mail", &mail).unwrap(),
+			hex!("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e").into()
+		);
+		assert_eq!(
+			ether_mail_domain().separator(),
+			hex!("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f").into()
+		);
+		assert_eq!(
+			types.digest(&ether_mail_domain(), "Mail", &mail).unwrap(),
+			hex!("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2").into()
+		);
+	}
+
+	#[test]
+	fn nested_arrays_of_structs() {
+		let types = Types::parse(
+			"Mail(Person from,Person[] to,string contents)\
+			Person(string name,address[] wallets)\
+			Group(string name,Person[] members)",
+		)
+		.unwrap();
+		assert_eq!(
+			types.encode_type("Mail").unwrap(),
+			b"Mail(Person from,Person[] to,string contents)Person(string name,address[] wallets)"
+				.to_vec()
+		);
+		assert_eq!(
+			types.encode_type("Group").unwrap(),
+			b"Group(string name,Person[] members)Person(string name,address[] wallets)".to_vec()
+		);
+
+		let wallets =
+			|wallets: &[[u8; 20]]| Token::Array(wallets.iter().map(|w| address(*w)).collect());
+		let mail = Token::Tuple(vec![
+			Token::Tuple(vec![
+				Token::String(b"Cow".to_vec()),
+				wallets(&[
+					hex!("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+					hex!("DeaDbeefdEAdbeefdEadbEEFdeadbeEFdEaDbeeF"),
+				]),
+			]),
+			Token::Array(vec![Token::Tuple(vec![
+				Token::String(b"Bob".to_vec()),
+				wallets(&[
+					hex!("bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"),
+					hex!("B0BdaBea57B0BDABeA57b0bdABEA57b0BDabEa57"),
+					hex!("B0B0b0b0b0b0B000000000000000000000000000"),
+				]),
+			])]),
+			Token::String(b"Hello, Bob!".to_vec()),
+		]);
+
+		assert_eq!(
+			types.type_hash("Person").unwrap(),
+			hex!("fabfe1ed996349fc6027709802be19d047da1aa5d6894ff5f6486d92db2e6860").into()
+		);
+		assert_eq!(
+			types.type_hash("Mail").unwrap(),
+			hex!("4bd8a9a2b93427bb184aca81e24beb30ffa3c747e2a33d4225ec08bf12e2e753").into()
+		);
+		assert_eq!(
+			types.hash_struct("Mail", &mail).unwrap(),
+			hex!("eb4221181ff3f1a83ea7313993ca9218496e424604ba9492bb4052c03d5c3df8").into()
+		);
+		assert_eq!(
+			types.digest(&ether_mail_domain(), "Mail", &mail).unwrap(),
+			hex!("a85c2e2b118698e88db68a8105b794a8cc7cec074e89ef991cb4f5f533819cc2").into()
+		);
+	}
+
+	#[test]
+	fn domain_with_salt() {
+		let salt = H256::repeat_byte(0x42);
+		let domain = Domain { salt: Some(salt), ..ether_mail_domain() };
+		let (types, _) = domain.to_struct();
+		let encoded_type = types.encode_type("EIP712Domain").unwrap();
+		assert_eq!(
+			encoded_type,
+			b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract,bytes32 salt)"
+				.to_vec()
+		);
+
+		let expected = keccak(&encode(&[
+			Token::FixedBytes(keccak(&encoded_type).to_vec()),
+			Token::FixedBytes(keccak(b"Ether Mail").to_vec()),
+			Token::FixedBytes(keccak(b"1").to_vec()),
+			Token::Uint(U256::from(1)),
+			address(hex!("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC")),
+			Token::FixedBytes(salt.as_bytes().to_vec()),
+		]));
+		assert_eq!(domain.separator(), expected.into());
+
+		let (types, _) = Domain { chain_id: Some(U256::from(1)), ..Default::default() }.to_struct();
+		assert_eq!(
+			types.encode_type("EIP712Domain").unwrap(),
+			b"EIP712Domain(uint256 chainId)".to_vec()
+		);
+	}
+
+	#[test]
+	fn eip2612_permit_type_hash() {
+		let types = Types::parse(
+			"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+		)
+		.unwrap();
+		assert_eq!(
+			types.type_hash("Permit").unwrap(),
+			hex!("6e71edae12b1b97f4d1f60370fef10105fa2faae0126114a169c64845d6126c9").into()
+		);
+	}
+
+	#[test]
+	fn reject_mismatched_values() {
+		let types =
+			Types::parse("Person(string name,address wallet)Pair(Person[2] people)").unwrap();
+		let cow = person("Cow", [0x11u8; 20]);
+
+		assert!(matches!(
+			types.hash_struct("Person", &Token::Bool(true)),
+			Err(Error::TypeMismatch)
+		));
+		assert!(matches!(
+			types.hash_struct("Person", &Token::Tuple(vec![Token::Bool(true), address([0u8; 20])])),
+			Err(Error::TypeMismatch)
+		));
+		assert!(matches!(
+			types.hash_struct("Pair", &Token::Tuple(vec![Token::Array(vec![cow.clone()])])),
+			Err(Error::TypeMismatch)
+		));
+		assert!(types
+			.hash_struct("Pair", &Token::Tuple(vec![Token::FixedArray(vec![cow.clone(), cow])]))
+			.is_ok());
+		assert!(matches!(types.type_hash("Mail"), Err(Error::InvalidName)));
+		assert!(matches!(Types::parse("Person(string)"), Err(Error::InvalidType)));
+	}
+}
@@ -8,7 +8,11 @@
 
 //! ABI encoder.
 
-use crate::{util::pad_u32, Token, Word};
+use crate::{
+	signature::{canonical_signature, parse_function},
+	util::pad_u32,
+	Error, Token, Word,
+};
 use sp_std::prelude::*;
 use tiny_keccak::{Hasher, Keccak}; //vec::{Vec};
 
@@ -54,7 +58,7 @@ impl Mediate {
 	fn head_len(&self) -> u32 {
 		match *self {
 			Mediate::Raw(ref raw) => 32 * raw.len() as u32,
-			Mediate::RawTuple(ref mediates) => 32 * mediates.len() as u32,
+			Mediate::RawTuple(ref mediates) => mediates.iter().map(|m| m.head_len()).sum(),
 			Mediate::Prefixed(_)
 			| Mediate::PrefixedArray(_)
 			| Mediate::PrefixedArrayWithLength(_)
@@ -138,17 +142,25 @@ pub fn encode(tokens: &[Token]) -> Vec<u8> {
 	encode_head_tail(mediates).iter().flat_map(|word| word.to_vec()).collect()
 }
 
-pub fn encode_function(signature: &str, inputs: &[Token]) -> Vec<u8> {
-	encode_function_u8(signature.as_bytes(), inputs)
-}
+/// Encodes a function call. The `inputs` are checked against the params parsed from
+/// `signature` and the selector is computed from its canonical form.
+pub fn encode_function(signature: &str, inputs: &[Token]) -> Result<Vec<u8>, Error> {
+	let (name, params) = parse_function(signature)?;
+	if !Token::types_check(inputs, &params) {
+		return Err(Error::TypeMismatch);
+	}
 
-pub fn encode_function_u8(signature: &[u8], inputs: &[Token]) -> Vec<u8> {
 	let mut signed: [u8; 4] = [0; 4];
 	let mut sponge = Keccak::v256();
-	sponge.update(signature);
+	sponge.update(&canonical_signature(name, &params));
 	sponge.finalize(&mut signed);
 	let encoded = encode(inputs);
-	signed.to_vec().into_iter().chain(encoded.into_iter()).collect()
+	Ok(signed.to_vec().into_iter().chain(encoded.into_iter()).collect())
+}
+
+pub fn encode_function_u8(signature: &[u8], inputs: &[Token]) -> Result<Vec<u8>, Error> {
+	let signature = sp_std::str::from_utf8(signature).map_err(|_| Error::InvalidName)?;
+	encode_function(signature, inputs)
 }
 
 fn encode_token(token: &Token) -> Mediate {
@@ -199,7 +211,7 @@ fn encode_token(token: &Token) -> Mediate {
 
 #[cfg(test)]
 mod tests {
-	use crate::{encode, encode_function, util::pad_u32, Token};
+	use crate::{encode, encode_function, util::pad_u32, Error, Token};
 	use hex_literal::hex;
 
 	#[test]
@@ -691,6 +703,26 @@ mod tests {
 		assert_eq!(encoded, expected);
 	}
 
+	#[test]
+	fn encode_nested_static_tuple_before_dynamic_value() {
+		let inner = Token::Tuple(vec![Token::Uint(1.into()), Token::Uint(2.into())]);
+		let outer = Token::Tuple(vec![Token::Address([0x11u8; 20].into()), inner]);
+		let encoded = encode(&[outer, Token::Bytes(vec![0x12])]);
+
+		let expected = hex!(
+			"
+			0000000000000000000000001111111111111111111111111111111111111111
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000080
+			0000000000000000000000000000000000000000000000000000000000000001
+			1200000000000000000000000000000000000000000000000000000000000000
+		"
+		)
+		.to_vec();
+		assert_eq!(encoded, expected);
+	}
+
 	#[test]
 	fn encode_dynamic_tuple() {
 		let string1 = Token::String("gavofyork".into());
@@ -868,17 +900,36 @@ mod tests {
 		let mut uint = [0u8; 32];
 		uint[31] = 69;
 
-		let encoded = encode_function(signature, &[Token::Uint(uint.into()), Token::Bool(true)]);
+		let to = Token::Address([0x11u8; 20].into());
+		let encoded = encode_function(signature, &[to, Token::Uint(uint.into())]).unwrap();
 		println!("Encoded {}", hex::encode(&encoded));
 		let expected = hex!(
 			"
-			a9059cbb00000000000000000000000000000000000000000000000000000000
-			0000004500000000000000000000000000000000000000000000000000000000
-			00000001
+			a9059cbb00000000000000000000000011111111111111111111111111111111
+			1111111100000000000000000000000000000000000000000000000000000000
+			00000045
 			"
 		)
 		.to_vec();
 		assert_eq!(encoded, expected);
+
+		// the selector is computed from the canonical signature
+		let to = Token::Address([0x11u8; 20].into());
+		let named =
+			encode_function("transfer(address to, uint amount)", &[to, Token::Uint(uint.into())]);
+		assert_eq!(named.unwrap(), expected);
+	}
+
+	#[test]
+	fn test_function_encode_type_mismatch() {
+		let signature = "transfer(address,uint256)";
+
+		let res = encode_function(signature, &[Token::Uint(69.into()), Token::Bool(true)]);
+		assert!(matches!(res, Err(Error::TypeMismatch)));
+		let res = encode_function(signature, &[Token::Address([0x11u8; 20].into())]);
+		assert!(matches!(res, Err(Error::TypeMismatch)));
+		let res = encode_function("transfer(address,uint257)", &[]);
+		assert!(matches!(res, Err(Error::InvalidType)));
 	}
 	#[test]
 	fn test_function_encode_call() {
@@ -887,7 +938,8 @@ mod tests {
 		let mut uint = [0u8; 32];
 		uint[31] = 69;
 
-		let encoded = encode_function(signature, &[Token::Uint(uint.into()), Token::Bool(true)]);
+		let encoded =
+			encode_function(signature, &[Token::Uint(uint.into()), Token::Bool(true)]).unwrap();
 		let expected = hex!(
 			"
 			cdcd77c000000000000000000000000000000000000000000000000000000000
@@ -0,0 +1,223 @@
+// Copyright 2019-2024 Ferrum Inc.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE or
+// http://www.apache.org/licenses/LICENSE-2.0>. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Contract event.
+
+use crate::{
+	decoder::decode,
+	signature::{canonical_signature, parse_declarations, split_signature},
+	Error, Param, ParamKind, Token, H256,
+};
+use tiny_keccak::{Hasher, Keccak};
+
+use sp_std::prelude::*; //vec::{Vec};
+
+/// Contract event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+	/// Event name.
+	pub name: Vec<u8>,
+	/// Event params.
+	pub inputs: Vec<Param>,
+	/// If anonymous, the event signature is not emitted as the first topic.
+	pub anonymous: bool,
+}
+
+/// Decoded log param.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogParam {
+	/// Param name.
+	pub name: Vec<u8>,
+	/// Decoded value. Indexed params of dynamic types only hold the 32 byte hash of the value
+	/// as `Token::FixedBytes`.
+	pub value: Token,
+}
+
+impl Event {
+	/// Parses a human-readable event such as
+	/// `Transfer(address indexed from, address indexed to, uint256 value)`. The `event` keyword
+	/// and a trailing `anonymous` are optional.
+	pub fn parse(signature: &str) -> Result<Event, Error> {
+		let signature = signature.trim();
+		let signature = signature.strip_prefix("event ").unwrap_or(signature);
+		let (name, params, rest) = split_signature(signature)?;
+		let anonymous = match rest.trim_end_matches(';').trim() {
+			"" => false,
+			"anonymous" => true,
+			_ => return Err(Error::InvalidType),
+		};
+
+		let inputs = parse_declarations(params)?
+			.into_iter()
+			.map(|(kind, rest)| {
+				let mut indexed = false;
+				let mut name: &str = "";
+				for word in rest.split_whitespace() {
+					match word {
+						"indexed" => indexed = true,
+						word => name = word,
+					}
+				}
+				Param { name: name.as_bytes().to_vec(), kind, indexed }
+			})
+			.collect();
+
+		Ok(Event { name: name.as_bytes().to_vec(), inputs, anonymous })
+	}
+
+	/// Returns the event signature hash, the first topic of a non anonymous event.
+	pub fn signature(&self) -> H256 {
+		let kinds: Vec<ParamKind> = self.inputs.iter().map(|p| p.kind.clone()).collect();
+		let name = sp_std::str::from_utf8(&self.name).unwrap_or_default();
+
+		let mut hash = [0u8; 32];
+		let mut sponge = Keccak::v256();
+		sponge.update(&canonical_signature(name, &kinds));
+		sponge.finalize(&mut hash);
+		hash.into()
+	}
+
+	/// Decodes a log, as returned from `eth_getLogs`, into the event params in declaration
+	/// order.
+	pub fn decode_log(&self, topics: &[H256], data: &[u8]) -> Result<Vec<LogParam>, Error> {
+		let topics = if self.anonymous {
+			topics
+		} else {
+			match topics.split_first() {
+				Some((topic0, topics)) if *topic0 == self.signature() => topics,
+				_ => return Err(Error::InvalidData),
+			}
+		};
+
+		if topics.len() != self.inputs.iter().filter(|p| p.indexed).count() {
+			return Err(Error::InvalidData);
+		}
+
+		let data_kinds: Vec<ParamKind> =
+			self.inputs.iter().filter(|p| !p.indexed).map(|p| p.kind.clone()).collect();
+		let mut data_tokens = decode(&data_kinds, data)?.into_iter();
+		let mut topics = topics.iter();
+
+		self.inputs
+			.iter()
+			.map(|param| {
+				let value = if param.indexed {
+					let topic = topics.next().ok_or(Error::InvalidData)?;
+					decode_topic(&param.kind, topic)?
+				} else {
+					data_tokens.next().ok_or(Error::InvalidData)?
+				};
+				Ok(LogParam { name: param.name.clone(), value })
+			})
+			.collect()
+	}
+}
+
+/// Indexed values of dynamic types, arrays and tuples are stored as the keccak hash of their
+/// encoding, so only the hash can be returned.
+fn decode_topic(kind: &ParamKind, topic: &H256) -> Result<Token, Error> {
+	match kind {
+		ParamKind::Bytes
+		| ParamKind::String
+		| ParamKind::Array(_)
+		| ParamKind::FixedArray(_, _)
+		| ParamKind::Tuple(_) => Ok(Token::FixedBytes(topic.as_bytes().to_vec())),
+		kind => decode(&[kind.clone()], topic.as_bytes())?.pop().ok_or(Error::InvalidData),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{encode, Error, Event, LogParam, ParamKind, Token, H256};
+	use hex_literal::hex;
+
+	fn topic(address: [u8; 20]) -> H256 {
+		let mut topic = [0u8; 32];
+		topic[12..].copy_from_slice(&address);
+		topic.into()
+	}
+
+	#[test]
+	fn erc20_event_signatures() {
+		let transfer =
+			Event::parse("Transfer(address indexed from, address indexed to, uint256 value)")
+				.unwrap();
+		assert_eq!(
+			transfer.signature(),
+			hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").into()
+		);
+
+		let approval = Event::parse(
+			"event Approval(address indexed owner, address indexed spender, uint256 value);",
+		)
+		.unwrap();
+		assert_eq!(
+			approval.signature(),
+			hex!("8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925").into()
+		);
+		assert_eq!(approval.name, b"Approval".to_vec());
+		assert_eq!(approval.inputs[1].name, b"spender".to_vec());
+		assert!(approval.inputs[1].indexed);
+		assert!(!approval.inputs[2].indexed);
+	}
+
+	#[test]
+	fn decode_transfer_log() {
+		let transfer =
+			Event::parse("Transfer(address indexed from, address indexed to, uint256 value)")
+				.unwrap();
+		let topics = [transfer.signature(), topic([0x11u8; 20]), topic([0x22u8; 20])];
+		let data = hex!("00000000000000000000000000000000000000000000000000000000000003e8");
+
+		let log = transfer.decode_log(&topics, &data).unwrap();
+		assert_eq!(
+			log,
+			vec![
+				LogParam { name: b"from".to_vec(), value: Token::Address([0x11u8; 20].into()) },
+				LogParam { name: b"to".to_vec(), value: Token::Address([0x22u8; 20].into()) },
+				LogParam { name: b"value".to_vec(), value: Token::Uint(1000.into()) },
+			]
+		);
+
+		// wrong event
+		let approval =
+			Event::parse("Approval(address indexed owner, address indexed spender, uint256 value)")
+				.unwrap();
+		assert!(matches!(approval.decode_log(&topics, &data), Err(Error::InvalidData)));
+		// missing topic
+		assert!(matches!(transfer.decode_log(&topics[..2], &data), Err(Error::InvalidData)));
+	}
+
+	#[test]
+	fn decode_indexed_dynamic_params_as_hashes() {
+		let event =
+			Event::parse("Message(string indexed topic, bytes indexed id, string text)").unwrap();
+		assert_eq!(event.inputs[0].kind, ParamKind::String);
+
+		let topics = [event.signature(), H256::repeat_byte(0x42), H256::repeat_byte(0x43)];
+		let data = encode(&[Token::String(b"hello".to_vec())]);
+
+		let log = event.decode_log(&topics, &data).unwrap();
+		assert_eq!(log[0].value, Token::FixedBytes(vec![0x42u8; 32]));
+		assert_eq!(log[1].value, Token::FixedBytes(vec![0x43u8; 32]));
+		assert_eq!(log[2].value, Token::String(b"hello".to_vec()));
+	}
+
+	#[test]
+	fn decode_anonymous_log() {
+		let event = Event::parse("Ping(uint64 indexed id, bool ok) anonymous").unwrap();
+		assert!(event.anonymous);
+
+		let topics = [H256::from_low_u64_be(7)];
+		let data = encode(&[Token::Bool(true)]);
+
+		let log = event.decode_log(&topics, &data).unwrap();
+		assert_eq!(log[0], LogParam { name: b"id".to_vec(), value: Token::Uint(7.into()) });
+		assert_eq!(log[1], LogParam { name: b"ok".to_vec(), value: Token::Bool(true) });
+	}
+}
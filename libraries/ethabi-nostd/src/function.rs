@@ -0,0 +1,111 @@
+// Copyright 2019-2024 Ferrum Inc.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE or
+// http://www.apache.org/licenses/LICENSE-2.0>. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Contract function.
+
+use crate::{
+	decoder::decode, encode, signature::canonical_signature, Error, Param, ParamKind, Token,
+};
+use tiny_keccak::{Hasher, Keccak};
+
+use sp_std::prelude::*; //vec::{Vec};
+
+/// Contract function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+	/// Function name.
+	pub name: Vec<u8>,
+	/// Function input params.
+	pub inputs: Vec<Param>,
+	/// Function output params.
+	pub outputs: Vec<Param>,
+}
+
+impl Function {
+	/// Returns the canonical signature, e.g. `transfer(address,uint256)`.
+	pub fn signature(&self) -> Vec<u8> {
+		let name = sp_std::str::from_utf8(&self.name).unwrap_or_default();
+		canonical_signature(name, &self.input_kinds())
+	}
+
+	/// Returns the 4 byte selector of the function.
+	pub fn selector(&self) -> [u8; 4] {
+		let mut selector = [0u8; 4];
+		let mut sponge = Keccak::v256();
+		sponge.update(&self.signature());
+		sponge.finalize(&mut selector);
+		selector
+	}
+
+	/// Returns the input param types.
+	pub fn input_kinds(&self) -> Vec<ParamKind> {
+		self.inputs.iter().map(|p| p.kind.clone()).collect()
+	}
+
+	/// Returns the output param types.
+	pub fn output_kinds(&self) -> Vec<ParamKind> {
+		self.outputs.iter().map(|p| p.kind.clone()).collect()
+	}
+
+	/// Encodes a call of the function, prefixed with its selector.
+	pub fn encode_input(&self, inputs: &[Token]) -> Result<Vec<u8>, Error> {
+		if !Token::types_check(inputs, &self.input_kinds()) {
+			return Err(Error::TypeMismatch);
+		}
+
+		let mut call = self.selector().to_vec();
+		call.extend(encode(inputs));
+		Ok(call)
+	}
+
+	/// Decodes the data returned by a call of the function.
+	pub fn decode_output(&self, data: &[u8]) -> Result<Vec<Token>, Error> {
+		decode(&self.output_kinds(), data)
+	}
+
+	/// Decodes the data returned by a call of the function, paired with the output param names.
+	pub fn decode_output_named(&self, data: &[u8]) -> Result<Vec<(Vec<u8>, Token)>, Error> {
+		let tokens = self.decode_output(data)?;
+		Ok(self.outputs.iter().map(|p| p.name.clone()).zip(tokens).collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{encode, Function, Param, ParamKind, Token};
+	use hex_literal::hex;
+
+	fn param(name: &str, kind: ParamKind) -> Param {
+		Param { name: name.as_bytes().to_vec(), kind, indexed: false }
+	}
+
+	#[test]
+	fn encode_and_decode_call() {
+		let function = Function {
+			name: b"balanceOf".to_vec(),
+			inputs: vec![param("owner", ParamKind::Address)],
+			outputs: vec![param("balance", ParamKind::Uint(256))],
+		};
+		assert_eq!(function.signature(), b"balanceOf(address)".to_vec());
+		assert_eq!(function.selector(), hex!("70a08231"));
+
+		let call = function.encode_input(&[Token::Address([0x11u8; 20].into())]).unwrap();
+		assert_eq!(
+			call,
+			hex!("70a082310000000000000000000000001111111111111111111111111111111111111111")
+				.to_vec()
+		);
+		assert!(function.encode_input(&[Token::Uint(1.into())]).is_err());
+
+		let output = encode(&[Token::Uint(1000.into())]);
+		assert_eq!(
+			function.decode_output_named(&output).unwrap(),
+			vec![(b"balance".to_vec(), Token::Uint(1000.into()))]
+		);
+	}
+}
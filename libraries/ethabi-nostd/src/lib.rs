@@ -11,19 +11,30 @@
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
+#[cfg(feature = "serde")]
+pub mod contract;
 pub mod decoder;
+pub mod eip712;
 pub mod encoder;
-// mod event;
+pub mod event;
+pub mod function;
+pub mod packed;
 pub mod param;
+pub mod signature;
 // mod std;
 pub mod token;
 pub mod util;
 
+#[cfg(feature = "serde")]
+pub use crate::contract::Contract;
 pub use crate::{
 	// decoder::decode,
 	encoder::{encode, encode_function},
-	// event::Event,
+	event::{Event, LogParam},
+	function::Function,
+	packed::{encode_packed, encode_packed_as},
 	param::{Param, ParamKind},
+	signature::parse_signature,
 	token::Token,
 };
 
@@ -33,6 +44,10 @@ pub enum Error {
 	InvalidName,
 	/// Invalid data.
 	InvalidData,
+	/// Invalid or unsupported type, in a signature or for packed encoding.
+	InvalidType,
+	/// Tokens do not match the param types they are encoded as.
+	TypeMismatch,
 }
 
 /// ABI Address
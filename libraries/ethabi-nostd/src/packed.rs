@@ -0,0 +1,268 @@
+// Copyright 2019-2024 Ferrum Inc.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE or
+// http://www.apache.org/licenses/LICENSE-2.0>. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Non-standard packed encoder, the one behind Solidity's `abi.encodePacked`.
+
+use crate::{encode, Error, ParamKind, Token, Word, U256};
+
+use sp_std::prelude::*; //vec::{Vec};
+
+/// Encodes tokens the way `abi.encodePacked` does. `Int` and `Uint` tokens carry no size so they
+/// are packed as 256 bit values, use `encode_packed_as` for narrower types.
+pub fn encode_packed(tokens: &[Token]) -> Result<Vec<u8>, Error> {
+	let mut packed = vec![];
+	for token in tokens {
+		pack_token(token, None, &mut packed)?;
+	}
+	Ok(packed)
+}
+
+/// Encodes tokens the way `abi.encodePacked` does for the given param types, e.g. a token of an
+/// `uint16` param is packed into two bytes.
+pub fn encode_packed_as(types: &[ParamKind], tokens: &[Token]) -> Result<Vec<u8>, Error> {
+	if !Token::types_check(tokens, types) {
+		return Err(Error::TypeMismatch);
+	}
+
+	let mut packed = vec![];
+	for (token, kind) in tokens.iter().zip(types) {
+		pack_token(token, Some(kind), &mut packed)?;
+	}
+	Ok(packed)
+}
+
+fn pack_token(token: &Token, kind: Option<&ParamKind>, packed: &mut Vec<u8>) -> Result<(), Error> {
+	match *token {
+		Token::Address(ref address) => packed.extend_from_slice(address.as_bytes()),
+		Token::Bool(b) => packed.push(b as u8),
+		Token::Bytes(ref bytes) | Token::String(ref bytes) => packed.extend_from_slice(bytes),
+		Token::FixedBytes(ref bytes) => {
+			packed.extend_from_slice(bytes);
+			// a shorter value of a bytesN param is right padded to N bytes
+			if let Some(ParamKind::FixedBytes(size)) = kind {
+				packed.resize(packed.len() + size.saturating_sub(bytes.len()), 0);
+			}
+		},
+		Token::Uint(ref uint) => {
+			let (high, low) = split_word(uint, kind);
+			if !high.iter().all(|b| *b == 0) {
+				return Err(Error::InvalidData);
+			}
+			packed.extend_from_slice(&low);
+		},
+		Token::Int(ref int) => {
+			let (high, low) = split_word(int, kind);
+			// the dropped bytes have to be the sign extension of the packed ones
+			let sign = if low[0] & 0x80 == 0 { 0x00 } else { 0xff };
+			if !high.iter().all(|b| *b == sign) {
+				return Err(Error::InvalidData);
+			}
+			packed.extend_from_slice(&low);
+		},
+		Token::Array(ref tokens) | Token::FixedArray(ref tokens) => {
+			let elem_kind = match kind {
+				Some(ParamKind::Array(elem_kind)) | Some(ParamKind::FixedArray(elem_kind, _)) =>
+					Some(elem_kind.as_ref()),
+				_ => None,
+			};
+			for token in tokens {
+				pack_array_element(token, elem_kind, packed)?;
+			}
+		},
+		// structs and tuples have no packed encoding
+		Token::Tuple(_) => return Err(Error::InvalidType),
+	}
+
+	Ok(())
+}
+
+/// Array elements are padded to 32 bytes, as in the standard encoding. Arrays of dynamic types,
+/// nested arrays and tuples can't be packed.
+fn pack_array_element(
+	token: &Token,
+	kind: Option<&ParamKind>,
+	packed: &mut Vec<u8>,
+) -> Result<(), Error> {
+	match token {
+		Token::Bytes(_)
+		| Token::String(_)
+		| Token::Array(_)
+		| Token::FixedArray(_)
+		| Token::Tuple(_) => Err(Error::InvalidType),
+		token => {
+			// checks that the value fits its type
+			pack_token(token, kind, &mut vec![])?;
+			packed.extend(encode(&[token.clone()]));
+			Ok(())
+		},
+	}
+}
+
+/// Splits the 32 byte big endian value into the bytes dropped and the bytes kept when packing it
+/// as the given int type.
+fn split_word(value: &U256, kind: Option<&ParamKind>) -> (Vec<u8>, Vec<u8>) {
+	let mut word: Word = [0u8; 32];
+	value.to_big_endian(&mut word);
+	let size = match kind {
+		Some(ParamKind::Uint(bits)) | Some(ParamKind::Int(bits)) => (bits / 8).clamp(1, 32),
+		_ => 32,
+	};
+	let (high, low) = word.split_at(32 - size);
+	(high.to_vec(), low.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		packed::{encode_packed, encode_packed_as},
+		Error, ParamKind, Token, U256,
+	};
+	use hex_literal::hex;
+
+	#[test]
+	fn pack_solidity_docs_example() {
+		// abi.encodePacked(int16(-1), bytes1(0x42), uint16(0x03), string("Hello, world!"))
+		let packed = encode_packed_as(
+			&[ParamKind::Int(16), ParamKind::FixedBytes(1), ParamKind::Uint(16), ParamKind::String],
+			&[
+				Token::Int(U256::MAX),
+				Token::FixedBytes(vec![0x42]),
+				Token::Uint(3.into()),
+				Token::String(b"Hello, world!".to_vec()),
+			],
+		)
+		.unwrap();
+		assert_eq!(packed, hex!("ffff42000348656c6c6f2c20776f726c6421").to_vec());
+	}
+
+	#[test]
+	fn pack_dynamic_values_without_length() {
+		// abi.encodePacked("a", "bc") == abi.encodePacked("ab", "c")
+		let packed =
+			encode_packed(&[Token::String(b"a".to_vec()), Token::String(b"bc".to_vec())]).unwrap();
+		assert_eq!(packed, b"abc".to_vec());
+		let packed =
+			encode_packed(&[Token::Bytes(vec![0x12, 0x34]), Token::Bytes(vec![])]).unwrap();
+		assert_eq!(packed, hex!("1234").to_vec());
+	}
+
+	#[test]
+	fn pack_static_values() {
+		// abi.encodePacked(address(0x1111..), true, uint256(1))
+		let packed = encode_packed(&[
+			Token::Address([0x11u8; 20].into()),
+			Token::Bool(true),
+			Token::Uint(1.into()),
+		])
+		.unwrap();
+		assert_eq!(
+			packed,
+			hex!(
+				"
+				1111111111111111111111111111111111111111
+				01
+				0000000000000000000000000000000000000000000000000000000000000001
+			"
+			)
+			.to_vec()
+		);
+
+		// abi.encodePacked(uint8(0xff), int8(-128), int32(-2), bytes4(0x01020304), uint64(7))
+		let packed = encode_packed_as(
+			&[
+				ParamKind::Uint(8),
+				ParamKind::Int(8),
+				ParamKind::Int(32),
+				ParamKind::FixedBytes(4),
+				ParamKind::Uint(64),
+			],
+			&[
+				Token::Uint(0xff.into()),
+				Token::Int(U256::MAX - 127),
+				Token::Int(U256::MAX - 1),
+				Token::FixedBytes(vec![1, 2, 3, 4]),
+				Token::Uint(7.into()),
+			],
+		)
+		.unwrap();
+		assert_eq!(packed, hex!("ff80fffffffe010203040000000000000007").to_vec());
+	}
+
+	#[test]
+	fn pad_array_elements() {
+		// uint16[] memory a = [1, 2]; abi.encodePacked(a)
+		let packed = encode_packed_as(
+			&[ParamKind::Array(Box::new(ParamKind::Uint(16)))],
+			&[Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())])],
+		)
+		.unwrap();
+		assert_eq!(
+			packed,
+			hex!(
+				"
+				0000000000000000000000000000000000000000000000000000000000000001
+				0000000000000000000000000000000000000000000000000000000000000002
+			"
+			)
+			.to_vec()
+		);
+
+		// bytes2[2] memory b = [bytes2(0x1234), bytes2(0x5678)]; abi.encodePacked(uint8(9), b)
+		let packed = encode_packed_as(
+			&[ParamKind::Uint(8), ParamKind::FixedArray(Box::new(ParamKind::FixedBytes(2)), 2)],
+			&[
+				Token::Uint(9.into()),
+				Token::FixedArray(vec![
+					Token::FixedBytes(vec![0x12, 0x34]),
+					Token::FixedBytes(vec![0x56, 0x78]),
+				]),
+			],
+		)
+		.unwrap();
+		assert_eq!(
+			packed,
+			hex!(
+				"
+				09
+				1234000000000000000000000000000000000000000000000000000000000000
+				5678000000000000000000000000000000000000000000000000000000000000
+			"
+			)
+			.to_vec()
+		);
+
+		// address[] memory c = [address(0x2222..)]; abi.encodePacked(c)
+		let packed =
+			encode_packed(&[Token::Array(vec![Token::Address([0x22u8; 20].into())])]).unwrap();
+		assert_eq!(
+			packed,
+			hex!("0000000000000000000000002222222222222222222222222222222222222222").to_vec()
+		);
+	}
+
+	#[test]
+	fn reject_unpackable_values() {
+		let strings = Token::Array(vec![Token::String(b"a".to_vec())]);
+		assert!(matches!(encode_packed(&[strings]), Err(Error::InvalidType)));
+
+		let nested = Token::Array(vec![Token::Array(vec![Token::Uint(1.into())])]);
+		assert!(matches!(encode_packed(&[nested]), Err(Error::InvalidType)));
+
+		let tuple = Token::Tuple(vec![Token::Uint(1.into())]);
+		assert!(matches!(encode_packed(&[tuple]), Err(Error::InvalidType)));
+
+		let too_big = encode_packed_as(&[ParamKind::Uint(16)], &[Token::Uint(0x10000.into())]);
+		assert!(matches!(too_big, Err(Error::InvalidData)));
+
+		let too_big = encode_packed_as(&[ParamKind::Int(8)], &[Token::Int(200.into())]);
+		assert!(matches!(too_big, Err(Error::InvalidData)));
+
+		let mismatch = encode_packed_as(&[ParamKind::Address], &[Token::Bool(true)]);
+		assert!(matches!(mismatch, Err(Error::TypeMismatch)));
+	}
+}
@@ -13,6 +13,8 @@ use sp_std::prelude::*;
 /// Event param specification.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Param {
+	/// Param name.
+	pub name: Vec<u8>,
 	/// Param type.
 	pub kind: ParamKind,
 	/// Indexed flag. If true, param is used to build block bloom.
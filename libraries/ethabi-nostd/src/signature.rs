@@ -0,0 +1,336 @@
+// Copyright 2019-2024 Ferrum Inc.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE or
+// http://www.apache.org/licenses/LICENSE-2.0>. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Parser for human-readable Solidity signatures.
+
+use crate::{Error, ParamKind};
+
+use sp_std::prelude::*; //vec::{Vec};
+
+/// Parses a function signature such as `transfer(address,uint256)`, or a tuple type such as
+/// `(uint64,address)`, into the kinds of its params.
+///
+/// Param names and qualifiers are allowed and ignored, so `transfer(address to, uint256 amount)`
+/// parses to the same params.
+pub fn parse_signature(signature: &str) -> Result<Vec<ParamKind>, Error> {
+	parse_function(signature).map(|(_, params)| params)
+}
+
+/// Splits a function signature into its name and the kinds of its params. The name is empty
+/// for a bare tuple type.
+pub fn parse_function(signature: &str) -> Result<(&str, Vec<ParamKind>), Error> {
+	let (name, params, rest) = split_signature(signature)?;
+	if !rest.is_empty() {
+		return Err(Error::InvalidType);
+	}
+
+	Ok((name, parse_params(params)?))
+}
+
+/// Parses a comma separated list of param declarations, e.g. `uint256 amount,bytes`.
+pub fn parse_params(params: &str) -> Result<Vec<ParamKind>, Error> {
+	Ok(parse_declarations(params)?.into_iter().map(|(kind, _)| kind).collect())
+}
+
+/// Splits a signature into its name, the text between its outer parens and whatever follows
+/// them.
+pub(crate) fn split_signature(signature: &str) -> Result<(&str, &str, &str), Error> {
+	let signature = signature.trim();
+	let open = signature.find('(').ok_or(Error::InvalidName)?;
+	let name = signature[..open].trim();
+	if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$') {
+		return Err(Error::InvalidName);
+	}
+	let close = closing_paren(signature, open)?;
+
+	Ok((name, &signature[open + 1..close], signature[close + 1..].trim()))
+}
+
+/// Parses a comma separated list of param declarations into the kind of each param and the
+/// names or qualifiers that follow its type.
+pub(crate) fn parse_declarations(params: &str) -> Result<Vec<(ParamKind, &str)>, Error> {
+	if params.trim().is_empty() {
+		return Ok(vec![]);
+	}
+
+	split_top_level(params)?.into_iter().map(parse_declaration).collect()
+}
+
+/// Parses a single type, e.g. `uint256`, `bytes32[]` or `(address,bytes)[2]`.
+pub fn parse_param(param: &str) -> Result<ParamKind, Error> {
+	let param = param.trim();
+
+	if let Some(rest) = param.strip_suffix(']') {
+		let open = rest.rfind('[').ok_or(Error::InvalidType)?;
+		let inner = Box::new(parse_param(&rest[..open])?);
+		let size = rest[open + 1..].trim();
+		return if size.is_empty() {
+			Ok(ParamKind::Array(inner))
+		} else {
+			let size = size.parse::<usize>().map_err(|_| Error::InvalidType)?;
+			Ok(ParamKind::FixedArray(inner, size))
+		};
+	}
+
+	let tuple = param.strip_prefix("tuple").unwrap_or(param);
+	if tuple.starts_with('(') {
+		if closing_paren(tuple, 0)? != tuple.len() - 1 {
+			return Err(Error::InvalidType);
+		}
+		let params = parse_params(&tuple[1..tuple.len() - 1])?;
+		return Ok(ParamKind::Tuple(params.into_iter().map(Box::new).collect()));
+	}
+
+	match param {
+		"address" => Ok(ParamKind::Address),
+		"bool" => Ok(ParamKind::Bool),
+		"string" => Ok(ParamKind::String),
+		"bytes" => Ok(ParamKind::Bytes),
+		"uint" => Ok(ParamKind::Uint(256)),
+		"int" => Ok(ParamKind::Int(256)),
+		_ => {
+			if let Some(size) = param.strip_prefix("bytes") {
+				match size.parse::<usize>() {
+					Ok(size) if (1..=32).contains(&size) => Ok(ParamKind::FixedBytes(size)),
+					_ => Err(Error::InvalidType),
+				}
+			} else if let Some(size) = param.strip_prefix("uint") {
+				int_size(size).map(ParamKind::Uint)
+			} else if let Some(size) = param.strip_prefix("int") {
+				int_size(size).map(ParamKind::Int)
+			} else {
+				Err(Error::InvalidType)
+			}
+		},
+	}
+}
+
+/// Writes the canonical form of the function signature, the one the selector is computed from.
+pub fn canonical_signature(name: &str, params: &[ParamKind]) -> Vec<u8> {
+	let mut out = name.as_bytes().to_vec();
+	write_params(params.iter(), &mut out);
+	out
+}
+
+fn write_params<'a>(params: impl Iterator<Item = &'a ParamKind>, out: &mut Vec<u8>) {
+	out.push(b'(');
+	for (i, param) in params.enumerate() {
+		if i > 0 {
+			out.push(b',');
+		}
+		write_param(param, out);
+	}
+	out.push(b')');
+}
+
+fn write_param(param: &ParamKind, out: &mut Vec<u8>) {
+	match param {
+		ParamKind::Address => out.extend_from_slice(b"address"),
+		ParamKind::Bytes => out.extend_from_slice(b"bytes"),
+		ParamKind::Bool => out.extend_from_slice(b"bool"),
+		ParamKind::String => out.extend_from_slice(b"string"),
+		ParamKind::Int(size) => {
+			out.extend_from_slice(b"int");
+			write_usize(*size, out);
+		},
+		ParamKind::Uint(size) => {
+			out.extend_from_slice(b"uint");
+			write_usize(*size, out);
+		},
+		ParamKind::FixedBytes(size) => {
+			out.extend_from_slice(b"bytes");
+			write_usize(*size, out);
+		},
+		ParamKind::Array(inner) => {
+			write_param(inner, out);
+			out.extend_from_slice(b"[]");
+		},
+		ParamKind::FixedArray(inner, size) => {
+			write_param(inner, out);
+			out.push(b'[');
+			write_usize(*size, out);
+			out.push(b']');
+		},
+		ParamKind::Tuple(params) => write_params(params.iter().map(|p| p.as_ref()), out),
+	}
+}
+
+fn write_usize(value: usize, out: &mut Vec<u8>) {
+	if value >= 10 {
+		write_usize(value / 10, out);
+	}
+	out.push(b'0' + (value % 10) as u8);
+}
+
+fn int_size(size: &str) -> Result<usize, Error> {
+	match size.parse::<usize>() {
+		Ok(size) if size % 8 == 0 && (8..=256).contains(&size) => Ok(size),
+		_ => Err(Error::InvalidType),
+	}
+}
+
+/// Parses a param declaration, the type followed by optional names or qualifiers.
+fn parse_declaration(declaration: &str) -> Result<(ParamKind, &str), Error> {
+	let declaration = declaration.trim();
+	if declaration.is_empty() {
+		return Err(Error::InvalidType);
+	}
+
+	let mut depth = 0usize;
+	let end = declaration
+		.char_indices()
+		.find(|(_, c)| {
+			match c {
+				'(' | '[' => depth += 1,
+				')' | ']' => depth = depth.saturating_sub(1),
+				_ => {},
+			}
+			depth == 0 && c.is_whitespace()
+		})
+		.map_or(declaration.len(), |(i, _)| i);
+
+	Ok((parse_param(&declaration[..end])?, declaration[end..].trim()))
+}
+
+/// Splits on the commas that are not nested in a tuple or array.
+fn split_top_level(params: &str) -> Result<Vec<&str>, Error> {
+	let mut parts = vec![];
+	let mut depth = 0usize;
+	let mut start = 0;
+	for (i, c) in params.char_indices() {
+		match c {
+			'(' | '[' => depth += 1,
+			')' | ']' => depth = depth.checked_sub(1).ok_or(Error::InvalidType)?,
+			',' if depth == 0 => {
+				parts.push(&params[start..i]);
+				start = i + 1;
+			},
+			_ => {},
+		}
+	}
+	if depth != 0 {
+		return Err(Error::InvalidType);
+	}
+	parts.push(&params[start..]);
+	Ok(parts)
+}
+
+/// Finds the paren closing the one at `open`.
+fn closing_paren(s: &str, open: usize) -> Result<usize, Error> {
+	let mut depth = 0usize;
+	for (i, c) in s[open..].char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' => {
+				depth -= 1;
+				if depth == 0 {
+					return Ok(open + i);
+				}
+			},
+			_ => {},
+		}
+	}
+	Err(Error::InvalidType)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{canonical_signature, parse_function, parse_param, parse_signature};
+	use crate::{Error, ParamKind};
+
+	#[test]
+	fn parse_elementary_types() {
+		assert_eq!(parse_param("address").unwrap(), ParamKind::Address);
+		assert_eq!(parse_param("bool").unwrap(), ParamKind::Bool);
+		assert_eq!(parse_param("string").unwrap(), ParamKind::String);
+		assert_eq!(parse_param("bytes").unwrap(), ParamKind::Bytes);
+		assert_eq!(parse_param("bytes32").unwrap(), ParamKind::FixedBytes(32));
+		assert_eq!(parse_param("uint").unwrap(), ParamKind::Uint(256));
+		assert_eq!(parse_param("uint64").unwrap(), ParamKind::Uint(64));
+		assert_eq!(parse_param("int8").unwrap(), ParamKind::Int(8));
+
+		assert!(matches!(parse_param("bytes33"), Err(Error::InvalidType)));
+		assert!(matches!(parse_param("uint7"), Err(Error::InvalidType)));
+		assert!(matches!(parse_param("uint512"), Err(Error::InvalidType)));
+		assert!(matches!(parse_param("foo"), Err(Error::InvalidType)));
+	}
+
+	#[test]
+	fn parse_arrays_and_tuples() {
+		assert_eq!(
+			parse_param("uint256[]").unwrap(),
+			ParamKind::Array(Box::new(ParamKind::Uint(256)))
+		);
+		assert_eq!(
+			parse_param("address[2][]").unwrap(),
+			ParamKind::Array(Box::new(ParamKind::FixedArray(Box::new(ParamKind::Address), 2)))
+		);
+		assert_eq!(
+			parse_param("(uint64,bytes[])[]").unwrap(),
+			ParamKind::Array(Box::new(ParamKind::Tuple(vec![
+				Box::new(ParamKind::Uint(64)),
+				Box::new(ParamKind::Array(Box::new(ParamKind::Bytes))),
+			])))
+		);
+		assert_eq!(
+			parse_param("tuple(bool)").unwrap(),
+			ParamKind::Tuple(vec![Box::new(ParamKind::Bool)])
+		);
+
+		assert!(matches!(parse_param("uint256[x]"), Err(Error::InvalidType)));
+		assert!(matches!(parse_param("(uint256"), Err(Error::InvalidType)));
+	}
+
+	#[test]
+	fn parse_function_signatures() {
+		let (name, params) = parse_function(
+			"finalizeSingleSigner(uint256,uint256,uint256[],bytes32,address[],bytes32,uint64,bytes)",
+		)
+		.unwrap();
+		assert_eq!(name, "finalizeSingleSigner");
+		assert_eq!(
+			params,
+			vec![
+				ParamKind::Uint(256),
+				ParamKind::Uint(256),
+				ParamKind::Array(Box::new(ParamKind::Uint(256))),
+				ParamKind::FixedBytes(32),
+				ParamKind::Array(Box::new(ParamKind::Address)),
+				ParamKind::FixedBytes(32),
+				ParamKind::Uint(64),
+				ParamKind::Bytes,
+			]
+		);
+
+		assert_eq!(parse_signature("authorityMgr()").unwrap(), vec![]);
+		assert_eq!(
+			parse_signature("transfer(address to, uint256 amount)").unwrap(),
+			vec![ParamKind::Address, ParamKind::Uint(256)]
+		);
+		assert_eq!(
+			parse_signature("(uint256,uint256,uint256)").unwrap(),
+			vec![ParamKind::Uint(256), ParamKind::Uint(256), ParamKind::Uint(256)]
+		);
+
+		assert!(matches!(parse_signature("transfer"), Err(Error::InvalidName)));
+		assert!(matches!(parse_signature("trans fer(address)"), Err(Error::InvalidName)));
+		assert!(matches!(parse_signature("transfer(address,)"), Err(Error::InvalidType)));
+		assert!(matches!(parse_signature("transfer(address))"), Err(Error::InvalidType)));
+	}
+
+	#[test]
+	fn canonical_form() {
+		let (name, params) =
+			parse_function("mine(uint chainId, (address to, bytes data)[] txs, bytes32 salt)")
+				.unwrap();
+		assert_eq!(
+			canonical_signature(name, &params),
+			b"mine(uint256,(address,bytes)[],bytes32)".to_vec()
+		);
+	}
+}
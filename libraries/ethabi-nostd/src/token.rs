@@ -113,7 +113,8 @@ impl Token {
 			},
 			Token::Tuple(ref tokens) => {
 				if let ParamKind::Tuple(ref param_type) = *param_type {
-					tokens.iter().enumerate().all(|(i, t)| t.type_check(&param_type[i]))
+					param_type.len() == tokens.len()
+						&& tokens.iter().zip(param_type).all(|(t, p)| t.type_check(p))
 				} else {
 					false
 				}
@@ -161,6 +162,33 @@ impl Token {
 		}
 	}
 
+	/// Converts an `Int` token to `i128`, if its value fits.
+	pub fn to_i128(self) -> Option<i128> {
+		match self {
+			Token::Int(int) => {
+				let mut word = [0u8; 32];
+				int.to_big_endian(&mut word);
+				let (high, low) = word.split_at(16);
+				let sign = if low[0] & 0x80 == 0 { 0x00 } else { 0xff };
+				if !high.iter().all(|b| *b == sign) {
+					return None;
+				}
+				let mut bytes = [0u8; 16];
+				bytes.copy_from_slice(low);
+				Some(i128::from_be_bytes(bytes))
+			},
+			_ => None,
+		}
+	}
+
+	/// Converts an `Uint` token to `u64`, if its value fits.
+	pub fn to_u64_checked(self) -> Option<u64> {
+		match self {
+			Token::Uint(uint) if uint <= U256::from(u64::MAX) => Some(uint.low_u64()),
+			_ => None,
+		}
+	}
+
 	/// Converts token to...
 	pub fn to_bool(self) -> Option<bool> {
 		match self {
@@ -223,7 +251,7 @@ impl Token {
 
 #[cfg(test)]
 mod tests {
-	use crate::{ParamKind, Token};
+	use crate::{ParamKind, Token, U256};
 
 	#[test]
 	fn test_type_check() {
@@ -319,4 +347,21 @@ mod tests {
 			true
 		);
 	}
+
+	#[test]
+	fn test_checked_conversions() {
+		assert_eq!(Token::Int(U256::MAX).to_i128(), Some(-1));
+		assert_eq!(Token::Int(U256::from(i128::MAX as u128)).to_i128(), Some(i128::MAX));
+		assert_eq!(
+			Token::Int(U256::MAX - U256::from(i128::MAX as u128)).to_i128(),
+			Some(i128::MIN)
+		);
+		assert_eq!(Token::Int(U256::from(u128::MAX)).to_i128(), None);
+		assert_eq!(Token::Int(U256::MAX - U256::from(u128::MAX)).to_i128(), None);
+		assert_eq!(Token::Uint(1.into()).to_i128(), None);
+
+		assert_eq!(Token::Uint(U256::from(u64::MAX)).to_u64_checked(), Some(u64::MAX));
+		assert_eq!(Token::Uint(U256::from(u64::MAX) + 1).to_u64_checked(), None);
+		assert_eq!(Token::Int(1.into()).to_u64_checked(), None);
+	}
 }
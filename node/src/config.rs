@@ -13,7 +13,9 @@
 
 // You should have received a copy of the GNU General Public License
 // along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
-use pallet_quantum_portal::qp_types::{QpConfig, QpNetworkItem};
+use pallet_quantum_portal::qp_types::{
+	default_gas_limit_multiplier, QpConfig, QpNetworkItem, MIN_GAS_LIMIT_MULTIPLIER,
+};
 use serde::{Deserialize, Serialize};
 use std::{fs::File, io::BufReader, path::Path};
 
@@ -32,6 +34,21 @@ pub struct NetworkItem {
 	pub gateway_contract_address: Vec<u8>,
 	/// The ChainId for this network
 	pub id: u64,
+	/// Blocks to wait before remote state and transaction receipts are trusted
+	#[serde(default)]
+	pub confirmations: u64,
+	/// Percentage applied to gas estimates, defaults to a 20% safety margin
+	#[serde(default = "default_gas_limit_multiplier")]
+	pub gas_limit_multiplier: u32,
+	/// Highest gas price in wei the node will pay on this network
+	#[serde(default)]
+	pub max_gas_price: u128,
+	/// Wei the node may spend on this network in a rolling day
+	#[serde(default)]
+	pub daily_spend_budget: u128,
+	/// Multicall3 contract address used to batch reads, reads are sent one by one when empty
+	#[serde(default, with = "serde_bytes")]
+	pub multicall_address: Vec<u8>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -46,6 +63,24 @@ pub struct NetworkConfig {
 	/// The role of this node
 	#[serde(with = "serde_bytes")]
 	pub role: Vec<u8>,
+	/// Simulate transactions instead of sending them
+	#[serde(default)]
+	pub dry_run: bool,
+}
+
+impl NetworkConfig {
+	/// Rejects settings the offchain worker would refuse to run with
+	pub fn validate(&self) -> Result<(), String> {
+		for network_item in &self.network_vec {
+			if network_item.gas_limit_multiplier < MIN_GAS_LIMIT_MULTIPLIER {
+				return Err(format!(
+					"gas_limit_multiplier of network {} is {}, it must be at least {}",
+					network_item.id, network_item.gas_limit_multiplier, MIN_GAS_LIMIT_MULTIPLIER
+				));
+			}
+		}
+		Ok(())
+	}
 }
 
 pub fn convert(network_config: NetworkConfig) -> QpConfig {
@@ -58,11 +93,17 @@ pub fn convert(network_config: NetworkConfig) -> QpConfig {
 				url: network_item.url,
 				gateway_contract_address: network_item.gateway_contract_address,
 				id: network_item.id,
+				confirmations: network_item.confirmations,
+				gas_limit_multiplier: network_item.gas_limit_multiplier,
+				max_gas_price: network_item.max_gas_price,
+				daily_spend_budget: network_item.daily_spend_budget,
+				multicall_address: network_item.multicall_address,
 			})
 			.collect(),
 		pair_vec: network_config.pair_vec,
 		signer_public_key: network_config.signer_public_key,
 		role: role_as_bytes.into(),
+		dry_run: network_config.dry_run,
 	}
 }
 
@@ -71,10 +112,9 @@ pub fn read_config_from_file<P: AsRef<Path>>(path: P) -> Result<Config, String>
 		Ok(file) => {
 			let reader = BufReader::new(file);
 
-			match serde_json::from_reader(reader) {
-				Ok(config) => Ok(config),
-				Err(err) => Err(err.to_string()),
-			}
+			let config: Config = serde_json::from_reader(reader).map_err(|err| err.to_string())?;
+			config.networks.validate()?;
+			Ok(config)
 		},
 		Err(err) => Err(err.to_string()),
 	}
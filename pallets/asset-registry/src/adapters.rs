@@ -0,0 +1,147 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Adapters plugging the registry into the XCM executor configuration.
+use super::*;
+use frame_support::{
+	traits::ContainsPair,
+	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight},
+};
+use sp_runtime::traits::MaybeEquivalence;
+use sp_std::marker::PhantomData;
+use xcm::latest::prelude::*;
+use xcm_builder::TakeRevenue;
+use xcm_executor::{traits::WeightTrader, AssetsInHolding};
+
+/// Converts the location of a registered foreign asset to its id, and back.
+pub struct LocationToAssetId<T>(PhantomData<T>);
+impl<T: Config> MaybeEquivalence<Location, T::AssetId> for LocationToAssetId<T> {
+	fn convert(location: &Location) -> Option<T::AssetId> {
+		LocationAssets::<T>::get(location)
+	}
+
+	fn convert_back(asset_id: &T::AssetId) -> Option<Location> {
+		AssetLocations::<T>::get(asset_id)
+	}
+}
+
+/// Returns the chain holding the reserve of the asset at `location`: the relay chain for its
+/// native token, or the parachain the asset belongs to.
+fn reserve_location(location: &Location) -> Option<Location> {
+	match location.unpack() {
+		(1, []) => Some(Location::parent()),
+		(1, [Parachain(id), ..]) => Some(Location::new(1, [Parachain(*id)])),
+		_ => None,
+	}
+}
+
+/// Accepts the registered foreign assets deposited by their reserve chain.
+pub struct RegisteredReserveAssets<T>(PhantomData<T>);
+impl<T: Config> ContainsPair<Asset, Location> for RegisteredReserveAssets<T> {
+	fn contains(asset: &Asset, origin: &Location) -> bool {
+		let AssetId(location) = &asset.id;
+		LocationAssets::<T>::contains_key(location) &&
+			reserve_location(location).as_ref() == Some(origin)
+	}
+}
+
+/// Buys xcm execution weight with the first asset of the payment, at the units per second set for
+/// it in the registry. The fees are handed to `R` once the xcm is executed.
+pub struct UnitsPerSecondTrader<T, R: TakeRevenue> {
+	/// The weight bought so far.
+	weight: Weight,
+	/// The asset the weight is paid in, its units per second and the amount paid so far.
+	payment: Option<(Location, u128, u128)>,
+	_marker: PhantomData<(T, R)>,
+}
+
+impl<T, R: TakeRevenue> UnitsPerSecondTrader<T, R> {
+	fn fee(units_per_second: u128, weight: Weight) -> u128 {
+		units_per_second.saturating_mul(weight.ref_time() as u128) /
+			(WEIGHT_REF_TIME_PER_SECOND as u128)
+	}
+}
+
+impl<T: Config, R: TakeRevenue> WeightTrader for UnitsPerSecondTrader<T, R> {
+	fn new() -> Self {
+		Self { weight: Weight::zero(), payment: None, _marker: PhantomData }
+	}
+
+	fn buy_weight(
+		&mut self,
+		weight: Weight,
+		payment: AssetsInHolding,
+		_context: &XcmContext,
+	) -> Result<AssetsInHolding, XcmError> {
+		log::trace!(
+			target: "xcm::weight",
+			"UnitsPerSecondTrader::buy_weight weight: {:?}, payment: {:?}",
+			weight,
+			payment,
+		);
+
+		let AssetId(location) =
+			payment.fungible_assets_iter().next().ok_or(XcmError::AssetNotFound)?.id;
+
+		// The weight can only be paid in a single asset.
+		if let Some((paid_in, _, _)) = &self.payment {
+			if *paid_in != location {
+				return Err(XcmError::NotWithdrawable);
+			}
+		}
+
+		let units_per_second = UnitsPerSecond::<T>::get(&location).ok_or(XcmError::TooExpensive)?;
+		let amount = Self::fee(units_per_second, weight);
+		let unused = if amount == 0 {
+			payment
+		} else {
+			payment
+				.checked_sub((location.clone(), amount).into())
+				.map_err(|_| XcmError::TooExpensive)?
+		};
+
+		self.weight = self.weight.saturating_add(weight);
+		let paid = self.payment.as_ref().map_or(0, |(_, _, paid)| *paid).saturating_add(amount);
+		self.payment = Some((location, units_per_second, paid));
+
+		Ok(unused)
+	}
+
+	fn refund_weight(&mut self, weight: Weight, _context: &XcmContext) -> Option<Asset> {
+		let (location, units_per_second, paid) = self.payment.as_mut()?;
+
+		let weight = weight.min(self.weight);
+		let amount = Self::fee(*units_per_second, weight).min(*paid);
+		self.weight = self.weight.saturating_sub(weight);
+		*paid = paid.saturating_sub(amount);
+
+		if amount > 0 {
+			Some((location.clone(), amount).into())
+		} else {
+			None
+		}
+	}
+}
+
+impl<T, R: TakeRevenue> Drop for UnitsPerSecondTrader<T, R> {
+	fn drop(&mut self) {
+		if let Some((location, _, paid)) = self.payment.take() {
+			if paid > 0 {
+				R::take_revenue((location, paid).into());
+			}
+		}
+	}
+}
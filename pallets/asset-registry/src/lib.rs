@@ -0,0 +1,219 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Asset Registry Pallet
+//!
+//! Registers the XCM location of the foreign assets held on Ferrum, and the rate at which XCM
+//! execution fees are charged in each asset.
+//!
+//! The registry backs the XCM configuration of the runtime through the adapters of this crate:
+//! - [`LocationToAssetId`] converts the location of a registered asset to its id;
+//! - [`RegisteredReserveAssets`] accepts registered assets from their reserve chain;
+//! - [`UnitsPerSecondTrader`] buys execution weight at the configured units per second.
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use codec::{Decode, Encode};
+use frame_support::{pallet_prelude::*, transactional};
+use frame_system::pallet_prelude::*;
+use scale_info::TypeInfo;
+use sp_runtime::{traits::AtLeast32BitUnsigned, DispatchResult, RuntimeDebug};
+use sp_std::{boxed::Box, vec::Vec};
+use xcm::{latest::Location, VersionedLocation};
+
+mod adapters;
+mod mock;
+mod tests;
+pub mod weights;
+pub use adapters::*;
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// Metadata of a foreign asset, as exposed by its ERC20 precompile.
+#[derive(Clone, Default, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct AssetMetadata {
+	pub name: Vec<u8>,
+	pub symbol: Vec<u8>,
+	pub decimals: u8,
+}
+
+/// Creates the foreign assets in the runtime, usually in a `pallet_assets` instance.
+pub trait AssetRegistrar<AssetId, Balance> {
+	fn create_foreign_asset(
+		asset_id: AssetId,
+		min_balance: Balance,
+		metadata: AssetMetadata,
+		is_sufficient: bool,
+	) -> DispatchResult;
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The identifier of the foreign assets.
+		type AssetId: Member + Parameter + Copy + MaxEncodedLen;
+
+		/// The balance of the foreign assets.
+		type Balance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy + MaxEncodedLen;
+
+		/// Creates the registered assets.
+		type AssetRegistrar: AssetRegistrar<Self::AssetId, Self::Balance>;
+
+		/// The origin which may register assets and set their fee rates.
+		type RegistryOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// the location cannot be converted to the latest xcm version
+		BadLocation,
+		/// an asset is already registered with this id
+		AssetIdAlreadyRegistered,
+		/// an asset is already registered at this location
+		LocationAlreadyRegistered,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Registered foreign asset
+		ForeignAssetRegistered { asset_id: T::AssetId, location: Location, metadata: AssetMetadata },
+		/// Changed the fee rate of an asset
+		UnitsPerSecondChanged { location: Location, units_per_second: u128 },
+		/// Removed the fee rate of an asset
+		UnitsPerSecondRemoved { location: Location },
+	}
+
+	/// The location of each registered foreign asset
+	///
+	/// map AssetId => Location
+	#[pallet::storage]
+	#[pallet::getter(fn asset_location)]
+	pub type AssetLocations<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AssetId, Location, OptionQuery>;
+
+	/// The foreign asset registered at each location
+	///
+	/// map Location => AssetId
+	#[pallet::storage]
+	#[pallet::getter(fn location_asset)]
+	pub type LocationAssets<T: Config> =
+		StorageMap<_, Blake2_128Concat, Location, T::AssetId, OptionQuery>;
+
+	/// The units of an asset charged per second of xcm execution, for the assets fees may be
+	/// paid in
+	///
+	/// map Location => u128
+	#[pallet::storage]
+	#[pallet::getter(fn units_per_second)]
+	pub type UnitsPerSecond<T: Config> =
+		StorageMap<_, Blake2_128Concat, Location, u128, OptionQuery>;
+
+	#[pallet::pallet]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Creates the foreign asset `asset_id` and registers it at `location`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::register_foreign_asset())]
+		#[transactional]
+		pub fn register_foreign_asset(
+			origin: OriginFor<T>,
+			location: Box<VersionedLocation>,
+			asset_id: T::AssetId,
+			metadata: AssetMetadata,
+			min_balance: T::Balance,
+			is_sufficient: bool,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			let location = Self::latest_location(location)?;
+
+			ensure!(
+				!AssetLocations::<T>::contains_key(asset_id),
+				Error::<T>::AssetIdAlreadyRegistered
+			);
+			ensure!(
+				!LocationAssets::<T>::contains_key(&location),
+				Error::<T>::LocationAlreadyRegistered
+			);
+
+			T::AssetRegistrar::create_foreign_asset(
+				asset_id,
+				min_balance,
+				metadata.clone(),
+				is_sufficient,
+			)?;
+
+			AssetLocations::<T>::insert(asset_id, location.clone());
+			LocationAssets::<T>::insert(location.clone(), asset_id);
+
+			Self::deposit_event(Event::ForeignAssetRegistered { asset_id, location, metadata });
+			Ok(())
+		}
+
+		/// Sets the units of the asset at `location` charged per second of xcm execution.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::set_units_per_second())]
+		pub fn set_units_per_second(
+			origin: OriginFor<T>,
+			location: Box<VersionedLocation>,
+			units_per_second: u128,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			let location = Self::latest_location(location)?;
+
+			UnitsPerSecond::<T>::insert(location.clone(), units_per_second);
+
+			Self::deposit_event(Event::UnitsPerSecondChanged { location, units_per_second });
+			Ok(())
+		}
+
+		/// Stops accepting the asset at `location` for xcm execution fees.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::remove_units_per_second())]
+		pub fn remove_units_per_second(
+			origin: OriginFor<T>,
+			location: Box<VersionedLocation>,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			let location = Self::latest_location(location)?;
+
+			if UnitsPerSecond::<T>::take(&location).is_some() {
+				Self::deposit_event(Event::UnitsPerSecondRemoved { location });
+			}
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn latest_location(location: Box<VersionedLocation>) -> Result<Location, DispatchError> {
+		Location::try_from(*location).map_err(|_| Error::<T>::BadLocation.into())
+	}
+}
@@ -0,0 +1,133 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+#![cfg(test)]
+use super::*;
+use frame_support::{
+	construct_runtime,
+	traits::{ConstU32, ConstU64, Everything},
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+use std::cell::RefCell;
+use xcm::latest::Asset;
+use xcm_builder::TakeRevenue;
+
+pub type AccountId = u64;
+pub type AssetId = u128;
+pub type Balance = u128;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = Everything;
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeTask = RuntimeTask;
+	type Nonce = u64;
+	type Block = Block;
+	type RuntimeCall = RuntimeCall;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+	type SingleBlockMigrations = ();
+	type MultiBlockMigrator = ();
+	type PreInherents = ();
+	type PostInherents = ();
+	type PostTransactions = ();
+}
+
+thread_local! {
+	pub static CREATED_ASSETS: RefCell<Vec<(AssetId, Balance, AssetMetadata, bool)>> =
+		RefCell::new(vec![]);
+	pub static REVENUE: RefCell<Vec<Asset>> = RefCell::new(vec![]);
+}
+
+/// Records the created assets in `CREATED_ASSETS`.
+pub struct MockAssetRegistrar;
+impl AssetRegistrar<AssetId, Balance> for MockAssetRegistrar {
+	fn create_foreign_asset(
+		asset_id: AssetId,
+		min_balance: Balance,
+		metadata: AssetMetadata,
+		is_sufficient: bool,
+	) -> DispatchResult {
+		CREATED_ASSETS.with(|assets| {
+			assets.borrow_mut().push((asset_id, min_balance, metadata, is_sufficient))
+		});
+		Ok(())
+	}
+}
+
+/// Records the xcm fees in `REVENUE`.
+pub struct MockRevenue;
+impl TakeRevenue for MockRevenue {
+	fn take_revenue(revenue: Asset) {
+		REVENUE.with(|r| r.borrow_mut().push(revenue));
+	}
+}
+
+impl Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AssetId = AssetId;
+	type Balance = Balance;
+	type AssetRegistrar = MockAssetRegistrar;
+	type RegistryOrigin = EnsureRoot<AccountId>;
+	type WeightInfo = ();
+}
+
+construct_runtime!(
+	pub enum Runtime {
+		System: frame_system,
+		AssetRegistry: crate,
+	}
+);
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		ExtBuilder
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::<Runtime>::default()
+			.build_storage()
+			.expect("Frame system builds valid default genesis config");
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
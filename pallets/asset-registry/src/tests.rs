@@ -0,0 +1,264 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+#![cfg(test)]
+use super::*;
+use frame_support::{assert_noop, assert_ok, traits::ContainsPair};
+use mock::{RuntimeEvent, *};
+use sp_runtime::traits::{BadOrigin, MaybeEquivalence};
+use xcm::latest::prelude::*;
+use xcm_executor::{traits::WeightTrader, AssetsInHolding};
+
+const RELAY_TOKEN_ID: AssetId = 1;
+const SIBLING_TOKEN_ID: AssetId = 2;
+/// One unit of asset per picosecond of execution: the fee is the weight.
+const UNITS_PER_SECOND: u128 = 1_000_000_000_000;
+
+type Trader = UnitsPerSecondTrader<Runtime, MockRevenue>;
+
+fn relay_token() -> Location {
+	Location::parent()
+}
+
+fn sibling_token() -> Location {
+	Location::new(1, [Parachain(2001), PalletInstance(10)])
+}
+
+fn metadata() -> AssetMetadata {
+	AssetMetadata { name: b"Kusama".to_vec(), symbol: b"KSM".to_vec(), decimals: 12 }
+}
+
+fn register(location: Location, asset_id: AssetId) {
+	assert_ok!(AssetRegistry::register_foreign_asset(
+		RuntimeOrigin::root(),
+		Box::new(location.into()),
+		asset_id,
+		metadata(),
+		1,
+		true
+	));
+}
+
+fn set_units_per_second(location: Location, units_per_second: u128) {
+	assert_ok!(AssetRegistry::set_units_per_second(
+		RuntimeOrigin::root(),
+		Box::new(location.into()),
+		units_per_second
+	));
+}
+
+fn context() -> XcmContext {
+	XcmContext::with_message_id([0; 32])
+}
+
+#[test]
+fn register_foreign_asset_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			AssetRegistry::register_foreign_asset(
+				RuntimeOrigin::signed(1),
+				Box::new(relay_token().into()),
+				RELAY_TOKEN_ID,
+				metadata(),
+				1,
+				true
+			),
+			BadOrigin
+		);
+
+		register(relay_token(), RELAY_TOKEN_ID);
+
+		assert_eq!(AssetRegistry::asset_location(RELAY_TOKEN_ID), Some(relay_token()));
+		assert_eq!(AssetRegistry::location_asset(relay_token()), Some(RELAY_TOKEN_ID));
+		assert_eq!(
+			CREATED_ASSETS.with(|assets| assets.borrow().clone()),
+			vec![(RELAY_TOKEN_ID, 1, metadata(), true)]
+		);
+		System::assert_last_event(RuntimeEvent::AssetRegistry(
+			crate::Event::ForeignAssetRegistered {
+				asset_id: RELAY_TOKEN_ID,
+				location: relay_token(),
+				metadata: metadata(),
+			},
+		));
+	});
+}
+
+#[test]
+fn register_foreign_asset_rejects_duplicates() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(relay_token(), RELAY_TOKEN_ID);
+
+		assert_noop!(
+			AssetRegistry::register_foreign_asset(
+				RuntimeOrigin::root(),
+				Box::new(sibling_token().into()),
+				RELAY_TOKEN_ID,
+				metadata(),
+				1,
+				true
+			),
+			Error::<Runtime>::AssetIdAlreadyRegistered
+		);
+		assert_noop!(
+			AssetRegistry::register_foreign_asset(
+				RuntimeOrigin::root(),
+				Box::new(relay_token().into()),
+				SIBLING_TOKEN_ID,
+				metadata(),
+				1,
+				true
+			),
+			Error::<Runtime>::LocationAlreadyRegistered
+		);
+	});
+}
+
+#[test]
+fn set_and_remove_units_per_second_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			AssetRegistry::set_units_per_second(
+				RuntimeOrigin::signed(1),
+				Box::new(relay_token().into()),
+				UNITS_PER_SECOND
+			),
+			BadOrigin
+		);
+
+		set_units_per_second(relay_token(), UNITS_PER_SECOND);
+		assert_eq!(AssetRegistry::units_per_second(relay_token()), Some(UNITS_PER_SECOND));
+		System::assert_last_event(RuntimeEvent::AssetRegistry(
+			crate::Event::UnitsPerSecondChanged {
+				location: relay_token(),
+				units_per_second: UNITS_PER_SECOND,
+			},
+		));
+
+		assert_ok!(AssetRegistry::remove_units_per_second(
+			RuntimeOrigin::root(),
+			Box::new(relay_token().into())
+		));
+		assert_eq!(AssetRegistry::units_per_second(relay_token()), None);
+		System::assert_last_event(RuntimeEvent::AssetRegistry(
+			crate::Event::UnitsPerSecondRemoved { location: relay_token() },
+		));
+	});
+}
+
+#[test]
+fn location_to_asset_id_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		register(relay_token(), RELAY_TOKEN_ID);
+
+		assert_eq!(LocationToAssetId::<Runtime>::convert(&relay_token()), Some(RELAY_TOKEN_ID));
+		assert_eq!(
+			LocationToAssetId::<Runtime>::convert_back(&RELAY_TOKEN_ID),
+			Some(relay_token())
+		);
+		assert_eq!(LocationToAssetId::<Runtime>::convert(&sibling_token()), None);
+		assert_eq!(LocationToAssetId::<Runtime>::convert_back(&SIBLING_TOKEN_ID), None);
+	});
+}
+
+#[test]
+fn registered_assets_are_accepted_from_their_reserve() {
+	ExtBuilder::default().build().execute_with(|| {
+		let sibling = Location::new(1, [Parachain(2001)]);
+		let relay_asset: Asset = (relay_token(), 100).into();
+		let sibling_asset: Asset = (sibling_token(), 100).into();
+
+		// Not registered yet
+		assert!(!RegisteredReserveAssets::<Runtime>::contains(&relay_asset, &Location::parent()));
+
+		register(relay_token(), RELAY_TOKEN_ID);
+		register(sibling_token(), SIBLING_TOKEN_ID);
+
+		assert!(RegisteredReserveAssets::<Runtime>::contains(&relay_asset, &Location::parent()));
+		assert!(RegisteredReserveAssets::<Runtime>::contains(&sibling_asset, &sibling));
+		assert!(!RegisteredReserveAssets::<Runtime>::contains(&relay_asset, &sibling));
+		assert!(!RegisteredReserveAssets::<Runtime>::contains(&sibling_asset, &Location::parent()));
+	});
+}
+
+#[test]
+fn trader_charges_units_per_second() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_units_per_second(relay_token(), UNITS_PER_SECOND);
+
+		let mut trader = Trader::new();
+		let payment: AssetsInHolding = Asset::from((relay_token(), 10_000)).into();
+		let unused = trader
+			.buy_weight(Weight::from_parts(1_000, 0), payment, &context())
+			.expect("the payment covers the weight");
+		assert_eq!(unused, Asset::from((relay_token(), 9_000)).into());
+
+		// Refunds the unused weight
+		assert_eq!(
+			trader.refund_weight(Weight::from_parts(400, 0), &context()),
+			Some((relay_token(), 400).into())
+		);
+
+		drop(trader);
+		assert_eq!(REVENUE.with(|r| r.borrow().clone()), vec![Asset::from((relay_token(), 600))]);
+	});
+}
+
+#[test]
+fn trader_requires_units_per_second() {
+	ExtBuilder::default().build().execute_with(|| {
+		let mut trader = Trader::new();
+		let payment: AssetsInHolding = Asset::from((relay_token(), 10_000)).into();
+		assert_eq!(
+			trader.buy_weight(Weight::from_parts(1_000, 0), payment, &context()),
+			Err(XcmError::TooExpensive)
+		);
+
+		drop(trader);
+		assert!(REVENUE.with(|r| r.borrow().is_empty()));
+	});
+}
+
+#[test]
+fn trader_rejects_insufficient_payment() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_units_per_second(relay_token(), UNITS_PER_SECOND);
+
+		let mut trader = Trader::new();
+		let payment: AssetsInHolding = Asset::from((relay_token(), 999)).into();
+		assert_eq!(
+			trader.buy_weight(Weight::from_parts(1_000, 0), payment, &context()),
+			Err(XcmError::TooExpensive)
+		);
+	});
+}
+
+#[test]
+fn trader_is_paid_in_a_single_asset() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_units_per_second(relay_token(), UNITS_PER_SECOND);
+		set_units_per_second(sibling_token(), UNITS_PER_SECOND);
+
+		let mut trader = Trader::new();
+		let payment: AssetsInHolding = Asset::from((relay_token(), 1_000)).into();
+		assert_ok!(trader.buy_weight(Weight::from_parts(1_000, 0), payment, &context()));
+
+		let payment: AssetsInHolding = Asset::from((sibling_token(), 1_000)).into();
+		assert_eq!(
+			trader.buy_weight(Weight::from_parts(1_000, 0), payment, &context()),
+			Err(XcmError::NotWithdrawable)
+		);
+	});
+}
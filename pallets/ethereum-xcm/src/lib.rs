@@ -0,0 +1,188 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Ethereum Xcm Pallet
+//!
+//! Lets other chains call the EVM contracts deployed on Ferrum through XCM `Transact`.
+//!
+//! The XCM origin, converted to a 20 bytes account, is the sender of an Ethereum transaction
+//! built from an [`EthereumXcmTransaction`] and applied through `pallet_ethereum`, so it shows up
+//! in the Ethereum blocks like any other transaction.
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use ferrum_primitives::xcm_primitives::{EnsureProxy, EthereumXcmTransaction, XcmToEthereum};
+use fp_ethereum::ValidatedTransaction;
+use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+use frame_system::pallet_prelude::*;
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use scale_info::TypeInfo;
+use sp_core::{H160, U256};
+use sp_runtime::{traits::UniqueSaturatedInto, RuntimeDebug};
+
+mod mock;
+mod tests;
+pub use module::*;
+
+/// Origin of the Ethereum transactions sent through XCM.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum RawOrigin {
+	/// An Ethereum transaction sent by the given address through XCM.
+	XcmEthereumTransaction(H160),
+}
+
+/// Ensures the origin is an Ethereum transaction sent through XCM, returning its sender.
+pub struct EnsureXcmEthereumTransaction;
+impl<O: Into<Result<RawOrigin, O>> + From<RawOrigin>> EnsureOrigin<O>
+	for EnsureXcmEthereumTransaction
+{
+	type Success = H160;
+
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().map(|o| match o {
+			RawOrigin::XcmEthereumTransaction(source) => source,
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<O, ()> {
+		Ok(O::from(RawOrigin::XcmEthereumTransaction(Default::default())))
+	}
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_evm::Config {
+		/// Applies the Ethereum transactions, usually `pallet_ethereum`.
+		type ValidatedTransaction: ValidatedTransaction;
+
+		/// The origin of the XCM calls, resolving to the sender of the Ethereum transaction.
+		type XcmEthereumOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = H160>;
+
+		/// The weight reserved for the XCMP messages, which bounds the gas limit of a transaction.
+		type ReservedXcmpWeight: Get<Weight>;
+
+		/// Checks the sender may transact on behalf of another account.
+		type EnsureProxy: EnsureProxy<Self::AccountId>;
+	}
+
+	#[pallet::origin]
+	pub type Origin = RawOrigin;
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// the transaction cannot be converted, as for contract creations
+		UnsupportedTransaction,
+		/// the gas limit is above the weight reserved for xcm messages
+		GasLimitTooHigh,
+		/// the sender is not a proxy of the account to transact as
+		NotProxy,
+	}
+
+	/// The nonce of the Ethereum transactions of each XCM sender
+	///
+	/// map H160 => U256
+	#[pallet::storage]
+	#[pallet::getter(fn nonces)]
+	pub type Nonces<T: Config> = StorageMap<_, Blake2_128Concat, H160, U256, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Executes an Ethereum transaction sent by the XCM origin.
+		#[pallet::call_index(0)]
+		#[pallet::weight(Pallet::<T>::transact_weight(xcm_transaction))]
+		pub fn transact(
+			origin: OriginFor<T>,
+			xcm_transaction: EthereumXcmTransaction,
+		) -> DispatchResultWithPostInfo {
+			let source = T::XcmEthereumOrigin::ensure_origin(origin)?;
+			Self::validate_and_apply(source, xcm_transaction)
+		}
+
+		/// Executes an Ethereum transaction on behalf of `transact_as`, which must have set the
+		/// XCM origin as its proxy.
+		#[pallet::call_index(1)]
+		#[pallet::weight(
+			Pallet::<T>::transact_weight(xcm_transaction)
+				.saturating_add(T::DbWeight::get().reads(1))
+		)]
+		pub fn transact_through_proxy(
+			origin: OriginFor<T>,
+			transact_as: H160,
+			xcm_transaction: EthereumXcmTransaction,
+		) -> DispatchResultWithPostInfo {
+			let source = T::XcmEthereumOrigin::ensure_origin(origin)?;
+			T::EnsureProxy::ensure_ok(
+				T::AddressMapping::into_account_id(transact_as),
+				T::AddressMapping::into_account_id(source),
+			)
+			.map_err(|_| Error::<T>::NotProxy)?;
+
+			Self::validate_and_apply(transact_as, xcm_transaction)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn gas_limit(xcm_transaction: &EthereumXcmTransaction) -> U256 {
+		match xcm_transaction {
+			EthereumXcmTransaction::V1(v1_tx) => v1_tx.gas_limit,
+			EthereumXcmTransaction::V2(v2_tx) => v2_tx.gas_limit,
+		}
+	}
+
+	fn transact_weight(xcm_transaction: &EthereumXcmTransaction) -> Weight {
+		<T as pallet_evm::Config>::GasWeightMapping::gas_to_weight(
+			Self::gas_limit(xcm_transaction).unique_saturated_into(),
+			false,
+		)
+		// Nonces
+		.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+	}
+
+	fn validate_and_apply(
+		source: H160,
+		xcm_transaction: EthereumXcmTransaction,
+	) -> DispatchResultWithPostInfo {
+		let max_gas_limit = <T as pallet_evm::Config>::GasWeightMapping::weight_to_gas(
+			T::ReservedXcmpWeight::get(),
+		);
+		ensure!(
+			Self::gas_limit(&xcm_transaction) <= U256::from(max_gas_limit),
+			Error::<T>::GasLimitTooHigh
+		);
+
+		let nonce = Nonces::<T>::get(source);
+		let transaction = xcm_transaction
+			.into_transaction_v2(nonce, T::ChainId::get())
+			.ok_or(Error::<T>::UnsupportedTransaction)?;
+
+		Nonces::<T>::insert(source, nonce.saturating_add(U256::one()));
+
+		let (post_info, _) = T::ValidatedTransaction::apply(source, transaction)?;
+		Ok(post_info)
+	}
+}
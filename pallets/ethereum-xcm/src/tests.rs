@@ -0,0 +1,182 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+#![cfg(test)]
+use super::*;
+use ethereum::TransactionAction;
+use ferrum_primitives::xcm_primitives::{
+	EthereumXcmFee, EthereumXcmTransactionV1, EthereumXcmTransactionV2,
+};
+use frame_support::{assert_noop, assert_ok};
+use mock::*;
+use sp_runtime::traits::BadOrigin;
+
+fn xcm_origin(source: H160) -> RuntimeOrigin {
+	RawOrigin::XcmEthereumTransaction(source).into()
+}
+
+fn transfer_transaction(action: TransactionAction, value: u128) -> EthereumXcmTransaction {
+	EthereumXcmTransaction::V2(EthereumXcmTransactionV2 {
+		gas_limit: U256::from(21_000),
+		action,
+		value: U256::from(value),
+		input: Default::default(),
+		access_list: None,
+	})
+}
+
+fn executed_from(source: H160) -> bool {
+	System::events().iter().any(|record| {
+		matches!(
+			record.event,
+			RuntimeEvent::Ethereum(pallet_ethereum::Event::Executed { from, .. }) if from == source
+		)
+	})
+}
+
+#[test]
+fn transact_executes_the_ethereum_transaction() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(EthereumXcm::transact(
+			xcm_origin(ALICE),
+			transfer_transaction(TransactionAction::Call(CHARLIE), 100)
+		));
+
+		assert!(executed_from(ALICE));
+		assert_eq!(Balances::free_balance(CHARLIE), 100);
+		assert_eq!(Balances::free_balance(ALICE), 999_900);
+	});
+}
+
+#[test]
+fn transact_accepts_v1_transactions() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(EthereumXcm::transact(
+			xcm_origin(ALICE),
+			EthereumXcmTransaction::V1(EthereumXcmTransactionV1 {
+				gas_limit: U256::from(21_000),
+				fee_payment: EthereumXcmFee::Auto,
+				action: TransactionAction::Call(CHARLIE),
+				value: U256::from(100),
+				input: Default::default(),
+				access_list: None,
+			})
+		));
+
+		assert_eq!(Balances::free_balance(CHARLIE), 100);
+	});
+}
+
+#[test]
+fn transact_requires_xcm_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			EthereumXcm::transact(
+				RuntimeOrigin::signed(ALICE),
+				transfer_transaction(TransactionAction::Call(CHARLIE), 100)
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			EthereumXcm::transact(
+				RuntimeOrigin::root(),
+				transfer_transaction(TransactionAction::Call(CHARLIE), 100)
+			),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn nonces_are_tracked_per_sender() {
+	ExtBuilder::default().build().execute_with(|| {
+		for _ in 0..2 {
+			assert_ok!(EthereumXcm::transact(
+				xcm_origin(ALICE),
+				transfer_transaction(TransactionAction::Call(CHARLIE), 1)
+			));
+		}
+		assert_ok!(EthereumXcm::transact(
+			xcm_origin(BOB),
+			transfer_transaction(TransactionAction::Call(CHARLIE), 1)
+		));
+
+		assert_eq!(EthereumXcm::nonces(ALICE), U256::from(2));
+		assert_eq!(EthereumXcm::nonces(BOB), U256::one());
+		assert_eq!(EthereumXcm::nonces(CHARLIE), U256::zero());
+	});
+}
+
+#[test]
+fn transact_rejects_contract_creation() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			EthereumXcm::transact(
+				xcm_origin(ALICE),
+				transfer_transaction(TransactionAction::Create, 0)
+			),
+			Error::<Runtime>::UnsupportedTransaction
+		);
+	});
+}
+
+#[test]
+fn transact_rejects_gas_limit_above_reserved_weight() {
+	ExtBuilder::default().build().execute_with(|| {
+		let transaction = EthereumXcmTransaction::V2(EthereumXcmTransactionV2 {
+			gas_limit: U256::from(1_000_001),
+			action: TransactionAction::Call(CHARLIE),
+			value: U256::zero(),
+			input: Default::default(),
+			access_list: None,
+		});
+
+		assert_noop!(
+			EthereumXcm::transact(xcm_origin(ALICE), transaction),
+			Error::<Runtime>::GasLimitTooHigh
+		);
+	});
+}
+
+#[test]
+fn transact_through_proxy_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(EthereumXcm::transact_through_proxy(
+			xcm_origin(BOB),
+			ALICE,
+			transfer_transaction(TransactionAction::Call(CHARLIE), 100)
+		));
+
+		assert!(executed_from(ALICE));
+		assert_eq!(Balances::free_balance(ALICE), 999_900);
+		assert_eq!(Balances::free_balance(BOB), 1_000_000);
+		assert_eq!(EthereumXcm::nonces(ALICE), U256::one());
+		assert_eq!(EthereumXcm::nonces(BOB), U256::zero());
+	});
+}
+
+#[test]
+fn transact_through_proxy_requires_proxy() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			EthereumXcm::transact_through_proxy(
+				xcm_origin(ALICE),
+				BOB,
+				transfer_transaction(TransactionAction::Call(CHARLIE), 100)
+			),
+			Error::<Runtime>::NotProxy
+		);
+	});
+}
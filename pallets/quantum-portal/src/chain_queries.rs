@@ -15,10 +15,13 @@
 // along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use crate::chain_utils::{ChainRequestError, ChainRequestResult, ChainUtils, JsonSer, ToJson};
-use ethereum::TransactionV2;
-use serde::{Deserialize, Deserializer, Serialize};
-use sp_core::H256;
+use crate::chain_utils::{ChainRequestError, ChainRequestResult, ChainUtils};
+use ethabi_nostd::Address;
+use serde::{
+	ser::{Error as _, SerializeMap},
+	Deserialize, Deserializer, Serialize, Serializer,
+};
+use sp_core::{H256, U256};
 use sp_runtime::{
 	codec::{Decode, Encode},
 	offchain::{http, Duration},
@@ -27,33 +30,127 @@ use sp_std::{prelude::*, str};
 
 const FETCH_TIMEOUT_PERIOD: u64 = 30000; // in milli-seconds
 
-pub fn de_string_list_to_bytes_list<'de, D>(de: D) -> Result<Vec<Vec<u8>>, D::Error>
+pub fn de_string_to_bytes<'de, D>(de: D) -> Result<Vec<u8>, D::Error>
 where
 	D: Deserializer<'de>,
 {
-	let s: Vec<&str> = Deserialize::deserialize(de)?;
-	let list = s.iter().map(|v| v.as_bytes().to_vec()).collect();
-	Ok(list)
+	let s: &str = Deserialize::deserialize(de)?;
+	Ok(s.as_bytes().to_vec())
 }
 
-pub fn de_string_to_bytes<'de, D>(de: D) -> Result<Vec<u8>, D::Error>
+pub fn de_opt_string_to_bytes<'de, D>(de: D) -> Result<Option<Vec<u8>>, D::Error>
 where
 	D: Deserializer<'de>,
 {
-	let s: &str = Deserialize::deserialize(de)?;
-	Ok(s.as_bytes().to_vec())
+	let s: Option<&str> = Deserialize::deserialize(de)?;
+	Ok(s.map(|s| s.as_bytes().to_vec()))
 }
 
 // curl --data
 // '{"method":"eth_chainId","params":[],"id":1,"jsonrpc":"2.0"}' -H "Content-Type: application/json"
 // -X POST localhost:8545
-#[derive(Debug, Deserialize, Serialize, Encode, Decode)]
+#[derive(Debug, Serialize)]
 pub struct JsonRpcRequest {
 	pub id: u32,
-	#[serde(deserialize_with = "de_string_to_bytes")]
-	pub method: Vec<u8>,
-	#[serde(deserialize_with = "de_string_list_to_bytes_list")]
-	pub params: Vec<Vec<u8>>,
+	pub jsonrpc: &'static str,
+	pub method: &'static str,
+	pub params: Vec<JsonRpcParam>,
+}
+
+impl JsonRpcRequest {
+	pub fn new(method: &'static str, params: Vec<JsonRpcParam>) -> Self {
+		JsonRpcRequest { id: 1, jsonrpc: "2.0", method, params }
+	}
+}
+
+/// A JSON-RPC parameter, escaped by `serde_json` when the request is serialized
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonRpcParam {
+	/// A string, which must be valid UTF-8
+	String(Vec<u8>),
+	Number(u64),
+	/// An object, its fields serialized in order
+	Object(Vec<(&'static str, JsonRpcParam)>),
+	Array(Vec<JsonRpcParam>),
+	Block(BlockTag),
+}
+
+impl JsonRpcParam {
+	/// Data as a `0x` prefixed hex string
+	pub fn data(bytes: &[u8]) -> Self {
+		JsonRpcParam::String(ChainUtils::hex_add_0x(ChainUtils::bytes_to_hex(bytes).as_slice()))
+	}
+
+	pub fn address(address: Address) -> Self {
+		JsonRpcParam::String(ChainUtils::address_to_hex(address))
+	}
+
+	pub fn hash(hash: &H256) -> Self {
+		JsonRpcParam::String(ChainUtils::h256_to_hex_0x(hash))
+	}
+
+	/// A number as a hex quantity
+	pub fn quantity(value: &U256) -> Self {
+		JsonRpcParam::String(ChainUtils::u256_to_hex_0x(value))
+	}
+}
+
+impl From<BlockTag> for JsonRpcParam {
+	fn from(block: BlockTag) -> Self {
+		JsonRpcParam::Block(block)
+	}
+}
+
+impl Serialize for JsonRpcParam {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			JsonRpcParam::String(s) =>
+				serializer.serialize_str(str::from_utf8(s).map_err(S::Error::custom)?),
+			JsonRpcParam::Number(n) => serializer.serialize_u64(*n),
+			JsonRpcParam::Object(fields) => {
+				let mut map = serializer.serialize_map(Some(fields.len()))?;
+				for (name, value) in fields {
+					map.serialize_entry(name, value)?;
+				}
+				map.end()
+			},
+			JsonRpcParam::Array(items) => serializer.collect_seq(items),
+			JsonRpcParam::Block(block) => block.serialize(serializer),
+		}
+	}
+}
+
+/// Transaction object of `eth_call` and `eth_estimateGas`, fields that are not set are left out
+#[derive(Debug, Clone, Default)]
+pub struct CallRequest {
+	pub from: Option<Address>,
+	pub to: Address,
+	pub gas: Option<U256>,
+	pub gas_price: Option<U256>,
+	pub value: Option<U256>,
+	pub data: Vec<u8>,
+}
+
+impl From<CallRequest> for JsonRpcParam {
+	fn from(call: CallRequest) -> Self {
+		let mut fields = Vec::new();
+		if let Some(from) = call.from {
+			fields.push(("from", JsonRpcParam::address(from)));
+		}
+		fields.push(("to", JsonRpcParam::address(call.to)));
+		if let Some(gas) = call.gas {
+			fields.push(("gas", JsonRpcParam::quantity(&gas)));
+		}
+		if let Some(gas_price) = call.gas_price {
+			fields.push(("gasPrice", JsonRpcParam::quantity(&gas_price)));
+		}
+		if let Some(value) = call.value {
+			fields.push(("value", JsonRpcParam::quantity(&value)));
+		}
+		fields.push(("data", JsonRpcParam::data(&call.data)));
+		JsonRpcParam::Object(fields)
+	}
 }
 
 #[derive(Deserialize, Encode, Decode)]
@@ -70,6 +167,22 @@ pub struct CallResponse {
 	pub result: Vec<u8>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct JsonRpcErrorData {
+	pub code: i64,
+	#[serde(deserialize_with = "de_string_to_bytes")]
+	pub message: Vec<u8>,
+}
+
+/// Response of a call that is allowed to fail, such as simulating a transaction
+#[derive(Debug, Deserialize)]
+pub struct FallibleCallResponse {
+	#[serde(default, deserialize_with = "de_opt_string_to_bytes")]
+	pub result: Option<Vec<u8>>,
+	#[serde(default)]
+	pub error: Option<JsonRpcErrorData>,
+}
+
 #[allow(dead_code)]
 pub enum TransactionStatus {
 	NotFound,
@@ -78,81 +191,30 @@ pub enum TransactionStatus {
 	Failed,
 }
 
-impl ToJson for TransactionV2 {
-	type BaseType = TransactionV2;
-	fn to_json(&self) -> Vec<u8> {
-		let mut j = JsonSer::new();
-		let j = match self {
-			TransactionV2::Legacy(tx) => j
-				.start()
-				.string(
-					"nonce",
-					str::from_utf8(ChainUtils::u256_to_hex_0x(&tx.nonce).as_slice()).unwrap(),
-				)
-				.string(
-					"gas_price",
-					str::from_utf8(ChainUtils::u256_to_hex_0x(&tx.gas_price).as_slice()).unwrap(),
-				)
-				.string(
-					"gas_limit",
-					str::from_utf8(ChainUtils::u256_to_hex_0x(&tx.gas_limit).as_slice()).unwrap(),
-				)
-				// .string("action",
-				// 		str::from_utf8(ChainUtils::u256_to_hex_0x(&tx.action).as_slice()).unwrap())
-				.string(
-					"value",
-					str::from_utf8(ChainUtils::u256_to_hex_0x(&tx.value).as_slice()).unwrap(),
-				)
-				.string("input", str::from_utf8(&tx.input).unwrap())
-				.val(
-					"signature",
-					str::from_utf8(
-						JsonSer::new()
-							.start()
-							.string(
-								"r",
-								str::from_utf8(
-									ChainUtils::h256_to_hex_0x(tx.signature.r()).as_slice(),
-								)
-								.unwrap(),
-							)
-							.string(
-								"s",
-								str::from_utf8(
-									ChainUtils::h256_to_hex_0x(tx.signature.s()).as_slice(),
-								)
-								.unwrap(),
-							)
-							.num("v", tx.signature.v())
-							.end()
-							.to_vec()
-							.as_slice(),
-					)
-					.unwrap(),
-				)
-				.end()
-				.to_vec(),
-			TransactionV2::EIP1559(_) => Vec::new(),
-			TransactionV2::EIP2930(_) => Vec::new(),
-		};
-		j
+/// The block to run a state read against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockTag {
+	Latest,
+	Number(u64),
+}
+
+impl Serialize for BlockTag {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			BlockTag::Latest => serializer.serialize_str("latest"),
+			BlockTag::Number(number) => serializer.serialize_str(
+				str::from_utf8(ChainUtils::u64_to_hex_0x(*number).as_slice())
+					.map_err(S::Error::custom)?,
+			),
+		}
 	}
 }
 
 fn fetch_json_rpc_body(base_url: &str, req: &JsonRpcRequest) -> Result<Vec<u8>, ChainRequestError> {
-	let mut params = JsonSer::new();
-	req.params.iter().for_each(|p| {
-		params.arr_val(str::from_utf8(p.as_slice()).unwrap());
-	});
-	let mut json_req = JsonSer::new();
-	let json_req_s = json_req
-		.start()
-		.num("id", req.id as u64)
-		.string("method", str::from_utf8(&req.method).unwrap())
-		.string("jsonrpc", "2.0")
-		.arr("params", str::from_utf8(params.to_vec().as_slice()).unwrap())
-		.end()
-		.to_vec();
+	let json_req_s = serde_json::to_vec(req).map_err(|e| {
+		log::error!("Error while serializing json {:?}", e);
+		ChainRequestError::ConversionError
+	})?;
 	let json_req_str = str::from_utf8(&json_req_s).unwrap();
 	log::info!("About to submit {}", json_req_str);
 	let request: http::Request<Vec<&[u8]>> =
@@ -276,7 +338,7 @@ impl ChainQueries {
 	#[allow(dead_code)]
 	pub fn chain_id(url: &str) -> Result<u32, ChainRequestError> {
 		log::info!("About to get chain_id {}", url);
-		let req = JsonRpcRequest { id: 1, params: Vec::new(), method: b"eth_chainId".to_vec() };
+		let req = JsonRpcRequest::new("eth_chainId", Vec::new());
 		// log::info!("Have request {:?}", &req);
 		let res: Box<GetChainIdResponse> = fetch_json_rpc(url, &req)?;
 		log::info!("Result is {:?}", &res);
@@ -284,37 +346,51 @@ impl ChainQueries {
 		Ok(chain_id as u32)
 	}
 
+	pub fn block_number(url: &str) -> Result<u64, ChainRequestError> {
+		let req = JsonRpcRequest::new("eth_blockNumber", Vec::new());
+		let res: Box<CallResponse> = fetch_json_rpc(url, &req)?;
+		ChainUtils::hex_to_u64(&res.result)
+	}
+
 	pub fn get_transaction_receipt(
 		url: &str,
 		tx_id: &H256,
 	) -> ChainRequestResult<Option<GetTransactionReceiptResponseData>> {
 		log::info!("TX_ID is: {:?}", &tx_id.0);
-		let tx_id = ChainUtils::h256_to_hex_0x(tx_id);
-		log::info!(
-			"About to get eth_getTransactionReceipt {}: {}",
-			url,
-			str::from_utf8(tx_id.as_slice()).unwrap()
-		);
+		log::info!("About to get eth_getTransactionReceipt {}: {:?}", url, tx_id);
 
-		let req = JsonRpcRequest {
-			id: 1,
-			params: vec![ChainUtils::wrap_in_quotes(tx_id.as_slice()).to_vec()],
-			method: b"eth_getTransactionReceipt".to_vec(),
-		};
+		let req = JsonRpcRequest::new("eth_getTransactionReceipt", vec![JsonRpcParam::hash(tx_id)]);
 		// log::info!("Have request {:?}", &req);
 		let res: Box<GetTransactionReceiptResponse> = fetch_json_rpc(url, &req)?;
 		log::info!("Result is {:?}", &res);
 		Ok(res.result)
 	}
 
+	/// Returns the status of the transaction, a mined transaction is only reported as confirmed
+	/// or failed once it is at least `confirmations` blocks behind the chain head, until then a
+	/// reorg can still drop it and it is reported as pending
 	pub fn get_transaction_status(
 		url: &str,
 		tx_id: &H256,
+		confirmations: u64,
 	) -> ChainRequestResult<TransactionStatus> {
 		let rv = Self::get_transaction_receipt(url, tx_id)?;
 		let res = match rv {
 			None => TransactionStatus::NotFound,
 			Some(tx) => {
+				if confirmations > 0 {
+					let tx_block = ChainUtils::hex_to_u64(tx.blockNumber.as_slice())?;
+					let head = Self::block_number(url)?;
+					if head.saturating_sub(tx_block) < confirmations {
+						log::info!(
+							"Transaction mined at {} has {} of {} confirmations",
+							tx_block,
+							head.saturating_sub(tx_block),
+							confirmations
+						);
+						return Ok(TransactionStatus::Pending);
+					}
+				}
 				let status = ChainUtils::hex_to_u64(tx.status.as_slice())?;
 				if status == 1 {
 					TransactionStatus::Confirmed
@@ -326,3 +402,67 @@ impl ChainQueries {
 		Ok(res)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::chain_queries::{BlockTag, CallRequest, JsonRpcParam, JsonRpcRequest};
+	use ethabi_nostd::Address;
+	use sp_core::U256;
+
+	fn to_json(req: &JsonRpcRequest) -> String {
+		serde_json::to_string(req).unwrap()
+	}
+
+	#[test]
+	fn serializes_typed_params() {
+		let req = JsonRpcRequest::new(
+			"eth_getTransactionCount",
+			vec![JsonRpcParam::address(Address::repeat_byte(0xab)), BlockTag::Number(0x1b4).into()],
+		);
+		assert_eq!(
+			to_json(&req),
+			r#"{"id":1,"jsonrpc":"2.0","method":"eth_getTransactionCount","params":["0xabababababababababababababababababababab","0x1b4"]}"#
+		);
+
+		let req = JsonRpcRequest::new(
+			"test",
+			vec![
+				JsonRpcParam::Number(7),
+				JsonRpcParam::Array(vec![BlockTag::Latest.into(), JsonRpcParam::data(&[])]),
+			],
+		);
+		assert_eq!(
+			to_json(&req),
+			r#"{"id":1,"jsonrpc":"2.0","method":"test","params":[7,["latest","0x"]]}"#
+		);
+	}
+
+	#[test]
+	fn escapes_strings() {
+		let req = JsonRpcRequest::new(
+			"test",
+			vec![JsonRpcParam::Object(vec![("key", JsonRpcParam::String(b"a\"b\\c\n".to_vec()))])],
+		);
+		assert_eq!(
+			to_json(&req),
+			r#"{"id":1,"jsonrpc":"2.0","method":"test","params":[{"key":"a\"b\\c\n"}]}"#
+		);
+
+		let invalid = JsonRpcRequest::new("test", vec![JsonRpcParam::String(vec![0xff])]);
+		assert!(serde_json::to_string(&invalid).is_err());
+	}
+
+	#[test]
+	fn call_request_leaves_out_unset_fields() {
+		let call = CallRequest {
+			to: Address::repeat_byte(1),
+			value: Some(U256::from(16)),
+			data: vec![0xde, 0xad],
+			..Default::default()
+		};
+		assert_eq!(
+			serde_json::to_string(&JsonRpcParam::from(call)).unwrap(),
+			r#"{"to":"0x0101010101010101010101010101010101010101","value":"0x10","data":"0xdead"}"#
+		);
+	}
+}
@@ -14,12 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
 use ethereum::{LegacyTransaction, LegacyTransactionMessage, TransactionSignature};
-use parity_scale_codec::Encode;
 
 pub struct ChainUtils;
 use ethabi_nostd::{Address, H256, U256};
 
-use numtoa::NumToA;
 use sp_std::{prelude::*, str};
 use tiny_keccak::{Hasher, Keccak};
 
@@ -34,6 +32,10 @@ pub enum ChainRequestError {
 	InvalidHexCharacter,
 	SlotNotAvailable,
 	MinedBlockVerificationError,
+	GasPriceAboveCap,
+	SpendBudgetExceeded,
+	AbiEncodingError,
+	CallReverted,
 }
 
 #[derive(Debug, PartialEq)]
@@ -43,6 +45,7 @@ pub enum TransactionCreationError {
 	SignatureError,
 	MultisigError,
 	CannotFindContractAddress,
+	SubmissionFailed,
 }
 
 impl From<&[u8]> for ChainRequestError {
@@ -57,25 +60,8 @@ impl From<TransactionCreationError> for ChainRequestError {
 	}
 }
 
-pub trait ToJson {
-	type BaseType;
-	fn to_json(&self) -> Vec<u8>;
-}
-
 pub type ChainRequestResult<T> = Result<T, ChainRequestError>;
 
-fn u64_to_str(num: u64) -> Vec<u8> {
-	let mut num_buffer = [0u8; 20];
-	num.numtoa_str(10, &mut num_buffer);
-	log::info!("num2str1 : {:?}", num_buffer);
-	let mut s: Vec<u8> = Vec::new();
-	num_buffer.into_iter().filter(|u| *u != 0).for_each(|u| {
-		s.push(u);
-	});
-	log::info!("num2str2 : {:?}", s);
-	s
-}
-
 fn val(c: u8) -> Result<u8, ChainRequestError> {
 	match c {
 		b'A'..=b'F' => Ok(c - b'A' + 10),
@@ -157,14 +143,6 @@ impl ChainUtils {
 		Address::from_slice(&addr_bytes)
 	}
 
-	pub fn decode_address_response(hex: &[u8]) -> Address {
-		let truncated_hex = &hex[hex.len() - 40..];
-		log::info!("length looking for {:?}", truncated_hex.len());
-		let mut addr_bytes: [u8; 20] = [0; 20];
-		hex::decode_to_slice(truncated_hex, &mut addr_bytes).unwrap();
-		Address::from_slice(&addr_bytes)
-	}
-
 	pub fn hex_to_ecdsa_pub_key(hex: &[u8]) -> sp_core::ecdsa::Public {
 		let mut addr_bytes: [u8; 33] = [0; 33];
 		hex::decode_to_slice(hex, &mut addr_bytes).unwrap();
@@ -188,32 +166,22 @@ impl ChainUtils {
 		Ok(if s[0] == b'0' && s[1] == b'x' { &s[2..] } else { s })
 	}
 
-	pub fn wrap_in_quotes(s: &[u8]) -> Vec<u8> {
-		let mut zx = vec![b'"'];
-		zx.extend(s);
-		zx.extend(vec![b'"']);
-		zx
+	/// Formats a number as a JSON-RPC quantity, hex encoded without leading zeros
+	pub fn u256_to_hex_0x(i: &U256) -> Vec<u8> {
+		let mut be_bytes = [0u8; 32];
+		i.to_big_endian(&mut be_bytes);
+		Self::quantity_to_hex_0x(&be_bytes)
 	}
 
-	pub fn u256_to_hex_0x(i: &U256) -> Vec<u8> {
-		let fmted = i.encode();
-		let mut non_zero: bool = false;
-		let fmted: Vec<u8> = fmted
-			.into_iter()
-			.filter(|u| {
-				let u = *u;
-				if u != 0 {
-					non_zero = true;
-				}
-				non_zero || u != 0
-			})
-			.collect();
-		if fmted.is_empty() {
-			return vec![b'0', b'x', b'0'];
-		}
-		let mut zx = vec![b'0', b'x'];
-		zx.extend(fmted.into_iter().map(|i| i + b'0'));
-		zx
+	/// Same as `u256_to_hex_0x` for a u64
+	pub fn u64_to_hex_0x(i: u64) -> Vec<u8> {
+		Self::quantity_to_hex_0x(&i.to_be_bytes())
+	}
+
+	fn quantity_to_hex_0x(be_bytes: &[u8]) -> Vec<u8> {
+		let hex = Self::bytes_to_hex(be_bytes);
+		let first_non_zero = hex.iter().position(|c| *c != b'0').unwrap_or(hex.len() - 1);
+		Self::hex_add_0x(&hex[first_non_zero..])
 	}
 
 	pub fn h256_to_hex_0x(i: &H256) -> Vec<u8> {
@@ -275,6 +243,21 @@ impl ChainUtils {
 		Vec::from(&signed[12..32])
 	}
 
+	/// Recovers the ethereum address that signed the prehashed `hash`. The signature is
+	/// `r || s || v`, where `v` is either the raw recovery id or `27 + id`. Anything after the
+	/// 65th byte (the multisig padding) is ignored.
+	pub fn recover_eth_address(hash: &H256, signature: &[u8]) -> Option<Address> {
+		let mut sig = [0u8; 65];
+		sig.copy_from_slice(signature.get(..65)?);
+		sig[64] = match sig[64] {
+			0 | 1 => sig[64],
+			27 | 28 => sig[64] - 27,
+			_ => return None,
+		};
+		let pk = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &hash.0).ok()?;
+		Some(Address::from_slice(&Self::eth_address_from_public_key(&pk)))
+	}
+
 	#[allow(dead_code)]
 	pub fn keccack(msg: &[u8]) -> H256 {
 		let mut buf: [u8; 32] = [0; 32];
@@ -285,126 +268,24 @@ impl ChainUtils {
 	}
 }
 
-pub struct JsonSer {
-	buff: Vec<u8>,
-	empty: bool,
-}
-
-impl JsonSer {
-	pub fn new() -> Self {
-		JsonSer { buff: Vec::new(), empty: true }
-	}
-
-	pub fn start(&mut self) -> &mut Self {
-		if !self.buff.is_empty() {
-			panic!("JSON already started")
-		}
-		self.buff.push(b'{');
-		self
-	}
-
-	pub fn end(&mut self) -> &mut Self {
-		self.buff.push(b'}');
-		self
-	}
-
-	pub fn comma(&mut self) -> &mut Self {
-		if !self.empty {
-			self.buff.push(b',');
-		}
-		self
-	}
-
-	pub fn name(&mut self, name: &str) -> &mut Self {
-		self.comma();
-		self.buff.push(b'"');
-		name.as_bytes().iter().for_each(|b| self.buff.push(*b));
-		self.buff.push(b'"');
-		self.buff.push(b':');
-		self
-	}
-
-	#[allow(dead_code)]
-	pub fn arr_string(&mut self, val: &str) -> &mut Self {
-		self.comma();
-		self.buff.push(b'"');
-		val.as_bytes().iter().for_each(|b| self.buff.push(*b));
-		self.buff.push(b'"');
-		self.empty = false;
-		self
-	}
-
-	pub fn arr_val(&mut self, val: &str) -> &mut Self {
-		self.comma();
-		val.as_bytes().iter().for_each(|b| self.buff.push(*b));
-		self.empty = false;
-		self
-	}
-
-	pub fn string(&mut self, name: &str, val: &str) -> &mut Self {
-		self.name(name);
-		self.buff.push(b'"');
-		val.as_bytes().iter().for_each(|b| self.buff.push(*b));
-		self.buff.push(b'"');
-		self.empty = false;
-		self
-	}
-
-	#[allow(dead_code)]
-	pub fn u256(&mut self, name: &str, value: &U256) -> &mut Self {
-		self.string(name, str::from_utf8(ChainUtils::u256_to_hex_0x(value).as_slice()).unwrap())
-	}
-
-	pub fn num(&mut self, name: &str, val: u64) -> &mut Self {
-		let v = u64_to_str(val);
-		self.val(name, str::from_utf8(v.as_slice()).unwrap())
-	}
-
-	pub fn val(&mut self, name: &str, val: &str) -> &mut Self {
-		self.name(name);
-		val.as_bytes().iter().for_each(|b| self.buff.push(*b));
-		self.empty = false;
-		self
-	}
-
-	pub fn arr(&mut self, name: &str, val: &str) -> &mut Self {
-		self.name(name);
-		self.arr_start();
-		val.as_bytes().iter().for_each(|b| self.buff.push(*b));
-		self.arr_end();
-		self.empty = false;
-		self
-	}
-
-	pub fn arr_start(&mut self) -> &mut Self {
-		self.buff.push(b'[');
-		self.empty = true;
-		self
-	}
-
-	pub fn arr_end(&mut self) -> &mut Self {
-		self.buff.push(b']');
-		self.empty = false;
-		self
-	}
-
-	pub fn to_vec(&self) -> Vec<u8> {
-		self.buff.clone()
-	}
-}
-
 #[cfg(test)]
 mod tests {
-	use crate::chain_utils::{ChainUtils, JsonSer};
-	use sp_std::str;
+	use crate::chain_utils::ChainUtils;
+	use ethabi_nostd::{Address, U256};
+	use sp_core::{ecdsa, Pair};
 
 	#[test]
-	fn jsonify_num() {
-		let jo = JsonSer::new().start().num("id", 1).end().to_vec();
-		log::info!("Jos is {:?}", jo);
-		println!("Jo is {jo:?}");
-		let jos = str::from_utf8(jo.as_slice());
-		println!("Jos is {}", jos.unwrap());
+	fn u64_to_hex_quantity() {
+		assert_eq!(ChainUtils::u64_to_hex_0x(0), b"0x0".to_vec());
+		assert_eq!(ChainUtils::u64_to_hex_0x(1), b"0x1".to_vec());
+		assert_eq!(ChainUtils::u64_to_hex_0x(0x1b4), b"0x1b4".to_vec());
+		assert_eq!(ChainUtils::u64_to_hex_0x(u64::MAX), b"0xffffffffffffffff".to_vec());
+		assert_eq!(ChainUtils::u256_to_hex_0x(&U256::zero()), b"0x0".to_vec());
+		assert_eq!(ChainUtils::u256_to_hex_0x(&U256::from(1_000_000)), b"0xf4240".to_vec());
+		assert_eq!(
+			ChainUtils::u256_to_hex_0x(&(U256::one() << 128)),
+			b"0x100000000000000000000000000000000".to_vec()
+		);
 	}
 
 	#[test]
@@ -427,4 +308,26 @@ mod tests {
 		let addrh = hex::encode(addr.as_slice());
 		assert_eq!("1458e7bde6e509e4f8c122642bd61629aa46fa7c", addrh);
 	}
+
+	#[test]
+	fn recover_eth_address_from_signature() {
+		let pair = ecdsa::Pair::from_seed(&[7u8; 32]);
+		let signer =
+			Address::from_slice(&ChainUtils::eth_address_from_public_key(&pair.public().0));
+		let hash = ChainUtils::keccack(b"quantum portal");
+		let sig = pair.sign_prehashed(&hash.0);
+
+		// multisig format, r || s || 27 + v padded to 96 bytes
+		let mut multisig = sig.0[..64].to_vec();
+		multisig.push(27 + sig.0[64]);
+		multisig.extend([0u8; 31]);
+
+		assert_eq!(ChainUtils::recover_eth_address(&hash, &sig.0), Some(signer));
+		assert_eq!(ChainUtils::recover_eth_address(&hash, &multisig), Some(signer));
+		assert_ne!(
+			ChainUtils::recover_eth_address(&ChainUtils::keccack(b"other"), &multisig),
+			Some(signer)
+		);
+		assert_eq!(ChainUtils::recover_eth_address(&hash, &multisig[..64]), None);
+	}
 }
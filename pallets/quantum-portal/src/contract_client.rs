@@ -14,8 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
 use crate::{
-	chain_queries::{fetch_json_rpc, CallResponse, JsonRpcRequest},
-	chain_utils::{ChainRequestError, ChainUtils, JsonSer, TransactionCreationError},
+	chain_queries::{
+		fetch_json_rpc, BlockTag, CallRequest, CallResponse, ChainQueries, FallibleCallResponse,
+		JsonRpcParam, JsonRpcRequest,
+	},
+	chain_utils::{ChainRequestError, ChainRequestResult, ChainUtils, TransactionCreationError},
+	qp_contracts::{authority_mgr, gateway, ledger_mgr, miner_mgr, ContractCall},
+	qp_types::{DryRunRecord, FinalizerSignaturePayload},
 };
 use ethabi_nostd::{encoder, Address, Token};
 use ethereum::{LegacyTransaction, TransactionAction};
@@ -25,17 +30,41 @@ use rlp::Encodable;
 use serde::Deserialize;
 use sp_core::{ecdsa, H160, H256, U256};
 use sp_io::crypto;
+use sp_runtime::offchain::{
+	storage::{MutateStorageError, StorageRetrievalError, StorageValueRef},
+	storage_lock::{StorageLock, Time},
+};
 use sp_std::{
 	ops::{Div, Mul},
 	prelude::*,
 	str,
 };
 
+/// Length of the rolling window the spend budget applies to
+const SPEND_WINDOW_MILLIS: u64 = 24 * 3600 * 1000;
+
+/// Limits applied to every transaction the client sends
+#[derive(Debug, Clone, Default)]
+pub struct GasLimits {
+	/// Percentage applied to the `eth_estimateGas` result, 100 uses the raw estimate and lower
+	/// values are rejected when the config is loaded
+	pub gas_limit_multiplier: u32,
+	/// Highest gas price the client will pay, zero disables the cap
+	pub max_gas_price: U256,
+	/// Wei the client may spend in a rolling day, zero disables the budget
+	pub daily_spend_budget: U256,
+}
+
 #[derive(Debug, Clone)]
 pub struct ContractClient {
 	pub http_api: Vec<u8>,
 	pub gateway_contract_address: Address,
 	pub chain_id: u64,
+	pub confirmations: u64,
+	pub gas_limits: GasLimits,
+	pub dry_run: bool,
+	// Multicall3 contract batched reads go through, if the network has one
+	pub multicall_address: Option<Address>,
 	pub ledger_manager_address: Option<Address>,
 	pub authority_manager_address: Option<Address>,
 	pub miner_manager_address: Option<Address>,
@@ -90,11 +119,23 @@ impl From<ecdsa::Public> for ContractClientSignature {
 }
 
 impl ContractClient {
-	pub fn new(http_api: Vec<u8>, gateway_contract_address: &Address, chain_id: u64) -> Self {
+	pub fn new(
+		http_api: Vec<u8>,
+		gateway_contract_address: &Address,
+		chain_id: u64,
+		confirmations: u64,
+		gas_limits: GasLimits,
+		dry_run: bool,
+		multicall_address: Option<Address>,
+	) -> Self {
 		ContractClient {
 			http_api,
 			gateway_contract_address: *gateway_contract_address,
 			chain_id,
+			confirmations,
+			gas_limits,
+			dry_run,
+			multicall_address,
 			ledger_manager_address: None,
 			authority_manager_address: None,
 			miner_manager_address: None,
@@ -103,11 +144,11 @@ impl ContractClient {
 
 	pub fn get_ledger_manager_address(&self) -> Result<H160, ChainRequestError> {
 		// no cache, we fetch from the gateway contract
-		let signature = b"quantumPortalLedgerMgr()";
-		let res: Box<CallResponse> =
-			self.call(signature, &[], Some(self.gateway_contract_address))?;
-		log::info!("Ledger manager response is : {:?}", res);
-		let address = ChainUtils::decode_address_response(res.result.as_slice());
+		let address = self.query(
+			&gateway::QuantumPortalLedgerMgr,
+			Some(self.gateway_contract_address),
+			BlockTag::Latest,
+		)?;
 		log::info!("Ledger manager address is : {:?}", address);
 
 		Ok(address)
@@ -117,23 +158,17 @@ impl ContractClient {
 		let ledger_manager_address = self.get_ledger_manager_address()?;
 
 		// no cache, we fetch from the gateway contract
-		let signature = b"minerMgr()";
-		let res: Box<CallResponse> = self.call(signature, &[], Some(ledger_manager_address))?;
-		log::info!("Miner manager response is : {:?}", res);
-		let address = ChainUtils::decode_address_response(res.result.as_slice());
+		let address =
+			self.query(&ledger_mgr::MinerMgr, Some(ledger_manager_address), BlockTag::Latest)?;
 		log::info!("Miner manager address is : {:?}", address);
 
-		let signature = b"VERSION()";
-		let res: Box<CallResponse> = self.call(signature, &[], Some(address))?;
-		let version = &res.result.as_slice();
+		let version = self.query(&miner_mgr::Version, Some(address), BlockTag::Latest)?;
 		log::info!("Miner manager version is : {:?}", version);
 
-		let signature = b"NAME()";
-		let res: Box<CallResponse> = self.call(signature, &[], Some(address))?;
-		let name = &res.result.as_slice();
+		let name = self.query(&miner_mgr::Name, Some(address), BlockTag::Latest)?;
 		log::info!("Miner manager name is : {:?}", name);
 
-		Ok((address, version.to_vec(), name.to_vec()))
+		Ok((address, version, name))
 	}
 
 	pub fn get_authority_manager_address(
@@ -142,23 +177,17 @@ impl ContractClient {
 		let ledger_manager_address = self.get_ledger_manager_address()?;
 
 		// no cache, we fetch from the gateway contract
-		let signature = b"authorityMgr()";
-		let res: Box<CallResponse> = self.call(signature, &[], Some(ledger_manager_address))?;
-		log::info!("Authority manager response is : {:?}", res);
-		let address = ChainUtils::decode_address_response(res.result.as_slice());
+		let address =
+			self.query(&ledger_mgr::AuthorityMgr, Some(ledger_manager_address), BlockTag::Latest)?;
 		log::info!("Authority manager address is : {:?}", address);
 
-		let signature = b"VERSION()";
-		let res: Box<CallResponse> = self.call(signature, &[], Some(address))?;
-		let version = &res.result.as_slice();
+		let version = self.query(&authority_mgr::Version, Some(address), BlockTag::Latest)?;
 		log::info!("Authority manager version is : {:?}", version);
 
-		let signature = b"NAME()";
-		let res: Box<CallResponse> = self.call(signature, &[], Some(address))?;
-		let name = &res.result.as_slice();
+		let name = self.query(&authority_mgr::Name, Some(address), BlockTag::Latest)?;
 		log::info!("Authority manager name is : {:?}", name);
 
-		Ok((address, version.to_vec(), name.to_vec()))
+		Ok((address, version, name))
 	}
 
 	pub fn get_miner_for_block(
@@ -173,65 +202,48 @@ impl ContractClient {
 		log::info!("get_miner_for_block : block_timestamp is : {:?}", block_timestamp);
 		log::info!("get_miner_for_block : chain_timestamp is : {:?}", chain_timestamp);
 
-		// no cache, we fetch from the gateway contract
-		let signature = b"findMinerAtTime(bytes32,uint256,uint256)";
-		let res: Box<CallResponse> = self.call(
-			signature,
-			&[
-				Token::FixedBytes(block_hash.as_ref().to_vec()),
-				Token::Uint(U256::from(block_timestamp)),
-				Token::Uint(U256::from(chain_timestamp)),
-			],
+		let address = self.query(
+			&miner_mgr::FindMinerAtTime { block_hash, block_timestamp, chain_timestamp },
 			Some(miner_manager_address.0),
+			BlockTag::Latest,
 		)?;
-		log::info!("Miner manager response is : {:?}", res);
-		let address = ChainUtils::decode_address_response(res.result.as_slice());
 		log::info!("Selected miner address is : {:?}", address);
 
 		Ok(address)
 	}
 
+	/// Calls a contract function through its typed binding and decodes the result. As with
+	/// `call`, a missing address targets the ledger manager
+	pub fn query<C: ContractCall>(
+		&self,
+		call: &C,
+		address: Option<Address>,
+		block: BlockTag,
+	) -> Result<C::Output, ChainRequestError> {
+		let res: Box<CallResponse> = self.call(C::SIGNATURE, &call.inputs(), address, block)?;
+		C::decode_output(res.result.as_slice())
+	}
+
 	pub fn call<T>(
 		&self,
 		method_signature: &[u8],
 		inputs: &[Token],
 		address: Option<Address>,
+		block: BlockTag,
 	) -> Result<Box<T>, ChainRequestError>
 	where
 		T: for<'de> Deserialize<'de>,
 	{
 		log::info!("CALL : method_signature {:?}", method_signature);
 		log::info!("CALL : inputs {:?}", inputs);
-		let encoded_bytes = encoder::encode_function_u8(method_signature, inputs);
-		let encoded_bytes_0x = ChainUtils::bytes_to_hex(encoded_bytes.as_slice());
-		let encoded_bytes_slice = encoded_bytes_0x.as_slice();
-		let encoded_bytes_slice = ChainUtils::hex_add_0x(encoded_bytes_slice);
-
-		let encoded = str::from_utf8(encoded_bytes_slice.as_slice()).unwrap();
-		log::info!("encoded {}", encoded);
+		let encoded_bytes = encoder::encode_function_u8(method_signature, inputs)
+			.map_err(|_| ChainRequestError::AbiEncodingError)?;
 		let contract_address =
 			if let Some(address) = address { address } else { self.get_ledger_manager_address()? };
 
-		log::info!(
-			"contract address is {}",
-			str::from_utf8(ChainUtils::address_to_hex(contract_address).as_slice()).unwrap()
-		);
-		let call_json = JsonSer::new()
-			.start()
-			.string("data", encoded)
-			.string(
-				"to",
-				str::from_utf8(ChainUtils::address_to_hex(contract_address).as_slice()).unwrap(),
-			)
-			.end()
-			.to_vec();
-
-		log::info!("call_json is {}", str::from_utf8(&call_json).unwrap());
-		let req = JsonRpcRequest {
-			id: 1,
-			params: Vec::from([call_json, Vec::from("\"latest\"".as_bytes())]),
-			method: b"eth_call".to_vec(),
-		};
+		log::info!("contract address is {:?}", contract_address);
+		let call = CallRequest { to: contract_address, data: encoded_bytes, ..Default::default() };
+		let req = JsonRpcRequest::new("eth_call", vec![call.into(), block.into()]);
 		log::info!("Have request {:?}", str::from_utf8(method_signature).unwrap());
 		let http_api = str::from_utf8(&self.http_api[..]).unwrap();
 		fetch_json_rpc(http_api, &req)
@@ -251,25 +263,96 @@ impl ContractClient {
 		signing: &ContractClientSignature,
 		recipient_address: Address,
 	) -> Result<H256, ChainRequestError> {
-		let encoded_bytes = encoder::encode_function_u8(method_signature, inputs);
-		let encoded_bytes_0x = ChainUtils::bytes_to_hex(encoded_bytes.as_slice());
-		let encoded_bytes_slice = encoded_bytes_0x.as_slice();
-		let encoded_bytes_slice = ChainUtils::hex_add_0x(encoded_bytes_slice);
+		let encoded_bytes = encoder::encode_function_u8(method_signature, inputs)
+			.map_err(|_| ChainRequestError::AbiEncodingError)?;
+		let now = sp_io::offchain::timestamp().unix_millis();
+		if self.dry_run {
+			// A reverting call also fails gas estimation, simulate first so it is still recorded
+			return self.simulate(
+				method_signature,
+				inputs,
+				encoded_bytes,
+				gas_limit,
+				gas_price,
+				value,
+				nonce,
+				from,
+				signing,
+				recipient_address,
+				now,
+			);
+		}
+
+		let tx = self.prepare_transaction(
+			encoded_bytes,
+			gas_limit,
+			gas_price,
+			value,
+			nonce,
+			from,
+			signing,
+			recipient_address,
+		)?;
+		let max_cost = tx.gas_limit.saturating_mul(tx.gas_price).saturating_add(value);
+		self.reserve_spend(now, max_cost)?;
 
+		let raw_tx = tx.rlp_bytes();
+		let req = JsonRpcRequest::new("eth_sendRawTransaction", vec![JsonRpcParam::data(&raw_tx)]);
+		// log::info!("Have request {:?}", &req);
+		let http_api = str::from_utf8(&self.http_api[..]).unwrap();
+		let sent = fetch_json_rpc::<FallibleCallResponse>(http_api, &req);
+		log::info!("Have response {:?}", &sent);
+		// A transaction rejected by the node cannot spend, give the reservation back. It is kept
+		// when the outcome is unknown, as the transaction may have been broadcast anyway.
+		if is_rejected_by_node(&sent) {
+			self.release_spend(now, max_cost);
+		}
+		match *sent? {
+			FallibleCallResponse { error: Some(error), .. } =>
+				Err(ChainRequestError::JsonRpcError(error.message)),
+			FallibleCallResponse { result: Some(result), .. } =>
+				Ok(H256::from_slice(ChainUtils::hex_to_bytes(result.as_slice())?.as_slice())),
+			FallibleCallResponse { result: None, error: None } =>
+				Err(ChainRequestError::BadRemoteData),
+		}
+	}
+
+	/// Fills in the nonce, gas limit and gas price that were not given and signs the transaction
+	#[allow(clippy::too_many_arguments)]
+	fn prepare_transaction(
+		&self,
+		encoded_bytes: Vec<u8>,
+		gas_limit: Option<U256>,
+		gas_price: Option<U256>,
+		value: U256,
+		nonce: Option<U256>,
+		from: Address,
+		signing: &ContractClientSignature,
+		recipient_address: Address,
+	) -> Result<LegacyTransaction, ChainRequestError> {
 		let nonce_val = match nonce {
 			None => self.nonce(from)?,
 			Some(v) => v,
 		};
 		let gas_limit_val = match gas_limit {
 			None => {
-				self.estimate_gas(encoded_bytes_slice.as_slice(), &value, from, recipient_address)?
+				let estimate =
+					self.estimate_gas(encoded_bytes.as_slice(), &value, from, recipient_address)?;
+				self.apply_gas_limit_multiplier(estimate)
 			},
 			Some(v) => v,
 		};
 		let gas_price_val = match gas_price {
-			None => self.gas_price()?.mul(U256::from(125_u32)).div(U256::from(100_u32)),
-			Some(v) => v,
+			None => self.capped_gas_price(self.gas_price()?)?,
+			Some(v) => {
+				let max_gas_price = self.gas_limits.max_gas_price;
+				if !max_gas_price.is_zero() && v > max_gas_price {
+					return Err(ChainRequestError::GasPriceAboveCap);
+				}
+				v
+			},
 		};
+
 		let mut tx = LegacyTransaction {
 			nonce: nonce_val,
 			gas_price: gas_price_val,
@@ -283,32 +366,245 @@ impl ContractClient {
 		let sig_bytes: ecdsa::Signature = signing.signer(&hash)?;
 		let sig = ChainUtils::decode_transaction_signature(&sig_bytes.0, self.chain_id)?;
 		tx.signature = sig;
+		Ok(tx)
+	}
 
-		let raw_tx = tx.rlp_bytes();
-		let hex_tx = ChainUtils::bytes_to_hex(&raw_tx);
-		let hex_tx_fmtd =
-			ChainUtils::wrap_in_quotes(ChainUtils::hex_add_0x(hex_tx.as_slice()).as_slice());
-		let req = JsonRpcRequest {
-			id: 1,
-			params: Vec::from([hex_tx_fmtd]),
-			method: b"eth_sendRawTransaction".to_vec(),
+	/// Runs a transaction as an `eth_call` against the latest state instead of broadcasting it.
+	/// Only a call that succeeds is estimated and signed, as a reverting one would fail gas
+	/// estimation. The outcome is logged and saved to offchain storage, see `dry_run_key`, and
+	/// the hash the signed transaction would have had is returned, zero if the call reverted.
+	#[allow(clippy::too_many_arguments)]
+	fn simulate(
+		&self,
+		method_signature: &[u8],
+		inputs: &[Token],
+		encoded_bytes: Vec<u8>,
+		gas_limit: Option<U256>,
+		gas_price: Option<U256>,
+		value: U256,
+		nonce: Option<U256>,
+		from: Address,
+		signing: &ContractClientSignature,
+		recipient_address: Address,
+		now: u64,
+	) -> Result<H256, ChainRequestError> {
+		let call = CallRequest {
+			from: Some(from),
+			to: recipient_address,
+			gas: gas_limit,
+			gas_price,
+			value: Some(value),
+			data: encoded_bytes.clone(),
 		};
-		// log::info!("Have request {:?}", &req);
+		let req = JsonRpcRequest::new("eth_call", vec![call.into(), BlockTag::Latest.into()]);
 		let http_api = str::from_utf8(&self.http_api[..]).unwrap();
-		let rv: Box<CallResponse> = fetch_json_rpc(http_api, &req)?;
-		log::info!("Have response {:?}", &rv);
-		Ok(H256::from_slice(ChainUtils::hex_to_bytes(rv.result.as_slice())?.as_slice()))
+		let rv: Box<FallibleCallResponse> = fetch_json_rpc(http_api, &req)?;
+		let FallibleCallResponse { result, error } = *rv;
+		let (succeeded, output) = match (result, error) {
+			(_, Some(error)) => (false, error.message),
+			(Some(result), None) => (true, result),
+			(None, None) => return Err(ChainRequestError::BadRemoteData),
+		};
+
+		log::info!(
+			"DRY RUN on chain {}: {}({:?}) to {:?}",
+			self.chain_id,
+			str::from_utf8(method_signature).unwrap(),
+			inputs,
+			recipient_address
+		);
+		log::info!(
+			"DRY RUN on chain {}: transaction would {}: {}",
+			self.chain_id,
+			if succeeded { "succeed" } else { "revert" },
+			str::from_utf8(output.as_slice()).unwrap_or("<non utf8 output>")
+		);
+
+		let tx = if succeeded {
+			let tx = self.prepare_transaction(
+				encoded_bytes.clone(),
+				gas_limit,
+				gas_price,
+				value,
+				nonce,
+				from,
+				signing,
+				recipient_address,
+			)?;
+			log::info!(
+				"DRY RUN on chain {}: gas limit {}, gas price {}",
+				self.chain_id,
+				tx.gas_limit,
+				tx.gas_price
+			);
+			Some(tx)
+		} else {
+			None
+		};
+		let signed_transaction = tx.as_ref().map(|tx| tx.rlp_bytes().to_vec()).unwrap_or_default();
+
+		let record = DryRunRecord {
+			chain_id: self.chain_id,
+			timestamp: now,
+			to: recipient_address,
+			method_signature: method_signature.to_vec(),
+			calldata: encoded_bytes,
+			signed_transaction: signed_transaction.clone(),
+			gas_limit: tx.as_ref().map_or(U256::zero(), |tx| tx.gas_limit),
+			gas_price: tx.as_ref().map_or(U256::zero(), |tx| tx.gas_price),
+			succeeded,
+			output,
+		};
+		StorageValueRef::persistent(&self.dry_run_key(method_signature)).set(&record);
+
+		if signed_transaction.is_empty() {
+			return Ok(H256::zero());
+		}
+		Ok(ChainUtils::keccack(signed_transaction.as_slice()))
+	}
+
+	/// Records the finalizer signature that would have been submitted in dry run mode, under the
+	/// `submitSignature` function of this chain
+	pub fn record_signature_dry_run(&self, payload: &FinalizerSignaturePayload) {
+		log::info!(
+			"DRY RUN on chain {}: would submit finalizer signature for ({}, {}): {}",
+			self.chain_id,
+			payload.chain_id,
+			payload.block_number,
+			str::from_utf8(ChainUtils::bytes_to_hex(payload.signature.as_slice()).as_slice())
+				.unwrap()
+		);
+		StorageValueRef::persistent(&self.dry_run_key(b"submitSignature")).set(payload);
+	}
+
+	/// Offchain storage key of the last dry run record of a contract function on this chain,
+	/// `quantum-portal::dry-run::<chain id>::<function name>`. The persistent offchain storage
+	/// is read with the `offchain_localStorageGet` RPC.
+	fn dry_run_key(&self, method_signature: &[u8]) -> Vec<u8> {
+		let chain = ChainUtils::bytes_to_hex(self.chain_id.to_be_bytes().as_slice());
+		let name = method_signature.split(|b| *b == b'(').next().unwrap_or_default();
+		[b"quantum-portal::dry-run::".as_slice(), chain.as_slice(), b"::".as_slice(), name].concat()
+	}
+
+	fn apply_gas_limit_multiplier(&self, estimate: U256) -> U256 {
+		estimate
+			.saturating_mul(U256::from(self.gas_limits.gas_limit_multiplier))
+			.div(U256::from(100_u32))
+	}
+
+	/// Returns the gas price to send with, 125% of the network price limited to the configured
+	/// cap. Fails if the network price alone is already above the cap.
+	fn capped_gas_price(&self, network_price: U256) -> Result<U256, ChainRequestError> {
+		let gas_price = network_price.mul(U256::from(125_u32)).div(U256::from(100_u32));
+		let max_gas_price = self.gas_limits.max_gas_price;
+		if max_gas_price.is_zero() || gas_price <= max_gas_price {
+			return Ok(gas_price);
+		}
+		if network_price > max_gas_price {
+			log::warn!(
+				"Gas price {} on chain {} is above the cap of {}, skipping send",
+				network_price,
+				self.chain_id,
+				max_gas_price
+			);
+			return Err(ChainRequestError::GasPriceAboveCap);
+		}
+		Ok(max_gas_price)
+	}
+
+	fn spend_storage_key(&self) -> Vec<u8> {
+		let key = ChainUtils::bytes_to_hex(self.chain_id.to_be_bytes().as_slice());
+		[b"quantum-portal::spend::".as_slice(), key.as_slice()].concat()
+	}
+
+	/// Reserves the worst case cost of a transaction against the spend budget. The check and
+	/// the reservation happen in one storage mutation under a lock, so concurrent workers cannot
+	/// both fit into the same remaining budget.
+	fn reserve_spend(&self, now: u64, cost: U256) -> Result<(), ChainRequestError> {
+		let budget = self.gas_limits.daily_spend_budget;
+		if budget.is_zero() {
+			return Ok(());
+		}
+		self.mutate_spends(|spends| {
+			let spent = spends
+				.iter()
+				.filter(|(timestamp, _)| timestamp.saturating_add(SPEND_WINDOW_MILLIS) > now)
+				.fold(U256::zero(), |acc, (_, amount)| acc.saturating_add(*amount));
+			if spent.saturating_add(cost) > budget {
+				log::warn!(
+					"Sending on chain {} would spend {} on top of {} in the last day, budget is {}",
+					self.chain_id,
+					cost,
+					spent,
+					budget
+				);
+				return Err(ChainRequestError::SpendBudgetExceeded);
+			}
+			spends.retain(|(timestamp, _)| timestamp.saturating_add(SPEND_WINDOW_MILLIS) > now);
+			spends.push((now, cost));
+			Ok(())
+		})
+	}
+
+	/// Releases a reservation made by `reserve_spend` for a transaction rejected by the node
+	fn release_spend(&self, now: u64, cost: U256) {
+		if self.gas_limits.daily_spend_budget.is_zero() {
+			return;
+		}
+		let released = self.mutate_spends(|spends| {
+			if let Some(index) = spends.iter().position(|spend| *spend == (now, cost)) {
+				spends.remove(index);
+			}
+			Ok(())
+		});
+		if let Err(e) = released {
+			log::warn!("Failed to release spend reservation on chain {}: {:?}", self.chain_id, e);
+		}
+	}
+
+	/// Applies `f` to the spends recorded for this chain while holding the spend lock
+	fn mutate_spends<F>(&self, f: F) -> Result<(), ChainRequestError>
+	where
+		F: FnOnce(&mut Vec<(u64, U256)>) -> Result<(), ChainRequestError>,
+	{
+		let key = self.spend_storage_key();
+		let lock_key = [key.as_slice(), b"::lock".as_slice()].concat();
+		let mut lock = StorageLock::<Time>::new(&lock_key);
+		let _guard = lock.lock();
+		let res = StorageValueRef::persistent(&key).mutate(
+			|spends: Result<Option<Vec<(u64, U256)>>, StorageRetrievalError>| {
+				let mut spends = spends.ok().flatten().unwrap_or_default();
+				f(&mut spends).map(|_| spends)
+			},
+		);
+		match res {
+			Ok(_) => Ok(()),
+			Err(MutateStorageError::ValueFunctionFailed(e)) => Err(e),
+			Err(MutateStorageError::ConcurrentModification(_)) =>
+				Err(ChainRequestError::SpendBudgetExceeded),
+		}
+	}
+
+	pub fn block_number(&self) -> Result<u64, ChainRequestError> {
+		let http_api = str::from_utf8(&self.http_api[..]).unwrap();
+		ChainQueries::block_number(http_api)
+	}
+
+	/// Returns the block `confirmations` blocks behind the chain head, state read at this block
+	/// cannot be changed by a reorg shallower than the configured depth
+	pub fn confirmed_block(&self) -> Result<BlockTag, ChainRequestError> {
+		if self.confirmations == 0 {
+			return Ok(BlockTag::Latest);
+		}
+		let head = self.block_number()?;
+		Ok(BlockTag::Number(head.saturating_sub(self.confirmations)))
 	}
 
 	pub fn nonce(&self, from: Address) -> Result<U256, ChainRequestError> {
-		let req = JsonRpcRequest {
-			id: 1,
-			params: Vec::from([
-				ChainUtils::wrap_in_quotes(ChainUtils::address_to_hex(from).as_slice()),
-				b"\"latest\"".to_vec(),
-			]),
-			method: b"eth_getTransactionCount".to_vec(),
-		};
+		let req = JsonRpcRequest::new(
+			"eth_getTransactionCount",
+			vec![JsonRpcParam::address(from), BlockTag::Latest.into()],
+		);
 		let http_api = str::from_utf8(&self.http_api[..]).unwrap();
 		let rv: Box<CallResponse> = fetch_json_rpc(http_api, &req)?;
 		let nonce = ChainUtils::hex_to_u64(rv.result.as_slice())?;
@@ -316,7 +612,7 @@ impl ContractClient {
 	}
 
 	pub fn gas_price(&self) -> Result<U256, ChainRequestError> {
-		let req = JsonRpcRequest { id: 1, params: Vec::new(), method: b"eth_gasPrice".to_vec() };
+		let req = JsonRpcRequest::new("eth_gasPrice", Vec::new());
 		let http_api = str::from_utf8(&self.http_api[..]).unwrap();
 		let rv: Box<CallResponse> = fetch_json_rpc(http_api, &req)?;
 		let gp = ChainUtils::hex_to_u256(rv.result.as_slice())?;
@@ -330,26 +626,207 @@ impl ContractClient {
 		from: Address,
 		recipient_address: Address,
 	) -> Result<U256, ChainRequestError> {
-		let call_json = JsonSer::new()
-			.start()
-			.string("input", str::from_utf8(encoded).unwrap())
-			.string("from", str::from_utf8(ChainUtils::address_to_hex(from).as_slice()).unwrap())
-			.string(
-				"to",
-				str::from_utf8(ChainUtils::address_to_hex(recipient_address).as_slice()).unwrap(),
-			)
-			.string("value", str::from_utf8(ChainUtils::u256_to_hex_0x(value).as_slice()).unwrap())
-			.end()
-			.to_vec();
-		log::info!("estimateGas json is {}", str::from_utf8(&call_json).unwrap());
-		let req = JsonRpcRequest {
-			id: 1,
-			params: Vec::from([call_json, Vec::from("\"latest\"".as_bytes())]),
-			method: b"eth_estimateGas".to_vec(),
+		let call = CallRequest {
+			from: Some(from),
+			to: recipient_address,
+			value: Some(*value),
+			data: encoded.to_vec(),
+			..Default::default()
 		};
+		let req =
+			JsonRpcRequest::new("eth_estimateGas", vec![call.into(), BlockTag::Latest.into()]);
 		let http_api = str::from_utf8(&self.http_api[..]).unwrap();
 		let rv: Box<CallResponse> = fetch_json_rpc(http_api, &req)?;
 		let gp = ChainUtils::hex_to_u256(rv.result.as_slice())?;
 		Ok(gp)
 	}
 }
+
+/// Whether the node explicitly rejected a sent transaction with a JSON-RPC error, such as a
+/// nonce too low or insufficient funds. A failed request or an unreadable response is not a
+/// rejection, the transaction having maybe reached the node, and neither is an error telling
+/// that the node already has the transaction.
+fn is_rejected_by_node(sent: &ChainRequestResult<Box<FallibleCallResponse>>) -> bool {
+	let Ok(FallibleCallResponse { error: Some(error), .. }) = sent.as_deref() else {
+		return false;
+	};
+	let mentions = |text: &[u8]| error.message.windows(text.len()).any(|window| window == text);
+	!mentions(b"already known") && !mentions(b"known transaction")
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		chain_queries::FallibleCallResponse,
+		chain_utils::ChainRequestError,
+		contract_client::{is_rejected_by_node, ContractClient, GasLimits, SPEND_WINDOW_MILLIS},
+		qp_types::FinalizerSignaturePayload,
+	};
+	use sp_core::{
+		offchain::{testing::TestOffchainExt, OffchainDbExt, OffchainWorkerExt},
+		H160, H256, U256,
+	};
+	use sp_runtime::offchain::storage::StorageValueRef;
+
+	fn client(gas_limit_multiplier: u32, max_gas_price: u64) -> ContractClient {
+		budget_client(gas_limit_multiplier, max_gas_price, 0)
+	}
+
+	fn budget_client(
+		gas_limit_multiplier: u32,
+		max_gas_price: u64,
+		daily_spend_budget: u64,
+	) -> ContractClient {
+		let gas_limits = GasLimits {
+			gas_limit_multiplier,
+			max_gas_price: max_gas_price.into(),
+			daily_spend_budget: daily_spend_budget.into(),
+		};
+		ContractClient::new(Vec::new(), &H160::zero(), 1, 0, gas_limits, false, None)
+	}
+
+	fn offchain_ext() -> sp_io::TestExternalities {
+		let (offchain, _state) = TestOffchainExt::new();
+		let mut ext = sp_io::TestExternalities::default();
+		ext.register_extension(OffchainDbExt::new(offchain.clone()));
+		ext.register_extension(OffchainWorkerExt::new(offchain));
+		ext
+	}
+
+	#[test]
+	fn gas_limit_multiplier_is_applied_to_estimates() {
+		assert_eq!(client(100, 0).apply_gas_limit_multiplier(100_000.into()), 100_000.into());
+		assert_eq!(client(150, 0).apply_gas_limit_multiplier(100_000.into()), 150_000.into());
+	}
+
+	#[test]
+	fn gas_price_is_capped() {
+		// no cap, the usual 25% markup is applied
+		assert_eq!(client(100, 0).capped_gas_price(100.into()), Ok(125.into()));
+		// markup stays below the cap
+		assert_eq!(client(100, 200).capped_gas_price(100.into()), Ok(125.into()));
+		// markup is limited to the cap
+		assert_eq!(client(100, 110).capped_gas_price(100.into()), Ok(110.into()));
+		// network price itself is above the cap
+		assert_eq!(
+			client(100, 90).capped_gas_price(100.into()),
+			Err(ChainRequestError::GasPriceAboveCap)
+		);
+	}
+
+	#[test]
+	fn dry_run_records_are_keyed_by_function() {
+		let client = client(100, 0);
+		assert_eq!(
+			client.dry_run_key(b"finalize(uint256,uint256)"),
+			b"quantum-portal::dry-run::0000000000000001::finalize".to_vec()
+		);
+		assert_eq!(
+			client.dry_run_key(b"mineRemoteBlock(uint64,uint64)"),
+			b"quantum-portal::dry-run::0000000000000001::mineRemoteBlock".to_vec()
+		);
+	}
+
+	#[test]
+	fn dry_run_records_the_finalizer_signature() {
+		offchain_ext().execute_with(|| {
+			let payload = FinalizerSignaturePayload {
+				finalizer: H160::repeat_byte(1),
+				chain_id: 5,
+				block_number: 7,
+				finalizer_hash: H256::repeat_byte(2),
+				salt: H256::repeat_byte(2),
+				expiry: 3600,
+				signature: vec![3u8; 65].try_into().unwrap(),
+			};
+			client(100, 0).record_signature_dry_run(&payload);
+
+			let record = StorageValueRef::persistent(
+				b"quantum-portal::dry-run::0000000000000001::submitSignature",
+			)
+			.get::<FinalizerSignaturePayload>();
+			assert_eq!(record, Ok(Some(payload)));
+		});
+	}
+
+	#[test]
+	fn spend_budget_is_enforced() {
+		offchain_ext().execute_with(|| {
+			let client = budget_client(100, 0, 100);
+			assert_eq!(client.reserve_spend(0, 60.into()), Ok(()));
+			assert_eq!(
+				client.reserve_spend(1, 50.into()),
+				Err(ChainRequestError::SpendBudgetExceeded)
+			);
+			assert_eq!(client.reserve_spend(1, 40.into()), Ok(()));
+			assert_eq!(
+				client.reserve_spend(2, 1.into()),
+				Err(ChainRequestError::SpendBudgetExceeded)
+			);
+		});
+	}
+
+	#[test]
+	fn zero_spend_budget_is_unlimited() {
+		offchain_ext().execute_with(|| {
+			let client = budget_client(100, 0, 0);
+			assert_eq!(client.reserve_spend(0, U256::MAX), Ok(()));
+			assert_eq!(client.reserve_spend(0, U256::MAX), Ok(()));
+		});
+	}
+
+	#[test]
+	fn spend_window_resets() {
+		offchain_ext().execute_with(|| {
+			let client = budget_client(100, 0, 100);
+			assert_eq!(client.reserve_spend(0, 100.into()), Ok(()));
+			// the first spend is still within the window
+			assert_eq!(
+				client.reserve_spend(SPEND_WINDOW_MILLIS - 1, 1.into()),
+				Err(ChainRequestError::SpendBudgetExceeded)
+			);
+			// the first spend has left the window
+			assert_eq!(client.reserve_spend(SPEND_WINDOW_MILLIS, 100.into()), Ok(()));
+			assert_eq!(
+				client.reserve_spend(SPEND_WINDOW_MILLIS + 1, 1.into()),
+				Err(ChainRequestError::SpendBudgetExceeded)
+			);
+		});
+	}
+
+	#[test]
+	fn released_spend_is_returned_to_the_budget() {
+		offchain_ext().execute_with(|| {
+			let client = budget_client(100, 0, 100);
+			assert_eq!(client.reserve_spend(0, 70.into()), Ok(()));
+			assert_eq!(client.reserve_spend(1, 30.into()), Ok(()));
+			client.release_spend(0, 70.into());
+			assert_eq!(client.reserve_spend(2, 70.into()), Ok(()));
+			assert_eq!(
+				client.reserve_spend(3, 1.into()),
+				Err(ChainRequestError::SpendBudgetExceeded)
+			);
+		});
+	}
+
+	#[test]
+	fn only_node_rejections_release_the_spend() {
+		let response =
+			|body: &str| Ok(Box::new(serde_json::from_str::<FallibleCallResponse>(body).unwrap()));
+
+		assert!(is_rejected_by_node(&response(
+			r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"nonce too low"}}"#
+		)));
+		assert!(is_rejected_by_node(&response(
+			r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"insufficient funds"}}"#
+		)));
+		assert!(!is_rejected_by_node(&response(
+			r#"{"jsonrpc":"2.0","id":1,"result":"0x1111111111111111111111111111111111111111111111111111111111111111"}"#
+		)));
+		assert!(!is_rejected_by_node(&response(
+			r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"already known"}}"#
+		)));
+		// the request timed out or its response could not be read
+		assert!(!is_rejected_by_node(&Err(ChainRequestError::ErrorGettingJsonRpcResponse)));
+	}
+}
@@ -14,8 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
 use crate::chain_utils::ChainUtils;
-use ethabi_nostd::Address;
-use ethabi_nostd::{encoder, Token, H256, U256}; //vec::{Vec};
+use ethabi_nostd::{
+	eip712::{self, Domain, Types},
+	encoder, Address, Token, H256, U256,
+}; //vec::{Vec};
 use sp_std::prelude::*;
 
 pub struct EIP712Utils;
@@ -28,27 +30,70 @@ impl EIP712Utils {
 		chain_id: u64,
 		contract_address: Address,
 	) -> H256 {
-		let type_hash = ChainUtils::keccack(
-			b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
-		);
-		let hashed_name = ChainUtils::keccack(contract_name);
-		let hashed_version = ChainUtils::keccack(contract_version);
-
-		Self::get_encoded_hash(vec![
-			Token::FixedBytes(Vec::from(type_hash.as_bytes())),
-			Token::FixedBytes(Vec::from(hashed_name.as_bytes())),
-			Token::FixedBytes(Vec::from(hashed_version.as_bytes())),
-			Token::Uint(U256::from(chain_id)),
-			Token::Address(contract_address),
-		])
+		Domain {
+			name: Some(contract_name.to_vec()),
+			version: Some(contract_version.to_vec()),
+			chain_id: Some(U256::from(chain_id)),
+			verifying_contract: Some(contract_address),
+			salt: None,
+		}
+		.separator()
 	}
 
 	/// This function takes the domain_seperator_hash and eip_args_hash as input and returns the
 	/// EIP712 format hash
 	pub fn generate_eip_712_hash(domain_seperator_hash: &[u8], eip_args_hash: &[u8]) -> H256 {
-		let prefix = (b"\x19\x01").to_vec();
-		let concat = [&prefix[..], domain_seperator_hash, eip_args_hash].concat();
-		ChainUtils::keccack(&concat)
+		eip712::hash(&H256::from_slice(domain_seperator_hash), &H256::from_slice(eip_args_hash))
+	}
+
+	/// Returns the EIP712 `hashStruct` of a single struct, given its definition such as
+	/// `MinerSignature(bytes32 msgHash,uint64 expiry,bytes32 salt)` and its member values.
+	pub fn hash_struct(definition: &str, values: Vec<Token>) -> H256 {
+		let types = Types::parse(definition).expect("struct definitions are static and valid");
+		let name = &definition[..definition.find('(').unwrap_or_default()];
+		types
+			.hash_struct(name, &Token::Tuple(values))
+			.expect("struct values match their definition")
+	}
+
+	/// Generate the EIP712 hash a finalizer signs to approve the finalization of the given
+	/// remote block. This matches `ValidateAuthoritySignature` of the authority manager contract,
+	/// wrapping the `Finalize` message hash.
+	pub fn generate_finalize_hash(
+		domain_seperator_hash: &[u8],
+		remote_chain_id: u64,
+		block_nonce: u64,
+		finalizer_hash: Token,
+		finalizer_list: Vec<Token>,
+		salt: Token,
+		expiry: Token,
+	) -> H256 {
+		let finalize_method_signature = b"Finalize(uint256 remoteChainId,uint256 blockNonce,bytes32 finalizersHash,address[] finalizers,bytes32 salt,uint64 expiry)";
+		let finalize_method_signature_hash = ChainUtils::keccack(finalize_method_signature);
+
+		// The authority manager hashes the message with `abi.encode`, which encodes the finalizers
+		// array differently from an EIP712 `hashStruct`.
+		let encoded_message_hash = Self::get_encoded_hash(vec![
+			Token::FixedBytes(Vec::from(finalize_method_signature_hash.as_bytes())),
+			Token::Uint(U256::from(remote_chain_id)),
+			Token::Uint(U256::from(block_nonce)),
+			finalizer_hash,
+			Token::Array(finalizer_list),
+			salt.clone(),
+			expiry.clone(),
+		]);
+
+		let eip_args_hash = Self::hash_struct(
+			"ValidateAuthoritySignature(uint256 action,bytes32 msgHash,bytes32 salt,uint64 expiry)",
+			vec![
+				Token::Uint(U256::from(1)),                                    // action
+				Token::FixedBytes(Vec::from(encoded_message_hash.as_bytes())), // msgHash
+				salt,                                                          // salt
+				expiry,                                                        // expiry
+			],
+		);
+
+		Self::generate_eip_712_hash(domain_seperator_hash, &eip_args_hash[..])
 	}
 
 	/// This function takes a vector of Token inputs and returns the encoded keccak hash
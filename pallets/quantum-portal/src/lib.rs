@@ -18,25 +18,33 @@ mod chain_queries;
 mod chain_utils;
 mod contract_client;
 mod eip_712_utils;
+mod mock;
+mod qp_contracts;
 pub mod qp_types;
 mod quantum_portal_client;
 pub mod quantum_portal_service;
+mod tests;
 
 #[frame_support::pallet]
 pub mod pallet {
 	// Re-import necessary modules for pallet.
 	use crate::{
 		chain_utils::{ChainRequestError, ChainUtils},
-		contract_client::{ContractClient, ContractClientSignature},
+		contract_client::{ContractClient, ContractClientSignature, GasLimits},
+		eip_712_utils::EIP712Utils,
 		qp_types,
-		qp_types::{BlockNumber, ChainId, QpConfig, QpNetworkItem, Role},
+		qp_types::{
+			BlockNumber, ChainId, FinalizerSignaturePayload, QpConfig, QpNetworkItem, Role,
+			SignatureDomain,
+		},
 		quantum_portal_client::QuantumPortalClient,
 		quantum_portal_service::QuantumPortalService,
 	};
 
 	// Re-import necessary items from core and other external crates.
-	use crate::qp_types::MAX_PAIRS_TO_MINE;
+	use crate::qp_types::{FINALIZER_SIGNATURE_LENGTH, MAX_PAIRS_TO_MINE};
 	use core::convert::TryInto;
+	use ethabi_nostd::Token;
 	use ferrum_primitives::{OFFCHAIN_SIGNER_CONFIG_KEY, OFFCHAIN_SIGNER_CONFIG_PREFIX};
 	use frame_support::{pallet_prelude::*, traits::UnixTime};
 	use frame_system::{
@@ -46,10 +54,13 @@ pub mod pallet {
 		},
 		pallet_prelude::*,
 	};
-	use sp_core::crypto::KeyTypeId;
-	use sp_runtime::offchain::{
-		storage::StorageValueRef,
-		storage_lock::{StorageLock, Time},
+	use sp_core::{crypto::KeyTypeId, H160, H256, U256};
+	use sp_runtime::{
+		offchain::{
+			storage::StorageValueRef,
+			storage_lock::{StorageLock, Time},
+		},
+		traits::Convert,
 	};
 	use sp_std::{prelude::*, str};
 
@@ -62,8 +73,37 @@ pub mod pallet {
 		type RuntimeCall: From<frame_system::Call<Self>>;
 
 		type Timestamp: UnixTime;
+
+		/// Maps a finalizer account to the ethereum address its signatures recover to
+		type AccountIdToAddress: Convert<Self::AccountId, H160>;
+
+		/// Priority of unsigned finalizer signature submissions
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Number of blocks an unsigned finalizer signature submission stays valid in the pool
+		#[pallet::constant]
+		type UnsignedLongevity: Get<TransactionLongevity>;
+
+		/// Origin allowed to manage the finalizers, their threshold and signature domain
+		type FinalizerOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 	}
 
+	/// Seconds past the current time a submitted signature may expire
+	pub const MAX_SIGNATURE_EXPIRY_SECS: u64 = 24 * 3600;
+
+	/// Seconds past the current time the offchain worker signs a block with, when the block has
+	/// no pending signatures yet
+	pub const SIGNATURE_EXPIRY_SECS: u64 = 3600;
+	const _: () = assert!(SIGNATURE_EXPIRY_SECS < MAX_SIGNATURE_EXPIRY_SECS);
+
+	/// Blocks behind the newest signed block of a chain whose pending signatures are kept, and
+	/// blocks ahead of it that may be signed
+	pub const PENDING_SIGNATURE_BLOCKS: u64 = 16;
+
+	/// Invalid transaction code of an unsigned signature expiring too far in the future
+	pub const EXPIRY_TOO_FAR: u8 = 1;
+
 	#[pallet::pallet]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
@@ -73,6 +113,26 @@ pub mod pallet {
 		/// A finalizer was not found
 		FinalizerNotFound,
 		OffchainUnsignedTxSignedPayload,
+		/// No signature domain configured for the chain
+		SignatureDomainNotSet,
+		/// The signature is not in the expected `r || s || v` padded format
+		MalformedSignature,
+		/// The signature could not be recovered
+		InvalidSignature,
+		/// The signature does not recover to the finalizer for the expected digest
+		SignatureMismatch,
+		/// The finalizer already submitted a signature for the block
+		DuplicateSignature,
+		/// The signature expiry has passed
+		SignatureExpired,
+		/// The signature expiry is further than `MAX_SIGNATURE_EXPIRY_SECS` in the future
+		SignatureExpiryTooFar,
+		/// The signature expiry differs from the one of the pending signatures of the block
+		ExpiryMismatch,
+		/// The pending signatures of the block were already pruned
+		BlockTooOld,
+		/// The block is more than `PENDING_SIGNATURE_BLOCKS` ahead of the newest signed block
+		BlockTooFarAhead,
 	}
 
 	pub enum OffchainErr {
@@ -101,6 +161,20 @@ pub mod pallet {
 		Vec<(<T as frame_system::Config>::AccountId, Vec<u8>)>,
 	>;
 
+	/// Expiry shared by the pending finalize signatures of a block, set by the first of them
+	#[pallet::storage]
+	pub type PendingSignatureExpiry<T> =
+		StorageDoubleMap<_, Blake2_128Concat, ChainId, Blake2_128Concat, BlockNumber, u64>;
+
+	/// Newest block of a chain with pending finalize signatures
+	#[pallet::storage]
+	pub type NewestPendingBlock<T> = StorageMap<_, Blake2_128Concat, ChainId, BlockNumber>;
+
+	/// Newest block of a chain whose pending finalize signatures were pruned, no signature of it
+	/// or of an older block is accepted anymore
+	#[pallet::storage]
+	pub type NewestPrunedBlock<T> = StorageMap<_, Blake2_128Concat, ChainId, BlockNumber>;
+
 	/// Current registered finalizers by chainId
 	#[pallet::storage]
 	pub type RegisteredFinalizers<T> =
@@ -110,6 +184,11 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type FinalizerThreshold<T> = StorageMap<_, Blake2_128Concat, ChainId, u32>;
 
+	/// EIP712 domain finalizer signatures are checked against, by remote chainId
+	#[pallet::storage]
+	pub type FinalizerSignatureDomain<T> =
+		StorageMap<_, Blake2_128Concat, ChainId, SignatureDomain>;
+
 	pub type OffchainResult<A> = Result<A, OffchainErr>;
 
 	impl<T: Config> Pallet<T> {
@@ -117,6 +196,7 @@ pub mod pallet {
 			block_number: u64,
 			network_item: QpNetworkItem,
 			signer_public_key: Vec<u8>,
+			dry_run: bool,
 		) -> QuantumPortalClient<T> {
 			let rpc_endpoint = network_item.url;
 			let id = network_item.id;
@@ -124,7 +204,22 @@ pub mod pallet {
 			let signer = ChainUtils::hex_to_ecdsa_pub_key(&signer_public_key[..]);
 			let gateway_contract =
 				ChainUtils::hex_to_address(&network_item.gateway_contract_address[..]);
-			let client = ContractClient::new(rpc_endpoint, &gateway_contract, id);
+			let multicall = (!network_item.multicall_address.is_empty())
+				.then(|| ChainUtils::hex_to_address(&network_item.multicall_address[..]));
+			let gas_limits = GasLimits {
+				gas_limit_multiplier: network_item.gas_limit_multiplier,
+				max_gas_price: network_item.max_gas_price.into(),
+				daily_spend_budget: network_item.daily_spend_budget.into(),
+			};
+			let client = ContractClient::new(
+				rpc_endpoint,
+				&gateway_contract,
+				id,
+				network_item.confirmations,
+				gas_limits,
+				dry_run,
+				multicall,
+			);
 			QuantumPortalClient::new(
 				client,
 				ContractClientSignature::from(signer),
@@ -145,6 +240,7 @@ pub mod pallet {
 						block_number,
 						item,
 						qp_config_item.signer_public_key.clone(),
+						qp_config_item.dry_run,
 					)
 				})
 				.collect();
@@ -166,6 +262,231 @@ pub mod pallet {
 				.collect();
 			Ok(())
 		}
+
+		/// Store the finalizer signature for the given block after checking it on chain
+		pub fn do_submit_signature(
+			finalizer: T::AccountId,
+			chain_id: ChainId,
+			block_number: BlockNumber,
+			finalizer_hash: H256,
+			salt: H256,
+			expiry: u64,
+			signature: Vec<u8>,
+		) -> DispatchResult {
+			Self::ensure_valid_expiry(expiry)?;
+			Self::ensure_pending_window(chain_id, block_number)?;
+
+			// ensure the finalizer is registered and has not signed this block yet
+			let finalizers =
+				RegisteredFinalizers::<T>::get(chain_id).ok_or(Error::<T>::FinalizerNotFound)?;
+			ensure!(finalizers.contains(&finalizer), Error::<T>::FinalizerNotFound);
+
+			// the signatures of a block are combined so they share the expiry of the first one,
+			// those that expired before reaching the threshold are dropped to sign the block again
+			match Self::pending_expiry(chain_id, block_number) {
+				Some(pending) => ensure!(expiry == pending, Error::<T>::ExpiryMismatch),
+				None => PendingFinalizeSignatures::<T>::remove(chain_id, block_number),
+			}
+			ensure!(
+				!Self::has_submitted(&finalizer, chain_id, block_number),
+				Error::<T>::DuplicateSignature
+			);
+
+			// ensure the signature was produced by the finalizer over the finalize digest
+			Self::verify_finalize_signature(
+				&finalizer,
+				chain_id,
+				block_number,
+				finalizer_hash,
+				salt,
+				expiry,
+				&signature,
+			)?;
+
+			// a newer block, or any block once the window restarts, drops the pending signatures
+			// of the blocks outside the window around it
+			if Self::newest_pending_block(chain_id).map_or(true, |newest| block_number > newest) {
+				NewestPendingBlock::<T>::insert(chain_id, block_number);
+				Self::prune_pending_signatures(chain_id, block_number);
+			}
+
+			// insert the signature to storage
+			PendingSignatureExpiry::<T>::insert(chain_id, block_number, expiry);
+			PendingFinalizeSignatures::<T>::try_mutate(
+				chain_id,
+				block_number,
+				|signatures| -> DispatchResult {
+					let signatures = signatures.get_or_insert_with(Default::default);
+					signatures.push((finalizer.clone(), signature.clone()));
+					Self::deposit_event(Event::SignatureSubmitted {
+						chain_id,
+						finalizer,
+						block_number,
+						signature,
+					});
+					Ok(())
+				},
+			)
+		}
+
+		/// Ensure a signature `expiry`, in seconds, has not passed and is not too far ahead
+		pub fn ensure_valid_expiry(expiry: u64) -> Result<(), Error<T>> {
+			let now = T::Timestamp::now().as_secs();
+			ensure!(expiry > now, Error::<T>::SignatureExpired);
+			ensure!(
+				expiry <= now.saturating_add(MAX_SIGNATURE_EXPIRY_SECS),
+				Error::<T>::SignatureExpiryTooFar
+			);
+			Ok(())
+		}
+
+		/// Expiry of the pending signatures of a block, in seconds, unless it has passed
+		pub fn pending_expiry(chain_id: ChainId, block_number: BlockNumber) -> Option<u64> {
+			let now = T::Timestamp::now().as_secs();
+			PendingSignatureExpiry::<T>::get(chain_id, block_number).filter(|expiry| *expiry > now)
+		}
+
+		/// Expiry for a finalizer to sign a block with at `now`, in seconds. It is the one of the
+		/// pending signatures of the block so they can be combined, or else
+		/// `SIGNATURE_EXPIRY_SECS` from now, and never depends on when the block was mined.
+		pub fn signature_expiry(chain_id: ChainId, block_number: BlockNumber, now: u64) -> u64 {
+			Self::pending_expiry(chain_id, block_number)
+				.unwrap_or_else(|| now.saturating_add(SIGNATURE_EXPIRY_SECS))
+		}
+
+		/// Ensure signatures of `block_number` may be pending, the block being newer than the
+		/// pruned ones and at most `PENDING_SIGNATURE_BLOCKS` ahead of the newest signed block.
+		/// The window restarts from any block once the signatures of the newest one expired, so
+		/// the finalizers catch up after a downtime.
+		pub fn ensure_pending_window(
+			chain_id: ChainId,
+			block_number: BlockNumber,
+		) -> Result<(), Error<T>> {
+			let Some(newest) = Self::newest_pending_block(chain_id) else {
+				return Ok(());
+			};
+			if let Some(pruned) = NewestPrunedBlock::<T>::get(chain_id) {
+				ensure!(block_number > pruned, Error::<T>::BlockTooOld);
+			}
+			ensure!(
+				block_number <= newest.saturating_add(PENDING_SIGNATURE_BLOCKS),
+				Error::<T>::BlockTooFarAhead
+			);
+			Ok(())
+		}
+
+		/// Newest block of a chain with pending signatures, unless they expired
+		fn newest_pending_block(chain_id: ChainId) -> Option<BlockNumber> {
+			NewestPendingBlock::<T>::get(chain_id)
+				.filter(|newest| Self::pending_expiry(chain_id, *newest).is_some())
+		}
+
+		/// Remove the pending signatures of blocks more than `PENDING_SIGNATURE_BLOCKS` away from
+		/// the new newest block `block_number`, and stop accepting signatures of the blocks behind
+		fn prune_pending_signatures(chain_id: ChainId, block_number: BlockNumber) {
+			let pruned = block_number.checked_sub(PENDING_SIGNATURE_BLOCKS + 1);
+			let ahead = block_number.saturating_add(PENDING_SIGNATURE_BLOCKS);
+			let stale: Vec<BlockNumber> = PendingFinalizeSignatures::<T>::iter_key_prefix(chain_id)
+				.filter(|number| {
+					*number > ahead || pruned.map_or(false, |pruned| *number <= pruned)
+				})
+				.collect();
+			for number in stale {
+				PendingFinalizeSignatures::<T>::remove(chain_id, number);
+				PendingSignatureExpiry::<T>::remove(chain_id, number);
+			}
+			NewestPrunedBlock::<T>::set(chain_id, pruned);
+		}
+
+		/// Weight of storing a finalizer signature. Recovering the signer is priced like the
+		/// ECRecover precompile, and pruning touches at most `2 * PENDING_SIGNATURE_BLOCKS + 1`
+		/// blocks of the chain, the window the pending blocks are kept in.
+		pub fn submit_signature_weight() -> Weight {
+			T::DbWeight::get()
+				.reads_writes(13 + 2 * PENDING_SIGNATURE_BLOCKS, 8 + 4 * PENDING_SIGNATURE_BLOCKS)
+				.saturating_add(Weight::from_parts(75_000_000, 0))
+		}
+
+		/// Find the registered finalizer of the chain whose signatures recover to `address`
+		pub fn finalizer_for_address(
+			chain_id: ChainId,
+			address: H160,
+		) -> Result<T::AccountId, DispatchError> {
+			RegisteredFinalizers::<T>::get(chain_id)
+				.unwrap_or_default()
+				.into_iter()
+				.find(|finalizer| T::AccountIdToAddress::convert(finalizer.clone()) == address)
+				.ok_or_else(|| Error::<T>::FinalizerNotFound.into())
+		}
+
+		pub fn has_submitted(
+			finalizer: &T::AccountId,
+			chain_id: ChainId,
+			block_number: BlockNumber,
+		) -> bool {
+			PendingFinalizeSignatures::<T>::get(chain_id, block_number)
+				.map_or(false, |signatures| signatures.iter().any(|(f, _)| f == finalizer))
+		}
+
+		/// Recompute the EIP712 finalize digest for the given block and ensure `signature`
+		/// recovers to the ethereum address of `finalizer`
+		pub fn verify_finalize_signature(
+			finalizer: &T::AccountId,
+			chain_id: ChainId,
+			block_number: BlockNumber,
+			finalizer_hash: H256,
+			salt: H256,
+			expiry: u64,
+			signature: &[u8],
+		) -> DispatchResult {
+			let digest =
+				Self::finalize_digest(chain_id, block_number, finalizer_hash, salt, expiry)?;
+
+			// finalizers submit `r || s || v` padded with zeros to 96 bytes
+			ensure!(
+				signature.len() == FINALIZER_SIGNATURE_LENGTH as usize &&
+					signature[65..].iter().all(|b| *b == 0),
+				Error::<T>::MalformedSignature
+			);
+
+			let signer = ChainUtils::recover_eth_address(&digest, signature)
+				.ok_or(Error::<T>::InvalidSignature)?;
+			ensure!(
+				signer == T::AccountIdToAddress::convert(finalizer.clone()),
+				Error::<T>::SignatureMismatch
+			);
+			Ok(())
+		}
+
+		/// The EIP712 digest finalizers sign to approve the finalization of the given block,
+		/// under the signature domain of the chain
+		pub fn finalize_digest(
+			chain_id: ChainId,
+			block_number: BlockNumber,
+			finalizer_hash: H256,
+			salt: H256,
+			expiry: u64,
+		) -> Result<H256, DispatchError> {
+			let domain = FinalizerSignatureDomain::<T>::get(chain_id)
+				.ok_or(Error::<T>::SignatureDomainNotSet)?;
+
+			let domain_seperator_hash = EIP712Utils::generate_eip_712_domain_seperator_hash(
+				&domain.name,
+				&domain.version,
+				domain.chain_id,
+				domain.verifying_contract,
+			);
+			let digest = EIP712Utils::generate_finalize_hash(
+				&domain_seperator_hash[..],
+				chain_id,
+				block_number,
+				Token::FixedBytes(finalizer_hash.as_bytes().to_vec()),
+				vec![],
+				Token::FixedBytes(salt.as_bytes().to_vec()),
+				Token::Uint(U256::from(expiry)),
+			);
+			Ok(digest)
+		}
 	}
 
 	#[pallet::hooks]
@@ -192,8 +513,17 @@ pub mod pallet {
 				}
 
 				if let Ok(Some(config)) = decoded_config {
+					if let Err(e) = config.validate() {
+						log::info!("Invalid configuration: {}, exiting offchain worker", e);
+						return;
+					}
+
 					let expected_role = config.role.clone();
 
+					if config.dry_run {
+						log::info!("Dry run enabled, transactions are simulated and not sent");
+					}
+
 					if expected_role == Role::None {
 						log::info!("Not a miner or finalizer, exiting offchain worker");
 						return;
@@ -237,6 +567,8 @@ pub mod pallet {
 		},
 		/// Finalizer threshold set
 		FinalizerThresholdSet { chain_id: ChainId, threshold: u32 },
+		/// Finalizer signature domain set
+		SignatureDomainSet { chain_id: ChainId, domain: SignatureDomain },
 	}
 
 	#[pallet::call]
@@ -248,7 +580,7 @@ pub mod pallet {
 			chain_id: ChainId,
 			finalizer: T::AccountId,
 		) -> DispatchResult {
-			let caller = ensure_signed(origin);
+			T::FinalizerOrigin::ensure_origin(origin)?;
 
 			RegisteredFinalizers::<T>::try_mutate(
 				chain_id,
@@ -269,7 +601,7 @@ pub mod pallet {
 			chain_id: ChainId,
 			finalizer: T::AccountId,
 		) -> DispatchResult {
-			let caller = ensure_signed(origin);
+			T::FinalizerOrigin::ensure_origin(origin)?;
 
 			RegisteredFinalizers::<T>::try_mutate(
 				chain_id,
@@ -288,35 +620,25 @@ pub mod pallet {
 		}
 
 		#[pallet::call_index(2)]
-		#[pallet::weight(0)]
+		#[pallet::weight(Pallet::<T>::submit_signature_weight())]
 		pub fn submit_signature(
 			origin: OriginFor<T>,
 			chain_id: ChainId,
 			block_number: BlockNumber,
+			finalizer_hash: H256,
+			salt: H256,
+			expiry: u64,
 			signature: Vec<u8>,
 		) -> DispatchResult {
 			let caller = ensure_signed(origin)?;
-
-			// ensure the caller is a registered finalizer
-			let finalizers =
-				RegisteredFinalizers::<T>::get(chain_id).ok_or(Error::<T>::FinalizerNotFound)?;
-			ensure!(finalizers.contains(&caller), Error::<T>::FinalizerNotFound);
-
-			// insert the signature to storage
-			PendingFinalizeSignatures::<T>::try_mutate(
+			Self::do_submit_signature(
+				caller,
 				chain_id,
 				block_number,
-				|signatures| -> DispatchResult {
-					let signatures = signatures.get_or_insert_with(Default::default);
-					signatures.push((caller.clone(), signature.clone()));
-					Self::deposit_event(Event::SignatureSubmitted {
-						chain_id,
-						finalizer: caller,
-						block_number,
-						signature,
-					});
-					Ok(())
-				},
+				finalizer_hash,
+				salt,
+				expiry,
+				signature,
 			)
 		}
 
@@ -327,10 +649,94 @@ pub mod pallet {
 			chain_id: ChainId,
 			threshold: u32,
 		) -> DispatchResult {
-			let caller = ensure_signed(origin);
+			T::FinalizerOrigin::ensure_origin(origin)?;
 			FinalizerThreshold::<T>::insert(chain_id, threshold);
 			Self::deposit_event(Event::FinalizerThresholdSet { chain_id, threshold });
 			Ok(())
 		}
+
+		#[pallet::call_index(4)]
+		#[pallet::weight(0)]
+		pub fn set_signature_domain(
+			origin: OriginFor<T>,
+			chain_id: ChainId,
+			domain: SignatureDomain,
+		) -> DispatchResult {
+			T::FinalizerOrigin::ensure_origin(origin)?;
+			FinalizerSignatureDomain::<T>::insert(chain_id, domain.clone());
+			Self::deposit_event(Event::SignatureDomainSet { chain_id, domain });
+			Ok(())
+		}
+
+		#[pallet::call_index(5)]
+		#[pallet::weight(Pallet::<T>::submit_signature_weight())]
+		pub fn submit_signature_unsigned(
+			origin: OriginFor<T>,
+			payload: FinalizerSignaturePayload,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			let finalizer = Self::finalizer_for_address(payload.chain_id, payload.finalizer)?;
+			Self::do_submit_signature(
+				finalizer,
+				payload.chain_id,
+				payload.block_number,
+				payload.finalizer_hash,
+				payload.salt,
+				payload.expiry,
+				payload.signature.into_inner(),
+			)
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let payload = if let Call::submit_signature_unsigned { payload } = call {
+				payload
+			} else {
+				return InvalidTransaction::Call.into()
+			};
+
+			Self::ensure_valid_expiry(payload.expiry).map_err(|e| match e {
+				Error::<T>::SignatureExpired => InvalidTransaction::Stale,
+				_ => InvalidTransaction::Custom(EXPIRY_TOO_FAR),
+			})?;
+			Self::ensure_pending_window(payload.chain_id, payload.block_number).map_err(
+				|e| match e {
+					Error::<T>::BlockTooFarAhead => InvalidTransaction::Future,
+					_ => InvalidTransaction::Stale,
+				},
+			)?;
+			let finalizer = Self::finalizer_for_address(payload.chain_id, payload.finalizer)
+				.map_err(|_| InvalidTransaction::BadSigner)?;
+			// the pending signatures of the block are dropped once expired, until then they must
+			// share the expiry and not come from the same finalizer
+			if let Some(expiry) = Self::pending_expiry(payload.chain_id, payload.block_number) {
+				if expiry != payload.expiry ||
+					Self::has_submitted(&finalizer, payload.chain_id, payload.block_number)
+				{
+					return InvalidTransaction::Stale.into()
+				}
+			}
+			Self::verify_finalize_signature(
+				&finalizer,
+				payload.chain_id,
+				payload.block_number,
+				payload.finalizer_hash,
+				payload.salt,
+				payload.expiry,
+				&payload.signature,
+			)
+			.map_err(|_| InvalidTransaction::BadProof)?;
+
+			ValidTransaction::with_tag_prefix("QuantumPortalFinalizer")
+				.priority(T::UnsignedPriority::get())
+				.and_provides((payload.chain_id, payload.block_number, payload.finalizer))
+				.longevity(T::UnsignedLongevity::get())
+				.propagate(true)
+				.build()
+		}
 	}
 }
@@ -0,0 +1,171 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+#![cfg(test)]
+use super::*;
+use crate as quantum_portal;
+use crate::{chain_utils::ChainUtils, qp_types::SignatureDomain};
+use core::time::Duration;
+use frame_support::{
+	construct_runtime, parameter_types,
+	traits::{ConstU32, ConstU64, Everything, UnixTime},
+};
+use frame_system::{offchain::AppCrypto, EnsureRoot};
+use sp_core::{ecdsa, Pair, H160, H256};
+use sp_runtime::{
+	testing::{TestSignature, TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, Convert, Extrinsic as ExtrinsicT, IdentityLookup},
+	BuildStorage,
+};
+
+pub type AccountId = u64;
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CHAIN_ID: u64 = 26000;
+
+impl frame_system::Config for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeTask = RuntimeTask;
+	type Nonce = u64;
+	type Block = Block;
+	type RuntimeCall = RuntimeCall;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<AccountId>;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = Everything;
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+	type SingleBlockMigrations = ();
+	type MultiBlockMigrator = ();
+	type PreInherents = ();
+	type PostInherents = ();
+	type PostTransactions = ();
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		_public: UintAuthorityId,
+		_account: AccountId,
+		nonce: u64,
+	) -> Option<(RuntimeCall, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
+parameter_types! {
+	// seconds since the unix epoch returned by `MockTime`
+	pub static Now: u64 = 1_700_000_000;
+}
+
+pub struct MockTime;
+impl UnixTime for MockTime {
+	fn now() -> Duration {
+		Duration::from_secs(Now::get())
+	}
+}
+
+/// Test accounts sign with the ecdsa key seeded by their id
+pub fn finalizer_pair(who: AccountId) -> ecdsa::Pair {
+	ecdsa::Pair::from_seed(&[who as u8; 32])
+}
+
+pub struct AccountIdToAddress;
+impl Convert<AccountId, H160> for AccountIdToAddress {
+	fn convert(who: AccountId) -> H160 {
+		H160::from_slice(&ChainUtils::eth_address_from_public_key(&finalizer_pair(who).public().0))
+	}
+}
+
+impl Config for Runtime {
+	type RuntimeCall = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type Timestamp = MockTime;
+	type AccountIdToAddress = AccountIdToAddress;
+	type UnsignedPriority = ConstU64<100>;
+	type UnsignedLongevity = ConstU64<64>;
+	type FinalizerOrigin = EnsureRoot<AccountId>;
+}
+
+construct_runtime!(
+	pub enum Runtime {
+		System: frame_system,
+		QuantumPortal: quantum_portal,
+	}
+);
+
+pub fn signature_domain() -> SignatureDomain {
+	SignatureDomain {
+		name: b"FERRUM_QUANTUM_PORTAL_AUTHORITY_MGR".to_vec(),
+		version: b"000.010".to_vec(),
+		chain_id: CHAIN_ID,
+		verifying_contract: H160::repeat_byte(0x11),
+	}
+}
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		ExtBuilder
+	}
+}
+
+impl ExtBuilder {
+	/// Alice is the only finalizer of `CHAIN_ID`, whose signature domain is `signature_domain`
+	pub fn build(self) -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			RegisteredFinalizers::<Runtime>::insert(CHAIN_ID, vec![ALICE]);
+			FinalizerSignatureDomain::<Runtime>::insert(CHAIN_ID, signature_domain());
+		});
+		ext
+	}
+}
@@ -0,0 +1,856 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Typed bindings for the Quantum Portal contracts the worker talks to. Every call carries its
+//! Solidity signature next to typed arguments and the shape of what it returns, so the selector,
+//! the encoded inputs and the decoding of the result can not drift apart.
+use crate::{
+	chain_utils::{ChainRequestError, ChainRequestResult, ChainUtils},
+	qp_types::{QpLocalBlock, QpRemoteBlock, QpTransaction},
+};
+use ethabi_nostd::{
+	decoder::decode_strict, encoder::encode_function_u8, Address, ParamKind, Token,
+};
+use sp_core::{H256, U256};
+use sp_std::prelude::*;
+
+/// A function of a deployed contract
+pub trait ContractCall {
+	/// Solidity signature the selector is derived from
+	const SIGNATURE: &'static [u8];
+
+	/// Value the call returns once decoded
+	type Output;
+
+	/// Arguments of the call, in the order of the signature
+	fn inputs(&self) -> Vec<Token>;
+
+	/// Types of the values the call returns
+	fn output_kinds() -> Vec<ParamKind>;
+
+	/// Builds the output from the decoded values, `None` if they do not have the expected shape
+	fn from_tokens(tokens: Vec<Token>) -> Option<Self::Output>;
+
+	/// Selector and encoded arguments of the call
+	fn call_data(&self) -> ChainRequestResult<Vec<u8>> {
+		encode_function_u8(Self::SIGNATURE, &self.inputs())
+			.map_err(|_| ChainRequestError::AbiEncodingError)
+	}
+
+	/// Decodes the data returned by the call, rejecting malformed data
+	fn decode_return_data(data: &[u8]) -> ChainRequestResult<Self::Output> {
+		decode_strict(&Self::output_kinds(), data)
+			.ok()
+			.and_then(Self::from_tokens)
+			.ok_or(ChainRequestError::BadRemoteData)
+	}
+
+	/// Decodes the hex encoded result of an `eth_call`, rejecting malformed data
+	fn decode_output(hex: &[u8]) -> ChainRequestResult<Self::Output> {
+		Self::decode_return_data(&ChainUtils::hex_to_bytes(hex)?)
+	}
+}
+
+fn single(tokens: Vec<Token>) -> Option<Token> {
+	let mut tokens = tokens.into_iter();
+	match (tokens.next(), tokens.next()) {
+		(Some(token), None) => Some(token),
+		_ => None,
+	}
+}
+
+fn pair(tokens: Vec<Token>) -> Option<(Token, Token)> {
+	let mut tokens = tokens.into_iter();
+	match (tokens.next(), tokens.next(), tokens.next()) {
+		(Some(first), Some(second), None) => Some((first, second)),
+		_ => None,
+	}
+}
+
+fn address_output(tokens: Vec<Token>) -> Option<Address> {
+	single(tokens)?.to_address()
+}
+
+fn string_output(tokens: Vec<Token>) -> Option<Vec<u8>> {
+	single(tokens)?.to_string()
+}
+
+/// `LocalBlock(uint64 chainId, uint64 nonce, uint64 timestamp)`
+fn local_block_kind() -> ParamKind {
+	ParamKind::Tuple(vec![
+		Box::new(ParamKind::Uint(64)), // chainId
+		Box::new(ParamKind::Uint(64)), // nonce
+		Box::new(ParamKind::Uint(64)), // timestamp
+	])
+}
+
+fn local_block(token: Token) -> Option<QpLocalBlock> {
+	match token.to_tuple()?.as_slice() {
+		[chain_id, nonce, timestamp] => Some(QpLocalBlock {
+			chain_id: chain_id.clone().to_u64_checked()?,
+			nonce: nonce.clone().to_u64_checked()?,
+			timestamp: timestamp.clone().to_u64_checked()?,
+		}),
+		_ => None,
+	}
+}
+
+/// `MinedBlock(bytes32 blockHash, address miner, uint256 invalidBlock, uint256 stake,
+/// uint256 totalValue, LocalBlock blockMetadata)`
+fn mined_block_kind() -> ParamKind {
+	ParamKind::Tuple(vec![
+		Box::new(ParamKind::FixedBytes(32)), // blockHash
+		Box::new(ParamKind::Address),        // miner
+		Box::new(ParamKind::Uint(256)),      // invalidBlock
+		Box::new(ParamKind::Uint(256)),      // stake
+		Box::new(ParamKind::Uint(256)),      // totalValue
+		Box::new(local_block_kind()),        // blockMetadata
+	])
+}
+
+fn mined_block(token: Token) -> Option<QpRemoteBlock> {
+	match token.to_tuple()?.as_slice() {
+		[block_hash, miner, _invalid_block, stake, total_value, block_metadata] =>
+			Some(QpRemoteBlock {
+				block_hash: H256::from_slice(&block_hash.clone().to_fixed_bytes()?),
+				miner: miner.clone().to_address()?,
+				stake: stake.clone().to_uint()?,
+				total_value: total_value.clone().to_uint()?,
+				block_metadata: local_block(block_metadata.clone())?,
+			}),
+		_ => None,
+	}
+}
+
+/// `RemoteTransaction(uint64 timestamp, address remoteContract, address sourceMsgSender,
+/// address sourceBeneficiary, address token, uint256 amount, bytes[] methods, uint256 gas,
+/// uint256 fixedFee)`
+fn remote_transaction_kind() -> ParamKind {
+	ParamKind::Tuple(vec![
+		Box::new(ParamKind::Uint(64)),                          // timestamp
+		Box::new(ParamKind::Address),                           // remoteContract
+		Box::new(ParamKind::Address),                           // sourceMsgSender
+		Box::new(ParamKind::Address),                           // sourceBeneficiary
+		Box::new(ParamKind::Address),                           // token
+		Box::new(ParamKind::Uint(256)),                         // amount
+		Box::new(ParamKind::Array(Box::new(ParamKind::Bytes))), // methods
+		Box::new(ParamKind::Uint(256)),                         // gas
+		Box::new(ParamKind::Uint(256)),                         // fixedFee
+	])
+}
+
+/// Encodes the transaction as a `RemoteTransaction`, its method being the only entry of
+/// `methods`
+pub fn remote_transaction_token(tx: &QpTransaction) -> Token {
+	Token::Tuple(vec![
+		Token::Uint(U256::from(tx.timestamp)),
+		Token::Address(tx.remote_contract),
+		Token::Address(tx.source_msg_sender),
+		Token::Address(tx.source_beneficiary),
+		Token::Address(tx.token),
+		Token::Uint(tx.amount),
+		Token::Array(vec![Token::Bytes(tx.method.clone())]),
+		Token::Uint(tx.gas),
+		Token::Uint(tx.fixed_fee),
+	])
+}
+
+fn remote_transaction(token: Token) -> Option<QpTransaction> {
+	match token.to_tuple()?.as_slice() {
+		[timestamp, remote_contract, source_msg_sender, source_beneficiary, token, amount, methods, gas, fixed_fee] =>
+		{
+			// `methods` holds the single method of the transaction, see `remote_transaction_token`
+			let mut methods = methods.clone().to_array()?;
+			let method = match methods.len() {
+				0 => vec![],
+				1 => methods.remove(0).to_bytes()?,
+				_ => return None,
+			};
+			Some(QpTransaction {
+				timestamp: timestamp.clone().to_u64_checked()?,
+				remote_contract: remote_contract.clone().to_address()?,
+				source_msg_sender: source_msg_sender.clone().to_address()?,
+				source_beneficiary: source_beneficiary.clone().to_address()?,
+				token: token.clone().to_address()?,
+				amount: amount.clone().to_uint()?,
+				method,
+				gas: gas.clone().to_uint()?,
+				fixed_fee: fixed_fee.clone().to_uint()?,
+			})
+		},
+		_ => None,
+	}
+}
+
+fn remote_transactions(token: Token) -> Option<Vec<QpTransaction>> {
+	token.to_array()?.into_iter().map(remote_transaction).collect()
+}
+
+/// `QuantumPortalGateway`, the entry point configured for every network
+pub mod gateway {
+	use super::*;
+
+	/// `quantumPortalLedgerMgr()`, the ledger manager the gateway routes to
+	pub struct QuantumPortalLedgerMgr;
+
+	impl ContractCall for QuantumPortalLedgerMgr {
+		const SIGNATURE: &'static [u8] = b"quantumPortalLedgerMgr()";
+		type Output = Address;
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![ParamKind::Address]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<Address> {
+			address_output(tokens)
+		}
+	}
+}
+
+/// `QuantumPortalLedgerMgr`, which keeps the local and mined blocks
+pub mod ledger_mgr {
+	use super::*;
+
+	/// `minerMgr()`, the miner manager used by the ledger
+	pub struct MinerMgr;
+
+	impl ContractCall for MinerMgr {
+		const SIGNATURE: &'static [u8] = b"minerMgr()";
+		type Output = Address;
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![ParamKind::Address]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<Address> {
+			address_output(tokens)
+		}
+	}
+
+	/// `authorityMgr()`, the authority manager used by the ledger
+	pub struct AuthorityMgr;
+
+	impl ContractCall for AuthorityMgr {
+		const SIGNATURE: &'static [u8] = b"authorityMgr()";
+		type Output = Address;
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![ParamKind::Address]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<Address> {
+			address_output(tokens)
+		}
+	}
+
+	/// `isLocalBlockReady(uint64)`, whether the open local block for the chain can be mined
+	pub struct IsLocalBlockReady {
+		pub chain_id: u64,
+	}
+
+	impl ContractCall for IsLocalBlockReady {
+		const SIGNATURE: &'static [u8] = b"isLocalBlockReady(uint64)";
+		type Output = bool;
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![Token::Uint(self.chain_id.into())]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![ParamKind::Bool]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<bool> {
+			single(tokens)?.to_bool()
+		}
+	}
+
+	/// `lastRemoteMinedBlock(uint64)`, the last block mined from the given chain
+	pub struct LastRemoteMinedBlock {
+		pub chain_id: u64,
+	}
+
+	impl ContractCall for LastRemoteMinedBlock {
+		const SIGNATURE: &'static [u8] = b"lastRemoteMinedBlock(uint64)";
+		type Output = QpLocalBlock;
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![Token::Uint(self.chain_id.into())]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![local_block_kind()]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<QpLocalBlock> {
+			local_block(single(tokens)?)
+		}
+	}
+
+	/// `getLastFinalizedBlock(uint256)`, the last finalized block mined from the given chain
+	pub struct GetLastFinalizedBlock {
+		pub chain_id: u64,
+	}
+
+	impl ContractCall for GetLastFinalizedBlock {
+		const SIGNATURE: &'static [u8] = b"getLastFinalizedBlock(uint256)";
+		type Output = QpLocalBlock;
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![Token::Uint(self.chain_id.into())]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![local_block_kind()]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<QpLocalBlock> {
+			local_block(single(tokens)?)
+		}
+	}
+
+	/// `getLastLocalBlock(uint256)`, the last local block targeting the given chain
+	pub struct GetLastLocalBlock {
+		pub chain_id: u64,
+	}
+
+	impl ContractCall for GetLastLocalBlock {
+		const SIGNATURE: &'static [u8] = b"getLastLocalBlock(uint256)";
+		type Output = QpLocalBlock;
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![Token::Uint(self.chain_id.into())]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![local_block_kind()]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<QpLocalBlock> {
+			local_block(single(tokens)?)
+		}
+	}
+
+	/// `localBlockByNonce(uint64,uint64)`, a local block and its transactions
+	pub struct LocalBlockByNonce {
+		pub chain_id: u64,
+		pub nonce: u64,
+	}
+
+	impl ContractCall for LocalBlockByNonce {
+		const SIGNATURE: &'static [u8] = b"localBlockByNonce(uint64,uint64)";
+		type Output = (QpLocalBlock, Vec<QpTransaction>);
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![Token::Uint(self.chain_id.into()), Token::Uint(self.nonce.into())]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![local_block_kind(), ParamKind::Array(Box::new(remote_transaction_kind()))]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<Self::Output> {
+			let (block, txs) = pair(tokens)?;
+			Some((local_block(block)?, remote_transactions(txs)?))
+		}
+	}
+
+	/// `minedBlockByNonce(uint64,uint64)`, a mined block and its transactions
+	pub struct MinedBlockByNonce {
+		pub chain_id: u64,
+		pub nonce: u64,
+	}
+
+	impl ContractCall for MinedBlockByNonce {
+		const SIGNATURE: &'static [u8] = b"minedBlockByNonce(uint64,uint64)";
+		type Output = (QpRemoteBlock, Vec<QpTransaction>);
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![Token::Uint(self.chain_id.into()), Token::Uint(self.nonce.into())]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![mined_block_kind(), ParamKind::Array(Box::new(remote_transaction_kind()))]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<Self::Output> {
+			let (block, txs) = pair(tokens)?;
+			Some((mined_block(block)?, remote_transactions(txs)?))
+		}
+	}
+
+	/// `mineRemoteBlock(...)`, mines a block of the remote chain with a miner signature
+	pub struct MineRemoteBlock<'a> {
+		pub remote_chain_id: u64,
+		pub block_nonce: u64,
+		pub transactions: &'a [QpTransaction],
+		pub salt: H256,
+		pub expiry: u64,
+		pub multi_signature: Vec<u8>,
+	}
+
+	impl ContractCall for MineRemoteBlock<'_> {
+		const SIGNATURE: &'static [u8] = b"mineRemoteBlock(uint64,uint64,(uint64,address,address,address,address,uint256,bytes[],uint256,uint256)[],bytes32,uint64,bytes)";
+		type Output = ();
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![
+				Token::Uint(self.remote_chain_id.into()),
+				Token::Uint(self.block_nonce.into()),
+				Token::Array(self.transactions.iter().map(remote_transaction_token).collect()),
+				Token::FixedBytes(self.salt.as_bytes().to_vec()),
+				Token::Uint(self.expiry.into()),
+				Token::Bytes(self.multi_signature.clone()),
+			]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![]
+		}
+
+		fn from_tokens(_tokens: Vec<Token>) -> Option<()> {
+			Some(())
+		}
+	}
+
+	/// `finalizeSingleSigner(...)`, finalizes a mined block with the finalizers' multi signature
+	pub struct FinalizeSingleSigner {
+		pub remote_chain_id: u64,
+		pub block_nonce: u64,
+		pub invalid_block_nonces: Vec<u64>,
+		pub finalizers_hash: H256,
+		pub finalizers: Vec<Address>,
+		pub salt: H256,
+		pub expiry: u64,
+		pub multi_signature: Vec<u8>,
+	}
+
+	impl ContractCall for FinalizeSingleSigner {
+		const SIGNATURE: &'static [u8] =
+			b"finalizeSingleSigner(uint256,uint256,uint256[],bytes32,address[],bytes32,uint64,bytes)";
+		type Output = ();
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![
+				Token::Uint(self.remote_chain_id.into()),
+				Token::Uint(self.block_nonce.into()),
+				Token::Array(
+					self.invalid_block_nonces.iter().map(|n| Token::Uint((*n).into())).collect(),
+				),
+				Token::FixedBytes(self.finalizers_hash.as_bytes().to_vec()),
+				Token::Array(self.finalizers.iter().map(|a| Token::Address(*a)).collect()),
+				Token::FixedBytes(self.salt.as_bytes().to_vec()),
+				Token::Uint(self.expiry.into()),
+				Token::Bytes(self.multi_signature.clone()),
+			]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![]
+		}
+
+		fn from_tokens(_tokens: Vec<Token>) -> Option<()> {
+			Some(())
+		}
+	}
+}
+
+/// `QuantumPortalMinerMgr`, which assigns mining slots and verifies miner signatures
+pub mod miner_mgr {
+	use super::*;
+
+	/// `NAME()`, the EIP712 domain name of the contract
+	pub struct Name;
+
+	impl ContractCall for Name {
+		const SIGNATURE: &'static [u8] = b"NAME()";
+		type Output = Vec<u8>;
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![ParamKind::String]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<Vec<u8>> {
+			string_output(tokens)
+		}
+	}
+
+	/// `VERSION()`, the EIP712 domain version of the contract
+	pub struct Version;
+
+	impl ContractCall for Version {
+		const SIGNATURE: &'static [u8] = b"VERSION()";
+		type Output = Vec<u8>;
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![ParamKind::String]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<Vec<u8>> {
+			string_output(tokens)
+		}
+	}
+
+	/// `findMinerAtTime(bytes32,uint256,uint256)`, the miner assigned to mine the block
+	pub struct FindMinerAtTime {
+		pub block_hash: H256,
+		pub block_timestamp: u64,
+		pub chain_timestamp: u64,
+	}
+
+	impl ContractCall for FindMinerAtTime {
+		const SIGNATURE: &'static [u8] = b"findMinerAtTime(bytes32,uint256,uint256)";
+		type Output = Address;
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![
+				Token::FixedBytes(self.block_hash.as_bytes().to_vec()),
+				Token::Uint(self.block_timestamp.into()),
+				Token::Uint(self.chain_timestamp.into()),
+			]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![ParamKind::Address]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<Address> {
+			address_output(tokens)
+		}
+	}
+}
+
+/// `QuantumPortalAuthorityMgr`, which verifies finalizer signatures. Its EIP712 domain is read
+/// the same way as the miner manager's
+pub mod authority_mgr {
+	pub use super::miner_mgr::{Name, Version};
+}
+
+/// `Multicall3`, deployed at the same address on most chains, which runs a batch of calls in a
+/// single `eth_call`
+pub mod multicall {
+	use super::*;
+
+	/// A call of the batch, the batch still succeeds if a call that allows failure reverts
+	pub struct Call3 {
+		pub target: Address,
+		pub allow_failure: bool,
+		pub call_data: Vec<u8>,
+	}
+
+	impl Call3 {
+		/// Batches a call of the `target` contract, allowing it to fail
+		pub fn new<C: ContractCall>(target: Address, call: &C) -> ChainRequestResult<Self> {
+			Ok(Call3 { target, allow_failure: true, call_data: call.call_data()? })
+		}
+	}
+
+	/// `aggregate3((address,bool,bytes)[])`, whether each call succeeded and what it returned
+	pub struct Aggregate3 {
+		pub calls: Vec<Call3>,
+	}
+
+	impl ContractCall for Aggregate3 {
+		const SIGNATURE: &'static [u8] = b"aggregate3((address,bool,bytes)[])";
+		type Output = Vec<(bool, Vec<u8>)>;
+
+		fn inputs(&self) -> Vec<Token> {
+			vec![Token::Array(
+				self.calls
+					.iter()
+					.map(|call| {
+						Token::Tuple(vec![
+							Token::Address(call.target),
+							Token::Bool(call.allow_failure),
+							Token::Bytes(call.call_data.clone()),
+						])
+					})
+					.collect(),
+			)]
+		}
+
+		fn output_kinds() -> Vec<ParamKind> {
+			vec![ParamKind::Array(Box::new(ParamKind::Tuple(vec![
+				Box::new(ParamKind::Bool),  // success
+				Box::new(ParamKind::Bytes), // returnData
+			])))]
+		}
+
+		fn from_tokens(tokens: Vec<Token>) -> Option<Self::Output> {
+			single(tokens)?
+				.to_array()?
+				.into_iter()
+				.map(|result| match result.to_tuple()?.as_slice() {
+					[success, return_data] =>
+						Some((success.clone().to_bool()?, return_data.clone().to_bytes()?)),
+					_ => None,
+				})
+				.collect()
+		}
+	}
+
+	/// Decodes the result of a batched call, failing if the call reverted
+	pub fn decode_result<C: ContractCall>(
+		(success, return_data): (bool, Vec<u8>),
+	) -> ChainRequestResult<C::Output> {
+		if !success {
+			return Err(ChainRequestError::CallReverted);
+		}
+		C::decode_return_data(&return_data)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		authority_mgr, gateway,
+		ledger_mgr::*,
+		miner_mgr,
+		multicall::{decode_result, Aggregate3, Call3},
+		ContractCall,
+	};
+	use crate::{
+		chain_utils::{ChainRequestError, ChainUtils},
+		qp_types::QpTransaction,
+	};
+	use ethabi_nostd::{encode, encoder::encode_function_u8, Address, Contract, Token};
+	use sp_core::{H256, U256};
+
+	// ABIs of the deployed contracts, trimmed to the functions the worker calls
+	const GATEWAY_ABI: &[u8] = include_bytes!("../abi/QuantumPortalGateway.json");
+	const LEDGER_MGR_ABI: &[u8] = include_bytes!("../abi/QuantumPortalLedgerMgr.json");
+	const MINER_MGR_ABI: &[u8] = include_bytes!("../abi/QuantumPortalMinerMgr.json");
+	const AUTHORITY_MGR_ABI: &[u8] = include_bytes!("../abi/QuantumPortalAuthorityMgr.json");
+	const MULTICALL_ABI: &[u8] = include_bytes!("../abi/Multicall3.json");
+
+	/// Checks the signature, inputs and outputs of the call against the function of the contract
+	/// ABI
+	fn assert_matches_abi<C: ContractCall>(abi: &[u8], call: &C) {
+		let signature = sp_std::str::from_utf8(C::SIGNATURE).unwrap();
+		let contract = Contract::load(abi).expect("the ABI is valid");
+		let function = contract
+			.function_by_signature(signature)
+			.unwrap_or_else(|_| panic!("{signature} is not in the ABI"));
+
+		assert_eq!(function.signature(), C::SIGNATURE);
+		assert_eq!(function.output_kinds(), C::output_kinds(), "outputs of {signature}");
+
+		let call_data = call.call_data();
+		assert!(call_data.is_ok(), "inputs do not match {signature}");
+		assert_eq!(call_data.ok(), function.encode_input(&call.inputs()).ok(), "{signature}");
+	}
+
+	fn output_hex(tokens: &[Token]) -> Vec<u8> {
+		ChainUtils::hex_add_0x(&ChainUtils::bytes_to_hex(&encode(tokens)))
+	}
+
+	fn transaction() -> QpTransaction {
+		QpTransaction {
+			timestamp: 1_700_000_000,
+			remote_contract: Address::repeat_byte(1),
+			source_msg_sender: Address::repeat_byte(2),
+			source_beneficiary: Address::repeat_byte(3),
+			token: Address::repeat_byte(4),
+			amount: U256::from(1000),
+			method: vec![0xde, 0xad, 0xbe, 0xef],
+			gas: U256::from(u64::MAX) + 1,
+			fixed_fee: U256::from(7),
+		}
+	}
+
+	fn local_block_token(chain_id: u64, nonce: u64, timestamp: u64) -> Token {
+		Token::Tuple(vec![
+			Token::Uint(chain_id.into()),
+			Token::Uint(nonce.into()),
+			Token::Uint(timestamp.into()),
+		])
+	}
+
+	#[test]
+	fn calls_match_abi() {
+		assert_matches_abi(GATEWAY_ABI, &gateway::QuantumPortalLedgerMgr);
+		assert_matches_abi(LEDGER_MGR_ABI, &MinerMgr);
+		assert_matches_abi(LEDGER_MGR_ABI, &AuthorityMgr);
+		assert_matches_abi(LEDGER_MGR_ABI, &IsLocalBlockReady { chain_id: 1 });
+		assert_matches_abi(LEDGER_MGR_ABI, &LastRemoteMinedBlock { chain_id: 1 });
+		assert_matches_abi(LEDGER_MGR_ABI, &GetLastFinalizedBlock { chain_id: 1 });
+		assert_matches_abi(LEDGER_MGR_ABI, &GetLastLocalBlock { chain_id: 1 });
+		assert_matches_abi(LEDGER_MGR_ABI, &LocalBlockByNonce { chain_id: 1, nonce: 2 });
+		assert_matches_abi(LEDGER_MGR_ABI, &MinedBlockByNonce { chain_id: 1, nonce: 2 });
+		assert_matches_abi(
+			LEDGER_MGR_ABI,
+			&MineRemoteBlock {
+				remote_chain_id: 1,
+				block_nonce: 2,
+				transactions: &[transaction()],
+				salt: H256::zero(),
+				expiry: 3,
+				multi_signature: vec![0u8; 96],
+			},
+		);
+		assert_matches_abi(
+			LEDGER_MGR_ABI,
+			&FinalizeSingleSigner {
+				remote_chain_id: 1,
+				block_nonce: 2,
+				invalid_block_nonces: vec![2],
+				finalizers_hash: H256::repeat_byte(5),
+				finalizers: vec![Address::repeat_byte(6)],
+				salt: H256::repeat_byte(5),
+				expiry: 3,
+				multi_signature: vec![0u8; 96],
+			},
+		);
+		assert_matches_abi(MINER_MGR_ABI, &miner_mgr::Name);
+		assert_matches_abi(AUTHORITY_MGR_ABI, &authority_mgr::Version);
+		assert_matches_abi(
+			MINER_MGR_ABI,
+			&miner_mgr::FindMinerAtTime {
+				block_hash: H256::repeat_byte(7),
+				block_timestamp: 1,
+				chain_timestamp: 2,
+			},
+		);
+	}
+
+	#[test]
+	fn decodes_simple_outputs() {
+		let address = Address::repeat_byte(9);
+		assert_eq!(
+			gateway::QuantumPortalLedgerMgr::decode_output(&output_hex(&[Token::Address(address)])),
+			Ok(address)
+		);
+		assert_eq!(IsLocalBlockReady::decode_output(&output_hex(&[Token::Bool(true)])), Ok(true));
+		assert_eq!(
+			miner_mgr::Name::decode_output(&output_hex(&[Token::String(b"FERRUM".to_vec())])),
+			Ok(b"FERRUM".to_vec())
+		);
+
+		let block =
+			GetLastLocalBlock::decode_output(&output_hex(&[local_block_token(1, 2, 3)])).unwrap();
+		assert_eq!((block.chain_id, block.nonce, block.timestamp), (1, 2, 3));
+	}
+
+	#[test]
+	fn decodes_blocks_with_transactions() {
+		let tx = transaction();
+		let txs = Token::Array(vec![super::remote_transaction_token(&tx)]);
+
+		let (block, decoded) = LocalBlockByNonce::decode_output(&output_hex(&[
+			local_block_token(1, 2, 3),
+			txs.clone(),
+		]))
+		.unwrap();
+		assert_eq!(block.nonce, 2);
+		assert_eq!(decoded, vec![transaction()]);
+
+		let mined = Token::Tuple(vec![
+			Token::FixedBytes(H256::repeat_byte(8).as_bytes().to_vec()),
+			Token::Address(Address::repeat_byte(9)),
+			Token::Uint(0.into()),
+			Token::Uint(10.into()),
+			Token::Uint(20.into()),
+			local_block_token(1, 2, 3),
+		]);
+		let (block, decoded) =
+			MinedBlockByNonce::decode_output(&output_hex(&[mined, txs])).unwrap();
+		assert_eq!(block.block_hash, H256::repeat_byte(8));
+		assert_eq!(block.miner, Address::repeat_byte(9));
+		assert_eq!((block.stake, block.total_value), (10.into(), 20.into()));
+		assert_eq!(block.block_metadata.timestamp, 3);
+		assert_eq!(decoded, vec![transaction()]);
+	}
+
+	#[test]
+	fn rejects_malformed_outputs() {
+		// an address with dirty high bytes
+		let mut dirty = encode(&[Token::Address(Address::repeat_byte(9))]);
+		dirty[0] = 1;
+		let dirty = ChainUtils::hex_add_0x(&ChainUtils::bytes_to_hex(&dirty));
+		assert_eq!(
+			gateway::QuantumPortalLedgerMgr::decode_output(&dirty),
+			Err(ChainRequestError::BadRemoteData)
+		);
+
+		// a block value that does not fit the `uint64` of the struct
+		let block = Token::Tuple(vec![
+			Token::Uint(1.into()),
+			Token::Uint(U256::from(u64::MAX) + 1),
+			Token::Uint(3.into()),
+		]);
+		assert_eq!(
+			GetLastLocalBlock::decode_output(&output_hex(&[block])).map(|b| b.nonce),
+			Err(ChainRequestError::BadRemoteData)
+		);
+
+		// a transaction with more than one method
+		let mut tx = super::remote_transaction_token(&transaction()).to_tuple().unwrap();
+		tx[6] = Token::Array(vec![Token::Bytes(vec![1]), Token::Bytes(vec![2])]);
+		let output =
+			output_hex(&[local_block_token(1, 2, 3), Token::Array(vec![Token::Tuple(tx)])]);
+		assert!(LocalBlockByNonce::decode_output(&output).is_err());
+	}
+
+	#[test]
+	fn aggregates_calls() {
+		let ledger = Address::repeat_byte(1);
+		let call = Aggregate3 {
+			calls: vec![
+				Call3::new(ledger, &IsLocalBlockReady { chain_id: 1 }).unwrap(),
+				Call3::new(ledger, &GetLastLocalBlock { chain_id: 1 }).unwrap(),
+			],
+		};
+		assert_matches_abi(MULTICALL_ABI, &call);
+		assert_eq!(
+			call.calls[0].call_data,
+			encode_function_u8(b"isLocalBlockReady(uint64)", &[Token::Uint(1.into())]).unwrap()
+		);
+
+		let results = Aggregate3::decode_output(&output_hex(&[Token::Array(vec![
+			Token::Tuple(vec![Token::Bool(true), Token::Bytes(encode(&[Token::Bool(true)]))]),
+			Token::Tuple(vec![Token::Bool(false), Token::Bytes(vec![])]),
+		])]))
+		.unwrap();
+		assert_eq!(results.len(), 2);
+
+		let mut results = results.into_iter();
+		assert_eq!(decode_result::<IsLocalBlockReady>(results.next().unwrap()), Ok(true));
+		assert_eq!(
+			decode_result::<GetLastLocalBlock>(results.next().unwrap()).map(|b| b.nonce),
+			Err(ChainRequestError::CallReverted)
+		);
+	}
+}
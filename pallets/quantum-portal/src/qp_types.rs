@@ -16,12 +16,26 @@
 use ethabi_nostd::{Address, Token};
 use parity_scale_codec::{Decode, Encode};
 use serde::{Deserialize, Serialize};
-use sp_core::{H256, U256};
+use sp_core::{ConstU32, H160, H256, U256};
+use sp_runtime::BoundedVec;
 use sp_std::{prelude::*, str};
 
 pub type ChainId = u64;
 pub type BlockNumber = u64;
 
+// Safety margin applied to gas estimates when the network config does not set one
+pub const DEFAULT_GAS_LIMIT_MULTIPLIER: u32 = 120;
+
+// Lowest accepted gas limit multiplier, anything below would send with less gas than estimated
+pub const MIN_GAS_LIMIT_MULTIPLIER: u32 = 100;
+
+pub fn default_gas_limit_multiplier() -> u32 {
+	DEFAULT_GAS_LIMIT_MULTIPLIER
+}
+
+// Length of a finalizer signature, `r || s || v` padded with zeros to 96 bytes
+pub const FINALIZER_SIGNATURE_LENGTH: u32 = 96;
+
 // Limit on how many pairs to mine,
 // The current limit is 6, means mining both ways on 3 seperate chains
 pub const MAX_PAIRS_TO_MINE: usize = 6;
@@ -85,6 +99,23 @@ pub struct QpConfig {
 	pub pair_vec: Vec<(u64, u64)>,
 	pub signer_public_key: Vec<u8>,
 	pub role: Role,
+	// Simulate mine and finalize transactions instead of broadcasting them
+	#[serde(default)]
+	pub dry_run: bool,
+}
+
+impl QpConfig {
+	/// Checks the values that cannot be rejected by deserialization alone
+	pub fn validate(&self) -> Result<(), &'static str> {
+		if self
+			.network_vec
+			.iter()
+			.any(|network| network.gas_limit_multiplier < MIN_GAS_LIMIT_MULTIPLIER)
+		{
+			return Err("gas_limit_multiplier must be at least 100");
+		}
+		Ok(())
+	}
 }
 
 #[derive(
@@ -96,6 +127,66 @@ pub struct QpNetworkItem {
 	// #[serde(with = "serde_bytes")]
 	pub gateway_contract_address: Vec<u8>,
 	pub id: u64,
+	// Number of blocks remote reads and receipts must be behind the chain head
+	#[serde(default)]
+	pub confirmations: u64,
+	// Percentage applied to the estimated gas limit of sent transactions
+	#[serde(default = "default_gas_limit_multiplier")]
+	pub gas_limit_multiplier: u32,
+	// Highest gas price in wei the worker pays on this network, zero disables the cap
+	#[serde(default)]
+	pub max_gas_price: u128,
+	// Wei the worker may spend on this network in a rolling day, zero disables the budget
+	#[serde(default)]
+	pub daily_spend_budget: u128,
+	// Multicall3 contract used to batch remote reads, empty sends every read on its own
+	#[serde(default)]
+	pub multicall_address: Vec<u8>,
+}
+
+/// EIP712 domain of the authority manager contract that verifies finalizer signatures, used
+/// to check submitted signatures on chain
+#[derive(Clone, Eq, PartialEq, Decode, Encode, Debug, scale_info::TypeInfo)]
+pub struct SignatureDomain {
+	pub name: Vec<u8>,
+	pub version: Vec<u8>,
+	// chain id the finalize transaction is posted to
+	pub chain_id: ChainId,
+	pub verifying_contract: H160,
+}
+
+/// Finalizer signature submitted as an unsigned transaction. The EIP712 `signature` covers every
+/// other field and has to recover to `finalizer`, so it doubles as the payload signature
+#[derive(Clone, Eq, PartialEq, Decode, Encode, Debug, scale_info::TypeInfo)]
+pub struct FinalizerSignaturePayload {
+	pub finalizer: H160,
+	pub chain_id: ChainId,
+	pub block_number: BlockNumber,
+	pub finalizer_hash: H256,
+	pub salt: H256,
+	pub expiry: u64,
+	pub signature: BoundedVec<u8, ConstU32<FINALIZER_SIGNATURE_LENGTH>>,
+}
+
+/// Outcome of the last simulated call of a contract function on a chain, kept in offchain
+/// storage under `quantum-portal::dry-run::<chain id>::<function name>` so it can be read with
+/// `offchain_localStorageGet`. A call that reverted is not signed, its gas values are zero and
+/// its signed transaction is empty. The finalizer signatures are not sent to a contract, the
+/// dry run stores the `FinalizerSignaturePayload` that would have been submitted instead, under
+/// the `submitSignature` function.
+#[derive(Clone, Eq, PartialEq, Decode, Encode, Debug, scale_info::TypeInfo)]
+pub struct DryRunRecord {
+	pub chain_id: u64,
+	pub timestamp: u64,
+	pub to: H160,
+	pub method_signature: Vec<u8>,
+	pub calldata: Vec<u8>,
+	pub signed_transaction: Vec<u8>,
+	pub gas_limit: U256,
+	pub gas_price: U256,
+	pub succeeded: bool,
+	// Return data of the simulated call, or the revert message if it failed
+	pub output: Vec<u8>,
 }
 
 #[allow(non_camel_case_types)]
@@ -15,14 +15,22 @@
 // along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
 #![cfg_attr(not(feature = "std"), no_std)]
 use crate::{
-	chain_queries::CallResponse,
+	chain_queries::BlockTag,
 	chain_utils::{ChainRequestError, ChainRequestResult, ChainUtils, TransactionCreationError},
 	contract_client::{ContractClient, ContractClientSignature},
 	eip_712_utils::EIP712Utils,
-	qp_types::{QpLocalBlock, QpRemoteBlock, QpTransaction},
-	Config, Error, FinalizerThreshold, PendingFinalizeSignatures,
+	qp_contracts::{
+		ledger_mgr::{
+			FinalizeSingleSigner, GetLastFinalizedBlock, GetLastLocalBlock, IsLocalBlockReady,
+			LastRemoteMinedBlock, LocalBlockByNonce, MineRemoteBlock, MinedBlockByNonce,
+		},
+		multicall::{self, Aggregate3, Call3},
+		remote_transaction_token, ContractCall,
+	},
+	qp_types::{FinalizerSignaturePayload, QpLocalBlock, QpRemoteBlock, QpTransaction},
+	Config, FinalizerThreshold, Pallet, PendingFinalizeSignatures, PendingSignatureExpiry,
 };
-use ethabi_nostd::{decoder::decode, ParamKind, Token};
+use ethabi_nostd::Token;
 use frame_system::offchain::{
 	AppCrypto, CreateSignedTransaction, SendSignedTransaction, SendUnsignedTransaction,
 	SignedPayload, Signer, SigningTypes, SubmitTransaction,
@@ -30,10 +38,15 @@ use frame_system::offchain::{
 use sp_core::{H256, U256};
 use sp_std::{marker::PhantomData, prelude::*};
 
-#[allow(dead_code)]
-const DUMMY_HASH: H256 = H256::zero();
 const ZERO_HASH: H256 = H256::zero();
 
+/// The local blocks a remote chain holds for a target chain, as read by the miner
+pub struct SourceBlocks {
+	pub last_block: QpLocalBlock,
+	/// The block to mine next and its transactions, if the remote chain has it yet
+	pub next_block: Option<(QpLocalBlock, Vec<QpTransaction>)>,
+}
+
 pub struct QuantumPortalClient<T: Config> {
 	pub contract: ContractClient,
 	pub signer: ContractClientSignature,
@@ -42,123 +55,6 @@ pub struct QuantumPortalClient<T: Config> {
 	_phantom: PhantomData<T>,
 }
 
-fn local_block_tuple0() -> Vec<ParamKind> {
-	vec![ParamKind::Uint(256), ParamKind::Uint(256), ParamKind::Uint(256)]
-}
-
-fn local_block_tuple() -> ParamKind {
-	ParamKind::Tuple(vec![
-		Box::new(ParamKind::Uint(256)),
-		Box::new(ParamKind::Uint(256)),
-		Box::new(ParamKind::Uint(256)),
-	])
-}
-
-fn decode_remote_block_and_txs<T, F>(
-	data: &[u8],
-	mined_block_tuple: ParamKind,
-	block_tuple_decoder: F,
-) -> ChainRequestResult<(T, Vec<QpTransaction>)>
-where
-	F: Fn(Token) -> ChainRequestResult<T>,
-{
-	log::info!("decode_remote_block_and_txs {:?}", data);
-	// let dec = decode(
-	//     &[
-	//         ParamKind::Tuple(vec![
-	//             Box::new(mined_block_tuple),
-	//             Box::new(ParamKind::Array(
-	//                 Box::new(ParamKind::Tuple(vec![         // RemoteTransaction[]
-	//                                                         Box::new(ParamKind::Uint(256)),
-	// // timestamp
-	// Box::new(ParamKind::Address),       // remoteContract
-	// Box::new(ParamKind::Address),       // sourceMsgSender
-	// Box::new(ParamKind::Address),       // sourceBeneficiary
-	// Box::new(ParamKind::Address),       // token
-	// Box::new(ParamKind::Uint(256)),     // amount
-	// Box::new(ParamKind::Bytes),         // method
-	// Box::new(ParamKind::Uint(256)),     // gas                 ]))))
-	//         ])],
-	//     ChainUtils::hex_to_bytes(&data)?.as_slice(),
-	// ).unwrap();
-	let dec = decode(
-		&[
-			mined_block_tuple,
-			ParamKind::Array(Box::new(ParamKind::Tuple(vec![
-				// RemoteTransaction[]
-				Box::new(ParamKind::Uint(256)),                         // timestamp
-				Box::new(ParamKind::Address),                           // remoteContract
-				Box::new(ParamKind::Address),                           // sourceMsgSender
-				Box::new(ParamKind::Address),                           // sourceBeneficiary
-				Box::new(ParamKind::Address),                           // token
-				Box::new(ParamKind::Uint(256)),                         // amount
-				Box::new(ParamKind::Array(Box::new(ParamKind::Bytes))), // method
-				Box::new(ParamKind::Uint(256)),                         // gas
-				Box::new(ParamKind::Uint(256)),                         // fixedFee
-			]))),
-		],
-		ChainUtils::hex_to_bytes(data)?.as_slice(),
-	)
-	.unwrap();
-	log::info!("decoded {:?}, - {}", dec, dec.as_slice().len());
-	let dec: ChainRequestResult<Vec<Token>> = match dec.as_slice() {
-		[tuple, txs] => Ok(vec![tuple.clone(), txs.clone()]),
-		_ => Err(b"Unexpected output. Could not decode local block at first level"
-			.as_slice()
-			.into()),
-	};
-	let dec = dec?;
-	log::info!("decoded = 2 | {:?}, - {}", dec, dec.as_slice().len());
-	match dec.as_slice() {
-		[mined_block, remote_transactions] => {
-			let mined_block = mined_block.clone();
-			let remote_transactions = remote_transactions.clone();
-			log::info!("PRE = Mined block is opened up");
-			let block = block_tuple_decoder(mined_block)?;
-			log::info!("Mined block is opened up == {:?}", remote_transactions);
-			let remote_transactions = remote_transactions
-				.to_array()
-				.unwrap()
-				.into_iter()
-				.map(|t| {
-					decode_remote_transaction_from_tuple(t.to_tuple().unwrap().as_slice()).unwrap()
-				})
-				.collect();
-			Ok((block, remote_transactions))
-		},
-		_ => Err(b"Unexpected output. Could not decode local block".as_slice().into()),
-	}
-}
-
-fn decode_remote_transaction_from_tuple(dec: &[Token]) -> ChainRequestResult<QpTransaction> {
-	match dec {
-		[timestamp, remote_contract, source_msg_sender, source_beneficiary, token, amount, method, gas, fixed_fee] =>
-		{
-			let timestamp = timestamp.clone().to_uint().unwrap().as_u64();
-			let remote_contract = remote_contract.clone().to_address().unwrap();
-			let source_msg_sender = source_msg_sender.clone().to_address().unwrap();
-			let source_beneficiary = source_beneficiary.clone().to_address().unwrap();
-			let token = token.clone().to_address().unwrap();
-			let amount = amount.clone().to_uint().unwrap();
-			let fixed_fee = fixed_fee.clone().to_uint().unwrap();
-			let method = method.clone().to_bytes().unwrap();
-			let gas = gas.clone().to_uint().unwrap().as_u64();
-			Ok(QpTransaction {
-				timestamp,
-				remote_contract,
-				source_msg_sender,
-				source_beneficiary,
-				token,
-				amount,
-				method,
-				gas: gas.into(),
-				fixed_fee,
-			})
-		},
-		_ => Err(b"Unexpected output. Could not decode remote transaction".as_slice().into()),
-	}
-}
-
 impl<T: Config> QuantumPortalClient<T> {
 	pub fn new(
 		contract: ContractClient,
@@ -169,53 +65,80 @@ impl<T: Config> QuantumPortalClient<T> {
 		QuantumPortalClient { contract, signer, now, block_number, _phantom: Default::default() }
 	}
 
-	pub fn is_local_block_ready(&self, chain_id: u64) -> ChainRequestResult<bool> {
-		let signature = b"isLocalBlockReady(uint64)";
-		let res: Box<CallResponse> =
-			self.contract.call(signature, &[Token::Uint(U256::from(chain_id))], None)?;
-		let val = ChainUtils::hex_to_u256(&res.result)?;
-		Ok(!val.is_zero())
+	pub fn is_local_block_ready(&self, chain_id: u64, block: BlockTag) -> ChainRequestResult<bool> {
+		self.contract.query(&IsLocalBlockReady { chain_id }, None, block)
 	}
 
 	pub fn last_remote_mined_block(&self, chain_id: u64) -> ChainRequestResult<QpLocalBlock> {
-		let signature = b"lastRemoteMinedBlock(uint64)";
-		let res: Box<CallResponse> =
-			self.contract.call(signature, &[Token::Uint(U256::from(chain_id))], None)?;
-		self.decode_local_block(res.result.as_slice())
+		self.contract.query(&LastRemoteMinedBlock { chain_id }, None, BlockTag::Latest)
 	}
 
 	pub fn last_finalized_block(&self, chain_id: u64) -> ChainRequestResult<QpLocalBlock> {
-		let signature = b"getLastFinalizedBlock(uint256)";
-		let res: Box<CallResponse> =
-			self.contract.call(signature, &[Token::Uint(U256::from(chain_id))], None)?;
-		self.decode_local_block(res.result.as_slice())
+		self.contract.query(&GetLastFinalizedBlock { chain_id }, None, BlockTag::Latest)
 	}
 
-	pub fn last_local_block(&self, chain_id: u64) -> ChainRequestResult<QpLocalBlock> {
-		let signature = b"getLastLocalBlock(uint256)";
-		let res: Box<CallResponse> =
-			self.contract.call(signature, &[Token::Uint(U256::from(chain_id))], None)?;
-		self.decode_local_block(res.result.as_slice())
+	pub fn last_local_block(
+		&self,
+		chain_id: u64,
+		block: BlockTag,
+	) -> ChainRequestResult<QpLocalBlock> {
+		self.contract.query(&GetLastLocalBlock { chain_id }, None, block)
 	}
 
 	pub fn local_block_by_nonce(
 		&self,
 		chain_id: u64,
 		last_block_nonce: u64,
+		block: BlockTag,
 	) -> ChainRequestResult<(QpLocalBlock, Vec<QpTransaction>)> {
-		let signature = b"localBlockByNonce(uint64,uint64)";
-		let res: Box<CallResponse> = self.contract.call(
-			signature,
-			&[Token::Uint(U256::from(chain_id)), Token::Uint(U256::from(last_block_nonce))],
-			None,
-		)?;
-		decode_remote_block_and_txs(res.result.as_slice(), local_block_tuple(), |block| {
-			log::info!("1-DECODING BLOCK {:?}", block);
-			let block = block.to_tuple();
-			let block = block.unwrap();
-			log::info!("2-DECODING BLOCK {:?}", block);
-			Self::decode_local_block_from_tuple(block.as_slice())
-		})
+		self.contract
+			.query(&LocalBlockByNonce { chain_id, nonce: last_block_nonce }, None, block)
+	}
+
+	/// Reads the last local block for `chain_id` and the block with nonce `next_nonce`, or `None`
+	/// if the local block is not ready. With a multicall contract this is a single `eth_call`.
+	pub fn source_blocks(
+		&self,
+		chain_id: u64,
+		next_nonce: u64,
+		block: BlockTag,
+	) -> ChainRequestResult<Option<SourceBlocks>> {
+		let multicall_address = match self.contract.multicall_address {
+			Some(address) => address,
+			None => {
+				if !self.is_local_block_ready(chain_id, block)? {
+					return Ok(None);
+				}
+				let last_block = self.last_local_block(chain_id, block)?;
+				let next_block = if next_nonce <= last_block.nonce {
+					Some(self.local_block_by_nonce(chain_id, next_nonce, block)?)
+				} else {
+					None
+				};
+				return Ok(Some(SourceBlocks { last_block, next_block }));
+			},
+		};
+
+		let ledger_mgr = self.contract.get_ledger_manager_address()?;
+		let calls = vec![
+			Call3::new(ledger_mgr, &IsLocalBlockReady { chain_id })?,
+			Call3::new(ledger_mgr, &GetLastLocalBlock { chain_id })?,
+			Call3::new(ledger_mgr, &LocalBlockByNonce { chain_id, nonce: next_nonce })?,
+		];
+		let results = self.contract.query(&Aggregate3 { calls }, Some(multicall_address), block)?;
+		let [ready, last_block, next_block] =
+			<[_; 3]>::try_from(results).map_err(|_| ChainRequestError::BadRemoteData)?;
+
+		if !multicall::decode_result::<IsLocalBlockReady>(ready)? {
+			return Ok(None);
+		}
+		let last_block = multicall::decode_result::<GetLastLocalBlock>(last_block)?;
+		let next_block = if next_nonce <= last_block.nonce {
+			Some(multicall::decode_result::<LocalBlockByNonce>(next_block)?)
+		} else {
+			None
+		};
+		Ok(Some(SourceBlocks { last_block, next_block }))
 	}
 
 	pub fn mined_block_by_nonce(
@@ -223,40 +146,17 @@ impl<T: Config> QuantumPortalClient<T> {
 		chain_id: u64,
 		last_block_nonce: u64,
 	) -> ChainRequestResult<(QpRemoteBlock, Vec<QpTransaction>)> {
-		let signature = b"minedBlockByNonce(uint64,uint64)";
-		let res: Box<CallResponse> = self.contract.call(
-			signature,
-			&[Token::Uint(U256::from(chain_id)), Token::Uint(U256::from(last_block_nonce))],
+		self.contract.query(
+			&MinedBlockByNonce { chain_id, nonce: last_block_nonce },
 			None,
-		)?;
-		let mined_block_tuple = ParamKind::Tuple(vec![
-			// MinedBlock
-			Box::new(ParamKind::FixedBytes(32)), // blockHash
-			Box::new(ParamKind::Address),        // miner
-			Box::new(ParamKind::Uint(256)),      // invalid block
-			Box::new(ParamKind::Uint(256)),      // stake
-			Box::new(ParamKind::Uint(256)),      // totalValue
-			Box::new(local_block_tuple()),
-		]);
-		// let mined_block_tuple = vec![             // MinedBlock
-		//                                    ParamKind::FixedBytes(32),    // blockHash
-		//                                    ParamKind::Address,           // miner
-		//                                    ParamKind::Uint(256),         // stake
-		//                                    ParamKind::Uint(256),         // totalValue
-		//                                    local_block_tuple()
-		// ];
-		decode_remote_block_and_txs(res.result.as_slice(), mined_block_tuple, |block| {
-			log::info!("Decoding local block, {:?}", block);
-			Self::decode_mined_block_from_tuple(block.to_tuple().unwrap().as_slice())
-		})
+			BlockTag::Latest,
+		)
 	}
 
 	pub fn create_finalize_transaction(
 		&self,
 		remote_chain_id: u64,
 		block_nonce: u64,
-		_finalizer_hash: H256,
-		_finalizers: &[Vec<u8>],
 		verification_result: bool,
 	) -> ChainRequestResult<H256> {
 		// because of sp_std, so here are the alternatives:
@@ -273,16 +173,12 @@ impl<T: Config> QuantumPortalClient<T> {
 
 		let (block_details, _) = self.mined_block_by_nonce(remote_chain_id, block_nonce)?;
 
-		let method_signature =
-            b"finalizeSingleSigner(uint256,uint256,uint256[],bytes32,address[],bytes32,uint64,bytes)";
-
 		let salt = Token::FixedBytes(block_details.block_hash.as_ref().to_vec());
 		let finalizer_hash = Token::FixedBytes(block_details.block_hash.as_ref().to_vec());
 
-		let current_timestamp = block_details.block_metadata.timestamp;
-		// expirt 1hr from now
-		let expiry_buffer = core::time::Duration::from_secs(3600u64);
-		let expiry_time = current_timestamp.saturating_add(expiry_buffer.as_secs());
+		// expires an hour from now, or with the pending signatures of the block to combine them
+		let now = sp_io::offchain::timestamp().unix_millis() / 1000;
+		let expiry_time = Pallet::<T>::signature_expiry(remote_chain_id, block_nonce, now);
 		let expiry = Token::Uint(U256::from(expiry_time));
 
 		let multi_sig = self.generate_multi_signature(
@@ -294,14 +190,32 @@ impl<T: Config> QuantumPortalClient<T> {
 			expiry.clone(),
 		)?;
 
-		let call = crate::Call::submit_signature {
+		let payload = FinalizerSignaturePayload {
+			finalizer: self.signer.from,
 			chain_id: remote_chain_id,
 			block_number: block_nonce,
-			signature: multi_sig.clone(),
+			finalizer_hash: block_details.block_hash,
+			salt: block_details.block_hash,
+			expiry: expiry_time,
+			signature: multi_sig.try_into().map_err(|_| TransactionCreationError::MultisigError)?,
 		};
+		if self.contract.dry_run {
+			self.contract.record_signature_dry_run(&payload);
+			return Ok(Default::default());
+		}
 
-		let _ = SubmitTransaction::<T, crate::Call<T>>::submit_unsigned_transaction(call.into())
-			.map_err(|_| <Error<T>>::OffchainUnsignedTxSignedPayload);
+		let call = crate::Call::submit_signature_unsigned { payload };
+
+		SubmitTransaction::<T, crate::Call<T>>::submit_unsigned_transaction(call.into()).map_err(
+			|_| {
+				log::warn!(
+					"Failed to submit finalizer signature for ({}, {})",
+					remote_chain_id,
+					block_nonce
+				);
+				TransactionCreationError::SubmissionFailed
+			},
+		)?;
 
 		Ok(Default::default())
 	}
@@ -310,8 +224,6 @@ impl<T: Config> QuantumPortalClient<T> {
 		&self,
 		remote_chain_id: u64,
 		block_nonce: u64,
-		_finalizer_hash: H256,
-		_finalizers: &[Vec<u8>],
 		verification_result: bool,
 	) -> ChainRequestResult<H256> {
 		// because of sp_std, so here are the alternatives:
@@ -324,21 +236,11 @@ impl<T: Config> QuantumPortalClient<T> {
 		// ) ...
 		// The last item is a bit complicated, but for now we pass an empty array.
 		// Support buytes and dynamic arrays in future
-		let finalizer_list: Vec<Token> = vec![];
-
 		let (block_details, _) = self.mined_block_by_nonce(remote_chain_id, block_nonce)?;
 
-		let method_signature =
-            b"finalizeSingleSigner(uint256,uint256,uint256[],bytes32,address[],bytes32,uint64,bytes)";
-
-		let salt = Token::FixedBytes(block_details.block_hash.as_ref().to_vec());
-		let finalizer_hash = Token::FixedBytes(block_details.block_hash.as_ref().to_vec());
-
-		let current_timestamp = block_details.block_metadata.timestamp;
-		// expirt 1hr from now
-		let expiry_buffer = core::time::Duration::from_secs(3600u64);
-		let expiry_time = current_timestamp.saturating_add(expiry_buffer.as_secs());
-		let expiry = Token::Uint(U256::from(expiry_time));
+		// the signatures were made with the expiry of the first of them
+		let expiry_time = PendingSignatureExpiry::<T>::get(remote_chain_id, block_nonce)
+			.expect("Set with the signatures");
 
 		let multi_sigs = PendingFinalizeSignatures::<T>::get(remote_chain_id, block_nonce)
 			.expect("Should contain signatures");
@@ -350,9 +252,7 @@ impl<T: Config> QuantumPortalClient<T> {
 		for (_signer, sig) in multi_sigs {
 			multi_sigs_combined.extend(sig);
 		}
-		let mut multisig_compressed: Vec<u8> = multi_sigs_combined[0..64].to_vec();
-		multisig_compressed.extend([28u8]);
-		multisig_compressed.extend([0u8; 31]);
+		let multisig_compressed: Vec<u8> = multi_sigs_combined[0..96].to_vec();
 
 		log::info!(
 			"Extended signature of size {}: {}",
@@ -371,26 +271,23 @@ impl<T: Config> QuantumPortalClient<T> {
 			.unwrap()
 		);
 
-		// set this block nonce as invalid if verification failed
-		let invalid_block: Vec<Token> =
-			if !verification_result { vec![Token::Uint(U256::from(block_nonce))] } else { vec![] };
-
-		let inputs = [
-			Token::Uint(U256::from(remote_chain_id)),
-			Token::Uint(U256::from(block_nonce)),
-			Token::Array(invalid_block),
-			finalizer_hash,
-			Token::Array(finalizer_list),
-			salt,
-			expiry,
-			Token::Bytes(multisig_compressed),
-		];
+		let call = FinalizeSingleSigner {
+			remote_chain_id,
+			block_nonce,
+			// set this block nonce as invalid if verification failed
+			invalid_block_nonces: if !verification_result { vec![block_nonce] } else { vec![] },
+			finalizers_hash: block_details.block_hash,
+			finalizers: vec![],
+			salt: block_details.block_hash,
+			expiry: expiry_time,
+			multi_signature: multisig_compressed,
+		};
 
 		let recipient_address = self.contract.get_ledger_manager_address()?;
 
 		let res = self.contract.send(
-			method_signature,
-			&inputs,
+			FinalizeSingleSigner::SIGNATURE,
+			&call.inputs(),
 			None, //Some(U256::from(1000000 as u64)), // None,
 			None, //Some(U256::from(10000000000 as u64)), // None,
 			U256::zero(),
@@ -407,8 +304,6 @@ impl<T: Config> QuantumPortalClient<T> {
 		&self,
 		remote_chain_id: u64,
 		block_nonce: u64,
-		_finalizer_hash: H256,
-		_finalizers: &[Vec<u8>],
 		verification_result: bool,
 	) -> ChainRequestResult<H256> {
 		// because of sp_std, so here are the alternatives:
@@ -425,16 +320,12 @@ impl<T: Config> QuantumPortalClient<T> {
 
 		let (block_details, _) = self.mined_block_by_nonce(remote_chain_id, block_nonce)?;
 
-		let method_signature =
-            b"finalizeSingleSigner(uint256,uint256,uint256[],bytes32,address[],bytes32,uint64,bytes)";
-
 		let salt = Token::FixedBytes(block_details.block_hash.as_ref().to_vec());
 		let finalizer_hash = Token::FixedBytes(block_details.block_hash.as_ref().to_vec());
 
-		let current_timestamp = block_details.block_metadata.timestamp;
-		// expirt 1hr from now
-		let expiry_buffer = core::time::Duration::from_secs(3600u64);
-		let expiry_time = current_timestamp.saturating_add(expiry_buffer.as_secs());
+		// expires an hour from now, or with the pending signatures of the block to combine them
+		let now = sp_io::offchain::timestamp().unix_millis() / 1000;
+		let expiry_time = Pallet::<T>::signature_expiry(remote_chain_id, block_nonce, now);
 		let expiry = Token::Uint(U256::from(expiry_time));
 
 		let multi_sig = self.generate_multi_signature(
@@ -466,26 +357,23 @@ impl<T: Config> QuantumPortalClient<T> {
 				.unwrap()
 		);
 
-		// set this block nonce as invalid if verification failed
-		let invalid_block: Vec<Token> =
-			if !verification_result { vec![Token::Uint(U256::from(block_nonce))] } else { vec![] };
-
-		let inputs = [
-			Token::Uint(U256::from(remote_chain_id)),
-			Token::Uint(U256::from(block_nonce)),
-			Token::Array(invalid_block),
-			finalizer_hash,
-			Token::Array(finalizer_list),
-			salt,
-			expiry,
-			Token::Bytes(multi_sig),
-		];
+		let call = FinalizeSingleSigner {
+			remote_chain_id,
+			block_nonce,
+			// set this block nonce as invalid if verification failed
+			invalid_block_nonces: if !verification_result { vec![block_nonce] } else { vec![] },
+			finalizers_hash: block_details.block_hash,
+			finalizers: vec![],
+			salt: block_details.block_hash,
+			expiry: expiry_time,
+			multi_signature: multi_sig,
+		};
 
 		let recipient_address = self.contract.get_ledger_manager_address()?;
 
 		let res = self.contract.send(
-			method_signature,
-			&inputs,
+			FinalizeSingleSigner::SIGNATURE,
+			&call.inputs(),
 			None, //Some(U256::from(1000000 as u64)), // None,
 			None, //Some(U256::from(10000000000 as u64)), // None,
 			U256::zero(),
@@ -527,11 +415,6 @@ impl<T: Config> QuantumPortalClient<T> {
 		);
 		log::info!("domain_seperator_hash {:?}", domain_seperator_hash);
 
-		// Generate the finalize method sigature to encode the finalize call
-		let finalize_method_signature = b"Finalize(uint256 remoteChainId,uint256 blockNonce,bytes32 finalizersHash,address[] finalizers,bytes32 salt,uint64 expiry)";
-		let finalize_method_signature_hash = ChainUtils::keccack(finalize_method_signature);
-		log::info!("finalize_method_signature_hash {:?}", finalize_method_signature_hash);
-
 		log::info!("remote_chain_id {:?}", remote_chain_id);
 		log::info!("block_nonde {:?}", block_nonce);
 		log::info!("finalizer_hash {:?}", finalizer_hash);
@@ -539,37 +422,15 @@ impl<T: Config> QuantumPortalClient<T> {
 		log::info!("salt {:?}", salt);
 		log::info!("expiry {:?}", expiry);
 
-		// encode the finalize call to the expected format
-		let encoded_message_hash = EIP712Utils::get_encoded_hash(vec![
-			Token::FixedBytes(Vec::from(finalize_method_signature_hash.as_bytes())), /* finalize
-			                                                                          * method signature
-			                                                                          * hash */
-			Token::Uint(U256::from(remote_chain_id)), // remote chain id
-			Token::Uint(U256::from(block_nonce)),     // block nonce
-			finalizer_hash,                           // finalizers hash
-			Token::Array(finalizer_list),             // finalizers
-			salt.clone(),                             // salt
-			expiry.clone(),                           // expiry
-		]);
-		log::info!("encoded_message_hash {:?}", encoded_message_hash);
-
-		// Generate the ValidateAuthoritySignature method signature to encode the eip_args
-		let method_signature = b"ValidateAuthoritySignature(uint256 action,bytes32 msgHash,bytes32 salt,uint64 expiry)";
-		let method_hash = ChainUtils::keccack(method_signature);
-		log::info!("method_hash {:?}", method_hash);
-
-		// Generate the encoded eip message
-		let eip_args_hash = EIP712Utils::get_encoded_hash(vec![
-			Token::FixedBytes(Vec::from(method_hash.as_bytes())), // method hash
-			Token::Uint(U256::from(1)),                           // action
-			Token::FixedBytes(Vec::from(encoded_message_hash.as_bytes())), // msgHash
-			salt,                                                 // salt
-			expiry,                                               // expiry
-		]);
-		log::info!("eip_args_hash {:?}", eip_args_hash);
-
-		let eip_712_hash =
-			EIP712Utils::generate_eip_712_hash(&domain_seperator_hash[..], &eip_args_hash[..]);
+		let eip_712_hash = EIP712Utils::generate_finalize_hash(
+			&domain_seperator_hash[..],
+			remote_chain_id,
+			block_nonce,
+			finalizer_hash,
+			finalizer_list,
+			salt,
+			expiry,
+		);
 		log::info!("EIP712 Hash {:?}", eip_712_hash);
 
 		// Sign the eip message, we only consider a single signer here since we only expect a single
@@ -580,7 +441,7 @@ impl<T: Config> QuantumPortalClient<T> {
 		// This computation makes it match the implementation we have in qp smart contracts repo
 		// refer https://github.com/ferrumnet/quantum-portal-smart-contracts/blob/326341cdfcb55052437393228f1d58e014c90f7b/test/common/Eip712Utils.ts#L93
 		let mut multisig_compressed: Vec<u8> = multi_sig_bytes.0[0..64].to_vec();
-		multisig_compressed.extend([28u8]);
+		multisig_compressed.extend([27u8 + multi_sig_bytes.0[64]]);
 		multisig_compressed.extend([0u8; 31]);
 
 		log::info!(
@@ -603,38 +464,21 @@ impl<T: Config> QuantumPortalClient<T> {
 		txs: &Vec<QpTransaction>,
 		source_block: QpLocalBlock,
 	) -> ChainRequestResult<H256> {
-		let method_signature = b"mineRemoteBlock(uint64,uint64,(uint64,address,address,address,address,uint256,bytes,uint256,uint256)[],bytes32,uint64,bytes)";
-
 		// set timestamp 1hr from now
 		let current_timestamp = source_block.timestamp;
 		let expiry_buffer = core::time::Duration::from_secs(360000u64);
 		let expiry_time = current_timestamp.saturating_add(expiry_buffer.as_secs());
 		let expiry = Token::Uint(U256::from(expiry_time));
-		let salt = Token::FixedBytes(vec![0u8, 0u8]);
-
-		let tx_vec: Vec<Token> = txs
-			.iter()
-			.map(|t| {
-				Token::Tuple(vec![
-					Token::Uint(U256::from(t.timestamp)),
-					Token::Address(t.remote_contract),
-					Token::Address(t.source_msg_sender),
-					Token::Address(t.source_beneficiary),
-					Token::Address(t.token),
-					Token::Uint(t.amount),
-					Token::Array(vec![Token::Bytes(t.method.clone())]),
-					Token::Uint(t.gas),
-					Token::Uint(t.fixed_fee),
-				])
-			})
-			.collect();
+		let salt = H256::zero();
+
+		let tx_vec: Vec<Token> = txs.iter().map(remote_transaction_token).collect();
 
 		let multi_sig = self.generate_miner_signature(
 			remote_chain_id,
 			block_nonce,
-			tx_vec.clone(),
-			salt.clone(),
-			expiry.clone(),
+			tx_vec,
+			Token::FixedBytes(salt.as_bytes().to_vec()),
+			expiry,
 		)?;
 
 		log::info!(
@@ -645,16 +489,18 @@ impl<T: Config> QuantumPortalClient<T> {
 
 		let recipient_address = self.contract.get_ledger_manager_address()?;
 
+		let call = MineRemoteBlock {
+			remote_chain_id,
+			block_nonce,
+			transactions: txs,
+			salt,
+			expiry: expiry_time,
+			multi_signature: multi_sig,
+		};
+
 		let res = self.contract.send(
-			method_signature,
-			&[
-				Token::Uint(U256::from(remote_chain_id)),
-				Token::Uint(U256::from(block_nonce)),
-				Token::Array(tx_vec),
-				salt,
-				expiry,
-				Token::Bytes(multi_sig),
-			],
+			MineRemoteBlock::SIGNATURE,
+			&call.inputs(),
 			Some(U256::from(1000000_u32)), // None,
 			None,                          // Some(U256::from(60000000000 as u64)), // None,
 			U256::zero(),
@@ -694,11 +540,6 @@ impl<T: Config> QuantumPortalClient<T> {
 		);
 		log::info!("domain_seperator_hash {:?}", domain_seperator_hash);
 
-		// Generate the finalize method sigature to encode the finalize call
-		let miner_method_signature = b"MinerSignature(bytes32 msgHash,uint64 expiry,bytes32 salt)";
-		let miner_method_signature_hash = ChainUtils::keccack(miner_method_signature);
-		log::info!("miner_method_signature_hash {:?}", miner_method_signature_hash);
-
 		log::info!("remote_chain_id {:?}", remote_chain_id);
 		log::info!("block_nonce {:?}", block_nonce);
 		log::info!("txs {:?}", txs);
@@ -714,12 +555,14 @@ impl<T: Config> QuantumPortalClient<T> {
 		log::info!("encoded_message_hash {:?}", encoded_message_hash);
 
 		// Generate the encoded eip message
-		let eip_args_hash = EIP712Utils::get_encoded_hash(vec![
-			Token::FixedBytes(Vec::from(miner_method_signature_hash.as_bytes())), // method hash
-			Token::FixedBytes(Vec::from(encoded_message_hash.as_bytes())),        // msgHash
-			expiry,                                                               // expiry
-			salt,                                                                 // salt
-		]);
+		let eip_args_hash = EIP712Utils::hash_struct(
+			"MinerSignature(bytes32 msgHash,uint64 expiry,bytes32 salt)",
+			vec![
+				Token::FixedBytes(Vec::from(encoded_message_hash.as_bytes())), // msgHash
+				expiry,                                                        // expiry
+				salt,                                                          // salt
+			],
+		);
 		log::info!("eip_args_hash {:?}", eip_args_hash);
 
 		let eip_712_hash =
@@ -763,7 +606,11 @@ impl<T: Config> QuantumPortalClient<T> {
 				block.nonce
 			);
 			let (_mined_block, mined_txs) = self.mined_block_by_nonce(chain_id, block.nonce)?;
-			let (_source_block, source_txs) = self.local_block_by_nonce(chain_id, block.nonce)?;
+			// Verify against confirmed source state, a reorg must not change what we finalize
+			let confirmed_block = self.contract.confirmed_block()?;
+			log::info!("Reading source block at block {:?}", confirmed_block);
+			let (_source_block, source_txs) =
+				self.local_block_by_nonce(chain_id, block.nonce, confirmed_block)?;
 			// verify data before finalization
 			let verification_result = Self::compare_and_verify_mined_block(&source_txs, &mined_txs);
 
@@ -771,38 +618,19 @@ impl<T: Config> QuantumPortalClient<T> {
 			let multi_sigs = PendingFinalizeSignatures::<T>::get(chain_id, block.nonce);
 			let threshold = FinalizerThreshold::<T>::get(chain_id).unwrap_or_default();
 
-			if let Some(multi_sigs) = multi_sigs {
-				if multi_sigs.len() > threshold as usize {
-					log::info!("Calling mgr.post_transaction({}, {})", chain_id, block.nonce);
-					Ok(Some(self.post_finalize_transaction(
-						chain_id,
-						block.nonce,
-						H256::zero(),
-						&[self.signer.get_signer_address()],
-						verification_result,
-					)?))
-				} else {
-					// we dont have threshold so try to sign and post
-					log::info!("Calling mgr.finalize({}, {})", chain_id, block.nonce);
-					Ok(Some(self.create_finalize_transaction(
-						chain_id,
-						block.nonce,
-						H256::zero(),
-						&[self.signer.get_signer_address()],
-						verification_result,
-					)?))
-				}
+			// expired signatures are signed again instead
+			let threshold_reached = multi_sigs
+				.map_or(false, |multi_sigs| multi_sigs.len() > threshold as usize) &&
+				Pallet::<T>::pending_expiry(chain_id, block.nonce).is_some();
+			let tx = if threshold_reached {
+				log::info!("Calling mgr.post_transaction({}, {})", chain_id, block.nonce);
+				self.post_finalize_transaction(chain_id, block.nonce, verification_result)?
 			} else {
 				// we dont have threshold so try to sign and post
 				log::info!("Calling mgr.finalize({}, {})", chain_id, block.nonce);
-				Ok(Some(self.create_finalize_transaction(
-					chain_id,
-					block.nonce,
-					H256::zero(),
-					&[self.signer.get_signer_address()],
-					verification_result,
-				)?))
-			}
+				self.create_finalize_transaction(chain_id, block.nonce, verification_result)?
+			};
+			Ok(self.tracked_transaction(tx))
 		} else {
 			log::info!("Nothing to finalize for ({})", chain_id);
 			Ok(None)
@@ -813,30 +641,37 @@ impl<T: Config> QuantumPortalClient<T> {
 		let local_chain = self.contract.chain_id;
 		let remote_chain = remote_client.contract.chain_id;
 		log::info!("mine({} => {})", remote_chain, local_chain);
-		let block_ready = remote_client.is_local_block_ready(local_chain)?;
-		log::info!("local block ready? {}", block_ready);
-		if !block_ready {
-			return Ok(None);
-		}
-		log::info!("Getting last local block");
-		let last_block = remote_client.last_local_block(local_chain)?;
-		log::info!("Last local block is {:?}", last_block);
+		// Read the remote chain behind its head so we never mine a block that a reorg can remove
+		let remote_block = remote_client.contract.confirmed_block()?;
+		log::info!("Reading remote chain at block {:?}", remote_block);
 		let last_mined_block = self.last_remote_mined_block(remote_chain)?;
+		let next_nonce = last_mined_block.nonce.saturating_add(1);
+		log::info!("Getting last local block and source block {}", next_nonce);
+		let source_blocks =
+			match remote_client.source_blocks(local_chain, next_nonce, remote_block)? {
+				Some(source_blocks) => source_blocks,
+				None => {
+					log::info!("local block not ready");
+					return Ok(None);
+				},
+			};
+		let last_block = source_blocks.last_block;
+		log::info!("Last local block is {:?}", last_block);
 		log::info!("Local block f remote (chain {}) nonce is {}. Remote mined block on local (chain {}) is {}",
 			remote_chain, last_block.nonce, local_chain, last_mined_block.nonce);
-		if last_mined_block.nonce >= last_block.nonce {
-			log::info!("Nothing to mine!");
-			return Ok(None);
-		}
+		let source_block = match source_blocks.next_block {
+			Some(source_block) => source_block,
+			None => {
+				log::info!("Nothing to mine!");
+				return Ok(None);
+			},
+		};
 		log::info!("Last block is on chain1 for target {} is {}", local_chain, last_block.nonce);
 		let mined_block = self.mined_block_by_nonce(remote_chain, last_block.nonce)?;
 		let already_mined = !mined_block.0.block_hash.eq(&ZERO_HASH);
 		if already_mined {
 			return Err(ChainRequestError::RemoteBlockAlreadyMined);
 		}
-		log::info!("Getting source block?");
-		let source_block = remote_client
-			.local_block_by_nonce(local_chain, last_mined_block.nonce.saturating_add(1))?;
 		let default_qp_transaction = QpTransaction::default();
 		log::info!(
 			"Source block is GOT\n{:?}\n{:?}",
@@ -872,72 +707,19 @@ impl<T: Config> QuantumPortalClient<T> {
 		// }
 
 		log::info!("About to mine block {}:{}", remote_chain, source_block.0.nonce);
-		Ok(Some(self.create_mine_transaction(
-			remote_chain,
-			source_block.0.nonce,
-			&txs,
-			source_block.0,
-		)?))
-	}
-
-	fn decode_local_block(&self, data: &[u8]) -> ChainRequestResult<QpLocalBlock> {
-		let dec = decode(
-			// &[local_block_tuple()],
-			local_block_tuple0().as_slice(),
-			ChainUtils::hex_to_bytes(data)?.as_slice(),
-		)
-		.unwrap();
-		Self::decode_local_block_from_tuple(dec.as_slice())
-	}
-
-	fn decode_local_block_from_tuple(dec: &[Token]) -> ChainRequestResult<QpLocalBlock> {
-		log::info!("Decoding local block, {:?}", dec);
-		match dec {
-			[chain_id, nonce, timestamp] => {
-				let chain_id = chain_id.clone().to_uint();
-				let nonce = nonce.clone().to_uint();
-				let timestamp = timestamp.clone().to_uint();
-				Ok(QpLocalBlock {
-					chain_id: chain_id.unwrap().as_u64(),
-					nonce: nonce.unwrap().as_u64(),
-					timestamp: timestamp.unwrap().as_u64(),
-				})
-			},
-			_ => Err(b"Unexpected output. Could not decode local block".as_slice().into()),
-		}
+		let tx =
+			self.create_mine_transaction(remote_chain, source_block.0.nonce, &txs, source_block.0)?;
+		Ok(self.tracked_transaction(tx))
 	}
 
-	fn decode_mined_block_from_tuple(dec: &[Token]) -> ChainRequestResult<QpRemoteBlock> {
-		log::info!("decode_mined_block_from_tuple {:?}", dec);
-		match dec {
-			[block_hash, miner, stake, total_value, block_metadata] => {
-				log::info!(
-					"D {:?}::{:?}:{:?}:{:?}::{:?}",
-					block_hash,
-					miner,
-					stake,
-					total_value,
-					block_metadata
-				);
-				let block_hash = block_hash.clone();
-				let miner = miner.clone();
-				let stake = stake.clone();
-				let total_value = total_value.clone();
-				let block_metadata = block_metadata.clone();
-				log::info!("Decoding block metadata");
-				let block_metadata =
-					Self::decode_local_block_from_tuple(&block_metadata.to_tuple().unwrap())?;
-				log::info!("DecodED block metadata");
-				Ok(QpRemoteBlock {
-					block_hash: H256::from_slice(block_hash.to_fixed_bytes().unwrap().as_slice()),
-					miner: miner.to_address().unwrap(),
-					stake: stake.to_uint().unwrap(),
-					total_value: total_value.to_uint().unwrap(),
-					block_metadata,
-				})
-			},
-			_ => Err(b"Unexpected output. Could not decode mined block".as_slice().into()),
+	/// Returns the transaction to track as pending, in dry run mode nothing was broadcast so
+	/// there is nothing to wait for
+	fn tracked_transaction(&self, tx: H256) -> Option<H256> {
+		if self.contract.dry_run {
+			log::info!("DRY RUN: transaction {:?} was simulated and not sent", tx);
+			return None;
 		}
+		Some(tx)
 	}
 
 	fn compare_and_verify_mined_block(
@@ -282,6 +282,7 @@ impl<T: Config> QuantumPortalService<T> {
 		let status = ChainQueries::get_transaction_status(
 			str::from_utf8(&client.contract.http_api[..]).unwrap(),
 			tx_id,
+			client.contract.confirmations,
 		)?;
 		let res = match status {
 			TransactionStatus::Confirmed => {
@@ -13,394 +13,534 @@
 
 // You should have received a copy of the GNU General Public License
 // along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
-
-use crate as example_offchain_worker;
-use crate::*;
-use codec::Decode;
-use frame_support::{assert_ok, parameter_types};
-use sp_core::{
-	offchain::{testing, OffchainWorkerExt, TransactionPoolExt},
-	sr25519::Signature,
-	H256,
-};
-use std::sync::Arc;
-
-use sp_keystore::{testing::KeyStore, KeystoreExt, Keystore};
+#![cfg(test)]
+use super::*;
+use crate::qp_types::{FinalizerSignaturePayload, SignatureDomain};
+use frame_support::{assert_noop, assert_ok};
+use mock::{RuntimeEvent, *};
+use parity_scale_codec::{Decode, Encode};
+use sp_core::{Pair, H160, H256};
 use sp_runtime::{
-	testing::{Header, TestXt},
-	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify},
-	RuntimeAppPublic,
+	traits::{BadOrigin, Convert, ValidateUnsigned},
+	transaction_validity::{
+		InvalidTransaction, TransactionSource, TransactionValidity, TransactionValidityError,
+	},
+	DispatchResult,
 };
 
-type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
-type Block = frame_system::mocking::MockBlock<Test>;
-
-// For testing the module, we construct a mock runtime.
-frame_support::construct_runtime!(
-	pub enum Test where
-		Block = Block,
-		NodeBlock = Block,
-		UncheckedExtrinsic = UncheckedExtrinsic,
-	{
-		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		Example: example_offchain_worker::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
-	}
-);
+const BLOCK_NUMBER: u64 = 7;
+const FINALIZER_HASH: H256 = H256::repeat_byte(0xaa);
+const SALT: H256 = H256::repeat_byte(0xbb);
 
-parameter_types! {
-	pub const BlockHashCount: u64 = 250;
-	pub BlockWeights: frame_system::limits::BlockWeights =
-		frame_system::limits::BlockWeights::simple_max(1024);
-}
-impl frame_system::Config for Test {
-	type BaseCallFilter = frame_support::traits::Everything;
-	type BlockWeights = ();
-	type BlockLength = ();
-	type DbWeight = ();
-	type Origin = Origin;
-	type Call = Call;
-	type Index = u64;
-	type BlockNumber = u64;
-	type Hash = H256;
-	type Hashing = BlakeTwo256;
-	type AccountId = sp_core::sr25519::Public;
-	type Lookup = IdentityLookup<Self::AccountId>;
-	type Header = Header;
-	type Event = Event;
-	type BlockHashCount = BlockHashCount;
-	type Version = ();
-	type PalletInfo = PalletInfo;
-	type AccountData = ();
-	type OnNewAccount = ();
-	type OnKilledAccount = ();
-	type SystemWeightInfo = ();
-	type SS58Prefix = ();
-	type OnSetCode = ();
+fn expiry() -> u64 {
+	Now::get() + 3600
 }
 
-type Extrinsic = TestXt<Call, ()>;
-type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
+/// Signs the finalize digest of `block_number` as `who`, in the `r || s || v` format padded to
+/// 96 bytes that finalizers submit
+fn finalize_signature(who: AccountId, block_number: u64, expiry: u64) -> Vec<u8> {
+	let digest =
+		QuantumPortal::finalize_digest(CHAIN_ID, block_number, FINALIZER_HASH, SALT, expiry)
+			.unwrap();
+	let signature = finalizer_pair(who).sign_prehashed(&digest.0);
+	let mut multisig = signature.0[..64].to_vec();
+	multisig.push(27 + signature.0[64]);
+	multisig.extend([0u8; 31]);
+	multisig
+}
 
-impl frame_system::offchain::SigningTypes for Test {
-	type Public = <Signature as Verify>::Signer;
-	type Signature = Signature;
+fn submit_signature(who: AccountId, signature: Vec<u8>) -> DispatchResult {
+	submit_signature_at(who, BLOCK_NUMBER, expiry(), signature)
 }
 
-impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
-	where
-		Call: From<LocalCall>,
-{
-	type OverarchingCall = Call;
-	type Extrinsic = Extrinsic;
+fn submit_signature_at(
+	who: AccountId,
+	block_number: u64,
+	expiry: u64,
+	signature: Vec<u8>,
+) -> DispatchResult {
+	QuantumPortal::submit_signature(
+		RuntimeOrigin::signed(who),
+		CHAIN_ID,
+		block_number,
+		FINALIZER_HASH,
+		SALT,
+		expiry,
+		signature,
+	)
 }
 
-impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
-	where
-		Call: From<LocalCall>,
-{
-	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
-		call: Call,
-		_public: <Signature as Verify>::Signer,
-		_account: AccountId,
-		nonce: u64,
-	) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
-		Some((call, (nonce, ())))
+fn unsigned_payload(who: AccountId, expiry: u64, signature: Vec<u8>) -> FinalizerSignaturePayload {
+	FinalizerSignaturePayload {
+		finalizer: AccountIdToAddress::convert(who),
+		chain_id: CHAIN_ID,
+		block_number: BLOCK_NUMBER,
+		finalizer_hash: FINALIZER_HASH,
+		salt: SALT,
+		expiry,
+		signature: signature.try_into().unwrap(),
 	}
 }
 
-parameter_types! {
-	pub const GracePeriod: u64 = 5;
-	pub const UnsignedInterval: u64 = 128;
-	pub const UnsignedPriority: u64 = 1 << 20;
+fn validate_unsigned(payload: FinalizerSignaturePayload) -> TransactionValidity {
+	<QuantumPortal as ValidateUnsigned>::validate_unsigned(
+		TransactionSource::External,
+		&crate::Call::submit_signature_unsigned { payload },
+	)
 }
 
-impl Config for Test {
-	type Event = Event;
-	type AuthorityId = crypto::TestAuthId;
-	type Call = Call;
-	type GracePeriod = GracePeriod;
-	type UnsignedInterval = UnsignedInterval;
-	type UnsignedPriority = UnsignedPriority;
+#[test]
+fn submit_signature_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let signature = finalize_signature(ALICE, BLOCK_NUMBER, expiry());
+		assert_ok!(submit_signature(ALICE, signature.clone()));
+		System::assert_last_event(RuntimeEvent::QuantumPortal(crate::Event::SignatureSubmitted {
+			chain_id: CHAIN_ID,
+			block_number: BLOCK_NUMBER,
+			finalizer: ALICE,
+			signature: signature.clone(),
+		}));
+		assert_eq!(
+			PendingFinalizeSignatures::<Runtime>::get(CHAIN_ID, BLOCK_NUMBER),
+			Some(vec![(ALICE, signature)])
+		);
+		assert!(QuantumPortal::has_submitted(&ALICE, CHAIN_ID, BLOCK_NUMBER));
+	});
 }
 
 #[test]
-fn it_aggregates_the_price() {
-	sp_io::TestExternalities::default().execute_with(|| {
-		assert_eq!(Example::average_price(), None);
+fn submit_signature_rejects_wrong_signer() {
+	ExtBuilder::default().build().execute_with(|| {
+		// bob is not a finalizer of the chain
+		assert_noop!(
+			submit_signature(BOB, finalize_signature(BOB, BLOCK_NUMBER, expiry())),
+			Error::<Runtime>::FinalizerNotFound
+		);
+		// a registered finalizer submitting a signature of someone else
+		assert_noop!(
+			submit_signature(ALICE, finalize_signature(BOB, BLOCK_NUMBER, expiry())),
+			Error::<Runtime>::SignatureMismatch
+		);
+		// the signature covers a different expiry
+		assert_noop!(
+			submit_signature(ALICE, finalize_signature(ALICE, BLOCK_NUMBER, expiry() + 1)),
+			Error::<Runtime>::SignatureMismatch
+		);
+	});
+}
 
-		assert_ok!(Example::submit_price(Origin::signed(Default::default()), 27));
-		assert_eq!(Example::average_price(), Some(27));
+#[test]
+fn submit_signature_rejects_wrong_domain() {
+	ExtBuilder::default().build().execute_with(|| {
+		let signature = finalize_signature(ALICE, BLOCK_NUMBER, expiry());
+		let domain = SignatureDomain { chain_id: CHAIN_ID + 1, ..signature_domain() };
+		assert_ok!(QuantumPortal::set_signature_domain(RuntimeOrigin::root(), CHAIN_ID, domain));
+		assert_noop!(submit_signature(ALICE, signature), Error::<Runtime>::SignatureMismatch);
+
+		FinalizerSignatureDomain::<Runtime>::remove(CHAIN_ID);
+		assert_noop!(
+			submit_signature(ALICE, finalize_signature(ALICE, BLOCK_NUMBER, expiry())),
+			Error::<Runtime>::SignatureDomainNotSet
+		);
+	});
+}
 
-		assert_ok!(Example::submit_price(Origin::signed(Default::default()), 43));
-		assert_eq!(Example::average_price(), Some(35));
+#[test]
+fn submit_signature_rejects_duplicate() {
+	ExtBuilder::default().build().execute_with(|| {
+		let signature = finalize_signature(ALICE, BLOCK_NUMBER, expiry());
+		assert_ok!(submit_signature(ALICE, signature.clone()));
+		assert_noop!(submit_signature(ALICE, signature), Error::<Runtime>::DuplicateSignature);
 	});
 }
 
 #[test]
-fn should_make_http_call_and_parse_result() {
-	let (offchain, state) = testing::TestOffchainExt::new();
-	let mut t = sp_io::TestExternalities::default();
-	t.register_extension(OffchainWorkerExt::new(offchain));
-
-	price_oracle_response(&mut state.write());
-
-	t.execute_with(|| {
-		// when
-		let price = Example::fetch_price().unwrap();
-		// then
-		assert_eq!(price, 15523);
+fn submit_signature_rejects_malformed_signature() {
+	ExtBuilder::default().build().execute_with(|| {
+		let signature = finalize_signature(ALICE, BLOCK_NUMBER, expiry());
+		assert_noop!(
+			submit_signature(ALICE, signature[..65].to_vec()),
+			Error::<Runtime>::MalformedSignature
+		);
+		let mut dirty_padding = signature;
+		dirty_padding[95] = 1;
+		assert_noop!(submit_signature(ALICE, dirty_padding), Error::<Runtime>::MalformedSignature);
 	});
 }
 
 #[test]
-fn knows_how_to_mock_several_http_calls() {
-	let (offchain, state) = testing::TestOffchainExt::new();
-	let mut t = sp_io::TestExternalities::default();
-	t.register_extension(OffchainWorkerExt::new(offchain));
-
-	{
-		let mut state = state.write();
-		state.expect_request(testing::PendingRequest {
-			method: "GET".into(),
-			uri: "https://min-api.cryptocompare.com/data/price?fsym=BTC&tsyms=USD".into(),
-			response: Some(br#"{"USD": 1}"#.to_vec()),
-			sent: true,
-			..Default::default()
-		});
-
-		state.expect_request(testing::PendingRequest {
-			method: "GET".into(),
-			uri: "https://min-api.cryptocompare.com/data/price?fsym=BTC&tsyms=USD".into(),
-			response: Some(br#"{"USD": 2}"#.to_vec()),
-			sent: true,
-			..Default::default()
-		});
-
-		state.expect_request(testing::PendingRequest {
-			method: "GET".into(),
-			uri: "https://min-api.cryptocompare.com/data/price?fsym=BTC&tsyms=USD".into(),
-			response: Some(br#"{"USD": 3}"#.to_vec()),
-			sent: true,
-			..Default::default()
-		});
-	}
+fn set_signature_domain_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let domain = SignatureDomain { version: b"000.011".to_vec(), ..signature_domain() };
+		assert_noop!(
+			QuantumPortal::set_signature_domain(
+				RuntimeOrigin::signed(ALICE),
+				CHAIN_ID,
+				domain.clone()
+			),
+			BadOrigin
+		);
+		assert_ok!(QuantumPortal::set_signature_domain(
+			RuntimeOrigin::root(),
+			CHAIN_ID,
+			domain.clone()
+		));
+		System::assert_last_event(RuntimeEvent::QuantumPortal(crate::Event::SignatureDomainSet {
+			chain_id: CHAIN_ID,
+			domain: domain.clone(),
+		}));
+		assert_eq!(FinalizerSignatureDomain::<Runtime>::get(CHAIN_ID), Some(domain));
+	});
+}
 
-	t.execute_with(|| {
-		let price1 = Example::fetch_price().unwrap();
-		let price2 = Example::fetch_price().unwrap();
-		let price3 = Example::fetch_price().unwrap();
+#[test]
+fn submit_signature_rejects_invalid_expiry() {
+	ExtBuilder::default().build().execute_with(|| {
+		let expired = Now::get();
+		assert_noop!(
+			submit_signature_at(
+				ALICE,
+				BLOCK_NUMBER,
+				expired,
+				finalize_signature(ALICE, BLOCK_NUMBER, expired)
+			),
+			Error::<Runtime>::SignatureExpired
+		);
+		let too_far = Now::get() + MAX_SIGNATURE_EXPIRY_SECS + 1;
+		assert_noop!(
+			submit_signature_at(
+				ALICE,
+				BLOCK_NUMBER,
+				too_far,
+				finalize_signature(ALICE, BLOCK_NUMBER, too_far)
+			),
+			Error::<Runtime>::SignatureExpiryTooFar
+		);
+	});
+}
 
-		assert_eq!(price1, 100);
-		assert_eq!(price2, 200);
-		assert_eq!(price3, 300);
-	})
+#[test]
+fn signature_expiry_does_not_depend_on_the_mined_block() {
+	ExtBuilder::default().build().execute_with(|| {
+		// the block was mined two hours ago, an expiry derived from it has already passed
+		let mined_at = Now::get() - 2 * 3600;
+		let stale = mined_at + SIGNATURE_EXPIRY_SECS;
+		assert_noop!(
+			submit_signature_at(
+				ALICE,
+				BLOCK_NUMBER,
+				stale,
+				finalize_signature(ALICE, BLOCK_NUMBER, stale)
+			),
+			Error::<Runtime>::SignatureExpired
+		);
+
+		let expiry = QuantumPortal::signature_expiry(CHAIN_ID, BLOCK_NUMBER, Now::get());
+		assert_eq!(expiry, Now::get() + SIGNATURE_EXPIRY_SECS);
+		assert_ok!(submit_signature_at(
+			ALICE,
+			BLOCK_NUMBER,
+			expiry,
+			finalize_signature(ALICE, BLOCK_NUMBER, expiry)
+		));
+		assert_eq!(PendingSignatureExpiry::<Runtime>::get(CHAIN_ID, BLOCK_NUMBER), Some(expiry));
+	});
 }
 
 #[test]
-fn should_submit_signed_transaction_on_chain() {
-	const PHRASE: &str =
-		"news slush supreme milk chapter athlete soap sausage put clutch what kitten";
-
-	let (offchain, offchain_state) = testing::TestOffchainExt::new();
-	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
-	let keystore = KeyStore::new();
-	Keystore::sr25519_generate_new(
-		&keystore,
-		crate::crypto::Public::ID,
-		Some(&format!("{}/hunter1", PHRASE)),
-	)
-		.unwrap();
-
-	let mut t = sp_io::TestExternalities::default();
-	t.register_extension(OffchainWorkerExt::new(offchain));
-	t.register_extension(TransactionPoolExt::new(pool));
-	t.register_extension(KeystoreExt(Arc::new(keystore)));
-
-	price_oracle_response(&mut offchain_state.write());
-
-	t.execute_with(|| {
-		// when
-		Example::fetch_price_and_send_signed().unwrap();
-		// then
-		let tx = pool_state.write().transactions.pop().unwrap();
-		assert!(pool_state.read().transactions.is_empty());
-		let tx = Extrinsic::decode(&mut &*tx).unwrap();
-		assert_eq!(tx.signature.unwrap().0, 0);
-		assert_eq!(tx.call, Call::Example(crate::Call::submit_price { price: 15523 }));
+fn pending_signatures_share_their_expiry() {
+	ExtBuilder::default().build().execute_with(|| {
+		RegisteredFinalizers::<Runtime>::insert(CHAIN_ID, vec![ALICE, BOB]);
+		let expiry = expiry();
+		assert_ok!(submit_signature_at(
+			ALICE,
+			BLOCK_NUMBER,
+			expiry,
+			finalize_signature(ALICE, BLOCK_NUMBER, expiry)
+		));
+
+		// a later finalizer signs with the pending expiry so the signatures can be combined
+		Now::set(Now::get() + 60);
+		assert_eq!(QuantumPortal::signature_expiry(CHAIN_ID, BLOCK_NUMBER, Now::get()), expiry);
+		let later = expiry + 60;
+		assert_noop!(
+			submit_signature_at(
+				BOB,
+				BLOCK_NUMBER,
+				later,
+				finalize_signature(BOB, BLOCK_NUMBER, later)
+			),
+			Error::<Runtime>::ExpiryMismatch
+		);
+		assert_eq!(
+			validate_unsigned(FinalizerSignaturePayload {
+				finalizer: AccountIdToAddress::convert(BOB),
+				..unsigned_payload(ALICE, later, finalize_signature(BOB, BLOCK_NUMBER, later))
+			}),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Stale))
+		);
+		assert_ok!(submit_signature_at(
+			BOB,
+			BLOCK_NUMBER,
+			expiry,
+			finalize_signature(BOB, BLOCK_NUMBER, expiry)
+		));
+		assert_eq!(
+			PendingFinalizeSignatures::<Runtime>::get(CHAIN_ID, BLOCK_NUMBER).map(|s| s.len()),
+			Some(2)
+		);
 	});
 }
 
 #[test]
-fn should_submit_unsigned_transaction_on_chain_for_any_account() {
-	const PHRASE: &str =
-		"news slush supreme milk chapter athlete soap sausage put clutch what kitten";
-	let (offchain, offchain_state) = testing::TestOffchainExt::new();
-	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
-
-	let keystore = KeyStore::new();
-
-	Keystore::sr25519_generate_new(
-		&keystore,
-		crate::crypto::Public::ID,
-		Some(&format!("{}/hunter1", PHRASE)),
-	)
-		.unwrap();
-
-	let public_key = Keystore::sr25519_public_keys(&keystore, crate::crypto::Public::ID)
-		.get(0)
-		.unwrap()
-		.clone();
-
-	let mut t = sp_io::TestExternalities::default();
-	t.register_extension(OffchainWorkerExt::new(offchain));
-	t.register_extension(TransactionPoolExt::new(pool));
-	t.register_extension(KeystoreExt(Arc::new(keystore)));
-
-	price_oracle_response(&mut offchain_state.write());
-
-	let price_payload = PricePayload {
-		block_number: 1,
-		price: 15523,
-		public: <Test as SigningTypes>::Public::from(public_key),
-	};
-
-	// let signature = price_payload.sign::<crypto::TestAuthId>().unwrap();
-	t.execute_with(|| {
-		// when
-		Example::fetch_price_and_send_unsigned_for_any_account(1).unwrap();
-		// then
-		let tx = pool_state.write().transactions.pop().unwrap();
-		let tx = Extrinsic::decode(&mut &*tx).unwrap();
-		assert_eq!(tx.signature, None);
-		if let Call::Example(crate::Call::submit_price_unsigned_with_signed_payload {
-								 price_payload: body,
-								 signature,
-							 }) = tx.call
-		{
-			assert_eq!(body, price_payload);
-
-			let signature_valid =
-				<PricePayload<
-					<Test as SigningTypes>::Public,
-					<Test as frame_system::Config>::BlockNumber,
-				> as SignedPayload<Test>>::verify::<crypto::TestAuthId>(&price_payload, signature);
-
-			assert!(signature_valid);
-		}
+fn expired_pending_signatures_are_signed_again() {
+	ExtBuilder::default().build().execute_with(|| {
+		let expiry = expiry();
+		assert_ok!(submit_signature_at(
+			ALICE,
+			BLOCK_NUMBER,
+			expiry,
+			finalize_signature(ALICE, BLOCK_NUMBER, expiry)
+		));
+
+		// the threshold was not reached before the expiry, the block is signed again from now
+		Now::set(expiry);
+		assert_eq!(QuantumPortal::pending_expiry(CHAIN_ID, BLOCK_NUMBER), None);
+		let renewed = QuantumPortal::signature_expiry(CHAIN_ID, BLOCK_NUMBER, Now::get());
+		assert_eq!(renewed, expiry + SIGNATURE_EXPIRY_SECS);
+		assert_ok!(submit_signature_at(
+			ALICE,
+			BLOCK_NUMBER,
+			renewed,
+			finalize_signature(ALICE, BLOCK_NUMBER, renewed)
+		));
+		assert_eq!(
+			PendingFinalizeSignatures::<Runtime>::get(CHAIN_ID, BLOCK_NUMBER).map(|s| s.len()),
+			Some(1)
+		);
+		assert_eq!(PendingSignatureExpiry::<Runtime>::get(CHAIN_ID, BLOCK_NUMBER), Some(renewed));
 	});
 }
 
 #[test]
-fn should_submit_unsigned_transaction_on_chain_for_all_accounts() {
-	const PHRASE: &str =
-		"news slush supreme milk chapter athlete soap sausage put clutch what kitten";
-	let (offchain, offchain_state) = testing::TestOffchainExt::new();
-	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
-
-	let keystore = KeyStore::new();
-
-	Keystore::sr25519_generate_new(
-		&keystore,
-		crate::crypto::Public::ID,
-		Some(&format!("{}/hunter1", PHRASE)),
+fn old_pending_signatures_are_pruned() {
+	ExtBuilder::default().build().execute_with(|| {
+		let kept = BLOCK_NUMBER + PENDING_SIGNATURE_BLOCKS;
+		let pruning = kept + 1;
+		for block_number in [BLOCK_NUMBER, kept, pruning] {
+			assert_ok!(submit_signature_at(
+				ALICE,
+				block_number,
+				expiry(),
+				finalize_signature(ALICE, block_number, expiry())
+			));
+			if block_number == kept {
+				assert!(QuantumPortal::has_submitted(&ALICE, CHAIN_ID, BLOCK_NUMBER));
+			}
+		}
+		assert!(!QuantumPortal::has_submitted(&ALICE, CHAIN_ID, BLOCK_NUMBER));
+		assert!(QuantumPortal::has_submitted(&ALICE, CHAIN_ID, kept));
+		assert!(QuantumPortal::has_submitted(&ALICE, CHAIN_ID, pruning));
+	});
+}
+
+fn sign_block(block_number: u64) -> DispatchResult {
+	submit_signature_at(
+		ALICE,
+		block_number,
+		expiry(),
+		finalize_signature(ALICE, block_number, expiry()),
 	)
-		.unwrap();
-
-	let public_key = Keystore::sr25519_public_keys(&keystore, crate::crypto::Public::ID)
-		.get(0)
-		.unwrap()
-		.clone();
-
-	let mut t = sp_io::TestExternalities::default();
-	t.register_extension(OffchainWorkerExt::new(offchain));
-	t.register_extension(TransactionPoolExt::new(pool));
-	t.register_extension(KeystoreExt(Arc::new(keystore)));
-
-	price_oracle_response(&mut offchain_state.write());
-
-	let price_payload = PricePayload {
-		block_number: 1,
-		price: 15523,
-		public: <Test as SigningTypes>::Public::from(public_key),
-	};
-
-	// let signature = price_payload.sign::<crypto::TestAuthId>().unwrap();
-	t.execute_with(|| {
-		// when
-		Example::fetch_price_and_send_unsigned_for_all_accounts(1).unwrap();
-		// then
-		let tx = pool_state.write().transactions.pop().unwrap();
-		let tx = Extrinsic::decode(&mut &*tx).unwrap();
-		assert_eq!(tx.signature, None);
-		if let Call::Example(crate::Call::submit_price_unsigned_with_signed_payload {
-								 price_payload: body,
-								 signature,
-							 }) = tx.call
-		{
-			assert_eq!(body, price_payload);
-
-			let signature_valid =
-				<PricePayload<
-					<Test as SigningTypes>::Public,
-					<Test as frame_system::Config>::BlockNumber,
-				> as SignedPayload<Test>>::verify::<crypto::TestAuthId>(&price_payload, signature);
-
-			assert!(signature_valid);
+}
+
+#[test]
+fn far_ahead_blocks_are_rejected() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(sign_block(BLOCK_NUMBER));
+		let too_far = BLOCK_NUMBER + PENDING_SIGNATURE_BLOCKS + 1;
+		assert_noop!(sign_block(too_far), Error::<Runtime>::BlockTooFarAhead);
+		assert_eq!(
+			validate_unsigned(FinalizerSignaturePayload {
+				block_number: too_far,
+				..unsigned_payload(ALICE, expiry(), finalize_signature(ALICE, too_far, expiry()))
+			}),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Future))
+		);
+
+		// the pending signatures of the newest block are kept
+		assert_ok!(sign_block(too_far - 1));
+		assert!(QuantumPortal::has_submitted(&ALICE, CHAIN_ID, BLOCK_NUMBER));
+		assert_eq!(NewestPendingBlock::<Runtime>::get(CHAIN_ID), Some(too_far - 1));
+	});
+}
+
+#[test]
+fn pruned_blocks_cannot_be_signed_again() {
+	ExtBuilder::default().build().execute_with(|| {
+		let newest = BLOCK_NUMBER + PENDING_SIGNATURE_BLOCKS + 1;
+		for block_number in [BLOCK_NUMBER, newest - 1, newest] {
+			assert_ok!(sign_block(block_number));
 		}
+		assert_eq!(NewestPrunedBlock::<Runtime>::get(CHAIN_ID), Some(BLOCK_NUMBER));
+		assert_noop!(sign_block(BLOCK_NUMBER), Error::<Runtime>::BlockTooOld);
+		assert_eq!(
+			validate_unsigned(unsigned_payload(
+				ALICE,
+				expiry(),
+				finalize_signature(ALICE, BLOCK_NUMBER, expiry())
+			)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Stale))
+		);
+		// an older block still within the window is accepted
+		assert_ok!(sign_block(BLOCK_NUMBER + 1));
 	});
 }
 
 #[test]
-fn should_submit_raw_unsigned_transaction_on_chain() {
-	let (offchain, offchain_state) = testing::TestOffchainExt::new();
-	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
-
-	let keystore = KeyStore::new();
-
-	let mut t = sp_io::TestExternalities::default();
-	t.register_extension(OffchainWorkerExt::new(offchain));
-	t.register_extension(TransactionPoolExt::new(pool));
-	t.register_extension(KeystoreExt(Arc::new(keystore)));
-
-	price_oracle_response(&mut offchain_state.write());
-
-	t.execute_with(|| {
-		// when
-		Example::fetch_price_and_send_raw_unsigned(1).unwrap();
-		// then
-		let tx = pool_state.write().transactions.pop().unwrap();
-		assert!(pool_state.read().transactions.is_empty());
-		let tx = Extrinsic::decode(&mut &*tx).unwrap();
-		assert_eq!(tx.signature, None);
+fn pending_window_restarts_once_the_newest_signatures_expired() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(sign_block(BLOCK_NUMBER));
+		assert_ok!(sign_block(BLOCK_NUMBER + 1));
+
+		// after a downtime the finalizers sign a block far ahead of the expired ones
+		Now::set(expiry());
+		let caught_up = BLOCK_NUMBER + 10 * PENDING_SIGNATURE_BLOCKS;
+		assert_ok!(sign_block(caught_up));
+		assert_eq!(NewestPendingBlock::<Runtime>::get(CHAIN_ID), Some(caught_up));
 		assert_eq!(
-			tx.call,
-			Call::Example(crate::Call::submit_price_unsigned { block_number: 1, price: 15523 })
+			NewestPrunedBlock::<Runtime>::get(CHAIN_ID),
+			Some(caught_up - PENDING_SIGNATURE_BLOCKS - 1)
 		);
+		assert!(!QuantumPortal::has_submitted(&ALICE, CHAIN_ID, BLOCK_NUMBER));
+		assert!(!QuantumPortal::has_submitted(&ALICE, CHAIN_ID, BLOCK_NUMBER + 1));
+		assert_noop!(sign_block(BLOCK_NUMBER + 1), Error::<Runtime>::BlockTooOld);
 	});
 }
 
-fn price_oracle_response(state: &mut testing::OffchainState) {
-	state.expect_request(testing::PendingRequest {
-		method: "GET".into(),
-		uri: "https://min-api.cryptocompare.com/data/price?fsym=BTC&tsyms=USD".into(),
-		response: Some(br#"{"USD": 155.23}"#.to_vec()),
-		sent: true,
-		..Default::default()
+#[test]
+fn submit_signature_unsigned_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let signature = finalize_signature(ALICE, BLOCK_NUMBER, expiry());
+		let payload = unsigned_payload(ALICE, expiry(), signature.clone());
+		assert_noop!(
+			QuantumPortal::submit_signature_unsigned(RuntimeOrigin::signed(ALICE), payload.clone()),
+			BadOrigin
+		);
+		assert_ok!(QuantumPortal::submit_signature_unsigned(RuntimeOrigin::none(), payload));
+		assert_eq!(
+			PendingFinalizeSignatures::<Runtime>::get(CHAIN_ID, BLOCK_NUMBER),
+			Some(vec![(ALICE, signature)])
+		);
 	});
 }
 
 #[test]
-fn parse_price_works() {
-	let test_data = vec![
-		("{\"USD\":6536.92}", Some(653692)),
-		("{\"USD\":65.92}", Some(6592)),
-		("{\"USD\":6536.924565}", Some(653692)),
-		("{\"USD\":6536}", Some(653600)),
-		("{\"USD2\":6536}", None),
-		("{\"USD\":\"6432\"}", None),
-	];
-
-	for (json, expected) in test_data {
-		assert_eq!(expected, Example::parse_price(json));
-	}
+fn validate_unsigned_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let payload =
+			unsigned_payload(ALICE, expiry(), finalize_signature(ALICE, BLOCK_NUMBER, expiry()));
+		let valid = validate_unsigned(payload).unwrap();
+		assert_eq!(valid.priority, 100);
+		assert_eq!(valid.longevity, 64);
+		assert_eq!(
+			valid.provides,
+			vec![(
+				"QuantumPortalFinalizer",
+				(CHAIN_ID, BLOCK_NUMBER, AccountIdToAddress::convert(ALICE))
+			)
+				.encode()]
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_duplicate_as_stale() {
+	ExtBuilder::default().build().execute_with(|| {
+		let signature = finalize_signature(ALICE, BLOCK_NUMBER, expiry());
+		assert_ok!(submit_signature(ALICE, signature.clone()));
+		assert_eq!(
+			validate_unsigned(unsigned_payload(ALICE, expiry(), signature)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Stale))
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_bad_proof() {
+	ExtBuilder::default().build().execute_with(|| {
+		// signed by bob on behalf of alice
+		assert_eq!(
+			validate_unsigned(unsigned_payload(
+				ALICE,
+				expiry(),
+				finalize_signature(BOB, BLOCK_NUMBER, expiry())
+			)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::BadProof))
+		);
+		// the signature covers a different expiry
+		assert_eq!(
+			validate_unsigned(unsigned_payload(
+				ALICE,
+				expiry(),
+				finalize_signature(ALICE, BLOCK_NUMBER, expiry() + 1)
+			)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::BadProof))
+		);
+		// bob is not a finalizer of the chain
+		assert_eq!(
+			validate_unsigned(unsigned_payload(
+				BOB,
+				expiry(),
+				finalize_signature(BOB, BLOCK_NUMBER, expiry())
+			)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::BadSigner))
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_invalid_expiry() {
+	ExtBuilder::default().build().execute_with(|| {
+		let expired = Now::get();
+		assert_eq!(
+			validate_unsigned(unsigned_payload(
+				ALICE,
+				expired,
+				finalize_signature(ALICE, BLOCK_NUMBER, expired)
+			)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Stale))
+		);
+		let too_far = Now::get() + MAX_SIGNATURE_EXPIRY_SECS + 1;
+		assert_eq!(
+			validate_unsigned(unsigned_payload(
+				ALICE,
+				too_far,
+				finalize_signature(ALICE, BLOCK_NUMBER, too_far)
+			)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(EXPIRY_TOO_FAR)))
+		);
+
+		// the payload expires once the clock passes its expiry
+		let payload =
+			unsigned_payload(ALICE, expiry(), finalize_signature(ALICE, BLOCK_NUMBER, expiry()));
+		assert!(validate_unsigned(payload.clone()).is_ok());
+		Now::set(expiry());
+		assert_eq!(
+			validate_unsigned(payload),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Stale))
+		);
+	});
+}
+
+#[test]
+fn unsigned_signature_length_is_bounded() {
+	let oversized =
+		(H160::zero(), CHAIN_ID, BLOCK_NUMBER, FINALIZER_HASH, SALT, expiry(), vec![0u8; 97])
+			.encode();
+	assert!(FinalizerSignaturePayload::decode(&mut &oversized[..]).is_err());
+
+	let exact =
+		(H160::zero(), CHAIN_ID, BLOCK_NUMBER, FINALIZER_HASH, SALT, expiry(), vec![0u8; 96])
+			.encode();
+	assert!(FinalizerSignaturePayload::decode(&mut &exact[..]).is_ok());
 }
@@ -0,0 +1,263 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! EVM runner and precompile set rejecting the calls paused in the pallet.
+
+use crate::{Config, Error, Pallet, PausedEvmCalls};
+use fp_evm::{
+	CallInfo, Config as EvmConfig, CreateInfo, ExitRevert, IsPrecompileResult, PrecompileFailure,
+	PrecompileHandle, PrecompileResult, PrecompileSet,
+};
+use frame_support::{traits::Get, weights::Weight};
+use pallet_evm::{Runner, RunnerError};
+use sp_core::{H160, H256, U256};
+use sp_runtime::DispatchError;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// Wraps the `pallet_evm` runner `R`, rejecting the calls to a paused contract or function
+/// before running them. This covers the calls of `pallet_evm`, of the Ethereum transactions and
+/// of the runtime API, which are rejected before any fee is charged. The calls made by a contract
+/// or a precompile to another one are rejected by [`PausedEvmCallPrecompiles`].
+pub struct PausedEvmCallRunner<T, R>(PhantomData<(T, R)>);
+
+impl<T, R> PausedEvmCallRunner<T, R>
+where
+	T: Config + pallet_evm::Config,
+{
+	fn ensure_not_paused(target: H160, input: &[u8]) -> Result<(), RunnerError<DispatchError>> {
+		if Pallet::<T>::is_evm_call_paused(target, input) {
+			return Err(RunnerError {
+				error: Error::<T>::EvmCallPaused.into(),
+				weight: <T as frame_system::Config>::DbWeight::get().reads(2),
+			});
+		}
+		Ok(())
+	}
+}
+
+fn map_err<E: Into<DispatchError>>(e: RunnerError<E>) -> RunnerError<DispatchError> {
+	RunnerError { error: e.error.into(), weight: e.weight }
+}
+
+impl<T, R> Runner<T> for PausedEvmCallRunner<T, R>
+where
+	T: Config + pallet_evm::Config,
+	R: Runner<T>,
+{
+	type Error = DispatchError;
+
+	fn validate(
+		source: H160,
+		target: Option<H160>,
+		input: Vec<u8>,
+		value: U256,
+		gas_limit: u64,
+		max_fee_per_gas: Option<U256>,
+		max_priority_fee_per_gas: Option<U256>,
+		nonce: Option<U256>,
+		access_list: Vec<(H160, Vec<H256>)>,
+		is_transactional: bool,
+		weight_limit: Option<Weight>,
+		proof_size_base_cost: Option<u64>,
+		evm_config: &EvmConfig,
+	) -> Result<(), RunnerError<Self::Error>> {
+		if let Some(target) = target {
+			Self::ensure_not_paused(target, &input)?;
+		}
+		R::validate(
+			source,
+			target,
+			input,
+			value,
+			gas_limit,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			nonce,
+			access_list,
+			is_transactional,
+			weight_limit,
+			proof_size_base_cost,
+			evm_config,
+		)
+		.map_err(map_err)
+	}
+
+	fn call(
+		source: H160,
+		target: H160,
+		input: Vec<u8>,
+		value: U256,
+		gas_limit: u64,
+		max_fee_per_gas: Option<U256>,
+		max_priority_fee_per_gas: Option<U256>,
+		nonce: Option<U256>,
+		access_list: Vec<(H160, Vec<H256>)>,
+		is_transactional: bool,
+		validate: bool,
+		weight_limit: Option<Weight>,
+		proof_size_base_cost: Option<u64>,
+		config: &EvmConfig,
+	) -> Result<CallInfo, RunnerError<Self::Error>> {
+		Self::ensure_not_paused(target, &input)?;
+		R::call(
+			source,
+			target,
+			input,
+			value,
+			gas_limit,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			nonce,
+			access_list,
+			is_transactional,
+			validate,
+			weight_limit,
+			proof_size_base_cost,
+			config,
+		)
+		.map_err(map_err)
+	}
+
+	fn create(
+		source: H160,
+		init: Vec<u8>,
+		value: U256,
+		gas_limit: u64,
+		max_fee_per_gas: Option<U256>,
+		max_priority_fee_per_gas: Option<U256>,
+		nonce: Option<U256>,
+		access_list: Vec<(H160, Vec<H256>)>,
+		is_transactional: bool,
+		validate: bool,
+		weight_limit: Option<Weight>,
+		proof_size_base_cost: Option<u64>,
+		config: &EvmConfig,
+	) -> Result<CreateInfo, RunnerError<Self::Error>> {
+		R::create(
+			source,
+			init,
+			value,
+			gas_limit,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			nonce,
+			access_list,
+			is_transactional,
+			validate,
+			weight_limit,
+			proof_size_base_cost,
+			config,
+		)
+		.map_err(map_err)
+	}
+
+	fn create2(
+		source: H160,
+		init: Vec<u8>,
+		salt: H256,
+		value: U256,
+		gas_limit: u64,
+		max_fee_per_gas: Option<U256>,
+		max_priority_fee_per_gas: Option<U256>,
+		nonce: Option<U256>,
+		access_list: Vec<(H160, Vec<H256>)>,
+		is_transactional: bool,
+		validate: bool,
+		weight_limit: Option<Weight>,
+		proof_size_base_cost: Option<u64>,
+		config: &EvmConfig,
+	) -> Result<CreateInfo, RunnerError<Self::Error>> {
+		R::create2(
+			source,
+			init,
+			salt,
+			value,
+			gas_limit,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			nonce,
+			access_list,
+			is_transactional,
+			validate,
+			weight_limit,
+			proof_size_base_cost,
+			config,
+		)
+		.map_err(map_err)
+	}
+}
+
+/// Proof size of reading the count of paused EVM calls, its 32 bytes key and `u32` value
+const PAUSED_COUNT_PROOF_SIZE: u64 = 36;
+
+/// Proof size of looking up a paused EVM call: the 32 bytes prefix, the `Twox64Concat` key of
+/// the contract and selector, and the expiry
+const PAUSED_EVM_CALL_PROOF_SIZE: u64 = 32 + 8 + 25 + 6;
+
+/// Wraps the precompile set `P` of the runtime, reverting the calls to a paused contract or
+/// function. The EVM looks up the precompile set for every call frame, so this also covers the
+/// calls made by a contract to another one and the subcalls of the precompiles, such as the batch
+/// and proxy ones. The check reads the count of paused EVM calls, and only while it is not zero
+/// the paused calls of the contract. These reads are recorded as weight and proof size, not as
+/// gas, so a call with the 2300 gas stipend still goes through.
+pub struct PausedEvmCallPrecompiles<T, P> {
+	precompiles: P,
+	_marker: PhantomData<T>,
+}
+
+impl<T, P> PausedEvmCallPrecompiles<T, P> {
+	pub fn new(precompiles: P) -> Self {
+		Self { precompiles, _marker: PhantomData }
+	}
+}
+
+impl<T, P: Default> Default for PausedEvmCallPrecompiles<T, P> {
+	fn default() -> Self {
+		Self::new(P::default())
+	}
+}
+
+impl<T, P> PrecompileSet for PausedEvmCallPrecompiles<T, P>
+where
+	T: Config,
+	P: PrecompileSet,
+{
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		let any_paused = PausedEvmCalls::<T>::count() != 0;
+		let (reads, proof_size) = if any_paused {
+			(3, PAUSED_COUNT_PROOF_SIZE + 2 * PAUSED_EVM_CALL_PROOF_SIZE)
+		} else {
+			(1, PAUSED_COUNT_PROOF_SIZE)
+		};
+		let ref_time = <T as frame_system::Config>::DbWeight::get().reads(reads).ref_time();
+		if let Err(e) = handle.record_external_cost(Some(ref_time), Some(proof_size), None) {
+			return Some(Err(e.into()));
+		}
+
+		if any_paused && Pallet::<T>::is_evm_call_paused(handle.code_address(), handle.input()) {
+			return Some(Err(PrecompileFailure::Revert {
+				exit_status: ExitRevert::Reverted,
+				output: b"EvmCallPaused".to_vec(),
+			}));
+		}
+
+		self.precompiles.execute(handle)
+	}
+
+	fn is_precompile(&self, address: H160, gas: u64) -> IsPrecompileResult {
+		self.precompiles.is_precompile(address, gas)
+	}
+}
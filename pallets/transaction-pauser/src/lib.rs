@@ -18,29 +18,55 @@
 
 use frame_support::{
 	pallet_prelude::*,
-	traits::{CallMetadata, Contains, GetCallMetadata, PalletInfoAccess},
+	traits::{CallMetadata, Contains, GetCallMetadata, OriginTrait, PalletInfoAccess},
 	transactional,
 };
 use frame_system::pallet_prelude::*;
-use sp_runtime::DispatchResult;
+use sp_core::H160;
+use sp_runtime::{traits::BadOrigin, DispatchResult};
 use sp_std::{prelude::*, vec::Vec};
 
+mod evm;
+pub mod migrations;
 mod mock;
 mod tests;
 pub mod weights;
+pub use evm::{PausedEvmCallPrecompiles, PausedEvmCallRunner};
 pub use module::*;
 pub use weights::WeightInfo;
 
+/// The caller of an origin, recorded in the pause events.
+pub type PalletsOriginOf<T> =
+	<<T as frame_system::Config>::RuntimeOrigin as OriginTrait>::PalletsOrigin;
+
+/// A paused call, as indexed by the block at which its pause expires.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum PausedCall {
+	/// Substrate call, to every function of the pallet if no function name is given
+	Transaction { pallet_name: Vec<u8>, function_name: Option<Vec<u8>> },
+	/// EVM call, to every function of the contract if no selector is given
+	EvmCall { contract: H160, selector: Option<[u8; 4]> },
+}
+
 #[frame_support::pallet]
 pub mod module {
 	use super::*;
 
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
+		/// The overarching call, whose pallet and function names are the ones allowed to pause.
+		type RuntimeCall: GetCallMetadata + IsType<<Self as frame_system::Config>::RuntimeCall>;
+
 		/// The origin which may set filter.
-		type UpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		type UpdateOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+
+		/// Names of the pallets whose calls may not be paused on top of this one, such as the
+		/// governance pallets needed to lift a pause.
+		type UnpausablePallets: Get<&'static [&'static str]>;
 
 		/// Weight information for the extrinsics in this module.
 		type WeightInfo: WeightInfo;
@@ -48,81 +74,178 @@ pub mod module {
 
 	#[pallet::error]
 	pub enum Error<T> {
-		/// cannot pause self pallet
+		/// cannot pause self pallet or an unpausable one
 		InvalidPalletName,
 		/// invalid palet name given
 		CannotDecodeName,
+		/// the called contract or function is paused
+		EvmCallPaused,
+		/// no pallet of the runtime has the given name
+		UnknownPalletName,
+		/// the pallet has no call of the given name
+		UnknownFunctionName,
+		/// the expiry block is not in the future
+		InvalidExpiry,
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// Paused transaction
-		TransactionPaused { pallet_name_bytes: Vec<u8>, function_name_bytes: Vec<u8> },
-		/// Unpaused transaction
-		TransactionUnpaused { pallet_name_bytes: Vec<u8>, function_name_bytes: Vec<u8> },
+		/// Paused transaction, every function of the pallet if no function name is given
+		TransactionPaused {
+			pallet_name_bytes: Vec<u8>,
+			function_name_bytes: Option<Vec<u8>>,
+			expiry: Option<BlockNumberFor<T>>,
+			reason: Vec<u8>,
+			origin: PalletsOriginOf<T>,
+		},
+		/// Unpaused transaction, by an origin or on expiry
+		TransactionUnpaused { pallet_name_bytes: Vec<u8>, function_name_bytes: Option<Vec<u8>> },
+		/// Paused EVM call, to every function of the contract if no selector is given
+		EvmCallPaused {
+			contract: H160,
+			selector: Option<[u8; 4]>,
+			expiry: Option<BlockNumberFor<T>>,
+			reason: Vec<u8>,
+			origin: PalletsOriginOf<T>,
+		},
+		/// Unpaused EVM call, by an origin or on expiry
+		EvmCallUnpaused { contract: H160, selector: Option<[u8; 4]> },
+		/// Set the pauser admin
+		PauserAdminSet { admin: Option<T::AccountId> },
 	}
 
-	/// The paused transaction map
+	/// The paused transaction map, a `None` function name pausing every function of the pallet
 	///
-	/// map (PalletNameBytes, FunctionNameBytes) => Option<()>
+	/// map (PalletNameBytes, Option<FunctionNameBytes>) => Option<Option<ExpiryBlock>>
 	#[pallet::storage]
 	#[pallet::getter(fn paused_transactions)]
-	pub type PausedTransactions<T: Config> =
-		StorageMap<_, Twox64Concat, (Vec<u8>, Vec<u8>), (), OptionQuery>;
+	pub type PausedTransactions<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		(Vec<u8>, Option<Vec<u8>>),
+		Option<BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// The paused EVM call map, a `None` selector pausing every function of the contract
+	///
+	/// map (ContractAddress, Option<Selector>) => Option<Option<ExpiryBlock>>
+	#[pallet::storage]
+	#[pallet::getter(fn paused_evm_calls)]
+	pub type PausedEvmCalls<T: Config> = CountedStorageMap<
+		_,
+		Twox64Concat,
+		(H160, Option<[u8; 4]>),
+		Option<BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// The calls whose pause expires at a block, unpaused in its `on_initialize`
+	///
+	/// map ExpiryBlock => Vec<PausedCall>
+	#[pallet::storage]
+	#[pallet::getter(fn pause_expiries)]
+	pub type PauseExpiries<T: Config> =
+		StorageMap<_, Twox64Concat, BlockNumberFor<T>, Vec<PausedCall>, ValueQuery>;
+
+	/// The account allowed to pause and unpause along with `UpdateOrigin`, such as the admin
+	/// contract calling the pauser precompile
+	#[pallet::storage]
+	#[pallet::getter(fn pauser_admin)]
+	pub type PauserAdmin<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
 
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			let expired = PauseExpiries::<T>::take(now);
+			let count = expired.len() as u32;
+			for paused_call in expired {
+				match paused_call {
+					PausedCall::Transaction { pallet_name, function_name } => {
+						PausedTransactions::<T>::remove((&pallet_name, &function_name));
+						Self::deposit_event(Event::TransactionUnpaused {
+							pallet_name_bytes: pallet_name,
+							function_name_bytes: function_name,
+						});
+					},
+					PausedCall::EvmCall { contract, selector } => {
+						PausedEvmCalls::<T>::remove((contract, selector));
+						Self::deposit_event(Event::EvmCallUnpaused { contract, selector });
+					},
+				}
+			}
+			T::WeightInfo::expire_pauses(count)
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// Pauses the call `function_name` of the pallet `pallet_name`, or every call of the
+		/// pallet if no function name is given, until the block `expiry` if any. Pausing a paused
+		/// call replaces its expiry.
 		#[pallet::call_index(0)]
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::pause_transaction())]
 		#[transactional]
 		pub fn pause_transaction(
 			origin: OriginFor<T>,
 			pallet_name: Vec<u8>,
-			function_name: Vec<u8>,
+			function_name: Option<Vec<u8>>,
+			expiry: Option<BlockNumberFor<T>>,
+			reason: Vec<u8>,
 		) -> DispatchResult {
-			T::UpdateOrigin::ensure_origin(origin)?;
+			Self::ensure_pauser(origin.clone())?;
+			Self::ensure_valid_names(&pallet_name, function_name.as_deref())?;
+			Self::ensure_valid_expiry(expiry)?;
 
-			// not allowed to pause calls of this pallet to ensure safe
-			let pallet_name_string =
-				sp_std::str::from_utf8(&pallet_name).map_err(|_| Error::<T>::CannotDecodeName)?;
-			ensure!(
-				pallet_name_string != <Self as PalletInfoAccess>::name(),
-				Error::<T>::InvalidPalletName
-			);
-
-			PausedTransactions::<T>::mutate_exists(
-				(pallet_name.clone(), function_name.clone()),
-				|maybe_paused| {
-					if maybe_paused.is_none() {
-						*maybe_paused = Some(());
-						Self::deposit_event(Event::TransactionPaused {
-							pallet_name_bytes: pallet_name,
-							function_name_bytes: function_name,
-						});
-					}
+			let key = (pallet_name.clone(), function_name.clone());
+			let previous_expiry = PausedTransactions::<T>::get(&key).flatten();
+			PausedTransactions::<T>::insert(&key, expiry);
+			Self::update_expiry(
+				PausedCall::Transaction {
+					pallet_name: pallet_name.clone(),
+					function_name: function_name.clone(),
 				},
+				previous_expiry,
+				expiry,
 			);
+
+			Self::deposit_event(Event::TransactionPaused {
+				pallet_name_bytes: pallet_name,
+				function_name_bytes: function_name,
+				expiry,
+				reason,
+				origin: origin.into_caller(),
+			});
 			Ok(())
 		}
 
+		/// Unpauses the call `function_name` of the pallet `pallet_name`, or the pause of the
+		/// whole pallet if no function name is given. The names are not checked against the
+		/// runtime, so that the pauses of removed calls can be cleaned up.
 		#[pallet::call_index(1)]
-		#[pallet::weight(1)]
+		#[pallet::weight(T::WeightInfo::unpause_transaction())]
 		#[transactional]
 		pub fn unpause_transaction(
 			origin: OriginFor<T>,
 			pallet_name: Vec<u8>,
-			function_name: Vec<u8>,
+			function_name: Option<Vec<u8>>,
 		) -> DispatchResult {
-			T::UpdateOrigin::ensure_origin(origin)?;
-			if PausedTransactions::<T>::take((&pallet_name, &function_name)).is_some() {
+			Self::ensure_pauser(origin)?;
+			if let Some(expiry) = PausedTransactions::<T>::take((&pallet_name, &function_name)) {
+				Self::update_expiry(
+					PausedCall::Transaction {
+						pallet_name: pallet_name.clone(),
+						function_name: function_name.clone(),
+					},
+					expiry,
+					None,
+				);
 				Self::deposit_event(Event::TransactionUnpaused {
 					pallet_name_bytes: pallet_name,
 					function_name_bytes: function_name,
@@ -130,16 +253,177 @@ pub mod module {
 			};
 			Ok(())
 		}
+
+		/// Pauses the EVM calls to the function `selector` of `contract`, or to every function of
+		/// the contract if no selector is given, until the block `expiry` if any. Pausing a
+		/// paused call replaces its expiry.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::pause_evm_call())]
+		#[transactional]
+		pub fn pause_evm_call(
+			origin: OriginFor<T>,
+			contract: H160,
+			selector: Option<[u8; 4]>,
+			expiry: Option<BlockNumberFor<T>>,
+			reason: Vec<u8>,
+		) -> DispatchResult {
+			Self::ensure_pauser(origin.clone())?;
+			Self::ensure_valid_expiry(expiry)?;
+
+			let previous_expiry = PausedEvmCalls::<T>::get((contract, selector)).flatten();
+			PausedEvmCalls::<T>::insert((contract, selector), expiry);
+			Self::update_expiry(
+				PausedCall::EvmCall { contract, selector },
+				previous_expiry,
+				expiry,
+			);
+
+			Self::deposit_event(Event::EvmCallPaused {
+				contract,
+				selector,
+				expiry,
+				reason,
+				origin: origin.into_caller(),
+			});
+			Ok(())
+		}
+
+		/// Unpauses the EVM calls to the function `selector` of `contract`, or the pause of the
+		/// whole contract if no selector is given.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::unpause_evm_call())]
+		#[transactional]
+		pub fn unpause_evm_call(
+			origin: OriginFor<T>,
+			contract: H160,
+			selector: Option<[u8; 4]>,
+		) -> DispatchResult {
+			Self::ensure_pauser(origin)?;
+			if let Some(expiry) = PausedEvmCalls::<T>::take((contract, selector)) {
+				Self::update_expiry(PausedCall::EvmCall { contract, selector }, expiry, None);
+				Self::deposit_event(Event::EvmCallUnpaused { contract, selector });
+			};
+			Ok(())
+		}
+
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::set_pauser_admin())]
+		pub fn set_pauser_admin(
+			origin: OriginFor<T>,
+			admin: Option<T::AccountId>,
+		) -> DispatchResult {
+			// the admin is not allowed to replace itself
+			T::UpdateOrigin::ensure_origin(origin)?;
+			PauserAdmin::<T>::set(admin.clone());
+			Self::deposit_event(Event::PauserAdminSet { admin });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Ensures the origin is either `UpdateOrigin` or signed by the pauser admin.
+	fn ensure_pauser(origin: OriginFor<T>) -> DispatchResult {
+		if T::UpdateOrigin::try_origin(origin.clone()).is_ok() {
+			return Ok(());
+		}
+		let who = ensure_signed(origin)?;
+		ensure!(PauserAdmin::<T>::get() == Some(who), BadOrigin);
+		Ok(())
+	}
+
+	/// Ensures the names are the ones of a pallet of the runtime, other than this one, and of
+	/// one of its calls.
+	fn ensure_valid_names(pallet_name: &[u8], function_name: Option<&[u8]>) -> DispatchResult {
+		let pallet_name =
+			sp_std::str::from_utf8(pallet_name).map_err(|_| Error::<T>::CannotDecodeName)?;
+		// not allowed to pause calls of this pallet and of the governance to ensure safe
+		ensure!(
+			pallet_name != <Self as PalletInfoAccess>::name() &&
+				!T::UnpausablePallets::get().contains(&pallet_name),
+			Error::<T>::InvalidPalletName
+		);
+		ensure!(
+			<T as Config>::RuntimeCall::get_module_names().contains(&pallet_name),
+			Error::<T>::UnknownPalletName
+		);
+
+		if let Some(function_name) = function_name {
+			let function_name =
+				sp_std::str::from_utf8(function_name).map_err(|_| Error::<T>::CannotDecodeName)?;
+			ensure!(
+				<T as Config>::RuntimeCall::get_call_names(pallet_name).contains(&function_name),
+				Error::<T>::UnknownFunctionName
+			);
+		}
+		Ok(())
+	}
+
+	fn ensure_valid_expiry(expiry: Option<BlockNumberFor<T>>) -> DispatchResult {
+		if let Some(expiry) = expiry {
+			ensure!(expiry > frame_system::Pallet::<T>::block_number(), Error::<T>::InvalidExpiry);
+		}
+		Ok(())
+	}
+
+	/// Moves `paused_call` from the calls expiring at `previous_expiry` to the ones expiring at
+	/// `expiry`.
+	fn update_expiry(
+		paused_call: PausedCall,
+		previous_expiry: Option<BlockNumberFor<T>>,
+		expiry: Option<BlockNumberFor<T>>,
+	) {
+		if let Some(previous_expiry) = previous_expiry {
+			PauseExpiries::<T>::mutate_exists(previous_expiry, |maybe_calls| {
+				let is_empty = match maybe_calls {
+					Some(calls) => {
+						calls.retain(|call| call != &paused_call);
+						calls.is_empty()
+					},
+					None => false,
+				};
+				if is_empty {
+					*maybe_calls = None;
+				}
+			});
+		}
+		if let Some(expiry) = expiry {
+			PauseExpiries::<T>::append(expiry, paused_call);
+		}
+	}
+
+	/// Whether the call `function_name` of the pallet `pallet_name` is paused, either by name or
+	/// as a whole pallet.
+	pub fn is_transaction_paused(pallet_name: &[u8], function_name: &[u8]) -> bool {
+		PausedTransactions::<T>::contains_key((pallet_name, None::<Vec<u8>>)) ||
+			PausedTransactions::<T>::contains_key((pallet_name, Some(function_name)))
+	}
+
+	/// Whether a call to `contract` with `input` is paused, either as a whole contract or by the
+	/// selector of the called function.
+	pub fn is_evm_call_paused(contract: H160, input: &[u8]) -> bool {
+		// EVM calls are rarely paused, the count spares looking them up meanwhile
+		if PausedEvmCalls::<T>::count() == 0 {
+			return false;
+		}
+		if PausedEvmCalls::<T>::contains_key((contract, None::<[u8; 4]>)) {
+			return true;
+		}
+		match input.get(0..4) {
+			Some(selector) => PausedEvmCalls::<T>::contains_key((
+				contract,
+				Some(<[u8; 4]>::try_from(selector).expect("slice of 4 bytes; qed")),
+			)),
+			None => false,
+		}
 	}
 }
 
 pub struct PausedTransactionFilter<T>(sp_std::marker::PhantomData<T>);
-impl<T: Config> Contains<T::RuntimeCall> for PausedTransactionFilter<T>
-where
-	<T as frame_system::Config>::RuntimeCall: GetCallMetadata,
-{
-	fn contains(call: &T::RuntimeCall) -> bool {
-		let CallMetadata { function_name, pallet_name } = call.get_call_metadata();
-		PausedTransactions::<T>::contains_key((pallet_name.as_bytes(), function_name.as_bytes()))
+impl<T: Config> Contains<<T as frame_system::Config>::RuntimeCall> for PausedTransactionFilter<T> {
+	fn contains(call: &<T as frame_system::Config>::RuntimeCall) -> bool {
+		let CallMetadata { function_name, pallet_name } =
+			<T as Config>::RuntimeCall::from_ref(call).get_call_metadata();
+		Pallet::<T>::is_transaction_paused(pallet_name.as_bytes(), function_name.as_bytes())
 	}
 }
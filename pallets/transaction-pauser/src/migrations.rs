@@ -0,0 +1,57 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Migrations of the pallet storage.
+
+use crate::{Config, Pallet};
+use frame_support::{pallet_prelude::*, storage_alias, traits::OnRuntimeUpgrade};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// Migration to v1, in which a pause can cover a whole pallet and has an optional expiry.
+pub mod v1 {
+	use super::*;
+
+	/// The paused transactions of v0, only by pallet and function name.
+	#[storage_alias]
+	type PausedTransactions<T: Config> =
+		StorageMap<Pallet<T>, Twox64Concat, (Vec<u8>, Vec<u8>), (), OptionQuery>;
+
+	/// Moves the paused transactions to the key of their function and sets the pauses to never
+	/// expire.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() != 0 {
+				log::info!("pallet_transaction_pauser: v1 migration already applied, skipping");
+				return T::DbWeight::get().reads(1);
+			}
+
+			// drained before inserting, the keys of both versions sharing the same prefix. The
+			// paused EVM calls are new in v1, so there are none to migrate.
+			let paused_transactions: Vec<_> =
+				PausedTransactions::<T>::drain().map(|(key, ())| key).collect();
+			let count = paused_transactions.len() as u64;
+
+			for (pallet_name, function_name) in paused_transactions {
+				crate::PausedTransactions::<T>::insert((pallet_name, Some(function_name)), None);
+			}
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			log::info!("pallet_transaction_pauser: migrated {} pauses to v1", count);
+			T::DbWeight::get().reads_writes(count + 1, count * 2 + 1)
+		}
+	}
+}
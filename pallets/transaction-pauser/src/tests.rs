@@ -15,18 +15,37 @@
 // along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
 #![cfg(test)]
 use super::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
 use mock::{RuntimeEvent, *};
+use sp_core::H160;
 use sp_runtime::traits::BadOrigin;
 
 const BALANCE_TRANSFER: &<Runtime as frame_system::Config>::RuntimeCall =
-	&mock::RuntimeCall::Balances(pallet_balances::Call::transfer { dest: ALICE, value: 10 });
-const TOKENS_TRANSFER: &<Runtime as frame_system::Config>::RuntimeCall =
-	&mock::RuntimeCall::Tokens(orml_tokens::Call::transfer {
+	&mock::RuntimeCall::Balances(pallet_balances::Call::transfer_allow_death {
 		dest: ALICE,
-		currency_id: STABLE,
-		amount: 10,
+		value: 10,
 	});
+const BALANCE_TRANSFER_KEEP_ALIVE: &<Runtime as frame_system::Config>::RuntimeCall =
+	&mock::RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive {
+		dest: ALICE,
+		value: 10,
+	});
+const SYSTEM_REMARK: &<Runtime as frame_system::Config>::RuntimeCall =
+	&mock::RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+
+fn signed(who: AccountId) -> OriginCaller {
+	OriginCaller::system(frame_system::RawOrigin::Signed(who))
+}
+
+fn pause_transfer(expiry: Option<u64>) -> DispatchResult {
+	TransactionPause::pause_transaction(
+		RuntimeOrigin::signed(1),
+		b"Balances".to_vec(),
+		Some(b"transfer_allow_death".to_vec()),
+		expiry,
+		b"exploit".to_vec(),
+	)
+}
 
 #[test]
 fn pause_transaction_work() {
@@ -37,36 +56,45 @@ fn pause_transaction_work() {
 			TransactionPause::pause_transaction(
 				RuntimeOrigin::signed(5),
 				b"Balances".to_vec(),
-				b"transfer".to_vec()
+				Some(b"transfer_allow_death".to_vec()),
+				None,
+				vec![]
 			),
 			BadOrigin
 		);
 
 		assert_eq!(
-			TransactionPause::paused_transactions((b"Balances".to_vec(), b"transfer".to_vec())),
+			TransactionPause::paused_transactions((
+				b"Balances".to_vec(),
+				Some(b"transfer_allow_death".to_vec())
+			)),
 			None
 		);
-		assert_ok!(TransactionPause::pause_transaction(
-			RuntimeOrigin::signed(1),
-			b"Balances".to_vec(),
-			b"transfer".to_vec()
-		));
+		assert_ok!(pause_transfer(None));
 		System::assert_last_event(RuntimeEvent::TransactionPause(
 			crate::Event::TransactionPaused {
 				pallet_name_bytes: b"Balances".to_vec(),
-				function_name_bytes: b"transfer".to_vec(),
+				function_name_bytes: Some(b"transfer_allow_death".to_vec()),
+				expiry: None,
+				reason: b"exploit".to_vec(),
+				origin: signed(1),
 			},
 		));
 		assert_eq!(
-			TransactionPause::paused_transactions((b"Balances".to_vec(), b"transfer".to_vec())),
-			Some(())
+			TransactionPause::paused_transactions((
+				b"Balances".to_vec(),
+				Some(b"transfer_allow_death".to_vec())
+			)),
+			Some(None)
 		);
 
 		assert_noop!(
 			TransactionPause::pause_transaction(
 				RuntimeOrigin::signed(1),
 				b"TransactionPause".to_vec(),
-				b"pause_transaction".to_vec()
+				Some(b"pause_transaction".to_vec()),
+				None,
+				vec![]
 			),
 			Error::<Runtime>::InvalidPalletName
 		);
@@ -74,15 +102,86 @@ fn pause_transaction_work() {
 			TransactionPause::pause_transaction(
 				RuntimeOrigin::signed(1),
 				b"TransactionPause".to_vec(),
-				b"some_other_call".to_vec()
+				None,
+				None,
+				vec![]
+			),
+			Error::<Runtime>::InvalidPalletName
+		);
+	});
+}
+
+#[test]
+fn pause_transaction_checks_names() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			TransactionPause::pause_transaction(
+				RuntimeOrigin::signed(1),
+				b"OtherPallet".to_vec(),
+				Some(b"pause_transaction".to_vec()),
+				None,
+				vec![]
+			),
+			Error::<Runtime>::UnknownPalletName
+		);
+		assert_noop!(
+			TransactionPause::pause_transaction(
+				RuntimeOrigin::signed(1),
+				b"OtherPallet".to_vec(),
+				None,
+				None,
+				vec![]
+			),
+			Error::<Runtime>::UnknownPalletName
+		);
+		assert_noop!(
+			TransactionPause::pause_transaction(
+				RuntimeOrigin::signed(1),
+				b"Balances".to_vec(),
+				Some(b"transfer".to_vec()),
+				None,
+				vec![]
+			),
+			Error::<Runtime>::UnknownFunctionName
+		);
+		assert_noop!(
+			TransactionPause::pause_transaction(
+				RuntimeOrigin::signed(1),
+				b"Balances".to_vec(),
+				Some(vec![0xff]),
+				None,
+				vec![]
+			),
+			Error::<Runtime>::CannotDecodeName
+		);
+	});
+}
+
+#[test]
+fn pause_transaction_rejects_unpausable_pallets() {
+	ExtBuilder::default().build().execute_with(|| {
+		for pallet_name in [&b"Referenda"[..], b"TransactionPause"] {
+			assert_noop!(
+				TransactionPause::pause_transaction(
+					RuntimeOrigin::signed(1),
+					pallet_name.to_vec(),
+					None,
+					None,
+					vec![]
+				),
+				Error::<Runtime>::InvalidPalletName
+			);
+		}
+		assert_noop!(
+			TransactionPause::pause_transaction(
+				RuntimeOrigin::signed(1),
+				b"Referenda".to_vec(),
+				Some(b"submit".to_vec()),
+				None,
+				vec![]
 			),
 			Error::<Runtime>::InvalidPalletName
 		);
-		assert_ok!(TransactionPause::pause_transaction(
-			RuntimeOrigin::signed(1),
-			b"OtherPallet".to_vec(),
-			b"pause_transaction".to_vec()
-		));
 	});
 }
 
@@ -91,21 +190,20 @@ fn unpause_transaction_work() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 
-		assert_ok!(TransactionPause::pause_transaction(
-			RuntimeOrigin::signed(1),
-			b"Balances".to_vec(),
-			b"transfer".to_vec()
-		));
+		assert_ok!(pause_transfer(None));
 		assert_eq!(
-			TransactionPause::paused_transactions((b"Balances".to_vec(), b"transfer".to_vec())),
-			Some(())
+			TransactionPause::paused_transactions((
+				b"Balances".to_vec(),
+				Some(b"transfer_allow_death".to_vec())
+			)),
+			Some(None)
 		);
 
 		assert_noop!(
 			TransactionPause::unpause_transaction(
 				RuntimeOrigin::signed(5),
 				b"Balances".to_vec(),
-				b"transfer".to_vec()
+				Some(b"transfer_allow_death".to_vec())
 			),
 			BadOrigin
 		);
@@ -113,16 +211,19 @@ fn unpause_transaction_work() {
 		assert_ok!(TransactionPause::unpause_transaction(
 			RuntimeOrigin::signed(1),
 			b"Balances".to_vec(),
-			b"transfer".to_vec()
+			Some(b"transfer_allow_death".to_vec())
 		));
 		System::assert_last_event(RuntimeEvent::TransactionPause(
 			crate::Event::TransactionUnpaused {
 				pallet_name_bytes: b"Balances".to_vec(),
-				function_name_bytes: b"transfer".to_vec(),
+				function_name_bytes: Some(b"transfer_allow_death".to_vec()),
 			},
 		));
 		assert_eq!(
-			TransactionPause::paused_transactions((b"Balances".to_vec(), b"transfer".to_vec())),
+			TransactionPause::paused_transactions((
+				b"Balances".to_vec(),
+				Some(b"transfer_allow_death".to_vec())
+			)),
 			None
 		);
 	});
@@ -132,30 +233,344 @@ fn unpause_transaction_work() {
 fn paused_transaction_filter_work() {
 	ExtBuilder::default().build().execute_with(|| {
 		assert!(!PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER));
-		assert!(!PausedTransactionFilter::<Runtime>::contains(TOKENS_TRANSFER));
+		assert!(!PausedTransactionFilter::<Runtime>::contains(SYSTEM_REMARK));
+		assert_ok!(pause_transfer(None));
 		assert_ok!(TransactionPause::pause_transaction(
+			RuntimeOrigin::signed(1),
+			b"System".to_vec(),
+			Some(b"remark".to_vec()),
+			None,
+			vec![]
+		));
+		assert!(PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER));
+		assert!(!PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER_KEEP_ALIVE));
+		assert!(PausedTransactionFilter::<Runtime>::contains(SYSTEM_REMARK));
+		assert_ok!(TransactionPause::unpause_transaction(
 			RuntimeOrigin::signed(1),
 			b"Balances".to_vec(),
-			b"transfer".to_vec()
+			Some(b"transfer_allow_death".to_vec())
 		));
+		assert_ok!(TransactionPause::unpause_transaction(
+			RuntimeOrigin::signed(1),
+			b"System".to_vec(),
+			Some(b"remark".to_vec())
+		));
+		assert!(!PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER));
+		assert!(!PausedTransactionFilter::<Runtime>::contains(SYSTEM_REMARK));
+	});
+}
+
+#[test]
+fn pause_whole_pallet_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
 		assert_ok!(TransactionPause::pause_transaction(
 			RuntimeOrigin::signed(1),
-			b"Tokens".to_vec(),
-			b"transfer".to_vec()
+			b"Balances".to_vec(),
+			None,
+			None,
+			b"exploit".to_vec()
+		));
+		System::assert_last_event(RuntimeEvent::TransactionPause(
+			crate::Event::TransactionPaused {
+				pallet_name_bytes: b"Balances".to_vec(),
+				function_name_bytes: None,
+				expiry: None,
+				reason: b"exploit".to_vec(),
+				origin: signed(1),
+			},
 		));
 		assert!(PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER));
-		assert!(PausedTransactionFilter::<Runtime>::contains(TOKENS_TRANSFER));
+		assert!(PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER_KEEP_ALIVE));
+		assert!(!PausedTransactionFilter::<Runtime>::contains(SYSTEM_REMARK));
+
+		// unpausing a single function leaves the pallet paused
 		assert_ok!(TransactionPause::unpause_transaction(
 			RuntimeOrigin::signed(1),
 			b"Balances".to_vec(),
-			b"transfer".to_vec()
+			Some(b"transfer_allow_death".to_vec())
 		));
+		assert!(PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER));
+
 		assert_ok!(TransactionPause::unpause_transaction(
 			RuntimeOrigin::signed(1),
-			b"Tokens".to_vec(),
-			b"transfer".to_vec()
+			b"Balances".to_vec(),
+			None
+		));
+		System::assert_last_event(RuntimeEvent::TransactionPause(
+			crate::Event::TransactionUnpaused {
+				pallet_name_bytes: b"Balances".to_vec(),
+				function_name_bytes: None,
+			},
+		));
+		assert!(!PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER));
+		assert!(!PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER_KEEP_ALIVE));
+	});
+}
+
+#[test]
+fn pause_expiry_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(2);
+		let paused_transfer = PausedCall::Transaction {
+			pallet_name: b"Balances".to_vec(),
+			function_name: Some(b"transfer_allow_death".to_vec()),
+		};
+
+		assert_noop!(pause_transfer(Some(2)), Error::<Runtime>::InvalidExpiry);
+
+		assert_ok!(pause_transfer(Some(5)));
+		assert_eq!(TransactionPause::pause_expiries(5), vec![paused_transfer.clone()]);
+
+		// pausing again replaces the expiry
+		assert_ok!(pause_transfer(Some(8)));
+		assert!(!PauseExpiries::<Runtime>::contains_key(5));
+		assert_eq!(TransactionPause::pause_expiries(8), vec![paused_transfer.clone()]);
+
+		TransactionPause::on_initialize(5);
+		assert!(PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER));
+
+		TransactionPause::on_initialize(8);
+		System::assert_last_event(RuntimeEvent::TransactionPause(
+			crate::Event::TransactionUnpaused {
+				pallet_name_bytes: b"Balances".to_vec(),
+				function_name_bytes: Some(b"transfer_allow_death".to_vec()),
+			},
 		));
 		assert!(!PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER));
-		assert!(!PausedTransactionFilter::<Runtime>::contains(TOKENS_TRANSFER));
+		assert!(!PauseExpiries::<Runtime>::contains_key(8));
+
+		// unpausing removes the expiry
+		assert_ok!(pause_transfer(Some(10)));
+		assert_ok!(TransactionPause::unpause_transaction(
+			RuntimeOrigin::signed(1),
+			b"Balances".to_vec(),
+			Some(b"transfer_allow_death".to_vec())
+		));
+		assert!(!PauseExpiries::<Runtime>::contains_key(10));
+	});
+}
+
+#[test]
+fn pause_evm_call_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let contract = H160::repeat_byte(0x11);
+
+		assert_noop!(
+			TransactionPause::pause_evm_call(
+				RuntimeOrigin::signed(5),
+				contract,
+				None,
+				None,
+				vec![]
+			),
+			BadOrigin
+		);
+
+		assert_ok!(TransactionPause::pause_evm_call(
+			RuntimeOrigin::signed(1),
+			contract,
+			Some([1, 2, 3, 4]),
+			None,
+			b"exploit".to_vec()
+		));
+		System::assert_last_event(RuntimeEvent::TransactionPause(crate::Event::EvmCallPaused {
+			contract,
+			selector: Some([1, 2, 3, 4]),
+			expiry: None,
+			reason: b"exploit".to_vec(),
+			origin: signed(1),
+		}));
+		assert_eq!(TransactionPause::paused_evm_calls((contract, Some([1, 2, 3, 4]))), Some(None));
+		assert_eq!(TransactionPause::paused_evm_calls((contract, None::<[u8; 4]>)), None);
+
+		assert_noop!(
+			TransactionPause::unpause_evm_call(
+				RuntimeOrigin::signed(5),
+				contract,
+				Some([1, 2, 3, 4])
+			),
+			BadOrigin
+		);
+		assert_ok!(TransactionPause::unpause_evm_call(
+			RuntimeOrigin::signed(1),
+			contract,
+			Some([1, 2, 3, 4])
+		));
+		System::assert_last_event(RuntimeEvent::TransactionPause(crate::Event::EvmCallUnpaused {
+			contract,
+			selector: Some([1, 2, 3, 4]),
+		}));
+		assert_eq!(TransactionPause::paused_evm_calls((contract, Some([1, 2, 3, 4]))), None);
+	});
+}
+
+#[test]
+fn pause_evm_call_expiry_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let contract = H160::repeat_byte(0x11);
+
+		assert_noop!(
+			TransactionPause::pause_evm_call(
+				RuntimeOrigin::signed(1),
+				contract,
+				None,
+				Some(1),
+				vec![]
+			),
+			Error::<Runtime>::InvalidExpiry
+		);
+		assert_ok!(TransactionPause::pause_evm_call(
+			RuntimeOrigin::signed(1),
+			contract,
+			None,
+			Some(3),
+			vec![]
+		));
+		assert_eq!(
+			TransactionPause::pause_expiries(3),
+			vec![PausedCall::EvmCall { contract, selector: None }]
+		);
+		assert!(TransactionPause::is_evm_call_paused(contract, &[]));
+
+		TransactionPause::on_initialize(3);
+		System::assert_last_event(RuntimeEvent::TransactionPause(crate::Event::EvmCallUnpaused {
+			contract,
+			selector: None,
+		}));
+		assert!(!TransactionPause::is_evm_call_paused(contract, &[]));
+	});
+}
+
+#[test]
+fn is_evm_call_paused_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let contract = H160::repeat_byte(0x11);
+		let other = H160::repeat_byte(0x22);
+
+		assert!(!TransactionPause::is_evm_call_paused(contract, &[1, 2, 3, 4, 5]));
+
+		// a single function
+		assert_ok!(TransactionPause::pause_evm_call(
+			RuntimeOrigin::signed(1),
+			contract,
+			Some([1, 2, 3, 4]),
+			None,
+			vec![]
+		));
+		assert!(TransactionPause::is_evm_call_paused(contract, &[1, 2, 3, 4, 5]));
+		assert!(TransactionPause::is_evm_call_paused(contract, &[1, 2, 3, 4]));
+		assert!(!TransactionPause::is_evm_call_paused(contract, &[1, 2, 3, 5]));
+		assert!(!TransactionPause::is_evm_call_paused(contract, &[1, 2, 3]));
+		assert!(!TransactionPause::is_evm_call_paused(other, &[1, 2, 3, 4]));
+
+		// the whole contract
+		assert_ok!(TransactionPause::pause_evm_call(
+			RuntimeOrigin::signed(1),
+			contract,
+			None,
+			None,
+			vec![]
+		));
+		assert!(TransactionPause::is_evm_call_paused(contract, &[1, 2, 3, 5]));
+		assert!(TransactionPause::is_evm_call_paused(contract, &[]));
+		assert!(!TransactionPause::is_evm_call_paused(other, &[]));
+	});
+}
+
+#[test]
+fn paused_evm_calls_are_counted() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let contract = H160::repeat_byte(0x11);
+		assert_eq!(PausedEvmCalls::<Runtime>::count(), 0);
+
+		assert_ok!(TransactionPause::pause_evm_call(
+			RuntimeOrigin::signed(1),
+			contract,
+			Some([1, 2, 3, 4]),
+			None,
+			vec![]
+		));
+		assert_ok!(TransactionPause::pause_evm_call(
+			RuntimeOrigin::signed(1),
+			contract,
+			None,
+			Some(3),
+			vec![]
+		));
+		assert_eq!(PausedEvmCalls::<Runtime>::count(), 2);
+
+		assert_ok!(TransactionPause::unpause_evm_call(
+			RuntimeOrigin::signed(1),
+			contract,
+			Some([1, 2, 3, 4])
+		));
+		assert_eq!(PausedEvmCalls::<Runtime>::count(), 1);
+		assert!(TransactionPause::is_evm_call_paused(contract, &[1, 2, 3, 4]));
+
+		TransactionPause::on_initialize(3);
+		assert_eq!(PausedEvmCalls::<Runtime>::count(), 0);
+		assert!(!TransactionPause::is_evm_call_paused(contract, &[1, 2, 3, 4]));
+	});
+}
+
+#[test]
+fn pauser_admin_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let contract = H160::repeat_byte(0x11);
+
+		assert_noop!(
+			TransactionPause::set_pauser_admin(RuntimeOrigin::signed(5), Some(5)),
+			BadOrigin
+		);
+		assert_ok!(TransactionPause::set_pauser_admin(RuntimeOrigin::signed(1), Some(5)));
+		System::assert_last_event(RuntimeEvent::TransactionPause(crate::Event::PauserAdminSet {
+			admin: Some(5),
+		}));
+
+		// the admin pauses and unpauses, but can't replace itself
+		assert_ok!(TransactionPause::pause_evm_call(
+			RuntimeOrigin::signed(5),
+			contract,
+			None,
+			None,
+			vec![]
+		));
+		System::assert_last_event(RuntimeEvent::TransactionPause(crate::Event::EvmCallPaused {
+			contract,
+			selector: None,
+			expiry: None,
+			reason: vec![],
+			origin: signed(5),
+		}));
+		assert_ok!(TransactionPause::pause_transaction(
+			RuntimeOrigin::signed(5),
+			b"Balances".to_vec(),
+			Some(b"transfer_allow_death".to_vec()),
+			None,
+			vec![]
+		));
+		assert_ok!(TransactionPause::unpause_evm_call(RuntimeOrigin::signed(5), contract, None));
+		assert_noop!(
+			TransactionPause::set_pauser_admin(RuntimeOrigin::signed(5), Some(6)),
+			BadOrigin
+		);
+
+		assert_ok!(TransactionPause::set_pauser_admin(RuntimeOrigin::signed(1), None));
+		assert_noop!(
+			TransactionPause::pause_evm_call(
+				RuntimeOrigin::signed(5),
+				contract,
+				None,
+				None,
+				vec![]
+			),
+			BadOrigin
+		);
 	});
 }
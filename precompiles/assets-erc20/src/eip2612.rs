@@ -0,0 +1,221 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_support::{
+	ensure,
+	instances::{
+		Instance1, Instance10, Instance11, Instance12, Instance13, Instance14, Instance15,
+		Instance16, Instance2, Instance3, Instance4, Instance5, Instance6, Instance7, Instance8,
+		Instance9,
+	},
+	storage::types::{StorageDoubleMap, ValueQuery},
+	traits::StorageInstance,
+	Blake2_128Concat,
+};
+use pallet_evm_precompile_balances_erc20::eip2612::{permit_digest, recover_signer, token_domain};
+
+/// Associates pallet Instance to a prefix used for the Nonces storage.
+/// This trait is implemented for () and the 16 substrate Instance.
+pub trait InstanceToPrefix {
+	/// Prefix used for the Nonces storage.
+	type NoncesPrefix: StorageInstance;
+}
+
+// We use a macro to implement the trait for () and the 16 substrate Instance.
+macro_rules! impl_prefix {
+	($instance:ident, $name:literal) => {
+		// Using `paste!` we generate a dedicated module to avoid collisions
+		// between each instance `Nonces` struct.
+		paste::paste! {
+			mod [<_impl_prefix_ $instance:snake>] {
+				use super::*;
+
+				pub struct Nonces;
+
+				impl StorageInstance for Nonces {
+					const STORAGE_PREFIX: &'static str = "Nonces";
+
+					fn pallet_prefix() -> &'static str {
+						$name
+					}
+				}
+
+				impl InstanceToPrefix for $instance {
+					type NoncesPrefix = Nonces;
+				}
+			}
+		}
+	};
+}
+
+// Since the macro expect a `ident` to be used with `paste!` we cannot provide `()` directly.
+type Instance0 = ();
+
+impl_prefix!(Instance0, "Erc20Instance0Assets");
+impl_prefix!(Instance1, "Erc20Instance1Assets");
+impl_prefix!(Instance2, "Erc20Instance2Assets");
+impl_prefix!(Instance3, "Erc20Instance3Assets");
+impl_prefix!(Instance4, "Erc20Instance4Assets");
+impl_prefix!(Instance5, "Erc20Instance5Assets");
+impl_prefix!(Instance6, "Erc20Instance6Assets");
+impl_prefix!(Instance7, "Erc20Instance7Assets");
+impl_prefix!(Instance8, "Erc20Instance8Assets");
+impl_prefix!(Instance9, "Erc20Instance9Assets");
+impl_prefix!(Instance10, "Erc20Instance10Assets");
+impl_prefix!(Instance11, "Erc20Instance11Assets");
+impl_prefix!(Instance12, "Erc20Instance12Assets");
+impl_prefix!(Instance13, "Erc20Instance13Assets");
+impl_prefix!(Instance14, "Erc20Instance14Assets");
+impl_prefix!(Instance15, "Erc20Instance15Assets");
+impl_prefix!(Instance16, "Erc20Instance16Assets");
+
+/// Storage type used to store EIP2612 nonces, per asset.
+pub type NoncesStorage<Instance> = StorageDoubleMap<
+	<Instance as InstanceToPrefix>::NoncesPrefix,
+	// Asset precompile address
+	Blake2_128Concat,
+	H160,
+	// Owner
+	Blake2_128Concat,
+	H160,
+	// Nonce
+	U256,
+	ValueQuery,
+>;
+
+pub struct Eip2612<Runtime, Instance = ()>(PhantomData<(Runtime, Instance)>);
+
+impl<Runtime, Instance> Eip2612<Runtime, Instance>
+where
+	Instance: InstanceToPrefix + 'static,
+	Runtime: pallet_assets::Config<Instance> + pallet_evm::Config + pallet_timestamp::Config,
+	Runtime: AccountIdAssetIdConversion<Runtime::AccountId, AssetIdOf<Runtime, Instance>>,
+	<Runtime as frame_system::pallet::Config>::RuntimeCall:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	<Runtime as frame_system::pallet::Config>::RuntimeCall:
+		From<pallet_assets::Call<Runtime, Instance>>,
+	<<Runtime as frame_system::pallet::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
+		From<Option<<Runtime as frame_system::pallet::Config>::AccountId>>,
+	BalanceOf<Runtime, Instance>: TryFrom<U256> + Into<U256>,
+	<Runtime as pallet_timestamp::Config>::Moment: Into<U256>,
+{
+	pub fn compute_domain_separator(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		address: H160,
+	) -> [u8; 32] {
+		token_domain(&Self::name(asset_id), Runtime::ChainId::get(), address)
+			.separator()
+			.into()
+	}
+
+	pub fn generate_permit(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		address: H160,
+		owner: H160,
+		spender: H160,
+		value: U256,
+		nonce: U256,
+		deadline: U256,
+	) -> [u8; 32] {
+		let domain = token_domain(&Self::name(asset_id), Runtime::ChainId::get(), address);
+
+		permit_digest(&domain, owner, spender, value, nonce, deadline)
+	}
+
+	// Translated from
+	// https://github.com/Uniswap/v2-core/blob/master/contracts/UniswapV2ERC20.sol#L81
+	pub(crate) fn permit(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+		owner: Address,
+		spender: Address,
+		value: U256,
+		deadline: U256,
+		v: u8,
+		r: H256,
+		s: H256,
+	) -> EvmResult {
+		// NoncesStorage: Blake2_128(16) + contract(20) + Blake2_128(16) + owner(20) + nonce(32)
+		handle.record_db_read::<Runtime>(104)?;
+		// Metadata, for the name in the domain
+		Erc20AssetsPrecompileSet::<Runtime, Instance>::record_metadata_read(handle)?;
+
+		let owner: H160 = owner.into();
+		let spender: H160 = spender.into();
+		let address = handle.context().address;
+
+		// pallet_timestamp is in ms while Ethereum use second timestamps.
+		let timestamp: U256 = (pallet_timestamp::Pallet::<Runtime>::get()).into() / 1000;
+
+		ensure!(deadline >= timestamp, revert("Permit expired"));
+
+		let nonce = NoncesStorage::<Instance>::get(address, owner);
+
+		let permit = Self::generate_permit(
+			asset_id.clone(),
+			address,
+			owner,
+			spender,
+			value,
+			nonce,
+			deadline,
+		);
+
+		let signer = recover_signer(&permit, v, r, s);
+
+		ensure!(signer == Some(owner), revert("Invalid permit"));
+
+		NoncesStorage::<Instance>::insert(address, owner, nonce + U256::one());
+
+		Erc20AssetsPrecompileSet::<Runtime, Instance>::approve_inner(
+			asset_id, handle, owner, spender, value,
+		)?;
+
+		log3(address, SELECTOR_LOG_APPROVAL, owner, spender, solidity::encode_event_data(value))
+			.record(handle)?;
+
+		Ok(())
+	}
+
+	pub(crate) fn nonces(handle: &mut impl PrecompileHandle, owner: Address) -> EvmResult<U256> {
+		// NoncesStorage: Blake2_128(16) + contract(20) + Blake2_128(16) + owner(20) + nonce(32)
+		handle.record_db_read::<Runtime>(104)?;
+
+		let owner: H160 = owner.into();
+
+		Ok(NoncesStorage::<Instance>::get(handle.context().address, owner))
+	}
+
+	pub(crate) fn domain_separator(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<H256> {
+		// ChainId
+		handle.record_db_read::<Runtime>(8)?;
+		// Metadata, for the name in the domain
+		Erc20AssetsPrecompileSet::<Runtime, Instance>::record_metadata_read(handle)?;
+
+		Ok(Self::compute_domain_separator(asset_id, handle.context().address).into())
+	}
+
+	/// Name of the asset in its EIP712 domain, from its metadata.
+	fn name(asset_id: AssetIdOf<Runtime, Instance>) -> Vec<u8> {
+		<pallet_assets::Pallet<Runtime, Instance> as MetadataInspect<Runtime::AccountId>>::name(
+			asset_id,
+		)
+	}
+}
@@ -0,0 +1,448 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile set to interact with the assets of a pallet_assets instance using the ERC20
+//! interface standard. Each asset is exposed at its own address, derived from its asset id.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::MaxEncodedLen;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	sp_runtime::traits::{Bounded, Dispatchable, StaticLookup, Zero},
+	traits::{
+		fungibles::{
+			approvals::Inspect as ApprovalsInspect, metadata::Inspect as MetadataInspect, Inspect,
+		},
+		Get,
+	},
+};
+use pallet_evm::AddressMapping;
+use pallet_evm_precompile_balances_erc20::{SELECTOR_LOG_APPROVAL, SELECTOR_LOG_TRANSFER};
+use precompile_utils::prelude::*;
+use sp_core::{H160, H256, U256};
+use sp_std::{
+	convert::{TryFrom, TryInto},
+	marker::PhantomData,
+	vec::Vec,
+};
+
+mod eip2612;
+use eip2612::Eip2612;
+pub use eip2612::InstanceToPrefix;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Alias for the Balance type for the provided Runtime and Instance.
+pub type BalanceOf<Runtime, Instance = ()> = <Runtime as pallet_assets::Config<Instance>>::Balance;
+
+/// Alias for the Asset Id type for the provided Runtime and Instance.
+pub type AssetIdOf<Runtime, Instance = ()> = <Runtime as pallet_assets::Config<Instance>>::AssetId;
+
+/// Maps the addresses of the precompile set to the asset ids they expose.
+/// The runtime implements it, usually as an address prefix followed by the asset id.
+pub trait AccountIdAssetIdConversion<Account, AssetId> {
+	/// Returns the address prefix and the asset id of the account, if it is an asset address.
+	fn account_to_asset_id(account: Account) -> Option<(Vec<u8>, AssetId)>;
+
+	/// Returns the address exposing the asset, given the prefix of its instance.
+	fn asset_id_to_account(prefix: &[u8], asset_id: AssetId) -> Account;
+}
+
+/// Precompile set exposing each asset of a pallet_assets instance as an ERC20.
+/// Approvals are the ones of pallet_assets, the precompile only stores the EIP2612 nonces.
+pub struct Erc20AssetsPrecompileSet<Runtime, Instance: 'static = ()>(
+	PhantomData<(Runtime, Instance)>,
+);
+
+impl<Runtime, Instance: 'static> Default for Erc20AssetsPrecompileSet<Runtime, Instance> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<Runtime, Instance: 'static> Erc20AssetsPrecompileSet<Runtime, Instance> {
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+#[precompile_utils::precompile]
+#[precompile::precompile_set]
+impl<Runtime, Instance> Erc20AssetsPrecompileSet<Runtime, Instance>
+where
+	Instance: InstanceToPrefix + 'static,
+	Runtime: pallet_assets::Config<Instance> + pallet_evm::Config + pallet_timestamp::Config,
+	Runtime: AccountIdAssetIdConversion<Runtime::AccountId, AssetIdOf<Runtime, Instance>>,
+	<Runtime as frame_system::pallet::Config>::RuntimeCall:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	<Runtime as frame_system::pallet::Config>::RuntimeCall:
+		From<pallet_assets::Call<Runtime, Instance>>,
+	<<Runtime as frame_system::pallet::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
+		From<Option<<Runtime as frame_system::pallet::Config>::AccountId>>,
+	BalanceOf<Runtime, Instance>: TryFrom<U256> + Into<U256>,
+	<Runtime as pallet_timestamp::Config>::Moment: Into<U256>,
+{
+	/// Resolves the asset exposed at the address, only existing assets are precompiles.
+	#[precompile::discriminant]
+	fn discriminant(address: H160, gas: u64) -> DiscriminantResult<AssetIdOf<Runtime, Instance>> {
+		let extra_cost = RuntimeHelper::<Runtime>::db_read_gas_cost();
+		if gas < extra_cost {
+			return DiscriminantResult::OutOfGas;
+		}
+
+		let account = Runtime::AddressMapping::into_account_id(address);
+		let asset_id = match Runtime::account_to_asset_id(account) {
+			Some((_, asset_id)) => asset_id,
+			None => return DiscriminantResult::None(extra_cost),
+		};
+
+		if pallet_assets::Pallet::<Runtime, Instance>::asset_exists(asset_id.clone()) {
+			DiscriminantResult::Some(asset_id, extra_cost)
+		} else {
+			DiscriminantResult::None(extra_cost)
+		}
+	}
+
+	#[precompile::public("totalSupply()")]
+	#[precompile::view]
+	fn total_supply(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<U256> {
+		// Asset:
+		// Blake2_128(16) + AssetId(16) + AssetDetails((4 * AccountId(20)) + (3 * Balance(16)) + 15)
+		handle.record_db_read::<Runtime>(223)?;
+
+		Ok(pallet_assets::Pallet::<Runtime, Instance>::total_issuance(asset_id).into())
+	}
+
+	#[precompile::public("balanceOf(address)")]
+	#[precompile::view]
+	fn balance_of(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+		owner: Address,
+	) -> EvmResult<U256> {
+		// Account:
+		// Blake2_128(16) + AssetId(16) + Blake2_128(16) + AccountId(20) + AssetAccount(19 + Extra)
+		handle.record_db_read::<Runtime>(
+			87 + <Runtime as pallet_assets::Config<Instance>>::Extra::max_encoded_len(),
+		)?;
+
+		let owner: H160 = owner.into();
+		let owner: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner);
+
+		Ok(pallet_assets::Pallet::<Runtime, Instance>::balance(asset_id, &owner).into())
+	}
+
+	#[precompile::public("allowance(address,address)")]
+	#[precompile::view]
+	fn allowance(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+		owner: Address,
+		spender: Address,
+	) -> EvmResult<U256> {
+		// Approvals:
+		// Blake2_128(16) + AssetId(16) + (2 * (Blake2_128(16) + AccountId(20))) + Approval(32)
+		handle.record_db_read::<Runtime>(136)?;
+
+		let owner: H160 = owner.into();
+		let spender: H160 = spender.into();
+
+		let owner: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner);
+		let spender: Runtime::AccountId = Runtime::AddressMapping::into_account_id(spender);
+
+		Ok(pallet_assets::Pallet::<Runtime, Instance>::allowance(asset_id, &owner, &spender).into())
+	}
+
+	#[precompile::public("approve(address,uint256)")]
+	fn approve(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+		spender: Address,
+		value: U256,
+	) -> EvmResult<bool> {
+		handle.record_log_costs_manual(3, 32)?;
+
+		let caller = handle.context().caller;
+		let spender: H160 = spender.into();
+
+		Self::approve_inner(asset_id, handle, caller, spender, value)?;
+
+		log3(
+			handle.context().address,
+			SELECTOR_LOG_APPROVAL,
+			caller,
+			spender,
+			solidity::encode_event_data(value),
+		)
+		.record(handle)?;
+
+		// Build output.
+		Ok(true)
+	}
+
+	#[precompile::public("transfer(address,uint256)")]
+	fn transfer(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+		to: Address,
+		value: U256,
+	) -> EvmResult<bool> {
+		handle.record_log_costs_manual(3, 32)?;
+
+		let to: H160 = to.into();
+
+		// Build call with origin.
+		{
+			let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+			let to = Runtime::AddressMapping::into_account_id(to);
+			let value = Self::u256_to_amount(value).in_field("value")?;
+
+			// Dispatch call (if enough gas).
+			RuntimeHelper::<Runtime>::try_dispatch(
+				handle,
+				Some(origin).into(),
+				pallet_assets::Call::<Runtime, Instance>::transfer {
+					id: asset_id.into(),
+					target: Runtime::Lookup::unlookup(to),
+					amount: value,
+				},
+			)?;
+		}
+
+		log3(
+			handle.context().address,
+			SELECTOR_LOG_TRANSFER,
+			handle.context().caller,
+			to,
+			solidity::encode_event_data(value),
+		)
+		.record(handle)?;
+
+		Ok(true)
+	}
+
+	#[precompile::public("transferFrom(address,address,uint256)")]
+	fn transfer_from(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+		from: Address,
+		to: Address,
+		value: U256,
+	) -> EvmResult<bool> {
+		handle.record_log_costs_manual(3, 32)?;
+
+		let from: H160 = from.into();
+		let to: H160 = to.into();
+
+		{
+			let caller: Runtime::AccountId =
+				Runtime::AddressMapping::into_account_id(handle.context().caller);
+			let from: Runtime::AccountId = Runtime::AddressMapping::into_account_id(from);
+			let to: Runtime::AccountId = Runtime::AddressMapping::into_account_id(to);
+			let value = Self::u256_to_amount(value).in_field("value")?;
+
+			// If caller is "from", it can spend as much as it wants from its own balance.
+			if caller != from {
+				// Dispatch call (if enough gas).
+				RuntimeHelper::<Runtime>::try_dispatch(
+					handle,
+					Some(caller).into(),
+					pallet_assets::Call::<Runtime, Instance>::transfer_approved {
+						id: asset_id.into(),
+						owner: Runtime::Lookup::unlookup(from),
+						destination: Runtime::Lookup::unlookup(to),
+						amount: value,
+					},
+				)?;
+			} else {
+				// Dispatch call (if enough gas).
+				RuntimeHelper::<Runtime>::try_dispatch(
+					handle,
+					Some(from).into(),
+					pallet_assets::Call::<Runtime, Instance>::transfer {
+						id: asset_id.into(),
+						target: Runtime::Lookup::unlookup(to),
+						amount: value,
+					},
+				)?;
+			}
+		}
+
+		log3(
+			handle.context().address,
+			SELECTOR_LOG_TRANSFER,
+			from,
+			to,
+			solidity::encode_event_data(value),
+		)
+		.record(handle)?;
+
+		Ok(true)
+	}
+
+	#[precompile::public("name()")]
+	#[precompile::view]
+	fn name(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<UnboundedBytes> {
+		Self::record_metadata_read(handle)?;
+
+		let name = <pallet_assets::Pallet<Runtime, Instance> as MetadataInspect<
+			Runtime::AccountId,
+		>>::name(asset_id);
+
+		Ok(name.as_slice().into())
+	}
+
+	#[precompile::public("symbol()")]
+	#[precompile::view]
+	fn symbol(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<UnboundedBytes> {
+		Self::record_metadata_read(handle)?;
+
+		let symbol = <pallet_assets::Pallet<Runtime, Instance> as MetadataInspect<
+			Runtime::AccountId,
+		>>::symbol(asset_id);
+
+		Ok(symbol.as_slice().into())
+	}
+
+	#[precompile::public("decimals()")]
+	#[precompile::view]
+	fn decimals(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<u8> {
+		Self::record_metadata_read(handle)?;
+
+		Ok(<pallet_assets::Pallet<Runtime, Instance> as MetadataInspect<
+			Runtime::AccountId,
+		>>::decimals(asset_id))
+	}
+
+	#[precompile::public("permit(address,address,uint256,uint256,uint8,bytes32,bytes32)")]
+	fn eip2612_permit(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+		owner: Address,
+		spender: Address,
+		value: U256,
+		deadline: U256,
+		v: u8,
+		r: H256,
+		s: H256,
+	) -> EvmResult {
+		<Eip2612<Runtime, Instance>>::permit(
+			asset_id, handle, owner, spender, value, deadline, v, r, s,
+		)
+	}
+
+	#[precompile::public("nonces(address)")]
+	#[precompile::view]
+	fn eip2612_nonces(
+		_asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+		owner: Address,
+	) -> EvmResult<U256> {
+		<Eip2612<Runtime, Instance>>::nonces(handle, owner)
+	}
+
+	#[precompile::public("DOMAIN_SEPARATOR()")]
+	#[precompile::view]
+	fn eip2612_domain_separator(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<H256> {
+		<Eip2612<Runtime, Instance>>::domain_separator(asset_id, handle)
+	}
+
+	/// Replaces the allowance of `spender` on the assets of `owner`, pallet_assets only
+	/// increases approvals so any previous one is cancelled first.
+	fn approve_inner(
+		asset_id: AssetIdOf<Runtime, Instance>,
+		handle: &mut impl PrecompileHandle,
+		owner: H160,
+		spender: H160,
+		value: U256,
+	) -> EvmResult {
+		// Approvals:
+		// Blake2_128(16) + AssetId(16) + (2 * (Blake2_128(16) + AccountId(20))) + Approval(32)
+		handle.record_db_read::<Runtime>(136)?;
+
+		let owner: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner);
+		let spender: Runtime::AccountId = Runtime::AddressMapping::into_account_id(spender);
+		// Amount saturate if too high.
+		let amount = Self::u256_to_amount(value).unwrap_or_else(|_| Bounded::max_value());
+
+		if !pallet_assets::Pallet::<Runtime, Instance>::allowance(
+			asset_id.clone(),
+			&owner,
+			&spender,
+		)
+		.is_zero()
+		{
+			RuntimeHelper::<Runtime>::try_dispatch(
+				handle,
+				Some(owner.clone()).into(),
+				pallet_assets::Call::<Runtime, Instance>::cancel_approval {
+					id: asset_id.clone().into(),
+					delegate: Runtime::Lookup::unlookup(spender.clone()),
+				},
+			)?;
+		}
+
+		// Approving zero only removes the previous approval.
+		if !amount.is_zero() {
+			RuntimeHelper::<Runtime>::try_dispatch(
+				handle,
+				Some(owner).into(),
+				pallet_assets::Call::<Runtime, Instance>::approve_transfer {
+					id: asset_id.into(),
+					delegate: Runtime::Lookup::unlookup(spender),
+					amount,
+				},
+			)?;
+		}
+
+		Ok(())
+	}
+
+	fn record_metadata_read(handle: &mut impl PrecompileHandle) -> EvmResult {
+		// Metadata:
+		// Blake2_128(16) + AssetId(16) + AssetMetadata[deposit(16) + name(StringLimit)
+		// + symbol(StringLimit) + decimals(1) + is_frozen(1)]
+		handle.record_db_read::<Runtime>(
+			50 + (2 * <Runtime as pallet_assets::Config<Instance>>::StringLimit::get()) as usize,
+		)
+	}
+
+	fn u256_to_amount(value: U256) -> MayRevert<BalanceOf<Runtime, Instance>> {
+		value
+			.try_into()
+			.map_err(|_| RevertReason::value_is_too_large("balance type").into())
+	}
+}
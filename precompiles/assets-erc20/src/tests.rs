@@ -0,0 +1,609 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::str::from_utf8;
+
+use crate::{eip2612::Eip2612, mock::*, *};
+
+use fp_evm::{IsPrecompileResult, PrecompileSet};
+use libsecp256k1::{sign, Message, SecretKey};
+use precompile_utils::testing::*;
+use sp_core::{H256, U256};
+
+const ASSET: AssetId = 0;
+
+fn precompiles() -> Precompiles<Runtime> {
+	PrecompilesValue::get()
+}
+
+/// Externalities with a single asset, of which CryptoAlith holds 1000.
+fn ext() -> sp_io::TestExternalities {
+	ExtBuilder::default()
+		.with_asset(ASSET, b"Foreign token", b"FT", 12)
+		.with_asset_balances(vec![(ASSET, CryptoAlith.into(), 1000)])
+		.build()
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::balance_of_selectors().contains(&0x70a08231));
+	assert!(PCall::total_supply_selectors().contains(&0x18160ddd));
+	assert!(PCall::approve_selectors().contains(&0x095ea7b3));
+	assert!(PCall::allowance_selectors().contains(&0xdd62ed3e));
+	assert!(PCall::transfer_selectors().contains(&0xa9059cbb));
+	assert!(PCall::transfer_from_selectors().contains(&0x23b872dd));
+	assert!(PCall::name_selectors().contains(&0x06fdde03));
+	assert!(PCall::symbol_selectors().contains(&0x95d89b41));
+	assert!(PCall::decimals_selectors().contains(&0x313ce567));
+	assert!(PCall::eip2612_nonces_selectors().contains(&0x7ecebe00));
+	assert!(PCall::eip2612_permit_selectors().contains(&0xd505accf));
+	assert!(PCall::eip2612_domain_separator_selectors().contains(&0x3644e515));
+}
+
+#[test]
+fn modifiers() {
+	ext().execute_with(|| {
+		let mut tester =
+			PrecompilesModifierTester::new(precompiles(), CryptoAlith, asset_address(ASSET));
+
+		tester.test_view_modifier(PCall::balance_of_selectors());
+		tester.test_view_modifier(PCall::total_supply_selectors());
+		tester.test_default_modifier(PCall::approve_selectors());
+		tester.test_view_modifier(PCall::allowance_selectors());
+		tester.test_default_modifier(PCall::transfer_selectors());
+		tester.test_default_modifier(PCall::transfer_from_selectors());
+		tester.test_view_modifier(PCall::name_selectors());
+		tester.test_view_modifier(PCall::symbol_selectors());
+		tester.test_view_modifier(PCall::decimals_selectors());
+		tester.test_view_modifier(PCall::eip2612_nonces_selectors());
+		tester.test_default_modifier(PCall::eip2612_permit_selectors());
+		tester.test_view_modifier(PCall::eip2612_domain_separator_selectors());
+	});
+}
+
+#[test]
+fn only_existing_assets_are_precompiles() {
+	ext().execute_with(|| {
+		let is_precompile = |address: H160| match precompiles().is_precompile(address, 100_000) {
+			IsPrecompileResult::Answer { is_precompile, .. } => is_precompile,
+			IsPrecompileResult::OutOfGas => panic!("enough gas is provided"),
+		};
+
+		assert!(is_precompile(asset_address(ASSET)));
+		assert!(!is_precompile(asset_address(ASSET + 1)));
+		assert!(!is_precompile(Bob.into()));
+	});
+}
+
+#[test]
+fn get_total_supply() {
+	ExtBuilder::default()
+		.with_asset(ASSET, b"Foreign token", b"FT", 12)
+		.with_asset_balances(vec![(ASSET, CryptoAlith.into(), 1000), (ASSET, Bob.into(), 2500)])
+		.build()
+		.execute_with(|| {
+			precompiles()
+				.prepare_test(CryptoAlith, asset_address(ASSET), PCall::total_supply {})
+				.expect_cost(0) // TODO: Test db read/write costs
+				.expect_no_logs()
+				.execute_returns(U256::from(3500u64));
+		});
+}
+
+#[test]
+fn get_balances_known_user() {
+	ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::balance_of { owner: Address(CryptoAlith.into()) },
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(1000u64));
+	});
+}
+
+#[test]
+fn get_balances_unknown_user() {
+	ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::balance_of { owner: Address(Bob.into()) },
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(0u64));
+	});
+}
+
+#[test]
+fn approve() {
+	ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::approve { spender: Address(Bob.into()), value: 500.into() },
+			)
+			.expect_log(log3(
+				asset_address(ASSET),
+				SELECTOR_LOG_APPROVAL,
+				CryptoAlith,
+				Bob,
+				solidity::encode_event_data(U256::from(500)),
+			))
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::allowance {
+					owner: Address(CryptoAlith.into()),
+					spender: Address(Bob.into()),
+				},
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(500u64));
+	});
+}
+
+#[test]
+fn approve_replaces_previous_allowance() {
+	ext().execute_with(|| {
+		for value in [500, 300] {
+			precompiles()
+				.prepare_test(
+					CryptoAlith,
+					asset_address(ASSET),
+					PCall::approve { spender: Address(Bob.into()), value: value.into() },
+				)
+				.execute_returns(true);
+		}
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::allowance {
+					owner: Address(CryptoAlith.into()),
+					spender: Address(Bob.into()),
+				},
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(300u64));
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::approve { spender: Address(Bob.into()), value: 0.into() },
+			)
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::allowance {
+					owner: Address(CryptoAlith.into()),
+					spender: Address(Bob.into()),
+				},
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(0u64));
+	});
+}
+
+#[test]
+fn transfer() {
+	ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::transfer { to: Address(Bob.into()), value: 400.into() },
+			)
+			.expect_log(log3(
+				asset_address(ASSET),
+				SELECTOR_LOG_TRANSFER,
+				CryptoAlith,
+				Bob,
+				solidity::encode_event_data(U256::from(400)),
+			))
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::balance_of { owner: Address(CryptoAlith.into()) },
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(600));
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::balance_of { owner: Address(Bob.into()) },
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(400));
+	});
+}
+
+#[test]
+fn transfer_not_enough_funds() {
+	ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::transfer { to: Address(Bob.into()), value: 1400.into() },
+			)
+			.execute_reverts(|output| {
+				from_utf8(&output).unwrap().contains("Dispatched call failed with error: ") &&
+					from_utf8(&output).unwrap().contains("BalanceLow")
+			});
+	});
+}
+
+#[test]
+fn transfer_from() {
+	ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::approve { spender: Address(Bob.into()), value: 500.into() },
+			)
+			.execute_some();
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				asset_address(ASSET),
+				PCall::transfer_from {
+					from: Address(CryptoAlith.into()),
+					to: Address(Charlie.into()),
+					value: 400.into(),
+				},
+			)
+			.expect_log(log3(
+				asset_address(ASSET),
+				SELECTOR_LOG_TRANSFER,
+				CryptoAlith,
+				Charlie,
+				solidity::encode_event_data(U256::from(400)),
+			))
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::balance_of { owner: Address(CryptoAlith.into()) },
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(600));
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::balance_of { owner: Address(Charlie.into()) },
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(400));
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::allowance {
+					owner: Address(CryptoAlith.into()),
+					spender: Address(Bob.into()),
+				},
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(100u64));
+	});
+}
+
+#[test]
+fn transfer_from_above_allowance() {
+	ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::approve { spender: Address(Bob.into()), value: 300.into() },
+			)
+			.execute_some();
+
+		precompiles()
+			.prepare_test(
+				Bob, // Bob is the one sending transferFrom!
+				asset_address(ASSET),
+				PCall::transfer_from {
+					from: Address(CryptoAlith.into()),
+					to: Address(Bob.into()),
+					value: 400.into(),
+				},
+			)
+			.execute_reverts(|output| {
+				from_utf8(&output).unwrap().contains("Dispatched call failed with error: ") &&
+					from_utf8(&output).unwrap().contains("Unapproved")
+			});
+	});
+}
+
+#[test]
+fn transfer_from_self() {
+	ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				CryptoAlith, // CryptoAlith sending transferFrom herself, no need for allowance.
+				asset_address(ASSET),
+				PCall::transfer_from {
+					from: Address(CryptoAlith.into()),
+					to: Address(Bob.into()),
+					value: 400.into(),
+				},
+			)
+			.expect_log(log3(
+				asset_address(ASSET),
+				SELECTOR_LOG_TRANSFER,
+				CryptoAlith,
+				Bob,
+				solidity::encode_event_data(U256::from(400)),
+			))
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::balance_of { owner: Address(Bob.into()) },
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(400));
+	});
+}
+
+#[test]
+fn get_metadata() {
+	ext().execute_with(|| {
+		precompiles()
+			.prepare_test(CryptoAlith, asset_address(ASSET), PCall::name {})
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(UnboundedBytes::from("Foreign token"));
+
+		precompiles()
+			.prepare_test(CryptoAlith, asset_address(ASSET), PCall::symbol {})
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(UnboundedBytes::from("FT"));
+
+		precompiles()
+			.prepare_test(CryptoAlith, asset_address(ASSET), PCall::decimals {})
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(12u8);
+	});
+}
+
+#[test]
+fn permit_valid() {
+	ext().execute_with(|| {
+		let owner: H160 = CryptoAlith.into();
+		let spender: H160 = Bob.into();
+		let value: U256 = 500u16.into();
+		let deadline: U256 = 0u8.into();
+
+		let permit = Eip2612::<Runtime>::generate_permit(
+			ASSET,
+			asset_address(ASSET),
+			owner,
+			spender,
+			value,
+			0u8.into(), // nonce
+			deadline,
+		);
+
+		let secret_key = SecretKey::parse(&alith_secret_key()).unwrap();
+		let message = Message::parse(&permit);
+		let (rs, v) = sign(&message, &secret_key);
+
+		precompiles()
+			.prepare_test(
+				Charlie, // can be anyone
+				asset_address(ASSET),
+				PCall::eip2612_permit {
+					owner: Address(owner),
+					spender: Address(spender),
+					value,
+					deadline,
+					v: v.serialize(),
+					r: rs.r.b32().into(),
+					s: rs.s.b32().into(),
+				},
+			)
+			.expect_log(log3(
+				asset_address(ASSET),
+				SELECTOR_LOG_APPROVAL,
+				CryptoAlith,
+				Bob,
+				solidity::encode_event_data(value),
+			))
+			.execute_returns(());
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::allowance {
+					owner: Address(CryptoAlith.into()),
+					spender: Address(Bob.into()),
+				},
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(500u16));
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::eip2612_nonces { owner: Address(CryptoAlith.into()) },
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(1u8));
+	});
+}
+
+#[test]
+fn permit_invalid_nonce() {
+	ext().execute_with(|| {
+		let owner: H160 = CryptoAlith.into();
+		let spender: H160 = Bob.into();
+		let value: U256 = 500u16.into();
+		let deadline: U256 = 0u8.into();
+
+		let permit = Eip2612::<Runtime>::generate_permit(
+			ASSET,
+			asset_address(ASSET),
+			owner,
+			spender,
+			value,
+			1u8.into(), // nonce
+			deadline,
+		);
+
+		let secret_key = SecretKey::parse(&alith_secret_key()).unwrap();
+		let message = Message::parse(&permit);
+		let (rs, v) = sign(&message, &secret_key);
+
+		precompiles()
+			.prepare_test(
+				Charlie, // can be anyone
+				asset_address(ASSET),
+				PCall::eip2612_permit {
+					owner: Address(owner),
+					spender: Address(spender),
+					value,
+					deadline,
+					v: v.serialize(),
+					r: rs.r.b32().into(),
+					s: rs.s.b32().into(),
+				},
+			)
+			.execute_reverts(|output| output == b"Invalid permit");
+
+		precompiles()
+			.prepare_test(
+				CryptoAlith,
+				asset_address(ASSET),
+				PCall::allowance {
+					owner: Address(CryptoAlith.into()),
+					spender: Address(Bob.into()),
+				},
+			)
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(U256::from(0u16));
+	});
+}
+
+#[test]
+fn permit_invalid_deadline() {
+	ext().execute_with(|| {
+		pallet_timestamp::Pallet::<Runtime>::set_timestamp(10_000);
+
+		let owner: H160 = CryptoAlith.into();
+		let spender: H160 = Bob.into();
+		let value: U256 = 500u16.into();
+		let deadline: U256 = 5u8.into(); // deadline < timestamp => expired
+
+		let permit = Eip2612::<Runtime>::generate_permit(
+			ASSET,
+			asset_address(ASSET),
+			owner,
+			spender,
+			value,
+			0u8.into(), // nonce
+			deadline,
+		);
+
+		let secret_key = SecretKey::parse(&alith_secret_key()).unwrap();
+		let message = Message::parse(&permit);
+		let (rs, v) = sign(&message, &secret_key);
+
+		precompiles()
+			.prepare_test(
+				Charlie, // can be anyone
+				asset_address(ASSET),
+				PCall::eip2612_permit {
+					owner: Address(owner),
+					spender: Address(spender),
+					value,
+					deadline,
+					v: v.serialize(),
+					r: rs.r.b32().into(),
+					s: rs.s.b32().into(),
+				},
+			)
+			.execute_reverts(|output| output == b"Permit expired");
+	});
+}
+
+#[test]
+fn get_domain_separator() {
+	ext().execute_with(|| {
+		precompiles()
+			.prepare_test(CryptoAlith, asset_address(ASSET), PCall::eip2612_domain_separator {})
+			.expect_cost(0) // TODO: Test db read/write costs
+			.expect_no_logs()
+			.execute_returns(H256::from(Eip2612::<Runtime>::compute_domain_separator(
+				ASSET,
+				asset_address(ASSET),
+			)));
+	});
+}
+
+#[test]
+fn test_solidity_interface_has_all_function_selectors_documented_and_implemented() {
+	check_precompile_implements_solidity_interfaces(
+		&["ERC20.sol", "Permit.sol"],
+		PCall::supports_selector,
+	)
+}
@@ -15,21 +15,62 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::*;
+use ethabi_nostd::eip712::{self, Domain};
 use frame_support::{ensure, traits::Get};
 use pallet_evm::AddressMapping;
 use sp_core::H256;
 use sp_io::hashing::keccak_256;
-use sp_std::vec::Vec;
 
 /// EIP2612 permit typehash.
 pub const PERMIT_TYPEHASH: [u8; 32] = keccak256!(
 	"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"
 );
 
-/// EIP2612 permit domain used to compute an individualized domain separator.
-const PERMIT_DOMAIN: [u8; 32] = keccak256!(
-	"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
-);
+/// EIP712 domain of the token with the given name deployed at `address`.
+pub fn token_domain(name: &[u8], chain_id: u64, address: H160) -> Domain {
+	Domain {
+		name: Some(name.to_vec()),
+		version: Some(b"1".to_vec()),
+		chain_id: Some(chain_id.into()),
+		verifying_contract: Some(address),
+		salt: None,
+	}
+}
+
+/// EIP712 digest of a permit for the token of the given domain.
+pub fn permit_digest(
+	domain: &Domain,
+	owner: H160,
+	spender: H160,
+	value: U256,
+	nonce: U256,
+	deadline: U256,
+) -> [u8; 32] {
+	let permit_content = solidity::encode_arguments((
+		H256::from(PERMIT_TYPEHASH),
+		Address(owner),
+		Address(spender),
+		value,
+		nonce,
+		deadline,
+	));
+	let permit_content = keccak_256(&permit_content);
+
+	eip712::hash(&domain.separator(), &permit_content.into()).into()
+}
+
+/// Recovers the address that signed the permit digest, if the signature is valid.
+pub fn recover_signer(digest: &[u8; 32], v: u8, r: H256, s: H256) -> Option<H160> {
+	let mut sig = [0u8; 65];
+	sig[0..32].copy_from_slice(r.as_bytes());
+	sig[32..64].copy_from_slice(s.as_bytes());
+	sig[64] = v;
+
+	let signer = sp_io::crypto::secp256k1_ecdsa_recover(&sig, digest).ok()?;
+	let signer = H160::from(H256::from_slice(keccak_256(&signer).as_slice()));
+
+	(signer != H160::zero()).then_some(signer)
+}
 
 pub struct Eip2612<Runtime, Metadata, Instance = ()>(PhantomData<(Runtime, Metadata, Instance)>);
 
@@ -47,20 +88,13 @@ where
 	BalanceOf<Runtime, Instance>: TryFrom<U256> + Into<U256>,
 	<Runtime as pallet_timestamp::Config>::Moment: Into<U256>,
 {
+	/// EIP712 domain of the precompile at the given address.
+	fn domain(address: H160) -> Domain {
+		token_domain(Metadata::name().as_bytes(), Runtime::ChainId::get(), address)
+	}
+
 	pub fn compute_domain_separator(address: H160) -> [u8; 32] {
-		let name: H256 = keccak_256(Metadata::name().as_bytes()).into();
-		let version: H256 = keccak256!("1").into();
-		let chain_id: U256 = Runtime::ChainId::get().into();
-
-		let domain_separator_inner = solidity::encode_arguments((
-			H256::from(PERMIT_DOMAIN),
-			name,
-			version,
-			chain_id,
-			Address(address),
-		));
-
-		keccak_256(&domain_separator_inner)
+		Self::domain(address).separator().into()
 	}
 
 	pub fn generate_permit(
@@ -71,23 +105,7 @@ where
 		nonce: U256,
 		deadline: U256,
 	) -> [u8; 32] {
-		let domain_separator = Self::compute_domain_separator(address);
-
-		let permit_content = solidity::encode_arguments((
-			H256::from(PERMIT_TYPEHASH),
-			Address(owner),
-			Address(spender),
-			value,
-			nonce,
-			deadline,
-		));
-		let permit_content = keccak_256(&permit_content);
-
-		let mut pre_digest = Vec::with_capacity(2 + 32 + 32);
-		pre_digest.extend_from_slice(b"\x19\x01");
-		pre_digest.extend_from_slice(&domain_separator);
-		pre_digest.extend_from_slice(&permit_content);
-		keccak_256(&pre_digest)
+		permit_digest(&Self::domain(address), owner, spender, value, nonce, deadline)
 	}
 
 	// Translated from
@@ -118,16 +136,9 @@ where
 		let permit =
 			Self::generate_permit(handle.context().address, owner, spender, value, nonce, deadline);
 
-		let mut sig = [0u8; 65];
-		sig[0..32].copy_from_slice(r.as_bytes());
-		sig[32..64].copy_from_slice(s.as_bytes());
-		sig[64] = v;
-
-		let signer = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &permit)
-			.map_err(|_| revert("Invalid permit"))?;
-		let signer = H160::from(H256::from_slice(keccak_256(&signer).as_slice()));
+		let signer = recover_signer(&permit, v, r, s);
 
-		ensure!(signer != H160::zero() && signer == owner, revert("Invalid permit"));
+		ensure!(signer == Some(owner), revert("Invalid permit"));
 
 		NoncesStorage::<Instance>::insert(owner, nonce + U256::one());
 
@@ -38,7 +38,7 @@ use sp_std::{
 	marker::PhantomData,
 };
 
-mod eip2612;
+pub mod eip2612;
 use eip2612::Eip2612;
 
 #[cfg(test)]
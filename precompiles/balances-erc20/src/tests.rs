@@ -748,6 +748,15 @@ fn withdraw_more_than_owned() {
 		});
 }
 
+#[test]
+fn permit_typehash_matches_eip712_type() {
+	let types = ethabi_nostd::eip712::Types::parse(
+		"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+	)
+	.unwrap();
+	assert_eq!(types.type_hash("Permit").unwrap().0, eip2612::PERMIT_TYPEHASH);
+}
+
 #[test]
 fn permit_valid() {
 	ExtBuilder::default()
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The Batch contract's address.
+address constant BATCH_ADDRESS = 0x0000000000000000000000000000000000000814;
+
+/// @dev The Batch contract's instance.
+Batch constant BATCH_CONTRACT = Batch(BATCH_ADDRESS);
+
+/// @title Batch precompile
+/// @dev Makes several calls in a single transaction, each of them with the caller as msg.sender.
+/// The values, call data and gas limits missing at the end of their arrays default to zero, empty
+/// and all the remaining gas. A gas limit of zero also forwards all the remaining gas.
+interface Batch {
+    /// @dev Makes the subcalls, carrying on when one of them fails.
+    /// @custom:selector 79df4b9c
+    /// @param to The addresses called.
+    /// @param value The values sent along the subcalls.
+    /// @param callData The call data of the subcalls.
+    /// @param gasLimit The gas limits of the subcalls.
+    function batchSome(
+        address[] memory to,
+        uint256[] memory value,
+        bytes[] memory callData,
+        uint64[] memory gasLimit
+    ) external;
+
+    /// @dev Makes the subcalls, skipping the ones after the first failure.
+    /// @custom:selector cf0491c7
+    /// @param to The addresses called.
+    /// @param value The values sent along the subcalls.
+    /// @param callData The call data of the subcalls.
+    /// @param gasLimit The gas limits of the subcalls.
+    function batchSomeUntilFailure(
+        address[] memory to,
+        uint256[] memory value,
+        bytes[] memory callData,
+        uint64[] memory gasLimit
+    ) external;
+
+    /// @dev Makes the subcalls, reverting all of them when one of them fails.
+    /// @custom:selector 96e292b8
+    /// @param to The addresses called.
+    /// @param value The values sent along the subcalls.
+    /// @param callData The call data of the subcalls.
+    /// @param gasLimit The gas limits of the subcalls.
+    function batchAll(
+        address[] memory to,
+        uint256[] memory value,
+        bytes[] memory callData,
+        uint64[] memory gasLimit
+    ) external;
+
+    /// @dev Emitted when a subcall succeeds.
+    /// @custom:selector bf855484633929c3d6688eb3caf8eff910fb4bef030a8d7dbc9390d26759714d
+    event SubcallSucceeded(uint256 index);
+
+    /// @dev Emitted when a subcall fails.
+    /// @custom:selector dbc5d06f4f877f959b1ff12d2161cdd693fa8e442ee53f1790b2804b24881f05
+    event SubcallFailed(uint256 index);
+}
@@ -0,0 +1,211 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile making several calls in a single EVM transaction, on behalf of the caller.
+//!
+//! Each subcall is given the caller as `msg.sender`, and emits a `SubcallSucceeded` or a
+//! `SubcallFailed` log with its index. The three functions differ in how failures are handled:
+//! `batchSome` carries on, `batchSomeUntilFailure` stops and `batchAll` reverts the whole batch.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use fp_evm::{Context, ExitError, ExitReason, Log, PrecompileFailure, PrecompileHandle, Transfer};
+use frame_support::traits::ConstU32;
+use precompile_utils::{evm::costs::call_cost, prelude::*};
+use sp_core::{H160, U256};
+use sp_std::{iter::repeat, marker::PhantomData, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// How failed subcalls are handled.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Mode {
+	/// Carry on with the next subcalls.
+	BatchSome,
+	/// Skip the next subcalls, the batch still succeeding.
+	BatchSomeUntilFailure,
+	/// Revert the whole batch.
+	BatchAll,
+}
+
+pub const LOG_SUBCALL_SUCCEEDED: [u8; 32] = keccak256!("SubcallSucceeded(uint256)");
+pub const LOG_SUBCALL_FAILED: [u8; 32] = keccak256!("SubcallFailed(uint256)");
+
+/// The maximum size of the call data of a subcall.
+pub const CALL_DATA_LIMIT: u32 = 2u32.pow(16);
+/// The maximum number of subcalls of a batch.
+pub const ARRAY_LIMIT: u32 = 2u32.pow(9);
+
+type GetCallDataLimit = ConstU32<CALL_DATA_LIMIT>;
+type GetArrayLimit = ConstU32<ARRAY_LIMIT>;
+
+pub fn log_subcall_succeeded(address: impl Into<H160>, index: usize) -> Log {
+	log1(address, LOG_SUBCALL_SUCCEEDED, solidity::encode_event_data(U256::from(index)))
+}
+
+pub fn log_subcall_failed(address: impl Into<H160>, index: usize) -> Log {
+	log1(address, LOG_SUBCALL_FAILED, solidity::encode_event_data(U256::from(index)))
+}
+
+/// Precompile making the batches of subcalls of the callers.
+#[derive(Debug, Clone)]
+pub struct BatchPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> BatchPrecompile<Runtime>
+where
+	Runtime: pallet_evm::Config,
+{
+	/// Makes the subcalls, carrying on when one of them fails.
+	#[precompile::public("batchSome(address[],uint256[],bytes[],uint64[])")]
+	fn batch_some(
+		handle: &mut impl PrecompileHandle,
+		to: BoundedVec<Address, GetArrayLimit>,
+		value: BoundedVec<U256, GetArrayLimit>,
+		call_data: BoundedVec<BoundedBytes<GetCallDataLimit>, GetArrayLimit>,
+		gas_limit: BoundedVec<u64, GetArrayLimit>,
+	) -> EvmResult {
+		Self::inner_batch(Mode::BatchSome, handle, to, value, call_data, gas_limit)
+	}
+
+	/// Makes the subcalls, skipping the ones after the first failure.
+	#[precompile::public("batchSomeUntilFailure(address[],uint256[],bytes[],uint64[])")]
+	fn batch_some_until_failure(
+		handle: &mut impl PrecompileHandle,
+		to: BoundedVec<Address, GetArrayLimit>,
+		value: BoundedVec<U256, GetArrayLimit>,
+		call_data: BoundedVec<BoundedBytes<GetCallDataLimit>, GetArrayLimit>,
+		gas_limit: BoundedVec<u64, GetArrayLimit>,
+	) -> EvmResult {
+		Self::inner_batch(Mode::BatchSomeUntilFailure, handle, to, value, call_data, gas_limit)
+	}
+
+	/// Makes the subcalls, reverting all of them when one of them fails.
+	#[precompile::public("batchAll(address[],uint256[],bytes[],uint64[])")]
+	fn batch_all(
+		handle: &mut impl PrecompileHandle,
+		to: BoundedVec<Address, GetArrayLimit>,
+		value: BoundedVec<U256, GetArrayLimit>,
+		call_data: BoundedVec<BoundedBytes<GetCallDataLimit>, GetArrayLimit>,
+		gas_limit: BoundedVec<u64, GetArrayLimit>,
+	) -> EvmResult {
+		Self::inner_batch(Mode::BatchAll, handle, to, value, call_data, gas_limit)
+	}
+
+	/// The values, call data and gas limits missing at the end of their arrays default to zero,
+	/// empty and all the remaining gas. A gas limit of zero also forwards all the remaining gas.
+	fn inner_batch(
+		mode: Mode,
+		handle: &mut impl PrecompileHandle,
+		to: BoundedVec<Address, GetArrayLimit>,
+		value: BoundedVec<U256, GetArrayLimit>,
+		call_data: BoundedVec<BoundedBytes<GetCallDataLimit>, GetArrayLimit>,
+		gas_limit: BoundedVec<u64, GetArrayLimit>,
+	) -> EvmResult {
+		let addresses = Vec::from(to).into_iter().enumerate();
+		let values = Vec::from(value).into_iter().map(Some).chain(repeat(None));
+		let calls_data =
+			Vec::from(call_data).into_iter().map(|x| Some(x.into())).chain(repeat(None));
+		let gas_limits = Vec::from(gas_limit)
+			.into_iter()
+			.map(|x| if x == 0 { None } else { Some(x) })
+			.chain(repeat(None));
+
+		// The cost of the log does not depend on the index
+		let log_cost = log_subcall_failed(handle.code_address(), 0)
+			.compute_cost()
+			.map_err(|_| revert("failed to compute log cost"))?;
+
+		for ((i, address), (value, (call_data, gas_limit))) in
+			addresses.zip(values.zip(calls_data.zip(gas_limits)))
+		{
+			let address = address.0;
+			let value = value.unwrap_or_default();
+			let call_data: Vec<u8> = call_data.unwrap_or_default();
+
+			let caller = handle.context().caller;
+			let sub_context = Context { caller, address, apparent_value: value };
+			let transfer = if value.is_zero() {
+				None
+			} else {
+				Some(Transfer { source: caller, target: address, value })
+			};
+
+			// Enough gas is kept to emit the log of the subcall
+			let forwarded_gas = match (handle.remaining_gas().checked_sub(log_cost), mode) {
+				(Some(remaining), _) => remaining,
+				(None, Mode::BatchAll) =>
+					return Err(PrecompileFailure::Error { exit_status: ExitError::OutOfGas }),
+				(None, _) => return Ok(()),
+			};
+
+			// The cost of the call itself is paid by the precompile
+			let call_cost = call_cost(value, <Runtime as pallet_evm::Config>::config());
+			let forwarded_gas = match forwarded_gas.checked_sub(call_cost) {
+				Some(remaining) => match gas_limit {
+					None => Some(remaining),
+					Some(limit) if limit <= remaining => Some(limit),
+					Some(_) => None,
+				},
+				None => None,
+			};
+
+			let Some(forwarded_gas) = forwarded_gas else {
+				let log = log_subcall_failed(handle.code_address(), i);
+				handle.record_log_costs(&[&log])?;
+				log.record(handle)?;
+
+				match mode {
+					Mode::BatchAll =>
+						return Err(PrecompileFailure::Error { exit_status: ExitError::OutOfGas }),
+					Mode::BatchSomeUntilFailure => return Ok(()),
+					Mode::BatchSome => continue,
+				}
+			};
+
+			let (reason, output) =
+				handle.call(address, transfer, call_data, Some(forwarded_gas), false, &sub_context);
+
+			let log = match reason {
+				ExitReason::Succeed(_) => Some(log_subcall_succeeded(handle.code_address(), i)),
+				ExitReason::Revert(_) | ExitReason::Error(_) =>
+					Some(log_subcall_failed(handle.code_address(), i)),
+				ExitReason::Fatal(_) => None,
+			};
+			if let Some(log) = log {
+				handle.record_log_costs(&[&log])?;
+				log.record(handle)?;
+			}
+
+			match (mode, reason) {
+				(_, ExitReason::Fatal(exit_status)) =>
+					return Err(PrecompileFailure::Fatal { exit_status }),
+				(Mode::BatchAll, ExitReason::Revert(exit_status)) =>
+					return Err(PrecompileFailure::Revert { exit_status, output }),
+				(Mode::BatchAll, ExitReason::Error(exit_status)) =>
+					return Err(PrecompileFailure::Error { exit_status }),
+				(Mode::BatchSomeUntilFailure, ExitReason::Revert(_) | ExitReason::Error(_)) =>
+					return Ok(()),
+				_ => (),
+			}
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,179 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{mock::*, *};
+
+use fp_evm::{ExitRevert, ExitSucceed};
+use precompile_utils::testing::*;
+
+fn precompiles() -> Precompiles<Runtime> {
+	PrecompilesValue::get()
+}
+
+fn succeed() -> SubcallOutput {
+	SubcallOutput {
+		reason: ExitReason::Succeed(ExitSucceed::Returned),
+		output: vec![],
+		cost: 100,
+		logs: vec![],
+	}
+}
+
+fn revert_with(reason: &[u8]) -> SubcallOutput {
+	SubcallOutput {
+		reason: ExitReason::Revert(ExitRevert::Reverted),
+		output: reason.to_vec(),
+		cost: 100,
+		logs: vec![],
+	}
+}
+
+/// A batch calling Bob then Charlie, sending `1` to Bob.
+fn batch_call(mode: Mode) -> PCall {
+	let to = vec![Address(Bob.into()), Address(Charlie.into())].into();
+	let value = vec![U256::one()].into();
+	let call_data = vec![BoundedBytes::from(vec![0xaa]), BoundedBytes::from(vec![0xbb])].into();
+	let gas_limit = vec![].into();
+
+	match mode {
+		Mode::BatchSome => PCall::batch_some { to, value, call_data, gas_limit },
+		Mode::BatchSomeUntilFailure =>
+			PCall::batch_some_until_failure { to, value, call_data, gas_limit },
+		Mode::BatchAll => PCall::batch_all { to, value, call_data, gas_limit },
+	}
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::batch_some_selectors().contains(&0x79df4b9c));
+	assert!(PCall::batch_some_until_failure_selectors().contains(&0xcf0491c7));
+	assert!(PCall::batch_all_selectors().contains(&0x96e292b8));
+}
+
+#[test]
+fn modifiers() {
+	ExtBuilder::default().build().execute_with(|| {
+		let mut tester = PrecompilesModifierTester::new(precompiles(), Alice, Precompile1);
+
+		tester.test_default_modifier(PCall::batch_some_selectors());
+		tester.test_default_modifier(PCall::batch_some_until_failure_selectors());
+		tester.test_default_modifier(PCall::batch_all_selectors());
+	});
+}
+
+#[test]
+fn subcalls_are_made_on_behalf_of_the_caller() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(Alice, Precompile1, batch_call(Mode::BatchAll))
+			.with_subcall_handle(
+				|Subcall { address, transfer, input, is_static, context, .. }| {
+					assert!(!is_static);
+					assert_eq!(context.caller, H160::from(Alice));
+					assert_eq!(context.address, address);
+
+					if address == H160::from(Bob) {
+						assert_eq!(input, vec![0xaa]);
+						assert_eq!(context.apparent_value, U256::one());
+						let transfer = transfer.expect("value is transferred");
+						assert_eq!(transfer.source, H160::from(Alice));
+						assert_eq!(transfer.target, H160::from(Bob));
+						assert_eq!(transfer.value, U256::one());
+					} else if address == H160::from(Charlie) {
+						assert_eq!(input, vec![0xbb]);
+						assert_eq!(context.apparent_value, U256::zero());
+						assert!(transfer.is_none());
+					} else {
+						panic!("unexpected subcall");
+					}
+
+					succeed()
+				},
+			)
+			.expect_log(log_subcall_succeeded(Precompile1, 0))
+			.expect_log(log_subcall_succeeded(Precompile1, 1))
+			.execute_returns(());
+	});
+}
+
+#[test]
+fn batch_some_carries_on_after_failure() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(Alice, Precompile1, batch_call(Mode::BatchSome))
+			.with_subcall_handle(|Subcall { address, .. }| {
+				if address == H160::from(Bob) {
+					revert_with(b"failed")
+				} else {
+					succeed()
+				}
+			})
+			.expect_log(log_subcall_failed(Precompile1, 0))
+			.expect_log(log_subcall_succeeded(Precompile1, 1))
+			.execute_returns(());
+	});
+}
+
+#[test]
+fn batch_some_until_failure_stops_at_failure() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(Alice, Precompile1, batch_call(Mode::BatchSomeUntilFailure))
+			.with_subcall_handle(|Subcall { address, .. }| {
+				assert_eq!(address, H160::from(Bob), "subcalls after the failure are skipped");
+				revert_with(b"failed")
+			})
+			.expect_log(log_subcall_failed(Precompile1, 0))
+			.execute_returns(());
+	});
+}
+
+#[test]
+fn batch_all_reverts_on_failure() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(Alice, Precompile1, batch_call(Mode::BatchAll))
+			.with_subcall_handle(|Subcall { address, .. }| {
+				assert_eq!(address, H160::from(Bob), "subcalls after the failure are skipped");
+				revert_with(b"failed")
+			})
+			.execute_reverts(|output| output == b"failed");
+	});
+}
+
+#[test]
+fn subcall_fails_when_gas_limit_is_too_high() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::batch_some {
+					to: vec![Address(Bob.into()), Address(Charlie.into())].into(),
+					value: vec![].into(),
+					call_data: vec![].into(),
+					gas_limit: vec![u64::MAX].into(),
+				},
+			)
+			.with_subcall_handle(|Subcall { address, .. }| {
+				assert_eq!(address, H160::from(Charlie), "Bob is not called");
+				succeed()
+			})
+			.expect_log(log_subcall_failed(Precompile1, 0))
+			.expect_log(log_subcall_succeeded(Precompile1, 1))
+			.execute_returns(());
+	});
+}
@@ -0,0 +1,215 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile exposing `pallet_multisig` to the EVM: the callers approve and dispatch the calls
+//! of the multisig accounts they are a signatory of.
+//!
+//! The signatories are given in any order, the precompile sorting them as the pallet expects.
+//! Calls are given SCALE encoded, and the weights in ref time.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::DecodeLimit;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	traits::ConstU32,
+	weights::Weight,
+};
+use pallet_evm::AddressMapping;
+use pallet_multisig::Timepoint;
+use precompile_utils::prelude::*;
+use sp_core::{H160, H256};
+use sp_runtime::traits::Dispatchable;
+use sp_std::{boxed::Box, marker::PhantomData, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The maximum size of the encoded calls.
+pub const CALL_DATA_LIMIT: u32 = 2u32.pow(16);
+type GetCallDataLimit = ConstU32<CALL_DATA_LIMIT>;
+
+/// The maximum nesting of the decoded calls, as for extrinsics.
+pub const CALL_DECODE_DEPTH_LIMIT: u32 = 256;
+
+/// The proof size bought along with the weights given in ref time by the callers.
+pub const DEFAULT_PROOF_SIZE: u64 = 256 * 1024;
+
+type MaxSignatoriesOf<Runtime> = <Runtime as pallet_multisig::Config>::MaxSignatories;
+
+/// Precompile dispatching the `pallet_multisig` calls of the callers.
+pub struct MultisigPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> MultisigPrecompile<Runtime>
+where
+	Runtime: pallet_multisig::Config + pallet_evm::Config,
+	<Runtime as frame_system::Config>::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo>
+		+ GetDispatchInfo
+		+ From<pallet_multisig::Call<Runtime>>,
+	<<Runtime as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
+		From<Option<Runtime::AccountId>>,
+	Runtime::AccountId: Into<H160>,
+{
+	/// Returns the address of the multisig account of `signatories` and `threshold`.
+	#[precompile::public("multisigAddress(address[],uint16)")]
+	#[precompile::view]
+	fn multisig_address(
+		_handle: &mut impl PrecompileHandle,
+		signatories: BoundedVec<Address, MaxSignatoriesOf<Runtime>>,
+		threshold: u16,
+	) -> EvmResult<Address> {
+		let signatories = Self::signatories(signatories);
+		let account = pallet_multisig::Pallet::<Runtime>::multi_account_id(&signatories, threshold);
+
+		Ok(Address(account.into()))
+	}
+
+	/// Dispatches `call` from the multisig account of threshold 1 of the caller and
+	/// `otherSignatories`.
+	#[precompile::public("asMultiThreshold1(address[],bytes)")]
+	fn as_multi_threshold_1(
+		handle: &mut impl PrecompileHandle,
+		other_signatories: BoundedVec<Address, MaxSignatoriesOf<Runtime>>,
+		call: BoundedBytes<GetCallDataLimit>,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let call = pallet_multisig::Call::<Runtime>::as_multi_threshold_1 {
+			other_signatories: Self::signatories(other_signatories),
+			call: Self::decode_call(call).in_field("call")?,
+		};
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		Ok(())
+	}
+
+	/// Approves `call` as the caller, one of the signatories of the multisig account of
+	/// `threshold`. The call is dispatched from the multisig account with at most `maxWeight` once
+	/// approved by `threshold` signatories. The first approval is given a timepoint height of 0,
+	/// the others the timepoint of the first approval.
+	#[precompile::public("asMulti(uint16,address[],uint32,uint32,bytes,uint64)")]
+	fn as_multi(
+		handle: &mut impl PrecompileHandle,
+		threshold: u16,
+		other_signatories: BoundedVec<Address, MaxSignatoriesOf<Runtime>>,
+		timepoint_height: u32,
+		timepoint_index: u32,
+		call: BoundedBytes<GetCallDataLimit>,
+		max_weight: u64,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let call = pallet_multisig::Call::<Runtime>::as_multi {
+			threshold,
+			other_signatories: Self::signatories(other_signatories),
+			maybe_timepoint: Self::maybe_timepoint(timepoint_height, timepoint_index),
+			call: Self::decode_call(call).in_field("call")?,
+			max_weight: Weight::from_parts(max_weight, DEFAULT_PROOF_SIZE),
+		};
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		Ok(())
+	}
+
+	/// Same as `asMulti`, the call being given by its hash. It is never dispatched, the final
+	/// approval being made with `asMulti`.
+	#[precompile::public("approveAsMulti(uint16,address[],uint32,uint32,bytes32,uint64)")]
+	fn approve_as_multi(
+		handle: &mut impl PrecompileHandle,
+		threshold: u16,
+		other_signatories: BoundedVec<Address, MaxSignatoriesOf<Runtime>>,
+		timepoint_height: u32,
+		timepoint_index: u32,
+		call_hash: H256,
+		max_weight: u64,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let call = pallet_multisig::Call::<Runtime>::approve_as_multi {
+			threshold,
+			other_signatories: Self::signatories(other_signatories),
+			maybe_timepoint: Self::maybe_timepoint(timepoint_height, timepoint_index),
+			call_hash: call_hash.into(),
+			max_weight: Weight::from_parts(max_weight, DEFAULT_PROOF_SIZE),
+		};
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		Ok(())
+	}
+
+	/// Cancels the pending call of hash `callHash` and timepoint `timepointHeight` and
+	/// `timepointIndex`, which the caller first approved.
+	#[precompile::public("cancelAsMulti(uint16,address[],uint32,uint32,bytes32)")]
+	fn cancel_as_multi(
+		handle: &mut impl PrecompileHandle,
+		threshold: u16,
+		other_signatories: BoundedVec<Address, MaxSignatoriesOf<Runtime>>,
+		timepoint_height: u32,
+		timepoint_index: u32,
+		call_hash: H256,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let call = pallet_multisig::Call::<Runtime>::cancel_as_multi {
+			threshold,
+			other_signatories: Self::signatories(other_signatories),
+			timepoint: Timepoint { height: timepoint_height.into(), index: timepoint_index },
+			call_hash: call_hash.into(),
+		};
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		Ok(())
+	}
+
+	/// The accounts of `signatories`, sorted.
+	fn signatories(
+		signatories: BoundedVec<Address, MaxSignatoriesOf<Runtime>>,
+	) -> Vec<Runtime::AccountId> {
+		let mut signatories: Vec<_> = Vec::from(signatories)
+			.into_iter()
+			.map(|address| Runtime::AddressMapping::into_account_id(address.into()))
+			.collect();
+		signatories.sort();
+		signatories
+	}
+
+	fn maybe_timepoint(
+		height: u32,
+		index: u32,
+	) -> Option<Timepoint<frame_system::pallet_prelude::BlockNumberFor<Runtime>>> {
+		(height != 0).then(|| Timepoint { height: height.into(), index })
+	}
+
+	fn decode_call(
+		call: BoundedBytes<GetCallDataLimit>,
+	) -> MayRevert<Box<<Runtime as pallet_multisig::Config>::RuntimeCall>> {
+		let call = call.into_vec();
+		<Runtime as pallet_multisig::Config>::RuntimeCall::decode_with_depth_limit(
+			CALL_DECODE_DEPTH_LIMIT,
+			&mut &call[..],
+		)
+		.map(Box::new)
+		.map_err(|_| RevertReason::custom("Failed decoding call").into())
+	}
+}
@@ -0,0 +1,205 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{mock::*, *};
+
+use codec::Encode;
+use precompile_utils::testing::*;
+
+fn precompiles() -> Precompiles<Runtime> {
+	PrecompilesValue::get()
+}
+
+fn addresses(accounts: Vec<AccountId>) -> BoundedVec<Address, MaxSignatoriesOf<Runtime>> {
+	accounts
+		.into_iter()
+		.map(|account| Address(account.into()))
+		.collect::<Vec<_>>()
+		.into()
+}
+
+/// The multisig account of Alice and Bob.
+fn multisig_account(threshold: u16) -> AccountId {
+	let mut signatories = vec![AccountId::from(Alice), AccountId::from(Bob)];
+	signatories.sort();
+	pallet_multisig::Pallet::<Runtime>::multi_account_id(&signatories, threshold)
+}
+
+/// Transfers 10 to Charlie.
+fn transfer_call() -> RuntimeCall {
+	RuntimeCall::Balances(pallet_balances::Call::transfer_allow_death {
+		dest: Charlie.into(),
+		value: 10,
+	})
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::multisig_address_selectors().contains(&0xeb885447));
+	assert!(PCall::as_multi_threshold_1_selectors().contains(&0xf9bcdd45));
+	assert!(PCall::as_multi_selectors().contains(&0x3bc6b7b4));
+	assert!(PCall::approve_as_multi_selectors().contains(&0xacfbffa1));
+	assert!(PCall::cancel_as_multi_selectors().contains(&0x8a9f4316));
+}
+
+#[test]
+fn modifiers() {
+	ExtBuilder::default().build().execute_with(|| {
+		let mut tester = PrecompilesModifierTester::new(precompiles(), Alice, Precompile1);
+
+		tester.test_view_modifier(PCall::multisig_address_selectors());
+		tester.test_default_modifier(PCall::as_multi_threshold_1_selectors());
+		tester.test_default_modifier(PCall::as_multi_selectors());
+		tester.test_default_modifier(PCall::approve_as_multi_selectors());
+		tester.test_default_modifier(PCall::cancel_as_multi_selectors());
+	});
+}
+
+#[test]
+fn multisig_address_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		// The signatories are sorted by the precompile
+		for signatories in [vec![Alice.into(), Bob.into()], vec![Bob.into(), Alice.into()]] {
+			precompiles()
+				.prepare_test(
+					Charlie,
+					Precompile1,
+					PCall::multisig_address { signatories: addresses(signatories), threshold: 2 },
+				)
+				.expect_no_logs()
+				.execute_returns(Address(multisig_account(2).into()));
+		}
+	});
+}
+
+#[test]
+fn as_multi_threshold_1_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(multisig_account(1), 1000)])
+		.build()
+		.execute_with(|| {
+			precompiles()
+				.prepare_test(
+					Alice,
+					Precompile1,
+					PCall::as_multi_threshold_1 {
+						other_signatories: addresses(vec![Bob.into()]),
+						call: transfer_call().encode().into(),
+					},
+				)
+				.expect_no_logs()
+				.execute_returns(());
+
+			assert_eq!(Balances::free_balance(multisig_account(1)), 990);
+			assert_eq!(Balances::free_balance(AccountId::from(Charlie)), 10);
+		});
+}
+
+#[test]
+fn as_multi_dispatches_once_approved() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000), (multisig_account(2), 1000)])
+		.build()
+		.execute_with(|| {
+			let as_multi = |other: AccountId, height| PCall::as_multi {
+				threshold: 2,
+				other_signatories: addresses(vec![other]),
+				timepoint_height: height,
+				timepoint_index: 0,
+				call: transfer_call().encode().into(),
+				max_weight: 1_000_000_000,
+			};
+
+			precompiles()
+				.prepare_test(Alice, Precompile1, as_multi(Bob.into(), 0))
+				.expect_no_logs()
+				.execute_returns(());
+			assert_eq!(Balances::free_balance(AccountId::from(Charlie)), 0);
+
+			precompiles()
+				.prepare_test(Bob, Precompile1, as_multi(Alice.into(), 1))
+				.expect_no_logs()
+				.execute_returns(());
+			assert_eq!(Balances::free_balance(multisig_account(2)), 990);
+			assert_eq!(Balances::free_balance(AccountId::from(Charlie)), 10);
+		});
+}
+
+#[test]
+fn cancel_as_multi_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			let call_hash = H256(transfer_call().using_encoded(sp_io::hashing::blake2_256));
+
+			precompiles()
+				.prepare_test(
+					Alice,
+					Precompile1,
+					PCall::approve_as_multi {
+						threshold: 2,
+						other_signatories: addresses(vec![Bob.into()]),
+						timepoint_height: 0,
+						timepoint_index: 0,
+						call_hash,
+						max_weight: 1_000_000_000,
+					},
+				)
+				.expect_no_logs()
+				.execute_returns(());
+			assert!(pallet_multisig::Multisigs::<Runtime>::contains_key(
+				multisig_account(2),
+				call_hash.0
+			));
+
+			precompiles()
+				.prepare_test(
+					Alice,
+					Precompile1,
+					PCall::cancel_as_multi {
+						threshold: 2,
+						other_signatories: addresses(vec![Bob.into()]),
+						timepoint_height: 1,
+						timepoint_index: 0,
+						call_hash,
+					},
+				)
+				.expect_no_logs()
+				.execute_returns(());
+			assert!(!pallet_multisig::Multisigs::<Runtime>::contains_key(
+				multisig_account(2),
+				call_hash.0
+			));
+			assert_eq!(Balances::free_balance(AccountId::from(Alice)), 1000);
+		});
+}
+
+#[test]
+fn undecodable_call_reverts() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::as_multi_threshold_1 {
+					other_signatories: addresses(vec![Bob.into()]),
+					call: vec![0xff, 0xff].into(),
+				},
+			)
+			.execute_reverts(|output| output == b"call: Failed decoding call");
+	});
+}
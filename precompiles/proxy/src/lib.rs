@@ -0,0 +1,288 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile exposing `pallet_proxy` to the EVM: accounts can add and remove their proxies,
+//! which can then make EVM calls on their behalf.
+//!
+//! Proxied calls are EVM subcalls made with the proxied account as `msg.sender`, filtered by the
+//! [`EvmProxyCallFilter`] of the proxy type. Only externally owned accounts can be proxied.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Decode;
+use fp_evm::{Context, ExitReason, PrecompileFailure, PrecompileHandle, Transfer};
+use frame_support::{
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	traits::ConstU32,
+};
+use pallet_evm::AddressMapping;
+use precompile_utils::{precompile_set::is_precompile_or_fail, prelude::*};
+use sp_core::{H160, U256};
+use sp_runtime::traits::{Dispatchable, StaticLookup, Zero};
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The maximum size of the call data of proxied calls.
+pub const CALL_DATA_LIMIT: u32 = 2u32.pow(16);
+type GetCallDataLimit = ConstU32<CALL_DATA_LIMIT>;
+
+/// An EVM call made by a proxy on behalf of the proxied account.
+pub struct EvmSubCall {
+	pub to: Address,
+	pub value: U256,
+	pub call_data: BoundedBytes<GetCallDataLimit>,
+}
+
+/// Filters the EVM calls the proxies of a type are allowed to make, as `InstanceFilter` does for
+/// the runtime calls. No EVM call is allowed by default.
+pub trait EvmProxyCallFilter: Sized + Send + Sync {
+	/// Whether the proxies of this type can make `call`, the called address having code if
+	/// `recipient_has_code`. `gas` is the gas left to check the call.
+	fn is_evm_proxy_call_allowed(
+		&self,
+		_call: &EvmSubCall,
+		_recipient_has_code: bool,
+		_gas: u64,
+	) -> EvmResult<bool> {
+		Ok(false)
+	}
+}
+
+/// Precompile managing the proxies of the callers through `pallet_proxy`, and making the calls of
+/// proxies on behalf of the proxied accounts.
+pub struct ProxyPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> ProxyPrecompile<Runtime>
+where
+	Runtime: pallet_proxy::Config + pallet_evm::Config + pallet_balances::Config,
+	<Runtime as frame_system::Config>::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo>
+		+ GetDispatchInfo
+		+ From<pallet_proxy::Call<Runtime>>
+		+ From<pallet_balances::Call<Runtime>>,
+	<<Runtime as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
+		From<Option<Runtime::AccountId>>,
+	<Runtime as pallet_proxy::Config>::ProxyType: Decode + EvmProxyCallFilter,
+	<Runtime as pallet_balances::Config>::Balance: TryFrom<U256>,
+{
+	/// Adds `delegate` as a proxy of the caller, of type `proxyType` and announcing its calls
+	/// `delay` blocks in advance. Reverts if `delegate` is already a proxy of the caller.
+	#[precompile::public("addProxy(address,uint8,uint32)")]
+	fn add_proxy(
+		handle: &mut impl PrecompileHandle,
+		delegate: Address,
+		proxy_type: u8,
+		delay: u32,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let delegate = Runtime::AddressMapping::into_account_id(delegate.into());
+		let proxy_type = Self::proxy_type(proxy_type).in_field("proxyType")?;
+
+		// The pallet allows a delegate to be added once per proxy type, which would let the
+		// restricted proxies of an account be upgraded from the EVM
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		if pallet_proxy::Proxies::<Runtime>::get(&origin)
+			.0
+			.iter()
+			.any(|def| def.delegate == delegate)
+		{
+			return Err(revert("Cannot add more than one proxy"));
+		}
+
+		let call = pallet_proxy::Call::<Runtime>::add_proxy {
+			delegate: Runtime::Lookup::unlookup(delegate),
+			proxy_type,
+			delay: delay.into(),
+		};
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		Ok(())
+	}
+
+	/// Removes the proxy `delegate` of type `proxyType` and delay `delay` of the caller.
+	#[precompile::public("removeProxy(address,uint8,uint32)")]
+	fn remove_proxy(
+		handle: &mut impl PrecompileHandle,
+		delegate: Address,
+		proxy_type: u8,
+		delay: u32,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let delegate = Runtime::AddressMapping::into_account_id(delegate.into());
+		let proxy_type = Self::proxy_type(proxy_type).in_field("proxyType")?;
+
+		let call = pallet_proxy::Call::<Runtime>::remove_proxy {
+			delegate: Runtime::Lookup::unlookup(delegate),
+			proxy_type,
+			delay: delay.into(),
+		};
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		Ok(())
+	}
+
+	/// Removes all the proxies of the caller.
+	#[precompile::public("removeProxies()")]
+	fn remove_proxies(handle: &mut impl PrecompileHandle) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let call = pallet_proxy::Call::<Runtime>::remove_proxies {};
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		Ok(())
+	}
+
+	/// Calls `callTo` with `callData` on behalf of `real`, of which the caller is a proxy. The
+	/// value sent along is taken from `real`, the value received being sent back to the caller.
+	#[precompile::public("proxy(address,address,bytes)")]
+	#[precompile::payable]
+	fn proxy(
+		handle: &mut impl PrecompileHandle,
+		real: Address,
+		call_to: Address,
+		call_data: BoundedBytes<GetCallDataLimit>,
+	) -> EvmResult {
+		let call = EvmSubCall { to: call_to, value: handle.context().apparent_value, call_data };
+
+		Self::inner_proxy(handle, real, None, call)
+	}
+
+	/// Same as `proxy`, the caller being a proxy of `real` of type `forceProxyType`.
+	#[precompile::public("proxyForceType(address,uint8,address,bytes)")]
+	#[precompile::payable]
+	fn proxy_force_type(
+		handle: &mut impl PrecompileHandle,
+		real: Address,
+		force_proxy_type: u8,
+		call_to: Address,
+		call_data: BoundedBytes<GetCallDataLimit>,
+	) -> EvmResult {
+		let proxy_type = Self::proxy_type(force_proxy_type).in_field("forceProxyType")?;
+		let call = EvmSubCall { to: call_to, value: handle.context().apparent_value, call_data };
+
+		Self::inner_proxy(handle, real, Some(proxy_type), call)
+	}
+
+	/// Returns whether `delegate` is a proxy of `real` of type `proxyType` and delay `delay`.
+	#[precompile::public("isProxy(address,address,uint8,uint32)")]
+	#[precompile::view]
+	fn is_proxy(
+		handle: &mut impl PrecompileHandle,
+		real: Address,
+		delegate: Address,
+		proxy_type: u8,
+		delay: u32,
+	) -> EvmResult<bool> {
+		let real = Runtime::AddressMapping::into_account_id(real.into());
+		let delegate = Runtime::AddressMapping::into_account_id(delegate.into());
+		let proxy_type = Self::proxy_type(proxy_type).in_field("proxyType")?;
+		let delay = delay.into();
+
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let is_proxy = pallet_proxy::Proxies::<Runtime>::get(real).0.iter().any(|def| {
+			def.delegate == delegate && def.proxy_type == proxy_type && def.delay == delay
+		});
+
+		Ok(is_proxy)
+	}
+
+	fn inner_proxy(
+		handle: &mut impl PrecompileHandle,
+		real: Address,
+		force_proxy_type: Option<<Runtime as pallet_proxy::Config>::ProxyType>,
+		call: EvmSubCall,
+	) -> EvmResult {
+		// Contracts cannot be proxied, their calls being made by their code only
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		if Self::has_code(real.0) ||
+			is_precompile_or_fail::<Runtime>(real.0, handle.remaining_gas())?
+		{
+			return Err(revert("real address must be EOA"));
+		}
+
+		let real_account = Runtime::AddressMapping::into_account_id(real.0);
+		let who = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let def =
+			pallet_proxy::Pallet::<Runtime>::find_proxy(&real_account, &who, force_proxy_type)
+				.map_err(|_| revert("Not proxy"))?;
+		// Announced calls are made through `pallet_proxy`
+		if !def.delay.is_zero() {
+			return Err(revert("Unannounced"));
+		}
+
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let recipient_has_code = Self::has_code(call.to.0);
+		if !def.proxy_type.is_evm_proxy_call_allowed(
+			&call,
+			recipient_has_code,
+			handle.remaining_gas(),
+		)? {
+			return Err(revert("CallFiltered"));
+		}
+
+		let EvmSubCall { to, value, call_data } = call;
+		let context = Context { caller: real.0, address: to.0, apparent_value: value };
+
+		let transfer = if value.is_zero() {
+			None
+		} else {
+			// The value received by the precompile is sent back, `real` paying the subcall
+			let precompile = Runtime::AddressMapping::into_account_id(handle.context().address);
+			let call = pallet_balances::Call::<Runtime>::transfer_allow_death {
+				dest: Runtime::Lookup::unlookup(who),
+				value: value.try_into().map_err(|_| RevertReason::value_is_too_large("balance"))?,
+			};
+			RuntimeHelper::<Runtime>::try_dispatch(handle, Some(precompile).into(), call)?;
+
+			Some(Transfer { source: real.0, target: to.0, value })
+		};
+
+		let (reason, output) = handle.call(
+			to.0,
+			transfer,
+			call_data.into(),
+			Some(handle.remaining_gas()),
+			false,
+			&context,
+		);
+
+		match reason {
+			ExitReason::Succeed(_) => Ok(()),
+			ExitReason::Revert(exit_status) =>
+				Err(PrecompileFailure::Revert { exit_status, output }),
+			ExitReason::Error(exit_status) => Err(PrecompileFailure::Error { exit_status }),
+			ExitReason::Fatal(exit_status) => Err(PrecompileFailure::Fatal { exit_status }),
+		}
+	}
+
+	fn has_code(address: H160) -> bool {
+		pallet_evm::AccountCodes::<Runtime>::decode_len(address).unwrap_or(0) > 0
+	}
+
+	fn proxy_type(proxy_type: u8) -> MayRevert<<Runtime as pallet_proxy::Config>::ProxyType> {
+		<Runtime as pallet_proxy::Config>::ProxyType::decode(&mut &[proxy_type][..])
+			.map_err(|_| RevertReason::custom("Failed decoding value to ProxyType").into())
+	}
+}
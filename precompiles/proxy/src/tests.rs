@@ -0,0 +1,331 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{mock::*, *};
+
+use fp_evm::ExitSucceed;
+use frame_support::assert_ok;
+use precompile_utils::testing::*;
+
+fn precompiles() -> Precompiles<Runtime> {
+	PrecompilesValue::get()
+}
+
+fn succeed() -> SubcallOutput {
+	SubcallOutput {
+		reason: ExitReason::Succeed(ExitSucceed::Returned),
+		output: vec![],
+		cost: 100,
+		logs: vec![],
+	}
+}
+
+/// Adds `delegate` as a proxy of `real` through the pallet.
+fn add_proxy(
+	real: impl Into<AccountId>,
+	delegate: impl Into<AccountId>,
+	proxy_type: ProxyType,
+	delay: u32,
+) {
+	assert_ok!(Proxy::add_proxy(
+		RuntimeOrigin::signed(real.into()),
+		delegate.into(),
+		proxy_type,
+		delay
+	));
+}
+
+fn is_proxy(
+	real: impl Into<AccountId>,
+	delegate: impl Into<AccountId>,
+	proxy_type: ProxyType,
+	delay: u32,
+) -> bool {
+	let (real, delegate) = (real.into(), delegate.into());
+	pallet_proxy::Proxies::<Runtime>::get(real)
+		.0
+		.iter()
+		.any(|def| def.delegate == delegate && def.proxy_type == proxy_type && def.delay == delay)
+}
+
+fn proxy_call(real: impl Into<H160>, call_to: impl Into<H160>) -> PCall {
+	PCall::proxy {
+		real: Address(real.into()),
+		call_to: Address(call_to.into()),
+		call_data: vec![0xaa].into(),
+	}
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::add_proxy_selectors().contains(&0x74a34dd3));
+	assert!(PCall::remove_proxy_selectors().contains(&0xfef3f708));
+	assert!(PCall::remove_proxies_selectors().contains(&0x14a5b5fa));
+	assert!(PCall::proxy_selectors().contains(&0x0d3cff86));
+	assert!(PCall::proxy_force_type_selectors().contains(&0x685b9d2f));
+	assert!(PCall::is_proxy_selectors().contains(&0xe26d38ed));
+}
+
+#[test]
+fn modifiers() {
+	ExtBuilder::default().build().execute_with(|| {
+		let mut tester = PrecompilesModifierTester::new(precompiles(), Alice, Precompile1);
+
+		tester.test_default_modifier(PCall::add_proxy_selectors());
+		tester.test_default_modifier(PCall::remove_proxy_selectors());
+		tester.test_default_modifier(PCall::remove_proxies_selectors());
+		tester.test_payable_modifier(PCall::proxy_selectors());
+		tester.test_payable_modifier(PCall::proxy_force_type_selectors());
+		tester.test_view_modifier(PCall::is_proxy_selectors());
+	});
+}
+
+#[test]
+fn add_proxy_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			precompiles()
+				.prepare_test(
+					Alice,
+					Precompile1,
+					PCall::add_proxy {
+						delegate: Address(Bob.into()),
+						proxy_type: ProxyType::Something as u8,
+						delay: 1,
+					},
+				)
+				.expect_no_logs()
+				.execute_returns(());
+
+			assert!(is_proxy(Alice, Bob, ProxyType::Something, 1));
+		});
+}
+
+#[test]
+fn add_proxy_reverts_for_existing_delegate() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			add_proxy(Alice, Bob, ProxyType::Something, 0);
+
+			precompiles()
+				.prepare_test(
+					Alice,
+					Precompile1,
+					PCall::add_proxy {
+						delegate: Address(Bob.into()),
+						proxy_type: ProxyType::Any as u8,
+						delay: 0,
+					},
+				)
+				.execute_reverts(|output| output == b"Cannot add more than one proxy");
+
+			assert!(!is_proxy(Alice, Bob, ProxyType::Any, 0));
+		});
+}
+
+#[test]
+fn add_proxy_reverts_for_unknown_type() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			precompiles()
+				.prepare_test(
+					Alice,
+					Precompile1,
+					PCall::add_proxy { delegate: Address(Bob.into()), proxy_type: 10, delay: 0 },
+				)
+				.execute_reverts(|output| {
+					output == b"proxyType: Failed decoding value to ProxyType"
+				});
+		});
+}
+
+#[test]
+fn remove_proxy_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			add_proxy(Alice, Bob, ProxyType::Something, 0);
+			add_proxy(Alice, Charlie, ProxyType::Any, 0);
+
+			precompiles()
+				.prepare_test(
+					Alice,
+					Precompile1,
+					PCall::remove_proxy {
+						delegate: Address(Bob.into()),
+						proxy_type: ProxyType::Something as u8,
+						delay: 0,
+					},
+				)
+				.expect_no_logs()
+				.execute_returns(());
+
+			assert!(!is_proxy(Alice, Bob, ProxyType::Something, 0));
+			assert!(is_proxy(Alice, Charlie, ProxyType::Any, 0));
+		});
+}
+
+#[test]
+fn remove_proxies_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			add_proxy(Alice, Bob, ProxyType::Something, 0);
+			add_proxy(Alice, Charlie, ProxyType::Any, 0);
+
+			precompiles()
+				.prepare_test(Alice, Precompile1, PCall::remove_proxies {})
+				.expect_no_logs()
+				.execute_returns(());
+
+			assert!(pallet_proxy::Proxies::<Runtime>::get(AccountId::from(Alice)).0.is_empty());
+		});
+}
+
+#[test]
+fn is_proxy_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			add_proxy(Alice, Bob, ProxyType::Something, 1);
+
+			let is_proxy_call = |proxy_type: ProxyType, delay| PCall::is_proxy {
+				real: Address(Alice.into()),
+				delegate: Address(Bob.into()),
+				proxy_type: proxy_type as u8,
+				delay,
+			};
+
+			precompiles()
+				.prepare_test(Charlie, Precompile1, is_proxy_call(ProxyType::Something, 1))
+				.expect_no_logs()
+				.execute_returns(true);
+			precompiles()
+				.prepare_test(Charlie, Precompile1, is_proxy_call(ProxyType::Any, 1))
+				.expect_no_logs()
+				.execute_returns(false);
+			precompiles()
+				.prepare_test(Charlie, Precompile1, is_proxy_call(ProxyType::Something, 0))
+				.expect_no_logs()
+				.execute_returns(false);
+		});
+}
+
+#[test]
+fn proxy_calls_on_behalf_of_real() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			add_proxy(Alice, Bob, ProxyType::Any, 0);
+
+			precompiles()
+				.prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+				.with_subcall_handle(
+					|Subcall { address, transfer, input, is_static, context, .. }| {
+						assert_eq!(address, H160::from(Charlie));
+						assert_eq!(input, vec![0xaa]);
+						assert!(transfer.is_none());
+						assert!(!is_static);
+						assert_eq!(context.caller, H160::from(Alice));
+						assert_eq!(context.address, H160::from(Charlie));
+
+						succeed()
+					},
+				)
+				.execute_returns(());
+		});
+}
+
+#[test]
+fn proxy_reverts_for_non_proxy() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			add_proxy(Alice, Bob, ProxyType::Any, 0);
+
+			precompiles()
+				.prepare_test(Charlie, Precompile1, proxy_call(Alice, Bob))
+				.execute_reverts(|output| output == b"Not proxy");
+
+			precompiles()
+				.prepare_test(
+					Bob,
+					Precompile1,
+					PCall::proxy_force_type {
+						real: Address(Alice.into()),
+						force_proxy_type: ProxyType::Something as u8,
+						call_to: Address(Charlie.into()),
+						call_data: vec![].into(),
+					},
+				)
+				.execute_reverts(|output| output == b"Not proxy");
+		});
+}
+
+#[test]
+fn proxy_reverts_for_proxy_with_delay() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			add_proxy(Alice, Bob, ProxyType::Any, 1);
+
+			precompiles()
+				.prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+				.execute_reverts(|output| output == b"Unannounced");
+		});
+}
+
+#[test]
+fn proxy_reverts_for_filtered_call() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			add_proxy(Alice, Bob, ProxyType::Something, 0);
+			pallet_evm::AccountCodes::<Runtime>::insert(H160::from(Charlie), vec![0x00]);
+
+			precompiles()
+				.prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+				.execute_reverts(|output| output == b"CallFiltered");
+		});
+}
+
+#[test]
+fn proxy_reverts_for_contract_real() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			add_proxy(Alice, Bob, ProxyType::Any, 0);
+			pallet_evm::AccountCodes::<Runtime>::insert(H160::from(Alice), vec![0x00]);
+
+			precompiles()
+				.prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+				.execute_reverts(|output| output == b"real address must be EOA");
+		});
+}
@@ -1,57 +1,97 @@
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
 
-/// @dev The finalizer precompile contract's address.
+/// @dev The QuantumPortal contract's address.
 address constant QUANTUM_PORTAL_PRECOMPILE = 0x0000000000000000000000000000000000000812;
 
-/**
- * @title QuantumPortalFinalizerPrecompile Interface
- * @dev Interface for managing finalizers in the Quantum Portal system.
- */
-interface QuantumPortalFinalizerPrecompile {
-
-    /**
-     * @notice Register Finalizer
-     * @dev Registers a finalizer for a specific chain ID.
-     * @param chainId The unique identifier of the blockchain.
-     * @param finalizer The address of the finalizer
-     */
-    function registerFinalizer(
-        uint256 chainId,
-        address finalizer
-    ) external;
+/// @dev The QuantumPortal contract's instance.
+QuantumPortal constant QUANTUM_PORTAL_CONTRACT = QuantumPortal(QUANTUM_PORTAL_PRECOMPILE);
 
-    /**
-     * @notice Get Finalizers
-     * @dev Retrieves the list of finalizers for a specific chain ID.
-     * @param chainId The unique identifier of the blockchain.
-     * @return The list of finalizers for the specified chain ID.
-     */
-    function getFinalizers(
-        uint256 chainId
-    ) external view returns (address[] memory);
-
-    /**
-     * @notice Add Finalizer
-     * @dev Adds a finalizer for a specific chain ID at a specified index.
-     * @param chainId The unique identifier of the blockchain.
-     * @param index The index at which to add the finalizer.
-     * @param finalizer The address of the finalizer to add.
-     */
-    function addFinalizer(
-        uint256 chainId,
-        uint256 index,
-        address finalizer
-    ) external;
+/// @title Quantum Portal interface
+/// @dev Manages the finalizers of the remote chains and submits their signatures. The management
+/// functions are restricted to the finalizer admin origin of the runtime.
+interface QuantumPortal {
+    /// @dev Registers a finalizer for a chain.
+    /// @custom:selector 3d3be081
+    /// @param chainId The id of the remote chain.
+    /// @param finalizer The address of the finalizer.
+    function registerFinalizer(uint256 chainId, address finalizer) external;
 
-    /**
-     * @notice Remove Finalizer
-     * @dev Removes a registered finalizer for a specific chain ID.
-     * @param chainId The unique identifier of the blockchain.
-     * @param finalizer The address of the finalizer
-     */
-    function removeFinalizer(
+    /// @dev Removes a finalizer of a chain.
+    /// @custom:selector 4895cdb5
+    /// @param chainId The id of the remote chain.
+    /// @param finalizer The address of the finalizer.
+    function removeFinalizer(uint256 chainId, address finalizer) external;
+
+    /// @dev Submits the signature of the caller, a finalizer of the chain, approving the
+    /// finalization of a block. The signature is checked against the EIP712 digest of the block.
+    /// Unlike a plain `submitSignature(uint256,uint256,bytes)`, the finalizer hash, salt and expiry
+    /// are passed along with the signature, as the digest is computed from them and the pallet
+    /// rejects expired signatures.
+    /// @custom:selector d7b83bae
+    /// @param chainId The id of the remote chain.
+    /// @param blockNumber The number of the finalized block.
+    /// @param finalizerHash The hash of the finalizers, as signed.
+    /// @param salt The salt of the signature.
+    /// @param expiry The expiry of the signature.
+    /// @param signature The signature, r || s || v padded with zeros to 96 bytes.
+    function submitSignature(
         uint256 chainId,
-        address finalizer
+        uint256 blockNumber,
+        bytes32 finalizerHash,
+        bytes32 salt,
+        uint64 expiry,
+        bytes memory signature
     ) external;
-}
\ No newline at end of file
+
+    /// @dev Sets the number of finalizer signatures required to finalize a block of a chain.
+    /// @custom:selector 1a4b63c6
+    /// @param chainId The id of the remote chain.
+    /// @param threshold The number of signatures.
+    function setFinalizerThreshold(uint256 chainId, uint32 threshold) external;
+
+    /// @dev Returns the finalizers of a chain. The gas cost grows with the number of finalizers.
+    /// @custom:selector e1fe609c
+    /// @param chainId The id of the remote chain.
+    function getFinalizers(uint256 chainId)
+        external
+        view
+        returns (address[] memory);
+
+    /// @dev Returns the number of finalizer signatures required to finalize a block of a chain,
+    /// 0 if not set.
+    /// @custom:selector 4615d5e9
+    /// @param chainId The id of the remote chain.
+    function getThreshold(uint256 chainId) external view returns (uint32);
+
+    /// @dev Returns the finalizers who signed a block, along with their signatures. The gas cost
+    /// grows with the number of signatures.
+    /// @custom:selector b6ca6073
+    /// @param chainId The id of the remote chain.
+    /// @param blockNumber The number of the block.
+    function getPendingSignatures(uint256 chainId, uint256 blockNumber)
+        external
+        view
+        returns (address[] memory finalizers, bytes[] memory signatures);
+
+    /// @dev Emitted when a finalizer is registered.
+    /// @custom:selector c887ad7f1b4bfd463764fcbba585d2237f4e4db91d4dd744a6fabb34dadecc9e
+    event FinalizerAdded(uint256 indexed chainId, address indexed finalizer);
+
+    /// @dev Emitted when a finalizer is removed.
+    /// @custom:selector c5578bbf79bba5c576104e23fcc47e27f9308f634223be886c964a31be8baf63
+    event FinalizerRemoved(uint256 indexed chainId, address indexed finalizer);
+
+    /// @dev Emitted when a finalizer submits a signature.
+    /// @custom:selector ea284f7070b6456a8db343ea0cf3668dadd2328138ffbec9aad660c0202802c1
+    event SignatureSubmitted(
+        uint256 indexed chainId,
+        uint256 indexed blockNumber,
+        address indexed finalizer,
+        bytes signature
+    );
+
+    /// @dev Emitted when the finalizer threshold of a chain is set.
+    /// @custom:selector 720884bf8e73e76bc05c8999cb8d153bc14bc5b2631bfeccb8bcda97403c5c4d
+    event FinalizerThresholdSet(uint256 indexed chainId, uint32 threshold);
+}
@@ -10,17 +10,17 @@
 // but WITHOUT ANY WARRANTY; without even the implied warranty of
 // MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
 // GNU General Public License for more details.
-// Licensed under the Apache License, Version 2.0 (the "License");
-// you may not use this file except in compliance with the License.
-// You may obtain a copy of the License at
-//
-// http://www.apache.org/licenses/LICENSE-2.0
-//
-// Unless required by applicable law or agreed to in writing, software
-// distributed under the License is distributed on an "AS IS" BASIS,
-// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
-// See the License for the specific language governing permissions and
-// limitations under the License.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile exposing the Quantum Portal pallet to the EVM: management of the finalizers and
+//! their threshold, submission of the finalizer signatures, and reads of the finalization state.
+//!
+//! The calls are dispatched with the caller as signed origin, so the management functions are
+//! only usable by the callers accepted by the `FinalizerOrigin` of the runtime. Each successful
+//! call emits a Solidity event mirroring the event of the pallet.
+
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use fp_evm::PrecompileHandle;
@@ -28,20 +28,53 @@ use frame_support::{
 	dispatch::{GetDispatchInfo, PostDispatchInfo},
 	traits::ConstU32,
 };
-
 use pallet_evm::AddressMapping;
-use pallet_quantum_portal::Call as QuantumPortalCall;
+use pallet_quantum_portal::{
+	qp_types::ChainId, Call as QuantumPortalCall, FinalizerThreshold, PendingFinalizeSignatures,
+	RegisteredFinalizers,
+};
 use precompile_utils::prelude::*;
-use sp_core::H160;
-
-use sp_core::U256;
+use sp_core::{H160, H256, U256};
 use sp_runtime::traits::Dispatchable;
-use sp_std::marker::PhantomData;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Solidity selector of the FinalizerAdded log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_FINALIZER_ADDED: [u8; 32] = keccak256!("FinalizerAdded(uint256,address)");
+
+/// Solidity selector of the FinalizerRemoved log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_FINALIZER_REMOVED: [u8; 32] =
+	keccak256!("FinalizerRemoved(uint256,address)");
 
-pub const SUBMISSION_SIZE_LIMIT: u32 = u32::MAX;
-type GetSubmissionSizeLimit = ConstU32<SUBMISSION_SIZE_LIMIT>;
+/// Solidity selector of the SignatureSubmitted log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_SIGNATURE_SUBMITTED: [u8; 32] =
+	keccak256!("SignatureSubmitted(uint256,uint256,address,bytes)");
 
-/// A precompile to wrap the functionality from pallet-preimage.
+/// Solidity selector of the FinalizerThresholdSet log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_FINALIZER_THRESHOLD_SET: [u8; 32] =
+	keccak256!("FinalizerThresholdSet(uint256,uint32)");
+
+/// Solidity selector of `submitSignature`, which finalizers may also call through their proxies.
+pub const SELECTOR_SUBMIT_SIGNATURE: [u8; 4] = [0xd7, 0xb8, 0x3b, 0xae];
+
+/// Whether `call_data` is a call of `submitSignature`.
+pub fn is_submit_signature_call(call_data: &[u8]) -> bool {
+	call_data.get(..4) == Some(&SELECTOR_SUBMIT_SIGNATURE[..])
+}
+
+/// Gas charged by the getters for each 32 bytes word of the finalizers and signatures they
+/// return, on top of the storage read, so that their cost grows with the number of items.
+pub const GAS_PER_RETURNED_WORD: u64 = 3;
+
+/// The size of the finalizer signatures: `r || s || v` padded with zeros to 96 bytes.
+pub const SIGNATURE_SIZE_LIMIT: u32 = 96;
+type GetSignatureSizeLimit = ConstU32<SIGNATURE_SIZE_LIMIT>;
+
+/// A precompile to wrap the functionality from pallet-quantum-portal.
 pub struct QuantumPortalPrecompile<Runtime>(PhantomData<Runtime>);
 
 #[precompile_utils::precompile]
@@ -58,57 +91,214 @@ where
 	<Runtime as frame_system::pallet::Config>::RuntimeCall:
 		From<pallet_quantum_portal::Call<Runtime>>,
 {
+	/// Registers `finalizer` for the chain `chainId`.
 	#[precompile::public("registerFinalizer(uint256,address)")]
 	fn register_finalizer(
 		handle: &mut impl PrecompileHandle,
 		chain_id: U256,
-		_finalizer: Address,
+		finalizer: Address,
 	) -> EvmResult {
+		handle.record_log_costs_manual(3, 0)?;
+
 		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let chain_id = Self::u256_to_u64(chain_id).in_field("chainId")?;
 
-		let chain_id = chain_id.as_u64();
 		let call = QuantumPortalCall::<Runtime>::register_finalizer {
-			finalizer: origin.clone(),
 			chain_id,
+			finalizer: Runtime::AddressMapping::into_account_id(finalizer.into()),
 		};
 
 		// Dispatch the call using the RuntimeHelper
 		<RuntimeHelper<Runtime>>::try_dispatch(handle, Some(origin).into(), call)?;
 
+		log3(
+			handle.context().address,
+			SELECTOR_LOG_FINALIZER_ADDED,
+			Self::chain_id_topic(chain_id),
+			finalizer.0,
+			Vec::new(),
+		)
+		.record(handle)?;
+
 		Ok(())
 	}
 
+	/// Removes `finalizer` from the finalizers of the chain `chainId`.
 	#[precompile::public("removeFinalizer(uint256,address)")]
 	fn remove_finalizer(
 		handle: &mut impl PrecompileHandle,
 		chain_id: U256,
-		_finalizer: Address,
+		finalizer: Address,
+	) -> EvmResult {
+		handle.record_log_costs_manual(3, 0)?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let chain_id = Self::u256_to_u64(chain_id).in_field("chainId")?;
+
+		let call = QuantumPortalCall::<Runtime>::remove_finalizer {
+			chain_id,
+			finalizer: Runtime::AddressMapping::into_account_id(finalizer.into()),
+		};
+
+		// Dispatch the call using the RuntimeHelper
+		<RuntimeHelper<Runtime>>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		log3(
+			handle.context().address,
+			SELECTOR_LOG_FINALIZER_REMOVED,
+			Self::chain_id_topic(chain_id),
+			finalizer.0,
+			Vec::new(),
+		)
+		.record(handle)?;
+
+		Ok(())
+	}
+
+	/// Submits the signature of the caller, a finalizer of the chain `chainId`, approving the
+	/// finalization of the block `blockNumber`. The signature is checked against the EIP712
+	/// digest of the block, `finalizerHash`, `salt` and `expiry`.
+	#[precompile::public("submitSignature(uint256,uint256,bytes32,bytes32,uint64,bytes)")]
+	fn submit_signature(
+		handle: &mut impl PrecompileHandle,
+		chain_id: U256,
+		block_number: U256,
+		finalizer_hash: H256,
+		salt: H256,
+		expiry: u64,
+		signature: BoundedBytes<GetSignatureSizeLimit>,
+	) -> EvmResult {
+		let signature = signature.into_vec();
+		let event_data = solidity::encode_event_data(UnboundedBytes::from(signature.clone()));
+		handle.record_log_costs_manual(4, event_data.len())?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let chain_id = Self::u256_to_u64(chain_id).in_field("chainId")?;
+		let block_number = Self::u256_to_u64(block_number).in_field("blockNumber")?;
+
+		let call = QuantumPortalCall::<Runtime>::submit_signature {
+			chain_id,
+			block_number,
+			finalizer_hash,
+			salt,
+			expiry,
+			signature,
+		};
+
+		// Dispatch the call using the RuntimeHelper
+		<RuntimeHelper<Runtime>>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		log4(
+			handle.context().address,
+			SELECTOR_LOG_SIGNATURE_SUBMITTED,
+			Self::chain_id_topic(chain_id),
+			H256::from_low_u64_be(block_number),
+			handle.context().caller,
+			event_data,
+		)
+		.record(handle)?;
+
+		Ok(())
+	}
+
+	/// Sets the number of finalizer signatures required to finalize a block of the chain
+	/// `chainId`.
+	#[precompile::public("setFinalizerThreshold(uint256,uint32)")]
+	fn set_finalizer_threshold(
+		handle: &mut impl PrecompileHandle,
+		chain_id: U256,
+		threshold: u32,
 	) -> EvmResult {
+		handle.record_log_costs_manual(2, 32)?;
+
 		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let chain_id = Self::u256_to_u64(chain_id).in_field("chainId")?;
 
-		let chain_id = chain_id.as_u64();
-		let call =
-			QuantumPortalCall::<Runtime>::remove_finalizer { finalizer: origin.clone(), chain_id };
+		let call = QuantumPortalCall::<Runtime>::set_finalizer_threshold { chain_id, threshold };
 
 		// Dispatch the call using the RuntimeHelper
 		<RuntimeHelper<Runtime>>::try_dispatch(handle, Some(origin).into(), call)?;
 
+		log2(
+			handle.context().address,
+			SELECTOR_LOG_FINALIZER_THRESHOLD_SET,
+			Self::chain_id_topic(chain_id),
+			solidity::encode_event_data(threshold),
+		)
+		.record(handle)?;
+
 		Ok(())
 	}
 
-	// #[precompile::public("setThreshold(u64)")]
-	// fn set_threshold(
-	// 	handle: &mut impl PrecompileHandle,
-	// 	chain_id: u64,
-	// 	threshold: u32,
-	// ) -> EvmResult {
-	// 	let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+	/// Returns the finalizers of the chain `chainId`.
+	#[precompile::public("getFinalizers(uint256)")]
+	#[precompile::view]
+	fn get_finalizers(
+		handle: &mut impl PrecompileHandle,
+		chain_id: U256,
+	) -> EvmResult<Vec<Address>> {
+		let chain_id = Self::u256_to_u64(chain_id).in_field("chainId")?;
 
-	// 	// let call = QuantumPortalCall::<Runtime>::set_finalizer_threshold { chain_id, threshold };
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let finalizers: Vec<Address> = RegisteredFinalizers::<Runtime>::get(chain_id)
+			.unwrap_or_default()
+			.into_iter()
+			.map(|finalizer| Address(finalizer.into()))
+			.collect();
+		// a word per finalizer
+		Self::record_returned_words(handle, finalizers.len())?;
 
-	// 	// // Dispatch the call using the RuntimeHelper
-	// 	// <RuntimeHelper<Runtime>>::try_dispatch(handle, Some(origin).into(), call.into(), 0)?;
+		Ok(finalizers)
+	}
+
+	/// Returns the number of finalizer signatures required to finalize a block of the chain
+	/// `chainId`, 0 if not set.
+	#[precompile::public("getThreshold(uint256)")]
+	#[precompile::view]
+	fn get_threshold(handle: &mut impl PrecompileHandle, chain_id: U256) -> EvmResult<u32> {
+		let chain_id = Self::u256_to_u64(chain_id).in_field("chainId")?;
+
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+
+		Ok(FinalizerThreshold::<Runtime>::get(chain_id).unwrap_or_default())
+	}
+
+	/// Returns the finalizers who submitted a signature for the block `blockNumber` of the chain
+	/// `chainId`, along with their signatures.
+	#[precompile::public("getPendingSignatures(uint256,uint256)")]
+	#[precompile::view]
+	fn get_pending_signatures(
+		handle: &mut impl PrecompileHandle,
+		chain_id: U256,
+		block_number: U256,
+	) -> EvmResult<(Vec<Address>, Vec<UnboundedBytes>)> {
+		let chain_id = Self::u256_to_u64(chain_id).in_field("chainId")?;
+		let block_number = Self::u256_to_u64(block_number).in_field("blockNumber")?;
+
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let pending =
+			PendingFinalizeSignatures::<Runtime>::get(chain_id, block_number).unwrap_or_default();
+		// a word per finalizer, and the offset, length and data words of each signature
+		let words = pending.iter().map(|(_, signature)| 3 + signature.len().div_ceil(32)).sum();
+		Self::record_returned_words(handle, words)?;
 
-	// 	Ok(())
-	// }
+		let (finalizers, signatures) = pending
+			.into_iter()
+			.map(|(finalizer, signature)| (Address(finalizer.into()), signature.into()))
+			.unzip();
+
+		Ok((finalizers, signatures))
+	}
+
+	fn record_returned_words(handle: &mut impl PrecompileHandle, words: usize) -> EvmResult {
+		handle.record_cost(GAS_PER_RETURNED_WORD.saturating_mul(words as u64))
+	}
+
+	fn chain_id_topic(chain_id: ChainId) -> H256 {
+		H256::from_low_u64_be(chain_id)
+	}
+
+	fn u256_to_u64(value: U256) -> MayRevert<u64> {
+		value.try_into().map_err(|_| RevertReason::value_is_too_large("uint64").into())
+	}
 }
@@ -0,0 +1,352 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::str::from_utf8;
+
+use crate::{mock::*, *};
+
+use frame_support::assert_ok;
+use libsecp256k1::{sign, Message, PublicKey, SecretKey};
+use pallet_quantum_portal::qp_types::SignatureDomain;
+use precompile_utils::testing::*;
+use sha3::{Digest, Keccak256};
+
+/// The chain of the finalizers.
+const CHAIN_ID: u64 = 26100;
+
+fn precompiles() -> Precompiles<Runtime> {
+	PrecompilesValue::get()
+}
+
+/// The key of the finalizer signing the blocks.
+fn finalizer_key() -> SecretKey {
+	SecretKey::parse(&[0x42; 32]).unwrap()
+}
+
+/// The address of the finalizer signing the blocks.
+fn finalizer() -> H160 {
+	let public = PublicKey::from_secret_key(&finalizer_key()).serialize();
+	H160::from_slice(&Keccak256::digest(&public[1..])[12..])
+}
+
+/// Registers `finalizer` and sets the signature domain of the chain, as the admin.
+fn setup_chain(finalizer: H160) {
+	assert_ok!(QuantumPortal::register_finalizer(
+		RuntimeOrigin::signed(account(Alice)),
+		CHAIN_ID,
+		account(finalizer),
+	));
+	assert_ok!(QuantumPortal::set_signature_domain(
+		RuntimeOrigin::signed(account(Alice)),
+		CHAIN_ID,
+		SignatureDomain {
+			name: b"FERRUM_QUANTUM_PORTAL_AUTHORITY_MGR".to_vec(),
+			version: b"000.010".to_vec(),
+			chain_id: CHAIN_ID,
+			verifying_contract: H160::repeat_byte(0x11),
+		},
+	));
+}
+
+/// The signature of the finalizer approving the finalization of `block_number`, in the
+/// `r || s || v` format padded with zeros to 96 bytes.
+fn finalizer_signature(
+	block_number: u64,
+	finalizer_hash: H256,
+	salt: H256,
+	expiry: u64,
+) -> Vec<u8> {
+	let digest =
+		QuantumPortal::finalize_digest(CHAIN_ID, block_number, finalizer_hash, salt, expiry)
+			.unwrap();
+	let (signature, recovery_id) = sign(&Message::parse(&digest.0), &finalizer_key());
+
+	let mut signature = signature.serialize().to_vec();
+	signature.push(recovery_id.serialize() + 27);
+	signature.extend([0u8; 31]);
+	signature
+}
+
+fn submit_signature_call(block_number: u64, signature: Vec<u8>) -> PCall {
+	PCall::submit_signature {
+		chain_id: CHAIN_ID.into(),
+		block_number: block_number.into(),
+		finalizer_hash: H256::repeat_byte(0x01),
+		salt: H256::repeat_byte(0x02),
+		expiry: 1_000,
+		signature: signature.into(),
+	}
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::register_finalizer_selectors().contains(&0x3d3be081));
+	assert!(PCall::remove_finalizer_selectors().contains(&0x4895cdb5));
+	assert!(PCall::submit_signature_selectors().contains(&0xd7b83bae));
+	assert!(PCall::set_finalizer_threshold_selectors().contains(&0x1a4b63c6));
+	assert!(PCall::get_finalizers_selectors().contains(&0xe1fe609c));
+	assert!(PCall::get_threshold_selectors().contains(&0x4615d5e9));
+	assert!(PCall::get_pending_signatures_selectors().contains(&0xb6ca6073));
+
+	assert_eq!(
+		crate::SELECTOR_LOG_FINALIZER_ADDED,
+		&Keccak256::digest(b"FinalizerAdded(uint256,address)")[..]
+	);
+
+	assert_eq!(
+		crate::SELECTOR_LOG_FINALIZER_REMOVED,
+		&Keccak256::digest(b"FinalizerRemoved(uint256,address)")[..]
+	);
+
+	assert_eq!(
+		crate::SELECTOR_LOG_SIGNATURE_SUBMITTED,
+		&Keccak256::digest(b"SignatureSubmitted(uint256,uint256,address,bytes)")[..]
+	);
+
+	assert_eq!(
+		crate::SELECTOR_LOG_FINALIZER_THRESHOLD_SET,
+		&Keccak256::digest(b"FinalizerThresholdSet(uint256,uint32)")[..]
+	);
+}
+
+#[test]
+fn submit_signature_calls_are_recognized() {
+	assert!(PCall::submit_signature_selectors()
+		.contains(&u32::from_be_bytes(crate::SELECTOR_SUBMIT_SIGNATURE)));
+
+	let call_data: Vec<u8> = submit_signature_call(7, vec![0u8; 65]).into();
+	assert!(crate::is_submit_signature_call(&call_data));
+
+	let call_data: Vec<u8> =
+		PCall::register_finalizer { chain_id: CHAIN_ID.into(), finalizer: Address(Bob.into()) }
+			.into();
+	assert!(!crate::is_submit_signature_call(&call_data));
+	assert!(!crate::is_submit_signature_call(&[0xd7, 0xb8, 0x3b]));
+	assert!(!crate::is_submit_signature_call(&[]));
+}
+
+#[test]
+fn modifiers() {
+	ExtBuilder::default().build().execute_with(|| {
+		let mut tester = PrecompilesModifierTester::new(precompiles(), Alice, Precompile1);
+
+		tester.test_default_modifier(PCall::register_finalizer_selectors());
+		tester.test_default_modifier(PCall::remove_finalizer_selectors());
+		tester.test_default_modifier(PCall::submit_signature_selectors());
+		tester.test_default_modifier(PCall::set_finalizer_threshold_selectors());
+		tester.test_view_modifier(PCall::get_finalizers_selectors());
+		tester.test_view_modifier(PCall::get_threshold_selectors());
+		tester.test_view_modifier(PCall::get_pending_signatures_selectors());
+	});
+}
+
+#[test]
+fn register_finalizer_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::register_finalizer {
+					chain_id: CHAIN_ID.into(),
+					finalizer: Address(Bob.into()),
+				},
+			)
+			.expect_log(log3(
+				Precompile1,
+				SELECTOR_LOG_FINALIZER_ADDED,
+				H256::from_low_u64_be(CHAIN_ID),
+				H160::from(Bob),
+				vec![],
+			))
+			.execute_returns(());
+
+		precompiles()
+			.prepare_test(Charlie, Precompile1, PCall::get_finalizers { chain_id: CHAIN_ID.into() })
+			// a word for the finalizer
+			.expect_cost(GAS_PER_RETURNED_WORD)
+			.expect_no_logs()
+			.execute_returns(vec![Address(Bob.into())]);
+	});
+}
+
+#[test]
+fn register_finalizer_requires_finalizer_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Bob,
+				Precompile1,
+				PCall::register_finalizer {
+					chain_id: CHAIN_ID.into(),
+					finalizer: Address(Bob.into()),
+				},
+			)
+			.execute_reverts(|output| from_utf8(output).unwrap().contains("BadOrigin"));
+
+		assert!(RegisteredFinalizers::<Runtime>::get(CHAIN_ID).is_none());
+	});
+}
+
+#[test]
+fn remove_finalizer_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_chain(Bob.into());
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::remove_finalizer {
+					chain_id: CHAIN_ID.into(),
+					finalizer: Address(Bob.into()),
+				},
+			)
+			.expect_log(log3(
+				Precompile1,
+				SELECTOR_LOG_FINALIZER_REMOVED,
+				H256::from_low_u64_be(CHAIN_ID),
+				H160::from(Bob),
+				vec![],
+			))
+			.execute_returns(());
+
+		precompiles()
+			.prepare_test(Charlie, Precompile1, PCall::get_finalizers { chain_id: CHAIN_ID.into() })
+			.expect_cost(0)
+			.expect_no_logs()
+			.execute_returns(Vec::<Address>::new());
+
+		// Bob is no longer a finalizer
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::remove_finalizer {
+					chain_id: CHAIN_ID.into(),
+					finalizer: Address(Bob.into()),
+				},
+			)
+			.execute_reverts(|output| from_utf8(output).unwrap().contains("FinalizerNotFound"));
+	});
+}
+
+#[test]
+fn set_finalizer_threshold_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(Charlie, Precompile1, PCall::get_threshold { chain_id: CHAIN_ID.into() })
+			.expect_no_logs()
+			.execute_returns(0u32);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::set_finalizer_threshold { chain_id: CHAIN_ID.into(), threshold: 3 },
+			)
+			.expect_log(log2(
+				Precompile1,
+				SELECTOR_LOG_FINALIZER_THRESHOLD_SET,
+				H256::from_low_u64_be(CHAIN_ID),
+				solidity::encode_event_data(3u32),
+			))
+			.execute_returns(());
+
+		precompiles()
+			.prepare_test(Charlie, Precompile1, PCall::get_threshold { chain_id: CHAIN_ID.into() })
+			.expect_no_logs()
+			.execute_returns(3u32);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				Precompile1,
+				PCall::set_finalizer_threshold { chain_id: CHAIN_ID.into(), threshold: 1 },
+			)
+			.execute_reverts(|output| from_utf8(output).unwrap().contains("BadOrigin"));
+	});
+}
+
+#[test]
+fn submit_signature_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_chain(finalizer());
+		let signature =
+			finalizer_signature(7, H256::repeat_byte(0x01), H256::repeat_byte(0x02), 1_000);
+
+		precompiles()
+			.prepare_test(finalizer(), Precompile1, submit_signature_call(7, signature.clone()))
+			.expect_log(log4(
+				Precompile1,
+				SELECTOR_LOG_SIGNATURE_SUBMITTED,
+				H256::from_low_u64_be(CHAIN_ID),
+				H256::from_low_u64_be(7),
+				finalizer(),
+				solidity::encode_event_data(UnboundedBytes::from(signature.clone())),
+			))
+			.execute_returns(());
+
+		precompiles()
+			.prepare_test(
+				Charlie,
+				Precompile1,
+				PCall::get_pending_signatures { chain_id: CHAIN_ID.into(), block_number: 7.into() },
+			)
+			// the finalizer, and the offset, length and 3 data words of the signature
+			.expect_cost(6 * GAS_PER_RETURNED_WORD)
+			.expect_no_logs()
+			.execute_returns((vec![Address(finalizer())], vec![UnboundedBytes::from(signature)]));
+	});
+}
+
+#[test]
+fn submit_signature_reverts_for_invalid_signature() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_chain(finalizer());
+		// signed for another block
+		let signature =
+			finalizer_signature(8, H256::repeat_byte(0x01), H256::repeat_byte(0x02), 1_000);
+
+		precompiles()
+			.prepare_test(finalizer(), Precompile1, submit_signature_call(7, signature))
+			.execute_reverts(|output| from_utf8(output).unwrap().contains("SignatureMismatch"));
+
+		assert!(PendingFinalizeSignatures::<Runtime>::get(CHAIN_ID, 7).is_none());
+	});
+}
+
+#[test]
+fn submit_signature_reverts_for_unregistered_finalizer() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_chain(Bob.into());
+		let signature =
+			finalizer_signature(7, H256::repeat_byte(0x01), H256::repeat_byte(0x02), 1_000);
+
+		precompiles()
+			.prepare_test(finalizer(), Precompile1, submit_signature_call(7, signature))
+			.execute_reverts(|output| from_utf8(output).unwrap().contains("FinalizerNotFound"));
+	});
+}
+
+#[test]
+fn chain_id_too_large_reverts() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(Charlie, Precompile1, PCall::get_threshold { chain_id: U256::MAX })
+			.execute_reverts(|output| output == b"chainId: Value is too large for uint64");
+	});
+}
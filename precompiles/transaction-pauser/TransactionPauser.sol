@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The TransactionPauser contract's address.
+address constant TRANSACTION_PAUSER_ADDRESS = 0x0000000000000000000000000000000000000817;
+
+/// @dev The TransactionPauser contract's instance.
+TransactionPauser constant TRANSACTION_PAUSER_CONTRACT = TransactionPauser(
+    TRANSACTION_PAUSER_ADDRESS
+);
+
+/// @title Transaction pauser interface
+/// @dev Pauses and unpauses the Substrate calls and the EVM calls. An empty function name or
+/// selector targets the whole pallet or contract. The pausing functions are restricted to the
+/// pauser admin set by the governance, usually an admin contract.
+interface TransactionPauser {
+    /// @dev Pauses a Substrate call, or every call of a pallet.
+    /// @custom:selector 7d133222
+    /// @param palletName The name of the pallet, as in the runtime.
+    /// @param functionName The name of the call, empty for the whole pallet.
+    /// @param expiry The block at which the call is unpaused, 0 to pause until unpaused.
+    /// @param reason The reason of the pause.
+    function pauseTransaction(
+        string memory palletName,
+        string memory functionName,
+        uint32 expiry,
+        string memory reason
+    ) external;
+
+    /// @dev Unpauses a Substrate call, or the pause of a whole pallet.
+    /// @custom:selector d8e50e1e
+    /// @param palletName The name of the pallet, as in the runtime.
+    /// @param functionName The name of the call, empty for the whole pallet.
+    function unpauseTransaction(string memory palletName, string memory functionName) external;
+
+    /// @dev Pauses the calls to a function of a contract, or to the whole contract.
+    /// @custom:selector 164993a0
+    /// @param contractAddress The address of the contract.
+    /// @param selector The 4-byte selector of the function, empty for the whole contract.
+    /// @param expiry The block at which the call is unpaused, 0 to pause until unpaused.
+    /// @param reason The reason of the pause.
+    function pauseEvmCall(
+        address contractAddress,
+        bytes memory selector,
+        uint32 expiry,
+        string memory reason
+    ) external;
+
+    /// @dev Unpauses the calls to a function of a contract, or to the whole contract.
+    /// @custom:selector 612f6197
+    /// @param contractAddress The address of the contract.
+    /// @param selector The 4-byte selector of the function, empty for the whole contract.
+    function unpauseEvmCall(address contractAddress, bytes memory selector) external;
+
+    /// @dev Returns whether a Substrate call is paused, either by name or as a whole pallet.
+    /// @custom:selector 3328f609
+    /// @param palletName The name of the pallet, as in the runtime.
+    /// @param functionName The name of the call, empty to only check the whole pallet.
+    function isTransactionPaused(string memory palletName, string memory functionName)
+        external
+        view
+        returns (bool);
+
+    /// @dev Returns whether the calls to a function of a contract are paused, either by selector
+    /// or as a whole contract.
+    /// @custom:selector 56906f8d
+    /// @param contractAddress The address of the contract.
+    /// @param selector The 4-byte selector of the function, empty to only check the whole
+    /// contract.
+    function isEvmCallPaused(address contractAddress, bytes memory selector)
+        external
+        view
+        returns (bool);
+
+    /// @dev Emitted when a Substrate call, or every call of a pallet, is paused.
+    /// @custom:selector 3c6fce740c95410aa47d29f9e3503f4afa99e46b4fa78cb6d8dc2f044631adb4
+    event TransactionPaused(
+        string palletName,
+        string functionName,
+        uint32 expiry,
+        string reason
+    );
+
+    /// @dev Emitted when a Substrate call is unpaused.
+    /// @custom:selector b63b4427fcba3554d8cd0a5b946b5b20d0341f6bd59f095e4a2352c3829db8bf
+    event TransactionUnpaused(string palletName, string functionName);
+
+    /// @dev Emitted when the calls to a function of a contract, or to the whole contract, are
+    /// paused.
+    /// @custom:selector bfc90376c58c5593341674668e19de360376ed08c71b8faef09ff558623878fb
+    event EvmCallPaused(
+        address indexed contractAddress,
+        bytes selector,
+        uint32 expiry,
+        string reason
+    );
+
+    /// @dev Emitted when the calls to a function of a contract, or to the whole contract, are
+    /// unpaused.
+    /// @custom:selector a2a94af0bbe7f80a1ea5d20efd2f61729d1bdca2d6e2dac4d1535d355971d9d4
+    event EvmCallUnpaused(address indexed contractAddress, bytes selector);
+}
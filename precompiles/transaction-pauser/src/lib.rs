@@ -0,0 +1,260 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile exposing the transaction pauser pallet to the EVM: pausing and unpausing of the
+//! Substrate calls by pallet and function name, and of the EVM calls by contract and selector.
+//! An empty function name or selector targets the whole pallet or contract, and a zero expiry
+//! block pauses until unpaused.
+//!
+//! The calls are dispatched with the caller as signed origin, so the pausing functions are only
+//! usable by the pauser admin set by the governance in the pallet, usually an admin contract.
+//! Each successful call emits a Solidity event mirroring the event of the pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use fp_evm::PrecompileHandle;
+use frame_support::{
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	traits::ConstU32,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_evm::AddressMapping;
+use pallet_transaction_pauser::Call as TransactionPauserCall;
+use precompile_utils::prelude::*;
+use sp_runtime::traits::Dispatchable;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Solidity selector of the TransactionPaused log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_TRANSACTION_PAUSED: [u8; 32] =
+	keccak256!("TransactionPaused(string,string,uint32,string)");
+
+/// Solidity selector of the TransactionUnpaused log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_TRANSACTION_UNPAUSED: [u8; 32] =
+	keccak256!("TransactionUnpaused(string,string)");
+
+/// Solidity selector of the EvmCallPaused log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_EVM_CALL_PAUSED: [u8; 32] =
+	keccak256!("EvmCallPaused(address,bytes,uint32,string)");
+
+/// Solidity selector of the EvmCallUnpaused log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_EVM_CALL_UNPAUSED: [u8; 32] = keccak256!("EvmCallUnpaused(address,bytes)");
+
+/// The maximum size of the pallet and function names.
+pub const NAME_SIZE_LIMIT: u32 = 128;
+type GetNameSizeLimit = ConstU32<NAME_SIZE_LIMIT>;
+
+/// The maximum size of the reason of a pause.
+pub const REASON_SIZE_LIMIT: u32 = 256;
+type GetReasonSizeLimit = ConstU32<REASON_SIZE_LIMIT>;
+
+/// The size of a function selector, which is empty to target the whole contract.
+pub const SELECTOR_SIZE_LIMIT: u32 = 4;
+type GetSelectorSizeLimit = ConstU32<SELECTOR_SIZE_LIMIT>;
+
+/// A precompile to wrap the functionality from pallet-transaction-pauser.
+pub struct TransactionPauserPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> TransactionPauserPrecompile<Runtime>
+where
+	Runtime: pallet_evm::pallet::Config
+		+ pallet_transaction_pauser::Config
+		+ frame_system::pallet::Config,
+	<Runtime as frame_system::Config>::RuntimeCall:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	<<Runtime as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
+		From<Option<<Runtime as frame_system::Config>::AccountId>>,
+	<Runtime as frame_system::pallet::Config>::RuntimeCall:
+		From<pallet_transaction_pauser::Call<Runtime>>,
+	BlockNumberFor<Runtime>: From<u32>,
+{
+	/// Pauses the Substrate call `functionName` of the pallet `palletName`, or every call of the
+	/// pallet if `functionName` is empty, until the block `expiry` if not zero.
+	#[precompile::public("pauseTransaction(string,string,uint32,string)")]
+	fn pause_transaction(
+		handle: &mut impl PrecompileHandle,
+		pallet_name: BoundedString<GetNameSizeLimit>,
+		function_name: BoundedString<GetNameSizeLimit>,
+		expiry: u32,
+		reason: BoundedString<GetReasonSizeLimit>,
+	) -> EvmResult {
+		let event_data = solidity::encode_event_data((
+			pallet_name.clone(),
+			function_name.clone(),
+			expiry,
+			reason.clone(),
+		));
+		handle.record_log_costs_manual(1, event_data.len())?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let call = TransactionPauserCall::<Runtime>::pause_transaction {
+			pallet_name: pallet_name.into_vec(),
+			function_name: Self::parse_function_name(function_name),
+			expiry: Self::parse_expiry(expiry),
+			reason: reason.into_vec(),
+		};
+
+		// Dispatch the call using the RuntimeHelper
+		<RuntimeHelper<Runtime>>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		log1(handle.context().address, SELECTOR_LOG_TRANSACTION_PAUSED, event_data)
+			.record(handle)?;
+
+		Ok(())
+	}
+
+	/// Unpauses the Substrate call `functionName` of the pallet `palletName`, or the pause of the
+	/// whole pallet if `functionName` is empty.
+	#[precompile::public("unpauseTransaction(string,string)")]
+	fn unpause_transaction(
+		handle: &mut impl PrecompileHandle,
+		pallet_name: BoundedString<GetNameSizeLimit>,
+		function_name: BoundedString<GetNameSizeLimit>,
+	) -> EvmResult {
+		let event_data = solidity::encode_event_data((pallet_name.clone(), function_name.clone()));
+		handle.record_log_costs_manual(1, event_data.len())?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let call = TransactionPauserCall::<Runtime>::unpause_transaction {
+			pallet_name: pallet_name.into_vec(),
+			function_name: Self::parse_function_name(function_name),
+		};
+
+		// Dispatch the call using the RuntimeHelper
+		<RuntimeHelper<Runtime>>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		log1(handle.context().address, SELECTOR_LOG_TRANSACTION_UNPAUSED, event_data)
+			.record(handle)?;
+
+		Ok(())
+	}
+
+	/// Pauses the calls to the function `selector` of `contract`, or to the whole contract if
+	/// `selector` is empty, until the block `expiry` if not zero.
+	#[precompile::public("pauseEvmCall(address,bytes,uint32,string)")]
+	fn pause_evm_call(
+		handle: &mut impl PrecompileHandle,
+		contract: Address,
+		selector: BoundedBytes<GetSelectorSizeLimit>,
+		expiry: u32,
+		reason: BoundedString<GetReasonSizeLimit>,
+	) -> EvmResult {
+		let event_data = solidity::encode_event_data((selector.clone(), expiry, reason.clone()));
+		handle.record_log_costs_manual(2, event_data.len())?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let call = TransactionPauserCall::<Runtime>::pause_evm_call {
+			contract: contract.into(),
+			selector: Self::parse_selector(selector).in_field("selector")?,
+			expiry: Self::parse_expiry(expiry),
+			reason: reason.into_vec(),
+		};
+
+		// Dispatch the call using the RuntimeHelper
+		<RuntimeHelper<Runtime>>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		log2(handle.context().address, SELECTOR_LOG_EVM_CALL_PAUSED, contract.0, event_data)
+			.record(handle)?;
+
+		Ok(())
+	}
+
+	/// Unpauses the calls to the function `selector` of `contract`, or the pause of the whole
+	/// contract if `selector` is empty.
+	#[precompile::public("unpauseEvmCall(address,bytes)")]
+	fn unpause_evm_call(
+		handle: &mut impl PrecompileHandle,
+		contract: Address,
+		selector: BoundedBytes<GetSelectorSizeLimit>,
+	) -> EvmResult {
+		let event_data = solidity::encode_event_data(selector.clone());
+		handle.record_log_costs_manual(2, event_data.len())?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let call = TransactionPauserCall::<Runtime>::unpause_evm_call {
+			contract: contract.into(),
+			selector: Self::parse_selector(selector).in_field("selector")?,
+		};
+
+		// Dispatch the call using the RuntimeHelper
+		<RuntimeHelper<Runtime>>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		log2(handle.context().address, SELECTOR_LOG_EVM_CALL_UNPAUSED, contract.0, event_data)
+			.record(handle)?;
+
+		Ok(())
+	}
+
+	/// Returns whether the Substrate call `functionName` of the pallet `palletName` is paused,
+	/// either by name or as a whole pallet. An empty `functionName` only checks the whole pallet.
+	#[precompile::public("isTransactionPaused(string,string)")]
+	#[precompile::view]
+	fn is_transaction_paused(
+		handle: &mut impl PrecompileHandle,
+		pallet_name: BoundedString<GetNameSizeLimit>,
+		function_name: BoundedString<GetNameSizeLimit>,
+	) -> EvmResult<bool> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost().saturating_mul(2))?;
+
+		Ok(pallet_transaction_pauser::Pallet::<Runtime>::is_transaction_paused(
+			pallet_name.as_bytes(),
+			function_name.as_bytes(),
+		))
+	}
+
+	/// Returns whether the calls to the function `selector` of `contract` are paused, either by
+	/// selector or as a whole contract. An empty `selector` only checks the whole contract.
+	#[precompile::public("isEvmCallPaused(address,bytes)")]
+	#[precompile::view]
+	fn is_evm_call_paused(
+		handle: &mut impl PrecompileHandle,
+		contract: Address,
+		selector: BoundedBytes<GetSelectorSizeLimit>,
+	) -> EvmResult<bool> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost().saturating_mul(2))?;
+
+		let selector = Self::parse_selector(selector).in_field("selector")?;
+
+		Ok(pallet_transaction_pauser::Pallet::<Runtime>::is_evm_call_paused(
+			contract.into(),
+			selector.as_ref().map_or(&[][..], |selector| &selector[..]),
+		))
+	}
+
+	fn parse_function_name(function_name: BoundedString<GetNameSizeLimit>) -> Option<Vec<u8>> {
+		let function_name = function_name.into_vec();
+		(!function_name.is_empty()).then_some(function_name)
+	}
+
+	fn parse_expiry(expiry: u32) -> Option<BlockNumberFor<Runtime>> {
+		(expiry != 0).then(|| expiry.into())
+	}
+
+	fn parse_selector(selector: BoundedBytes<GetSelectorSizeLimit>) -> MayRevert<Option<[u8; 4]>> {
+		let selector: Vec<u8> = selector.into_vec();
+		if selector.is_empty() {
+			return Ok(None);
+		}
+		let selector = <[u8; 4]>::try_from(selector)
+			.map_err(|_| RevertReason::custom("Selector must be empty or 4 bytes long"))?;
+		Ok(Some(selector))
+	}
+}
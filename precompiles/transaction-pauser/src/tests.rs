@@ -0,0 +1,614 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::str::from_utf8;
+
+use crate::{mock::*, *};
+
+use pallet_transaction_pauser::{PausedEvmCalls, PausedTransactions};
+use precompile_utils::testing::*;
+use sha3::{Digest, Keccak256};
+
+/// The paused contract.
+const CONTRACT: H160 = H160::repeat_byte(0x11);
+
+fn precompiles() -> Precompiles<Runtime> {
+	Precompiles::new()
+}
+
+fn names_event_data(pallet_name: &str, function_name: &str) -> Vec<u8> {
+	solidity::encode_event_data((
+		BoundedString::<GetNameSizeLimit>::from(pallet_name),
+		BoundedString::<GetNameSizeLimit>::from(function_name),
+	))
+}
+
+fn pause_names_event_data(
+	pallet_name: &str,
+	function_name: &str,
+	expiry: u32,
+	reason: &str,
+) -> Vec<u8> {
+	solidity::encode_event_data((
+		BoundedString::<GetNameSizeLimit>::from(pallet_name),
+		BoundedString::<GetNameSizeLimit>::from(function_name),
+		expiry,
+		BoundedString::<GetReasonSizeLimit>::from(reason),
+	))
+}
+
+fn selector_event_data(selector: &[u8]) -> Vec<u8> {
+	solidity::encode_event_data(BoundedBytes::<GetSelectorSizeLimit>::from(selector))
+}
+
+fn pause_selector_event_data(selector: &[u8], expiry: u32, reason: &str) -> Vec<u8> {
+	solidity::encode_event_data((
+		BoundedBytes::<GetSelectorSizeLimit>::from(selector),
+		expiry,
+		BoundedString::<GetReasonSizeLimit>::from(reason),
+	))
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::pause_transaction_selectors().contains(&0x7d133222));
+	assert!(PCall::unpause_transaction_selectors().contains(&0xd8e50e1e));
+	assert!(PCall::pause_evm_call_selectors().contains(&0x164993a0));
+	assert!(PCall::unpause_evm_call_selectors().contains(&0x612f6197));
+	assert!(PCall::is_transaction_paused_selectors().contains(&0x3328f609));
+	assert!(PCall::is_evm_call_paused_selectors().contains(&0x56906f8d));
+
+	assert_eq!(
+		crate::SELECTOR_LOG_TRANSACTION_PAUSED,
+		&Keccak256::digest(b"TransactionPaused(string,string,uint32,string)")[..]
+	);
+
+	assert_eq!(
+		crate::SELECTOR_LOG_TRANSACTION_UNPAUSED,
+		&Keccak256::digest(b"TransactionUnpaused(string,string)")[..]
+	);
+
+	assert_eq!(
+		crate::SELECTOR_LOG_EVM_CALL_PAUSED,
+		&Keccak256::digest(b"EvmCallPaused(address,bytes,uint32,string)")[..]
+	);
+
+	assert_eq!(
+		crate::SELECTOR_LOG_EVM_CALL_UNPAUSED,
+		&Keccak256::digest(b"EvmCallUnpaused(address,bytes)")[..]
+	);
+}
+
+#[test]
+fn modifiers() {
+	ExtBuilder::default().build().execute_with(|| {
+		let mut tester = PrecompilesModifierTester::new(precompiles(), Alice, Precompile1);
+
+		tester.test_default_modifier(PCall::pause_transaction_selectors());
+		tester.test_default_modifier(PCall::unpause_transaction_selectors());
+		tester.test_default_modifier(PCall::pause_evm_call_selectors());
+		tester.test_default_modifier(PCall::unpause_evm_call_selectors());
+		tester.test_view_modifier(PCall::is_transaction_paused_selectors());
+		tester.test_view_modifier(PCall::is_evm_call_paused_selectors());
+	});
+}
+
+#[test]
+fn pause_transaction_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::pause_transaction {
+					pallet_name: "Balances".into(),
+					function_name: "transfer_allow_death".into(),
+					expiry: 0,
+					reason: "exploit".into(),
+				},
+			)
+			.expect_log(log1(
+				Precompile1,
+				SELECTOR_LOG_TRANSACTION_PAUSED,
+				pause_names_event_data("Balances", "transfer_allow_death", 0, "exploit"),
+			))
+			.execute_returns(());
+
+		precompiles()
+			.prepare_test(
+				Charlie,
+				Precompile1,
+				PCall::is_transaction_paused {
+					pallet_name: "Balances".into(),
+					function_name: "transfer_allow_death".into(),
+				},
+			)
+			.expect_no_logs()
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::unpause_transaction {
+					pallet_name: "Balances".into(),
+					function_name: "transfer_allow_death".into(),
+				},
+			)
+			.expect_log(log1(
+				Precompile1,
+				SELECTOR_LOG_TRANSACTION_UNPAUSED,
+				names_event_data("Balances", "transfer_allow_death"),
+			))
+			.execute_returns(());
+
+		assert!(!PausedTransactions::<Runtime>::contains_key((
+			b"Balances".to_vec(),
+			Some(b"transfer_allow_death".to_vec())
+		)));
+	});
+}
+
+#[test]
+fn pause_whole_pallet_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::pause_transaction {
+					pallet_name: "Balances".into(),
+					function_name: "".into(),
+					expiry: 10,
+					reason: "exploit".into(),
+				},
+			)
+			.expect_log(log1(
+				Precompile1,
+				SELECTOR_LOG_TRANSACTION_PAUSED,
+				pause_names_event_data("Balances", "", 10, "exploit"),
+			))
+			.execute_returns(());
+
+		assert_eq!(
+			PausedTransactions::<Runtime>::get((b"Balances".to_vec(), None::<Vec<u8>>)),
+			Some(Some(10))
+		);
+
+		// every call of the pallet is paused
+		precompiles()
+			.prepare_test(
+				Charlie,
+				Precompile1,
+				PCall::is_transaction_paused {
+					pallet_name: "Balances".into(),
+					function_name: "transfer_keep_alive".into(),
+				},
+			)
+			.expect_no_logs()
+			.execute_returns(true);
+	});
+}
+
+#[test]
+fn pause_transaction_rejects_unknown_call() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::pause_transaction {
+					pallet_name: "Balances".into(),
+					function_name: "transfer".into(),
+					expiry: 0,
+					reason: "exploit".into(),
+				},
+			)
+			.execute_reverts(|output| from_utf8(output).unwrap().contains("UnknownFunctionName"));
+	});
+}
+
+#[test]
+fn pause_transaction_rejects_the_pauser_pallet() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::pause_transaction {
+					pallet_name: "TransactionPauser".into(),
+					function_name: "".into(),
+					expiry: 0,
+					reason: "exploit".into(),
+				},
+			)
+			.execute_reverts(|output| from_utf8(output).unwrap().contains("InvalidPalletName"));
+	});
+}
+
+#[test]
+fn pause_transaction_requires_pauser() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Bob,
+				Precompile1,
+				PCall::pause_transaction {
+					pallet_name: "Balances".into(),
+					function_name: "transfer_allow_death".into(),
+					expiry: 0,
+					reason: "exploit".into(),
+				},
+			)
+			.execute_reverts(|output| from_utf8(output).unwrap().contains("BadOrigin"));
+
+		assert!(!PausedTransactions::<Runtime>::contains_key((
+			b"Balances".to_vec(),
+			Some(b"transfer_allow_death".to_vec())
+		)));
+	});
+}
+
+#[test]
+fn pause_evm_call_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::pause_evm_call {
+					contract: Address(CONTRACT),
+					selector: vec![1u8, 2, 3, 4].into(),
+					expiry: 0,
+					reason: "exploit".into(),
+				},
+			)
+			.expect_log(log2(
+				Precompile1,
+				SELECTOR_LOG_EVM_CALL_PAUSED,
+				CONTRACT,
+				pause_selector_event_data(&[1, 2, 3, 4], 0, "exploit"),
+			))
+			.execute_returns(());
+
+		assert_eq!(PausedEvmCalls::<Runtime>::get((CONTRACT, Some([1u8, 2, 3, 4]))), Some(None));
+
+		precompiles()
+			.prepare_test(
+				Charlie,
+				Precompile1,
+				PCall::is_evm_call_paused {
+					contract: Address(CONTRACT),
+					selector: vec![1u8, 2, 3, 4].into(),
+				},
+			)
+			.expect_no_logs()
+			.execute_returns(true);
+
+		// only the function is paused
+		precompiles()
+			.prepare_test(
+				Charlie,
+				Precompile1,
+				PCall::is_evm_call_paused {
+					contract: Address(CONTRACT),
+					selector: Vec::<u8>::new().into(),
+				},
+			)
+			.expect_no_logs()
+			.execute_returns(false);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::unpause_evm_call {
+					contract: Address(CONTRACT),
+					selector: vec![1u8, 2, 3, 4].into(),
+				},
+			)
+			.expect_log(log2(
+				Precompile1,
+				SELECTOR_LOG_EVM_CALL_UNPAUSED,
+				CONTRACT,
+				selector_event_data(&[1, 2, 3, 4]),
+			))
+			.execute_returns(());
+
+		assert!(!PausedEvmCalls::<Runtime>::contains_key((CONTRACT, Some([1u8, 2, 3, 4]))));
+	});
+}
+
+#[test]
+fn pause_whole_contract_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::pause_evm_call {
+					contract: Address(CONTRACT),
+					selector: Vec::<u8>::new().into(),
+					expiry: 10,
+					reason: "exploit".into(),
+				},
+			)
+			.expect_log(log2(
+				Precompile1,
+				SELECTOR_LOG_EVM_CALL_PAUSED,
+				CONTRACT,
+				pause_selector_event_data(&[], 10, "exploit"),
+			))
+			.execute_returns(());
+
+		assert_eq!(PausedEvmCalls::<Runtime>::get((CONTRACT, None::<[u8; 4]>)), Some(Some(10)));
+
+		// every function of the contract is paused
+		precompiles()
+			.prepare_test(
+				Charlie,
+				Precompile1,
+				PCall::is_evm_call_paused {
+					contract: Address(CONTRACT),
+					selector: vec![5u8, 6, 7, 8].into(),
+				},
+			)
+			.expect_no_logs()
+			.execute_returns(true);
+	});
+}
+
+#[test]
+fn pause_evm_call_requires_pauser() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Bob,
+				Precompile1,
+				PCall::pause_evm_call {
+					contract: Address(CONTRACT),
+					selector: Vec::<u8>::new().into(),
+					expiry: 0,
+					reason: "exploit".into(),
+				},
+			)
+			.execute_reverts(|output| from_utf8(output).unwrap().contains("BadOrigin"));
+
+		assert!(!PausedEvmCalls::<Runtime>::contains_key((CONTRACT, None::<[u8; 4]>)));
+	});
+}
+
+#[test]
+fn pause_evm_call_rejects_invalid_selector() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::pause_evm_call {
+					contract: Address(CONTRACT),
+					selector: vec![1u8, 2].into(),
+					expiry: 0,
+					reason: "exploit".into(),
+				},
+			)
+			.execute_reverts(|output| {
+				output == b"selector: Selector must be empty or 4 bytes long"
+			});
+	});
+}
+
+/// Events as declared in `TransactionPauser.sol`.
+const EVM_CALL_PAUSED_EVENT: &str = "event EvmCallPaused(address indexed contractAddress, bytes \
+                                     selector, uint32 expiry, string reason)";
+const TRANSACTION_PAUSED_EVENT: &str = "event TransactionPaused(string palletName, string \
+                                        functionName, uint32 expiry, string reason)";
+
+/// Runs a call of `target` with `input` from Alice through the EVM.
+fn evm_call(target: impl Into<H160>, input: Vec<u8>) -> fp_evm::CallInfo {
+	use pallet_evm::Runner;
+
+	<Runtime as pallet_evm::Config>::Runner::call(
+		Alice.into(),
+		target.into(),
+		input,
+		sp_core::U256::zero(),
+		1_000_000,
+		None,
+		None,
+		None,
+		Vec::new(),
+		false,
+		false,
+		None,
+		None,
+		<Runtime as pallet_evm::Config>::config(),
+	)
+	.expect("the call is executed")
+}
+
+/// Runs `call` from Alice through the EVM and returns the logs it emitted.
+fn evm_call_logs(call: PCall) -> Vec<pallet_evm::Log> {
+	let info = evm_call(Precompile1, call.into());
+	assert!(info.exit_reason.is_succeed());
+	info.logs
+}
+
+fn decode_log(event: &str, log: &pallet_evm::Log) -> Vec<ethabi_nostd::Token> {
+	let event = ethabi_nostd::Event::parse(event).unwrap();
+	let topics: Vec<ethabi_nostd::H256> = log.topics.iter().map(|t| t.0.into()).collect();
+	event
+		.decode_log(&topics, &log.data)
+		.unwrap()
+		.into_iter()
+		.map(|param| param.value)
+		.collect()
+}
+
+#[test]
+fn log_selectors_match_solidity_events() {
+	let signature = |event: &str| ethabi_nostd::Event::parse(event).unwrap().signature().0;
+
+	assert_eq!(signature(TRANSACTION_PAUSED_EVENT), SELECTOR_LOG_TRANSACTION_PAUSED);
+	assert_eq!(signature(EVM_CALL_PAUSED_EVENT), SELECTOR_LOG_EVM_CALL_PAUSED);
+	assert_eq!(
+		signature("event TransactionUnpaused(string palletName, string functionName)"),
+		SELECTOR_LOG_TRANSACTION_UNPAUSED
+	);
+	assert_eq!(
+		signature("event EvmCallUnpaused(address indexed contractAddress, bytes selector)"),
+		SELECTOR_LOG_EVM_CALL_UNPAUSED
+	);
+}
+
+#[test]
+fn emitted_logs_decode_as_solidity_events() {
+	use ethabi_nostd::Token;
+
+	ExtBuilder::default().build().execute_with(|| {
+		let logs = evm_call_logs(PCall::pause_transaction {
+			pallet_name: "Balances".into(),
+			function_name: "transfer_allow_death".into(),
+			expiry: 7,
+			reason: "exploit".into(),
+		});
+		assert_eq!(logs.len(), 1);
+		assert_eq!(
+			decode_log(TRANSACTION_PAUSED_EVENT, &logs[0]),
+			vec![
+				Token::String(b"Balances".to_vec()),
+				Token::String(b"transfer_allow_death".to_vec()),
+				Token::Uint(7.into()),
+				Token::String(b"exploit".to_vec()),
+			]
+		);
+
+		let logs = evm_call_logs(PCall::pause_evm_call {
+			contract: Address(CONTRACT),
+			selector: vec![1u8, 2, 3, 4].into(),
+			expiry: 0,
+			reason: "exploit".into(),
+		});
+		assert_eq!(logs.len(), 1);
+		assert_eq!(
+			decode_log(EVM_CALL_PAUSED_EVENT, &logs[0]),
+			vec![
+				Token::Address(CONTRACT.0.into()),
+				Token::Bytes(vec![1u8, 2, 3, 4]),
+				Token::Uint(0.into()),
+				Token::String(b"exploit".to_vec()),
+			]
+		);
+	});
+}
+
+/// The selector paused in `CONTRACT`.
+const PAUSED_SELECTOR: [u8; 4] = [1, 2, 3, 4];
+/// A contract forwarding its call data to `CONTRACT`.
+const FORWARDER: H160 = H160::repeat_byte(0x22);
+/// The batch precompile of the mock.
+const BATCH: H160 = H160::from_low_u64_be(2);
+
+/// Bytecode calling `target` with the call data it received, reverting if the call fails.
+fn forwarder_code(target: H160) -> Vec<u8> {
+	let mut code = vec![
+		0x36, 0x60, 0x00, 0x60, 0x00, 0x37, // CALLDATACOPY(0, 0, CALLDATASIZE)
+		0x60, 0x00, 0x60, 0x00, 0x36, 0x60, 0x00, 0x60, 0x00, // out, in and value
+		0x73, // PUSH20 target
+	];
+	code.extend_from_slice(target.as_bytes());
+	code.extend_from_slice(&[
+		0x5a, 0xf1, // CALL with all the gas
+		0x60, 0x2e, 0x57, // JUMPI to the JUMPDEST on success
+		0x60, 0x00, 0x60, 0x00, 0xfd, // REVERT
+		0x5b, 0x00, // JUMPDEST, STOP
+	]);
+	code
+}
+
+fn pause_contract_selector() {
+	evm_call_logs(PCall::pause_evm_call {
+		contract: Address(CONTRACT),
+		selector: PAUSED_SELECTOR.to_vec().into(),
+		expiry: 0,
+		reason: "exploit".into(),
+	});
+}
+
+fn batch_calling_contract(selector: [u8; 4]) -> Vec<u8> {
+	pallet_evm_precompile_batch::BatchPrecompileCall::<Runtime>::batch_all {
+		to: vec![Address(CONTRACT)].into(),
+		value: vec![].into(),
+		call_data: vec![BoundedBytes::from(selector.to_vec())].into(),
+		gas_limit: vec![].into(),
+	}
+	.into()
+}
+
+fn assert_paused(info: fp_evm::CallInfo) {
+	assert_eq!(info.exit_reason, fp_evm::ExitReason::Revert(fp_evm::ExitRevert::Reverted));
+}
+
+#[test]
+fn paused_evm_call_reverts_at_top_level() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert!(evm_call(CONTRACT, PAUSED_SELECTOR.to_vec()).exit_reason.is_succeed());
+
+		pause_contract_selector();
+
+		let info = evm_call(CONTRACT, PAUSED_SELECTOR.to_vec());
+		assert_eq!(info.value, b"EvmCallPaused".to_vec());
+		assert_paused(info);
+		assert!(evm_call(CONTRACT, vec![5u8, 6, 7, 8]).exit_reason.is_succeed());
+	});
+}
+
+#[test]
+fn paused_evm_call_reverts_when_called_by_a_contract() {
+	ExtBuilder::default().build().execute_with(|| {
+		pallet_evm::AccountCodes::<Runtime>::insert(FORWARDER, forwarder_code(CONTRACT));
+		assert!(evm_call(FORWARDER, PAUSED_SELECTOR.to_vec()).exit_reason.is_succeed());
+
+		pause_contract_selector();
+
+		assert_paused(evm_call(FORWARDER, PAUSED_SELECTOR.to_vec()));
+		assert!(evm_call(FORWARDER, vec![5u8, 6, 7, 8]).exit_reason.is_succeed());
+	});
+}
+
+#[test]
+fn paused_evm_call_reverts_in_a_batch() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert!(evm_call(BATCH, batch_calling_contract(PAUSED_SELECTOR))
+			.exit_reason
+			.is_succeed());
+
+		pause_contract_selector();
+
+		let info = evm_call(BATCH, batch_calling_contract(PAUSED_SELECTOR));
+		assert_eq!(info.value, b"EvmCallPaused".to_vec());
+		assert_paused(info);
+		assert!(evm_call(BATCH, batch_calling_contract([5, 6, 7, 8])).exit_reason.is_succeed());
+
+		evm_call_logs(PCall::unpause_evm_call {
+			contract: Address(CONTRACT),
+			selector: PAUSED_SELECTOR.to_vec().into(),
+		});
+		assert!(evm_call(BATCH, batch_calling_contract(PAUSED_SELECTOR))
+			.exit_reason
+			.is_succeed());
+	});
+}
@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The XcmTransactor contract's address.
+address constant XCM_TRANSACTOR_ADDRESS = 0x0000000000000000000000000000000000000813;
+
+/// @dev The XcmTransactor contract's instance.
+XcmTransactor constant XCM_TRANSACTOR_CONTRACT = XcmTransactor(XCM_TRANSACTOR_ADDRESS);
+
+/// @title XCM transactor interface
+/// @dev Sends XCM messages from the calling contract: reserve transfers to sibling parachains,
+/// and remote calls dispatched by the account derived from the caller on the destination.
+interface XcmTransactor {
+    /// @dev An XCM location, as `parents` followed by the SCALE encoded junctions of its interior.
+    struct Location {
+        uint8 parents;
+        bytes[] interior;
+    }
+
+    /// @dev Returns the address of the account of a location on this chain.
+    /// @custom:selector a008cd8a
+    /// @param location The location of the account.
+    function locationToAddress(Location memory location)
+        external
+        view
+        returns (address);
+
+    /// @dev Reserve transfers an asset to a beneficiary on a sibling parachain.
+    /// @custom:selector 18f25e1b
+    /// @param paraId The id of the sibling parachain.
+    /// @param beneficiary The location of the beneficiary, as seen by the sibling.
+    /// @param asset The location of the transferred asset, as seen by this chain.
+    /// @param amount The transferred amount, which also pays the fees on the sibling.
+    /// @param weight The ref time bought on the sibling, uint64 max for no limit.
+    function transferToSibling(
+        uint32 paraId,
+        Location memory beneficiary,
+        Location memory asset,
+        uint256 amount,
+        uint64 weight
+    ) external;
+
+    /// @dev Dispatches a call on a sibling parachain from the account derived there from the caller.
+    /// The derived account pays the fees, and receives those left over.
+    /// @custom:selector eefd0974
+    /// @param paraId The id of the sibling parachain.
+    /// @param feeAsset The location of the asset paying the fees, as seen by this chain.
+    /// @param feeAmount The amount of fees withdrawn from the derived account.
+    /// @param weight The ref time bought on the sibling, uint64 max for no limit.
+    /// @param call The SCALE encoded call dispatched on the sibling.
+    /// @param transactWeight The maximum ref time of the call.
+    function transactThroughDerived(
+        uint32 paraId,
+        Location memory feeAsset,
+        uint256 feeAmount,
+        uint64 weight,
+        bytes memory call,
+        uint64 transactWeight
+    ) external;
+}
@@ -0,0 +1,177 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile letting EVM contracts send XCM messages: reserve transfers to sibling parachains,
+//! and remote `Transact` through the account derived from the contract on the destination.
+//!
+//! The messages are sent by `pallet_xcm` on behalf of the caller, through the XCM router of the
+//! runtime.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use fp_evm::PrecompileHandle;
+use frame_support::{
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	traits::{ConstU32, EnsureOrigin, Get},
+	weights::Weight,
+};
+use pallet_evm::AddressMapping;
+use precompile_utils::prelude::*;
+use sp_core::{H160, U256};
+use sp_runtime::traits::Dispatchable;
+use sp_std::{boxed::Box, marker::PhantomData, vec};
+use xcm::latest::prelude::*;
+use xcm_executor::traits::ConvertLocation;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The maximum size of the encoded call sent in `Transact`.
+pub const CALL_DATA_LIMIT: u32 = 2u32.pow(16);
+type GetCallDataLimit = ConstU32<CALL_DATA_LIMIT>;
+
+/// The proof size bought along with the weights given in ref time by the callers.
+pub const DEFAULT_PROOF_SIZE: u64 = 256 * 1024;
+
+/// Precompile sending the XCM messages of EVM contracts through `pallet_xcm`. The accounts of
+/// locations are given by `LocationToAccountId`.
+pub struct XcmTransactorPrecompile<Runtime, LocationToAccountId>(
+	PhantomData<(Runtime, LocationToAccountId)>,
+);
+
+#[precompile_utils::precompile]
+impl<Runtime, LocationToAccountId> XcmTransactorPrecompile<Runtime, LocationToAccountId>
+where
+	Runtime: pallet_evm::Config + pallet_xcm::Config + frame_system::Config,
+	<Runtime as frame_system::Config>::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo>
+		+ GetDispatchInfo
+		+ From<pallet_xcm::Call<Runtime>>,
+	<<Runtime as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
+		From<Option<Runtime::AccountId>>,
+	Runtime::AccountId: Into<H160>,
+	LocationToAccountId: ConvertLocation<Runtime::AccountId>,
+{
+	/// Returns the address of the account of `location` on this chain.
+	#[precompile::public("locationToAddress((uint8,bytes[]))")]
+	#[precompile::view]
+	fn location_to_address(
+		_handle: &mut impl PrecompileHandle,
+		location: Location,
+	) -> EvmResult<Address> {
+		let account = LocationToAccountId::convert_location(&location)
+			.ok_or_else(|| revert("location has no account"))?;
+
+		Ok(Address(account.into()))
+	}
+
+	/// Transfers `amount` of the asset at `asset` to `beneficiary` on the sibling parachain
+	/// `paraId`, which executes the transfer with `weight` paid in the transferred asset.
+	#[precompile::public(
+		"transferToSibling(uint32,(uint8,bytes[]),(uint8,bytes[]),uint256,uint64)"
+	)]
+	fn transfer_to_sibling(
+		handle: &mut impl PrecompileHandle,
+		para_id: u32,
+		beneficiary: Location,
+		asset: Location,
+		amount: U256,
+		weight: u64,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let amount = Self::u256_to_amount(amount).in_field("amount")?;
+
+		let call = pallet_xcm::Call::<Runtime>::limited_reserve_transfer_assets {
+			dest: Box::new(Location::new(1, [Parachain(para_id)]).into()),
+			beneficiary: Box::new(beneficiary.into()),
+			assets: Box::new(Asset::from((asset, amount)).into()),
+			fee_asset_item: 0,
+			weight_limit: Self::weight_limit(weight),
+		};
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		Ok(())
+	}
+
+	/// Dispatches `call` on the sibling parachain `paraId` from the account derived there from the
+	/// caller. The derived account pays `weight` with `feeAmount` of the asset at `feeAsset`, and
+	/// receives the fees left over.
+	#[precompile::public(
+		"transactThroughDerived(uint32,(uint8,bytes[]),uint256,uint64,bytes,uint64)"
+	)]
+	fn transact_through_derived(
+		handle: &mut impl PrecompileHandle,
+		para_id: u32,
+		fee_asset: Location,
+		fee_amount: U256,
+		weight: u64,
+		call: BoundedBytes<GetCallDataLimit>,
+		transact_weight: u64,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let fee_amount = Self::u256_to_amount(fee_amount).in_field("feeAmount")?;
+
+		let dest = Location::new(1, [Parachain(para_id)]);
+		let universal_location = <Runtime as pallet_xcm::Config>::UniversalLocation::get();
+		// Locations as seen by the destination
+		let fees: Asset = (fee_asset, fee_amount).into();
+		let fees = fees
+			.reanchored(&dest, &universal_location)
+			.map_err(|_| RevertReason::custom("cannot be reanchored").in_field("feeAsset"))?;
+		let derived_location = <Runtime as pallet_xcm::Config>::SendXcmOrigin::try_origin(
+			frame_system::RawOrigin::Signed(origin.clone()).into(),
+		)
+		.map_err(|_| revert("caller has no location"))?
+		.reanchored(&dest, &universal_location)
+		.map_err(|_| revert("cannot reanchor caller location"))?;
+
+		let message = Xcm(vec![
+			WithdrawAsset(fees.clone().into()),
+			BuyExecution { fees, weight_limit: Self::weight_limit(weight) },
+			Transact {
+				origin_kind: OriginKind::SovereignAccount,
+				require_weight_at_most: Weight::from_parts(transact_weight, DEFAULT_PROOF_SIZE),
+				call: call.into_vec().into(),
+			},
+			RefundSurplus,
+			DepositAsset { assets: Wild(AllCounted(1)), beneficiary: derived_location },
+		]);
+
+		let call = pallet_xcm::Call::<Runtime>::send {
+			dest: Box::new(dest.into()),
+			message: Box::new(VersionedXcm::from(message)),
+		};
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+		Ok(())
+	}
+
+	/// Weight limits are given in ref time, `u64::MAX` standing for no limit.
+	fn weight_limit(weight: u64) -> WeightLimit {
+		if weight == u64::MAX {
+			WeightLimit::Unlimited
+		} else {
+			WeightLimit::Limited(Weight::from_parts(weight, DEFAULT_PROOF_SIZE))
+		}
+	}
+
+	fn u256_to_amount(value: U256) -> MayRevert<u128> {
+		value.try_into().map_err(|_| RevertReason::value_is_too_large("u128").into())
+	}
+}
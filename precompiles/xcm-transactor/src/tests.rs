@@ -0,0 +1,213 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::str::from_utf8;
+
+use crate::{mock::*, *};
+
+use precompile_utils::testing::*;
+use sp_core::{H160, U256};
+
+fn precompiles() -> Precompiles<Runtime> {
+	PrecompilesValue::get()
+}
+
+fn sibling() -> Location {
+	Location::new(1, [Parachain(SIBLING_PARA_ID)])
+}
+
+/// The location of `account` on this chain.
+fn account_key(account: impl Into<H160>) -> Junction {
+	AccountKey20 { network: None, key: account.into().0 }
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::location_to_address_selectors().contains(&0xa008cd8a));
+	assert!(PCall::transfer_to_sibling_selectors().contains(&0x18f25e1b));
+	assert!(PCall::transact_through_derived_selectors().contains(&0xeefd0974));
+}
+
+#[test]
+fn modifiers() {
+	ExtBuilder::default().build().execute_with(|| {
+		let mut tester = PrecompilesModifierTester::new(precompiles(), Alice, Precompile1);
+
+		tester.test_view_modifier(PCall::location_to_address_selectors());
+		tester.test_default_modifier(PCall::transfer_to_sibling_selectors());
+		tester.test_default_modifier(PCall::transact_through_derived_selectors());
+	});
+}
+
+#[test]
+fn location_to_address_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(Alice, Precompile1, PCall::location_to_address { location: sibling() })
+			.expect_no_logs()
+			.execute_returns(Address(sibling_account(SIBLING_PARA_ID).into()));
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::location_to_address { location: Location::new(0, [account_key(Bob)]) },
+			)
+			.expect_no_logs()
+			.execute_returns(Address(Bob.into()));
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::location_to_address { location: Location::new(2, [Parachain(1)]) },
+			)
+			.execute_reverts(|output| output == b"location has no account");
+	});
+}
+
+#[test]
+fn transfer_to_sibling_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			let beneficiary = Location::new(0, [account_key(Bob)]);
+
+			precompiles()
+				.prepare_test(
+					Alice,
+					Precompile1,
+					PCall::transfer_to_sibling {
+						para_id: SIBLING_PARA_ID,
+						beneficiary: beneficiary.clone(),
+						asset: Location::here(),
+						amount: 400.into(),
+						weight: u64::MAX,
+					},
+				)
+				.expect_no_logs()
+				.execute_returns(());
+
+			// The reserve of the sibling is kept in its account
+			assert_eq!(Balances::free_balance(AccountId::from(Alice)), 600);
+			assert_eq!(Balances::free_balance(sibling_account(SIBLING_PARA_ID)), 400);
+
+			let sent = sent_xcm();
+			assert_eq!(sent.len(), 1);
+			let (dest, message) = &sent[0];
+			assert_eq!(dest, &sibling());
+			let deposited: Assets =
+				Asset::from((Location::new(1, [Parachain(PARA_ID)]), 400)).into();
+			assert!(matches!(
+				message.0.first(),
+				Some(ReserveAssetDeposited(assets)) if assets == &deposited
+			));
+			assert!(matches!(
+				message.0.last(),
+				Some(DepositAsset { beneficiary: to, .. }) if to == &beneficiary
+			));
+		});
+}
+
+#[test]
+fn transfer_to_sibling_fails_without_funds() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			precompiles()
+				.prepare_test(
+					Alice,
+					Precompile1,
+					PCall::transfer_to_sibling {
+						para_id: SIBLING_PARA_ID,
+						beneficiary: Location::new(0, [account_key(Bob)]),
+						asset: Location::here(),
+						amount: 2000.into(),
+						weight: u64::MAX,
+					},
+				)
+				.execute_reverts(|output| {
+					from_utf8(&output).unwrap().contains("Dispatched call failed with error: ")
+				});
+
+			precompiles()
+				.prepare_test(
+					Alice,
+					Precompile1,
+					PCall::transfer_to_sibling {
+						para_id: SIBLING_PARA_ID,
+						beneficiary: Location::new(0, [account_key(Bob)]),
+						asset: Location::here(),
+						amount: U256::MAX,
+						weight: u64::MAX,
+					},
+				)
+				.execute_reverts(|output| from_utf8(&output).unwrap().contains("amount"));
+
+			assert_eq!(Balances::free_balance(AccountId::from(Alice)), 1000);
+			assert!(sent_xcm().is_empty());
+		});
+}
+
+#[test]
+fn transact_through_derived_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let call = vec![10u8, 0, 42];
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				Precompile1,
+				PCall::transact_through_derived {
+					para_id: SIBLING_PARA_ID,
+					fee_asset: Location::here(),
+					fee_amount: 50.into(),
+					weight: 4_000,
+					call: call.clone().into(),
+					transact_weight: 1_000,
+				},
+			)
+			.expect_no_logs()
+			.execute_returns(());
+
+		// Fees and the derived account as seen by the sibling
+		let fees: Asset = (Location::new(1, [Parachain(PARA_ID)]), 50).into();
+		let derived = Location::new(1, [Parachain(PARA_ID), account_key(Alice)]);
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				sibling(),
+				Xcm(vec![
+					DescendOrigin(account_key(Alice).into()),
+					WithdrawAsset(fees.clone().into()),
+					BuyExecution {
+						fees,
+						weight_limit: Limited(Weight::from_parts(4_000, DEFAULT_PROOF_SIZE)),
+					},
+					Transact {
+						origin_kind: OriginKind::SovereignAccount,
+						require_weight_at_most: Weight::from_parts(1_000, DEFAULT_PROOF_SIZE),
+						call: call.into(),
+					},
+					RefundSurplus,
+					DepositAsset { assets: Wild(AllCounted(1)), beneficiary: derived },
+				])
+			)]
+		);
+	});
+}
@@ -41,6 +41,13 @@ pub trait EnsureProxy<AccountId> {
 	fn ensure_ok(delegator: AccountId, delegatee: AccountId) -> Result<(), &'static str>;
 }
 
+/// Denies every proxy, for runtimes without proxies.
+impl<AccountId> EnsureProxy<AccountId> for () {
+	fn ensure_ok(_delegator: AccountId, _delegatee: AccountId) -> Result<(), &'static str> {
+		Err("proxies are not supported")
+	}
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
 /// Manually sets a gas fee.
 pub struct ManualEthereumXcmFee {
@@ -0,0 +1,101 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fee handlers shared by the Ferrum runtimes.
+
+use frame_support::traits::{Currency, Get, Imbalance, OnUnbalanced};
+use pallet_balances::NegativeImbalance;
+use sp_runtime::{traits::Saturating, Percent};
+
+/// Logic for the author to get a portion of fees.
+pub struct ToAuthor<R>(sp_std::marker::PhantomData<R>);
+impl<R> OnUnbalanced<NegativeImbalance<R>> for ToAuthor<R>
+where
+	R: pallet_balances::Config + pallet_authorship::Config,
+	<R as frame_system::Config>::RuntimeEvent: From<pallet_balances::Event<R>>,
+{
+	fn on_nonzero_unbalanced(amount: NegativeImbalance<R>) {
+		if let Some(author) = <pallet_authorship::Pallet<R>>::author() {
+			<pallet_balances::Pallet<R>>::resolve_creating(&author, amount);
+		}
+	}
+}
+
+/// Split of fees among the treasury, burn and the block author.
+#[derive(Clone, Copy, PartialEq, Eq, sp_runtime::RuntimeDebug)]
+pub struct FeeSplit {
+	/// Share of the fees going to the treasury.
+	pub treasury: Percent,
+	/// Share of the fees burnt.
+	pub burn: Percent,
+}
+
+impl FeeSplit {
+	/// Split giving the treasury and burn the given percentages of the fees. Used in a constant,
+	/// it fails to compile if the shares add up to more than 100%.
+	pub const fn new(treasury: u8, burn: u8) -> Self {
+		assert!(treasury as u16 + burn as u16 <= 100, "fee split shares exceed 100%");
+		FeeSplit { treasury: Percent::from_percent(treasury), burn: Percent::from_percent(burn) }
+	}
+
+	/// Share of the fees going to the block author: what is left of the treasury and burn shares.
+	pub fn author(&self) -> Percent {
+		Percent::from_percent(100)
+			.saturating_sub(self.treasury)
+			.saturating_sub(self.burn)
+	}
+}
+
+/// Splits fees among the treasury, burn and the block author according to `Split`.
+pub struct SplitFees<R, Split>(sp_std::marker::PhantomData<(R, Split)>);
+impl<R, Split> OnUnbalanced<NegativeImbalance<R>> for SplitFees<R, Split>
+where
+	R: pallet_balances::Config + pallet_authorship::Config + pallet_treasury::Config,
+	pallet_treasury::Pallet<R>: OnUnbalanced<NegativeImbalance<R>>,
+	<R as frame_system::Config>::RuntimeEvent: From<pallet_balances::Event<R>>,
+	Split: Get<FeeSplit>,
+{
+	fn on_nonzero_unbalanced(amount: NegativeImbalance<R>) {
+		let split = Split::get();
+		let total = amount.peek();
+		let (to_treasury, rest) = amount.split(split.treasury * total);
+		// the burnt share is dropped, which reduces the total issuance
+		let (_to_burn, to_author) = rest.split(split.burn * total);
+
+		<pallet_treasury::Pallet<R> as OnUnbalanced<_>>::on_unbalanced(to_treasury);
+		<ToAuthor<R> as OnUnbalanced<_>>::on_unbalanced(to_author);
+	}
+}
+
+/// Splits transaction fees according to `Split`, tips going entirely to the block author.
+pub struct DealWithFees<R, Split>(sp_std::marker::PhantomData<(R, Split)>);
+impl<R, Split> OnUnbalanced<NegativeImbalance<R>> for DealWithFees<R, Split>
+where
+	R: pallet_balances::Config + pallet_authorship::Config + pallet_treasury::Config,
+	pallet_treasury::Pallet<R>: OnUnbalanced<NegativeImbalance<R>>,
+	<R as frame_system::Config>::RuntimeEvent: From<pallet_balances::Event<R>>,
+	Split: Get<FeeSplit>,
+{
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance<R>>) {
+		if let Some(fees) = fees_then_tips.next() {
+			<SplitFees<R, Split> as OnUnbalanced<_>>::on_unbalanced(fees);
+			if let Some(tips) = fees_then_tips.next() {
+				// for tips, if any, 100% to author
+				<ToAuthor<R> as OnUnbalanced<_>>::on_unbalanced(tips);
+			}
+		}
+	}
+}
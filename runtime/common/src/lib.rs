@@ -0,0 +1,56 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Ferrum Runtime Common
+//!
+//! Configuration shared by the Ferrum runtimes, which only differ by their version, the constants
+//! of their network and their governance.
+//!
+//! The pallet configs and runtime APIs are implemented for the `Runtime` of each runtime crate,
+//! so they are provided as macros expanded in the runtime crates:
+//! - [`impl_runtime_common`] implements the types, constants and pallet configs, along with the
+//!   `xcm_config` module ([`impl_xcm_config`]) and the `precompiles` module ([`impl_precompiles`]);
+//! - [`impl_runtime_apis_plus_common`] implements the runtime APIs.
+//!
+//! A runtime crate defines the constants of its network, its `VERSION` and the `Runtime` with
+//! `construct_runtime!`, and expands the macros at its root. It also provides a `governance`
+//! module with the `GeneralAdminOrigin`, `PauserOrigin`, `FinalizerAdminOrigin`, `TreasurerOrigin`
+//! and `TreasurySpendOrigin` used by the shared pallet configs, the `GovernanceCalls` allowed to
+//! the governance proxies and the `UnpausablePallets` of the transaction pauser, and the
+//! `Migrations` run by the `Executive` on runtime upgrade. Its
+//! `constants` also set the split of the fees among the treasury, burn and the collator:
+//!
+//! ```ignore
+//! pub mod constants;
+//! pub mod governance;
+//!
+//! pub type Migrations = ();
+//!
+//! ferrum_runtime_common::impl_runtime_common!(constants);
+//!
+//! construct_runtime!(/* ... */);
+//!
+//! ferrum_runtime_common::impl_runtime_apis_plus_common! {}
+//! ```
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod apis;
+pub mod impls;
+mod mock;
+mod precompiles;
+mod runtime;
+mod tests;
+mod xcm_config;
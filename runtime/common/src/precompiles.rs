@@ -0,0 +1,269 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+/// Implements the precompiles of the Ferrum runtimes.
+///
+/// It is expanded in the `precompiles` module of a runtime crate, by [`impl_runtime_common`].
+#[macro_export]
+macro_rules! impl_precompiles {
+	() => {
+		use crate::{
+			AccountId, AssetId, ForeignAssetInstance, LocalAssetInstance, ProxyType, Runtime,
+		};
+		use frame_support::{parameter_types, traits::Get};
+		use pallet_evm_precompile_assets_erc20::{
+			AccountIdAssetIdConversion, Erc20AssetsPrecompileSet,
+		};
+		use pallet_evm_precompile_balances_erc20::{Erc20BalancesPrecompile, Erc20Metadata};
+		use pallet_evm_precompile_batch::BatchPrecompile;
+		use pallet_evm_precompile_blake2::Blake2F;
+		use pallet_evm_precompile_bn128::{Bn128Add, Bn128Mul, Bn128Pairing};
+		use pallet_evm_precompile_modexp::Modexp;
+		use pallet_evm_precompile_multisig::MultisigPrecompile;
+		use pallet_evm_precompile_proxy::{EvmProxyCallFilter, EvmSubCall, ProxyPrecompile};
+		use pallet_evm_precompile_quantum_portal::{
+			is_submit_signature_call, QuantumPortalPrecompile,
+		};
+		use pallet_evm_precompile_sha3fips::Sha3FIPS256;
+		use pallet_evm_precompile_simple::{
+			ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256,
+		};
+		use pallet_evm_precompile_transaction_pauser::TransactionPauserPrecompile;
+		use pallet_evm_precompile_xcm_transactor::XcmTransactorPrecompile;
+		use precompile_utils::{precompile_set::*, EvmResult};
+		use sp_core::H160;
+		use sp_std::{marker::PhantomData, prelude::*};
+
+		// use crate::QuantumPortal;
+
+		pub struct NativeErc20Metadata;
+
+		/// ERC20 metadata for the native token.
+		impl Erc20Metadata for NativeErc20Metadata {
+			/// Returns the name of the token.
+			fn name() -> &'static str {
+				"FRM Token"
+			}
+
+			/// Returns the symbol of the token.
+			fn symbol() -> &'static str {
+				"FRM"
+			}
+
+			/// Returns the decimals places of the token.
+			fn decimals() -> u8 {
+				18
+			}
+
+			/// Must return `true` only if it represents the main native currency of
+			/// the network. It must be the currency used in `pallet_evm`.
+			fn is_native_currency() -> bool {
+				true
+			}
+		}
+
+		/// The asset precompile address prefix. Addresses that match against this prefix will be
+		/// routed to Erc20AssetsPrecompileSet being marked as foreign
+		pub const FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX: &[u8] = &[255u8; 4];
+		/// The asset precompile address prefix. Addresses that match against this prefix will be
+		/// routed to Erc20AssetsPrecompileSet being marked as local
+		pub const LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX: &[u8] = &[255u8, 255u8, 255u8, 254u8];
+
+		parameter_types! {
+			pub ForeignAssetPrefix: &'static [u8] = FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX;
+			pub LocalAssetPrefix: &'static [u8] = LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX;
+		}
+
+		/// Revert bytecode (`PUSH1 0x00 PUSH1 0x00 REVERT`) deployed at the address of each asset
+		/// precompile, so that Solidity's `extcodesize` checks accept calls to them.
+		const ASSET_PRECOMPILE_BYTECODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+		/// The address of an asset precompile is its prefix followed by the asset id in big endian.
+		impl AccountIdAssetIdConversion<AccountId, AssetId> for Runtime {
+			fn account_to_asset_id(account: AccountId) -> Option<(Vec<u8>, AssetId)> {
+				let address: H160 = account.into();
+				let (prefix, asset_id) = address.as_bytes().split_at(4);
+				if prefix != FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX &&
+					prefix != LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX
+				{
+					return None;
+				}
+				let asset_id: [u8; 16] = asset_id.try_into().ok()?;
+				Some((prefix.to_vec(), AssetId::from_be_bytes(asset_id)))
+			}
+
+			fn asset_id_to_account(prefix: &[u8], asset_id: AssetId) -> AccountId {
+				let mut address = [0u8; 20];
+				address[0..4].copy_from_slice(prefix);
+				address[4..20].copy_from_slice(&asset_id.to_be_bytes());
+				H160::from(address).into()
+			}
+		}
+
+		/// Deploys [`ASSET_PRECOMPILE_BYTECODE`] at the precompile address of the assets created in
+		/// a `pallet_assets` instance, and removes it when they are destroyed.
+		pub struct AssetPrecompileCode<Prefix>(PhantomData<Prefix>);
+
+		impl<Prefix: Get<&'static [u8]>> AssetPrecompileCode<Prefix> {
+			fn address(asset_id: &AssetId) -> H160 {
+				Runtime::asset_id_to_account(Prefix::get(), *asset_id).into()
+			}
+		}
+
+		impl<Prefix: Get<&'static [u8]>> pallet_assets::AssetsCallback<AssetId, AccountId>
+			for AssetPrecompileCode<Prefix>
+		{
+			fn created(asset_id: &AssetId, _owner: &AccountId) -> Result<(), ()> {
+				pallet_evm::AccountCodes::<Runtime>::insert(
+					Self::address(asset_id),
+					ASSET_PRECOMPILE_BYTECODE.to_vec(),
+				);
+				Ok(())
+			}
+
+			fn destroyed(asset_id: &AssetId) -> Result<(), ()> {
+				let address = Self::address(asset_id);
+				pallet_evm::AccountCodes::<Runtime>::remove(address);
+				pallet_evm::AccountCodesMetadata::<Runtime>::remove(address);
+				Ok(())
+			}
+		}
+
+		/// The address of the Quantum Portal precompile.
+		pub const QUANTUM_PORTAL_PRECOMPILE_ADDRESS: u64 = 2066;
+
+		/// The EVM calls the proxies of each type can make through the proxy precompile.
+		impl EvmProxyCallFilter for ProxyType {
+			fn is_evm_proxy_call_allowed(
+				&self,
+				call: &EvmSubCall,
+				recipient_has_code: bool,
+				gas: u64,
+			) -> EvmResult<bool> {
+				Ok(match self {
+					ProxyType::Any => true,
+					// plain transfers to accounts
+					ProxyType::Balances =>
+						!recipient_has_code && !is_precompile_or_fail::<Runtime>(call.to.0, gas)?,
+					// governance is only reachable through substrate calls
+					ProxyType::Governance => false,
+					// only the submission of finalizer signatures
+					ProxyType::Finalizer =>
+						call.value.is_zero() &&
+							call.to.0 == H160::from_low_u64_be(QUANTUM_PORTAL_PRECOMPILE_ADDRESS) &&
+							is_submit_signature_call(call.call_data.as_bytes()),
+				})
+			}
+		}
+
+		type EthereumPrecompilesChecks =
+			(AcceptDelegateCall, CallableByContract, CallableByPrecompile);
+
+		/// The PrecompileSet installed in the Ferrum runtime.
+		/// We include the nine Istanbul precompiles
+		/// (https://github.com/ethereum/go-ethereum/blob/3c46f557/core/vm/contracts.go#L69)
+		/// as well as a special precompile for dispatching Substrate extrinsics
+		/// The following distribution has been decided for the precompiles
+		/// 0-1023: Ethereum Mainnet Precompiles
+		/// 1024-2047 Precompiles that are not in Ethereum Mainnet but are neither Ferrum specific
+		/// 2048-4095 Ferrum specific precompiles
+		/// Assets are exposed as ERC20 at addresses starting with
+		/// `FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX` (foreign assets) or
+		/// `LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX` (local assets).
+		pub type FrontierPrecompiles<R> = PrecompileSetBuilder<
+			R,
+			(
+				// Skip precompiles if out of range.
+				PrecompilesInRangeInclusive<
+					(AddressU64<1>, AddressU64<4095>),
+					(
+						// Ethereum precompiles:
+						// We allow DELEGATECALL to stay compliant with Ethereum behavior.
+						PrecompileAt<AddressU64<1>, ECRecover, EthereumPrecompilesChecks>,
+						PrecompileAt<AddressU64<2>, Sha256, EthereumPrecompilesChecks>,
+						PrecompileAt<AddressU64<3>, Ripemd160, EthereumPrecompilesChecks>,
+						PrecompileAt<AddressU64<4>, Identity, EthereumPrecompilesChecks>,
+						PrecompileAt<AddressU64<5>, Modexp, EthereumPrecompilesChecks>,
+						PrecompileAt<AddressU64<6>, Bn128Add, EthereumPrecompilesChecks>,
+						PrecompileAt<AddressU64<7>, Bn128Mul, EthereumPrecompilesChecks>,
+						PrecompileAt<AddressU64<8>, Bn128Pairing, EthereumPrecompilesChecks>,
+						PrecompileAt<AddressU64<9>, Blake2F, EthereumPrecompilesChecks>,
+						// Non-Ferrum specific nor Ethereum precompiles :
+						PrecompileAt<
+							AddressU64<1024>,
+							Sha3FIPS256,
+							(CallableByContract, CallableByPrecompile),
+						>,
+						// PrecompileAt<AddressU64<1025>, Dispatch<R>>,
+						PrecompileAt<
+							AddressU64<1026>,
+							ECRecoverPublicKey,
+							(CallableByContract, CallableByPrecompile),
+						>,
+						// Ferrum specific precompiles:
+						PrecompileAt<
+							AddressU64<2050>,
+							Erc20BalancesPrecompile<R, NativeErc20Metadata>,
+							(CallableByContract, CallableByPrecompile),
+						>,
+						// Ferrum specific precompiles:
+						PrecompileAt<
+							AddressU64<{ QUANTUM_PORTAL_PRECOMPILE_ADDRESS }>,
+							QuantumPortalPrecompile<R>,
+							(CallableByContract, CallableByPrecompile),
+						>,
+						PrecompileAt<
+							AddressU64<2067>,
+							XcmTransactorPrecompile<R, crate::xcm_config::LocationToAccountId>,
+							(CallableByContract, CallableByPrecompile),
+						>,
+						PrecompileAt<
+							AddressU64<2068>,
+							BatchPrecompile<R>,
+							(SubcallWithMaxNesting<2>, CallableByContract),
+						>,
+						PrecompileAt<
+							AddressU64<2069>,
+							ProxyPrecompile<R>,
+							SubcallWithMaxNesting<0>,
+						>,
+						PrecompileAt<
+							AddressU64<2070>,
+							MultisigPrecompile<R>,
+							(CallableByContract, CallableByPrecompile),
+						>,
+						PrecompileAt<
+							AddressU64<2071>,
+							TransactionPauserPrecompile<R>,
+							(CallableByContract, CallableByPrecompile),
+						>,
+					),
+				>,
+				// Prefixed precompile sets, one ERC20 per asset
+				PrecompileSetStartingWith<
+					ForeignAssetPrefix,
+					Erc20AssetsPrecompileSet<R, ForeignAssetInstance>,
+					(CallableByContract, CallableByPrecompile),
+				>,
+				PrecompileSetStartingWith<
+					LocalAssetPrefix,
+					Erc20AssetsPrecompileSet<R, LocalAssetInstance>,
+					(CallableByContract, CallableByPrecompile),
+				>,
+			),
+		>;
+	};
+}
@@ -0,0 +1,1123 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+/// Implements the types, constants and pallet configs shared by the Ferrum runtimes, along with
+/// their `xcm_config` and `precompiles` modules.
+///
+/// It is expanded at the root of a runtime crate, next to the `Runtime` built by
+/// `construct_runtime!` and its `VERSION`. `$constants` is the module of the constants of the
+/// network of the runtime, defining:
+/// - `CHAIN_ID`: the EVM chain id.
+#[macro_export]
+macro_rules! impl_runtime_common {
+	($constants:ident) => {
+		pub use $crate::impls;
+
+		/// The XCM configuration of the runtime.
+		pub mod xcm_config {
+			$crate::impl_xcm_config!();
+		}
+
+		/// The precompiles of the runtime.
+		mod precompiles {
+			$crate::impl_precompiles!();
+		}
+
+		use crate::governance::{
+			FinalizerAdminOrigin, GeneralAdminOrigin, GovernanceCalls, PauserOrigin,
+			TreasurerOrigin, TreasurySpendOrigin, UnpausablePallets,
+		};
+		use $crate::impls::{DealWithFees, FeeSplit, SplitFees};
+		use codec::{Decode, Encode, MaxEncodedLen};
+		use cumulus_pallet_parachain_system::{
+			RelayNumberMonotonicallyIncreases, RelayNumberStrictlyIncreases,
+		};
+		use cumulus_primitives_core::{AggregateMessageOrigin, ParaId};
+		use ferrum_primitives::xcm_primitives::EnsureProxy;
+		pub use ferrum_primitives::*;
+		use fp_evm::weight_per_gas;
+		use fp_rpc::TransactionStatus;
+		use frame_support::{
+			construct_runtime, derive_impl,
+			dispatch::DispatchClass,
+			pallet_prelude::TransactionValidityError,
+			parameter_types,
+			traits::{
+				tokens::{PayFromAccount, UnityAssetBalanceConversion},
+				AsEnsureOriginWithArg, ConstU32, ConstU64, ConstU8, Contains, EitherOfDiverse,
+				Everything, FindAuthor, InstanceFilter, Nothing, OnFinalize, PalletInfoAccess,
+				TransformOrigin,
+			},
+			weights::{constants::RocksDbWeight, ConstantMultiplier, Weight},
+			ConsensusEngineId, PalletId,
+		};
+		use frame_system::{
+			limits::{BlockLength, BlockWeights},
+			EnsureNever,
+		};
+		use pallet_balances::NegativeImbalance;
+		use pallet_ethereum::{Call::transact, PostLogContent, Transaction as EthereumTransaction};
+		use pallet_evm::{
+			Account as EVMAccount, EVMCurrencyAdapter, EnsureAddressNever, EnsureAddressRoot,
+			FeeCalculator, GasWeightMapping, Runner,
+		};
+		use parachains_common::message_queue::{NarrowOriginToSibling, ParaIdToSibling};
+		pub use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+		use sp_core::{
+			crypto::{ByteArray, KeyTypeId},
+			OpaqueMetadata, H160, H256, U256,
+		};
+		use sp_runtime::{
+			create_runtime_str, generic, impl_opaque_keys,
+			traits::{
+				AccountIdConversion, BlakeTwo256, Block as BlockT, ConstBool, DispatchInfoOf,
+				Dispatchable, Get, IdentityLookup, PostDispatchInfoOf,
+			},
+			transaction_validity::{
+				InvalidTransaction, TransactionLongevity, TransactionPriority, TransactionSource,
+				TransactionValidity,
+			},
+			ApplyExtrinsicResult, DispatchResult, ExtrinsicInclusionMode, RuntimeDebug,
+		};
+		pub use sp_runtime::{MultiAddress, Perbill, Permill};
+		use sp_std::{marker::PhantomData, prelude::*};
+		#[cfg(feature = "std")]
+		use sp_version::NativeVersion;
+		use sp_version::RuntimeVersion;
+		use xcm_config::*;
+
+		use precompiles::{ForeignAssetPrefix, FrontierPrecompiles, LocalAssetPrefix};
+
+		#[cfg(any(feature = "std", test))]
+		pub use sp_runtime::BuildStorage;
+
+		// Polkadot imports
+		use frame_support::weights::constants::WEIGHT_REF_TIME_PER_SECOND;
+		use polkadot_runtime_common::{BlockHashCount, SlowAdjustingFeeUpdate};
+		use sp_runtime::traits::ConstU16;
+		// XCM Imports
+		use xcm::latest::prelude::BodyId;
+		use xcm_executor::XcmExecutor;
+
+		/// Block type as expected by this runtime.
+		pub type Block = generic::Block<Header, UncheckedExtrinsic>;
+		/// A Block signed with a Justification
+		pub type SignedBlock = generic::SignedBlock<Block>;
+		/// BlockId type as expected by this runtime.
+		pub type BlockId = generic::BlockId<Block>;
+		/// The SignedExtension to the basic transaction logic.
+		pub type SignedExtra = (
+			frame_system::CheckNonZeroSender<Runtime>,
+			frame_system::CheckSpecVersion<Runtime>,
+			frame_system::CheckTxVersion<Runtime>,
+			frame_system::CheckGenesis<Runtime>,
+			frame_system::CheckEra<Runtime>,
+			frame_system::CheckNonce<Runtime>,
+			frame_system::CheckWeight<Runtime>,
+			pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+		);
+		/// Unchecked extrinsic type as expected by this runtime.
+		pub type UncheckedExtrinsic =
+			fp_self_contained::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
+		/// Extrinsic type that has already been checked.
+		pub type CheckedExtrinsic =
+			fp_self_contained::CheckedExtrinsic<AccountId, RuntimeCall, SignedExtra, H160>;
+		/// The payload being signed in transactions.
+		pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+		/// Executive: handles dispatch to the various modules.
+		pub type Executive = frame_executive::Executive<
+			Runtime,
+			Block,
+			frame_system::ChainContext<Runtime>,
+			Runtime,
+			AllPalletsWithSystem,
+			Migrations,
+		>;
+
+		impl fp_self_contained::SelfContainedCall for RuntimeCall {
+			type SignedInfo = H160;
+
+			fn is_self_contained(&self) -> bool {
+				match self {
+					RuntimeCall::Ethereum(call) => call.is_self_contained(),
+					_ => false,
+				}
+			}
+
+			fn check_self_contained(
+				&self,
+			) -> Option<Result<Self::SignedInfo, TransactionValidityError>> {
+				match self {
+					RuntimeCall::Ethereum(call) => call.check_self_contained(),
+					_ => None,
+				}
+			}
+
+			fn validate_self_contained(
+				&self,
+				info: &Self::SignedInfo,
+				dispatch_info: &DispatchInfoOf<RuntimeCall>,
+				len: usize,
+			) -> Option<TransactionValidity> {
+				match self {
+					RuntimeCall::Ethereum(call) => {
+						if let Err(e) = ensure_ethereum_call_not_paused(call) {
+							return Some(Err(e));
+						}
+						call.validate_self_contained(info, dispatch_info, len)
+					},
+					_ => None,
+				}
+			}
+
+			fn pre_dispatch_self_contained(
+				&self,
+				info: &Self::SignedInfo,
+				dispatch_info: &DispatchInfoOf<RuntimeCall>,
+				len: usize,
+			) -> Option<Result<(), TransactionValidityError>> {
+				match self {
+					RuntimeCall::Ethereum(call) => {
+						if let Err(e) = ensure_ethereum_call_not_paused(call) {
+							return Some(Err(e));
+						}
+						call.pre_dispatch_self_contained(info, dispatch_info, len)
+					},
+					_ => None,
+				}
+			}
+
+			fn apply_self_contained(
+				self,
+				info: Self::SignedInfo,
+			) -> Option<sp_runtime::DispatchResultWithInfo<PostDispatchInfoOf<Self>>> {
+				match self {
+					call @ RuntimeCall::Ethereum(pallet_ethereum::Call::transact { .. }) =>
+						Some(call.dispatch(RuntimeOrigin::from(
+							pallet_ethereum::RawOrigin::EthereumTransaction(info),
+						))),
+					_ => None,
+				}
+			}
+		}
+
+		/// Rejects the Ethereum transactions calling a contract or function paused in the
+		/// transaction pauser, so that they don't enter the pool.
+		fn ensure_ethereum_call_not_paused(
+			call: &pallet_ethereum::Call<Runtime>,
+		) -> Result<(), TransactionValidityError> {
+			let transact { transaction } = call else {
+				return Ok(());
+			};
+			let (action, input) = match transaction {
+				EthereumTransaction::Legacy(t) => (&t.action, &t.input),
+				EthereumTransaction::EIP2930(t) => (&t.action, &t.input),
+				EthereumTransaction::EIP1559(t) => (&t.action, &t.input),
+			};
+			match action {
+				pallet_ethereum::TransactionAction::Call(target)
+					if TransactionPauser::is_evm_call_paused(*target, input) =>
+					Err(InvalidTransaction::Call.into()),
+				_ => Ok(()),
+			}
+		}
+
+		/// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
+		/// the specifics of the runtime. They can then be made to be agnostic over specific formats
+		/// of data like extrinsics, allowing for them to continue syncing the network through
+		/// upgrades to even the core data structures.
+		pub mod opaque {
+			use super::*;
+			use sp_runtime::{generic, traits::BlakeTwo256};
+
+			pub use sp_runtime::OpaqueExtrinsic as UncheckedExtrinsic;
+			/// Opaque block header type.
+			pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
+			/// Opaque block type.
+			pub type Block = generic::Block<Header, UncheckedExtrinsic>;
+			/// Opaque block identifier type.
+			pub type BlockId = generic::BlockId<Block>;
+		}
+
+		impl_opaque_keys! {
+			pub struct SessionKeys {
+				pub aura: Aura,
+			}
+		}
+
+		/// The version information used to identify this runtime when compiled natively.
+		#[cfg(feature = "std")]
+		pub fn native_version() -> NativeVersion {
+			NativeVersion { runtime_version: VERSION, can_author_with: Default::default() }
+		}
+
+		/// Current approximation of the gas/s consumption considering
+		/// EVM execution over compiled WASM (on 4.4Ghz CPU).
+		/// Given the 500ms Weight, from which 75% only are used for transactions,
+		/// the total EVM execution gas limit is: GAS_PER_SECOND * 0.500 * 0.75 ~= 15_000_000.
+		pub const GAS_PER_SECOND: u64 = 40_000_000;
+
+		/// Approximate ratio of the amount of Weight per Gas.
+		/// u64 works for approximations because Weight is a very small unit compared to gas.
+		pub const WEIGHT_PER_GAS: u64 = WEIGHT_REF_TIME_PER_SECOND / GAS_PER_SECOND;
+		/// The highest amount of new storage that can be created in a block (40KB).
+		pub const BLOCK_STORAGE_LIMIT: u64 = 40 * 1024;
+
+		const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
+		pub const NORMAL_WEIGHT: Weight = MAXIMUM_BLOCK_WEIGHT.saturating_mul(3).saturating_div(4);
+		// Here we assume Ethereum's base fee of 21000 gas and convert to weight, but we
+		// subtract roughly the cost of a balance transfer from it (about 1/3 the cost)
+		// and some cost to account for per-byte-fee.
+		// TODO: we should use benchmarking's overhead feature to measure this
+		pub const EXTRINSIC_BASE_WEIGHT: Weight = Weight::from_parts(10000 * WEIGHT_PER_GAS, 0);
+
+		pub struct RuntimeBlockWeightsImpl;
+		impl Get<frame_system::limits::BlockWeights> for RuntimeBlockWeightsImpl {
+			fn get() -> frame_system::limits::BlockWeights {
+				frame_system::limits::BlockWeights::builder()
+					.for_class(DispatchClass::Normal, |weights| {
+						weights.base_extrinsic = EXTRINSIC_BASE_WEIGHT;
+						weights.max_total = NORMAL_WEIGHT.into();
+					})
+					.for_class(DispatchClass::Operational, |weights| {
+						weights.max_total = MAXIMUM_BLOCK_WEIGHT.into();
+						weights.reserved = (MAXIMUM_BLOCK_WEIGHT - NORMAL_WEIGHT).into();
+					})
+					.avg_block_initialization(Perbill::from_percent(10))
+					.build()
+					.expect("Provided BlockWeight definitions are valid, qed")
+			}
+		}
+
+		parameter_types! {
+			pub const Version: RuntimeVersion = VERSION;
+			/// TODO: this is left here so that `impl_runtime_apis_plus_common` will find the same type for
+			/// `BlockWeights` in all runtimes. It can probably be removed once the custom
+			/// `RuntimeBlockWeights` has been pushed to each runtime.
+			pub RuntimeBlockLength: BlockLength =
+				BlockLength::max_with_normal_ratio(5 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
+			pub RuntimeBlockWeights: frame_system::limits::BlockWeights = RuntimeBlockWeightsImpl::get();
+			pub const SS58Prefix: u16 = 42;
+		}
+
+		impl frame_system::Config for Runtime {
+			/// The identifier used to distinguish between accounts.
+			type AccountId = AccountId;
+			/// The aggregated dispatch type that is available for extrinsics.
+			type RuntimeCall = RuntimeCall;
+			/// The lookup mechanism to get account ID from whatever is passed in dispatchers.
+			type Lookup = IdentityLookup<AccountId>;
+			/// The index type for storing how many extrinsics an account has signed.
+			type Nonce = Index;
+			/// The index type for blocks.
+			type Block = Block;
+			/// The type for hashing blocks and tries.
+			type Hash = Hash;
+			/// The hashing algorithm used.
+			type Hashing = BlakeTwo256;
+			/// The ubiquitous event type.
+			type RuntimeEvent = RuntimeEvent;
+			/// The ubiquitous origin type.
+			type RuntimeOrigin = RuntimeOrigin;
+			/// The aggregated RuntimeTask type.
+			type RuntimeTask = RuntimeTask;
+			/// Maximum number of block number to block hash mappings to keep (oldest pruned first).
+			type BlockHashCount = ConstU32<256>;
+			/// Maximum weight of each block. With a default weight system of 1byte == 1weight, 4mb
+			/// is ok.
+			type BlockWeights = RuntimeBlockWeights;
+			/// Maximum size of all encoded transactions (in bytes) that are allowed in one block.
+			type BlockLength = RuntimeBlockLength;
+			/// Runtime version.
+			type Version = Version;
+			type PalletInfo = PalletInfo;
+			type AccountData = pallet_balances::AccountData<Balance>;
+			type OnNewAccount = ();
+			type OnKilledAccount = ();
+			type DbWeight = ();
+			type BaseCallFilter = FerrumCallFilter;
+			type SystemWeightInfo = ();
+			/// This is used as an identifier of the chain. 42 is the generic substrate prefix.
+			type SS58Prefix = ConstU16<1287>;
+			type OnSetCode = cumulus_pallet_parachain_system::ParachainSetCode<Self>;
+			type MaxConsumers = frame_support::traits::ConstU32<16>;
+			type SingleBlockMigrations = ();
+			type MultiBlockMigrator = ();
+			type PreInherents = ();
+			type PostInherents = ();
+			type PostTransactions = ();
+		}
+
+		impl pallet_timestamp::Config for Runtime {
+			/// A timestamp: milliseconds since the unix epoch.
+			type Moment = u64;
+			type OnTimestampSet = Aura;
+			type MinimumPeriod = ConstU64<{ SLOT_DURATION / 2 }>;
+			type WeightInfo = ();
+		}
+
+		impl pallet_sudo::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type RuntimeCall = RuntimeCall;
+			type WeightInfo = ();
+		}
+
+		impl pallet_authorship::Config for Runtime {
+			type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Aura>;
+			type EventHandler = (CollatorSelection,);
+		}
+
+		parameter_types! {
+			pub const ExistentialDeposit: Balance = EXISTENTIAL_DEPOSIT;
+		}
+
+		impl pallet_balances::Config for Runtime {
+			type MaxReserves = ConstU32<50>;
+			type ReserveIdentifier = [u8; 4];
+			type MaxLocks = ConstU32<50>;
+			/// The type for recording an account's balance.
+			type Balance = Balance;
+			/// The ubiquitous event type.
+			type RuntimeEvent = RuntimeEvent;
+			type DustRemoval = ();
+			type ExistentialDeposit = ExistentialDeposit;
+			type AccountStore = System;
+			type FreezeIdentifier = ();
+			type MaxFreezes = ConstU32<0>;
+			type RuntimeHoldReason = RuntimeHoldReason;
+			type RuntimeFreezeReason = RuntimeFreezeReason;
+			type WeightInfo = ();
+		}
+
+		parameter_types! {
+			/// Relay Chain `TransactionByteFee` / 10
+			pub const TransactionByteFee: Balance = 10 * MICROUNIT;
+			pub const TransactionFeesSplit: FeeSplit = $constants::TRANSACTION_FEES_SPLIT;
+		}
+
+		impl pallet_transaction_payment::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<
+				Balances,
+				DealWithFees<Runtime, TransactionFeesSplit>,
+			>;
+			type WeightToFee = WeightToFee;
+			type LengthToFee = ConstantMultiplier<Balance, TransactionByteFee>;
+			type FeeMultiplierUpdate = SlowAdjustingFeeUpdate<Self>;
+			type OperationalFeeMultiplier = ConstU8<5>;
+		}
+
+		parameter_types! {
+			pub const AssetDeposit: Balance = 100 * UNIT;
+			pub const AssetAccountDeposit: Balance = EXISTENTIAL_DEPOSIT;
+			pub const ApprovalDeposit: Balance = 0;
+			pub const AssetsStringLimit: u32 = 50;
+			pub const MetadataDepositBase: Balance = UNIT;
+			pub const MetadataDepositPerByte: Balance = MILLIUNIT;
+		}
+
+		/// Identifier of an asset, in both the foreign and the local assets instances.
+		pub type AssetId = u128;
+
+		/// Instance of `pallet_assets` holding the assets of other chains, reserved over XCM.
+		pub type ForeignAssetInstance = pallet_assets::Instance1;
+		/// Instance of `pallet_assets` holding the assets issued on this chain.
+		pub type LocalAssetInstance = pallet_assets::Instance2;
+
+		// Assets are only created by governance, through `force_create`, so that the ids of the
+		// assets (and the addresses of their ERC20 precompiles) stay under control.
+		impl pallet_assets::Config<ForeignAssetInstance> for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type Balance = Balance;
+			type AssetId = AssetId;
+			type AssetIdParameter = AssetId;
+			type Currency = Balances;
+			type CreateOrigin = AsEnsureOriginWithArg<EnsureNever<AccountId>>;
+			type ForceOrigin = GeneralAdminOrigin;
+			type AssetDeposit = AssetDeposit;
+			type AssetAccountDeposit = AssetAccountDeposit;
+			type MetadataDepositBase = MetadataDepositBase;
+			type MetadataDepositPerByte = MetadataDepositPerByte;
+			type ApprovalDeposit = ApprovalDeposit;
+			type StringLimit = AssetsStringLimit;
+			type Freezer = ();
+			type Extra = ();
+			type CallbackHandle = precompiles::AssetPrecompileCode<ForeignAssetPrefix>;
+			type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+			type RemoveItemsLimit = ConstU32<1000>;
+			#[cfg(feature = "runtime-benchmarks")]
+			type BenchmarkHelper = ();
+		}
+
+		impl pallet_assets::Config<LocalAssetInstance> for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type Balance = Balance;
+			type AssetId = AssetId;
+			type AssetIdParameter = AssetId;
+			type Currency = Balances;
+			type CreateOrigin = AsEnsureOriginWithArg<EnsureNever<AccountId>>;
+			type ForceOrigin = GeneralAdminOrigin;
+			type AssetDeposit = AssetDeposit;
+			type AssetAccountDeposit = AssetAccountDeposit;
+			type MetadataDepositBase = MetadataDepositBase;
+			type MetadataDepositPerByte = MetadataDepositPerByte;
+			type ApprovalDeposit = ApprovalDeposit;
+			type StringLimit = AssetsStringLimit;
+			type Freezer = ();
+			type Extra = ();
+			type CallbackHandle = precompiles::AssetPrecompileCode<LocalAssetPrefix>;
+			type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+			type RemoveItemsLimit = ConstU32<1000>;
+			#[cfg(feature = "runtime-benchmarks")]
+			type BenchmarkHelper = ();
+		}
+
+		parameter_types! {
+			pub const AssetRegistryPalletId: PalletId = PalletId(*b"frm/asrg");
+		}
+
+		/// Creates the foreign assets registered in the asset registry in the `ForeignAssets`
+		/// instance, owned by the registry account.
+		pub struct ForeignAssetRegistrar;
+		impl pallet_asset_registry::AssetRegistrar<AssetId, Balance> for ForeignAssetRegistrar {
+			fn create_foreign_asset(
+				asset_id: AssetId,
+				min_balance: Balance,
+				metadata: pallet_asset_registry::AssetMetadata,
+				is_sufficient: bool,
+			) -> DispatchResult {
+				ForeignAssets::force_create(
+					RuntimeOrigin::root(),
+					asset_id,
+					AssetRegistryPalletId::get().into_account_truncating(),
+					is_sufficient,
+					min_balance,
+				)?;
+				ForeignAssets::force_set_metadata(
+					RuntimeOrigin::root(),
+					asset_id,
+					metadata.name,
+					metadata.symbol,
+					metadata.decimals,
+					false,
+				)
+			}
+		}
+
+		impl pallet_asset_registry::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type AssetId = AssetId;
+			type Balance = Balance;
+			type AssetRegistrar = ForeignAssetRegistrar;
+			type RegistryOrigin = GeneralAdminOrigin;
+			type WeightInfo = pallet_asset_registry::weights::SubstrateWeight<Runtime>;
+		}
+
+		parameter_types! {
+			pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+			pub const ProposalBond: Permill = Permill::from_percent(5);
+			pub const ProposalBondMinimum: Balance = 100 * UNIT;
+			pub const ProposalBondMaximum: Option<Balance> = None;
+			pub const SpendPeriod: BlockNumber = 6 * DAYS;
+			pub const TreasuryBurn: Permill = Permill::from_percent(0);
+			pub const MaxApprovals: u32 = 100;
+			pub const SpendPayoutPeriod: BlockNumber = 30 * DAYS;
+			pub TreasuryAccount: AccountId = Treasury::account_id();
+		}
+
+		/// Creates the arguments of the treasury benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		pub struct TreasuryBenchmarkHelper;
+		#[cfg(feature = "runtime-benchmarks")]
+		impl pallet_treasury::ArgumentsFactory<(), AccountId> for TreasuryBenchmarkHelper {
+			fn create_asset_kind(_seed: u32) {}
+
+			fn create_beneficiary(seed: [u8; 32]) -> AccountId {
+				H160::from_slice(&seed[..20]).into()
+			}
+		}
+
+		// The treasury funds the Quantum Portal miners and finalizers, out of its share of the
+		// fees. Spends are approved by the governance.
+		impl pallet_treasury::Config for Runtime {
+			type PalletId = TreasuryPalletId;
+			type Currency = Balances;
+			type ApproveOrigin = TreasurerOrigin;
+			type RejectOrigin = TreasurerOrigin;
+			type RuntimeEvent = RuntimeEvent;
+			type OnSlash = Treasury;
+			type ProposalBond = ProposalBond;
+			type ProposalBondMinimum = ProposalBondMinimum;
+			type ProposalBondMaximum = ProposalBondMaximum;
+			type SpendPeriod = SpendPeriod;
+			type Burn = TreasuryBurn;
+			type BurnDestination = ();
+			type SpendFunds = ();
+			type WeightInfo = ();
+			type MaxApprovals = MaxApprovals;
+			type SpendOrigin = TreasurySpendOrigin;
+			type AssetKind = ();
+			type Beneficiary = AccountId;
+			type BeneficiaryLookup = IdentityLookup<AccountId>;
+			type Paymaster = PayFromAccount<Balances, TreasuryAccount>;
+			type BalanceConverter = UnityAssetBalanceConversion;
+			type PayoutPeriod = SpendPayoutPeriod;
+			#[cfg(feature = "runtime-benchmarks")]
+			type BenchmarkHelper = TreasuryBenchmarkHelper;
+		}
+
+		parameter_types! {
+			pub const ReservedXcmpWeight: Weight = MAXIMUM_BLOCK_WEIGHT.saturating_div(4);
+			pub const ReservedDmpWeight: Weight = MAXIMUM_BLOCK_WEIGHT.saturating_div(4);
+		}
+
+		/// Maximum number of blocks simultaneously accepted by the Runtime, not yet included into
+		/// the relay chain.
+		pub const UNINCLUDED_SEGMENT_CAPACITY: u32 = 1;
+		/// How many parachain blocks are processed by the relay chain per parent. Limits the number
+		/// of blocks authored per slot.
+		pub const BLOCK_PROCESSING_VELOCITY: u32 = 1;
+		/// Relay chain slot duration, in milliseconds.
+		pub const RELAY_CHAIN_SLOT_DURATION_MILLIS: u32 = 6000;
+
+		type ConsensusHook = cumulus_pallet_aura_ext::FixedVelocityConsensusHook<
+			Runtime,
+			RELAY_CHAIN_SLOT_DURATION_MILLIS,
+			BLOCK_PROCESSING_VELOCITY,
+			UNINCLUDED_SEGMENT_CAPACITY,
+		>;
+
+		impl cumulus_pallet_parachain_system::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type OnSystemEvent = ();
+			type SelfParaId = ParachainInfo;
+			type ReservedDmpWeight = ReservedDmpWeight;
+			type OutboundXcmpMessageSource = XcmpQueue;
+			type XcmpMessageHandler = XcmpQueue;
+			type ReservedXcmpWeight = ReservedXcmpWeight;
+			type CheckAssociatedRelayNumber = RelayNumberMonotonicallyIncreases;
+			type ConsensusHook = ConsensusHook;
+			type DmpQueue = frame_support::traits::EnqueueWithOrigin<MessageQueue, RelayOrigin>;
+			type WeightInfo = ();
+		}
+
+		impl parachain_info::Config for Runtime {}
+
+		impl cumulus_pallet_aura_ext::Config for Runtime {}
+
+		impl cumulus_pallet_xcmp_queue::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type ChannelInfo = ParachainSystem;
+			type VersionWrapper = PolkadotXcm;
+			type XcmpQueue =
+				TransformOrigin<MessageQueue, AggregateMessageOrigin, ParaId, ParaIdToSibling>;
+			type MaxInboundSuspended = sp_core::ConstU32<1_000>;
+			type ControllerOrigin = GeneralAdminOrigin;
+			type ControllerOriginConverter = XcmOriginToTransactDispatchOrigin;
+			type WeightInfo = ();
+			type PriceForSiblingDelivery =
+				polkadot_runtime_common::xcm_sender::NoPriceForMessageDelivery<
+					cumulus_primitives_core::ParaId,
+				>;
+		}
+
+		parameter_types! {
+			pub const RelayOrigin: AggregateMessageOrigin = AggregateMessageOrigin::Parent;
+		}
+
+		// TODO: This pallet can be removed after the lazy migration is done and
+		// event `Completed` is emitted.
+		// https://github.com/paritytech/polkadot-sdk/pull/1246
+		impl cumulus_pallet_dmp_queue::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type DmpSink = frame_support::traits::EnqueueWithOrigin<MessageQueue, RelayOrigin>;
+			type WeightInfo = cumulus_pallet_dmp_queue::weights::SubstrateWeight<Runtime>;
+		}
+
+		parameter_types! {
+			pub const Period: u32 = 6 * HOURS;
+			pub const Offset: u32 = 0;
+		}
+
+		impl pallet_session::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type ValidatorId = <Self as frame_system::Config>::AccountId;
+			// we don't have stash and controller, thus we don't need the convert as well.
+			type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
+			type ShouldEndSession = pallet_session::PeriodicSessions<Period, Offset>;
+			type NextSessionRotation = pallet_session::PeriodicSessions<Period, Offset>;
+			type SessionManager = CollatorSelection;
+			// Essentially just Aura, but let's be pedantic.
+			type SessionHandler =
+				<SessionKeys as sp_runtime::traits::OpaqueKeys>::KeyTypeIdProviders;
+			type Keys = SessionKeys;
+			type WeightInfo = ();
+		}
+
+		impl pallet_aura::Config for Runtime {
+			type AuthorityId = AuraId;
+			type DisabledValidators = ();
+			type MaxAuthorities = ConstU32<100_000>;
+			type AllowMultipleBlocksPerSlot = ConstBool<false>;
+			type SlotDuration = ConstU64<SLOT_DURATION>;
+		}
+
+		parameter_types! {
+			pub const PotId: PalletId = PalletId(*b"PotStake");
+			pub const MaxCandidates: u32 = 1000;
+			pub const MinCandidates: u32 = 5;
+			pub const SessionLength: BlockNumber = 6 * HOURS;
+			pub const MaxInvulnerables: u32 = 100;
+			pub const ExecutiveBody: BodyId = BodyId::Executive;
+		}
+
+		// Privileged collator selection operations are restricted to the general admin origin.
+		pub type CollatorSelectionUpdateOrigin = GeneralAdminOrigin;
+
+		impl pallet_collator_selection::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type Currency = Balances;
+			type UpdateOrigin = CollatorSelectionUpdateOrigin;
+			type PotId = PotId;
+			type MaxCandidates = MaxCandidates;
+			type MaxInvulnerables = MaxInvulnerables;
+			// should be a multiple of session or things will get inconsistent
+			type KickThreshold = Period;
+			type ValidatorId = <Self as frame_system::Config>::AccountId;
+			type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
+			type ValidatorRegistration = Session;
+			type MinEligibleCollators = MinCandidates;
+			type WeightInfo = ();
+		}
+
+		pub struct FindAuthorTruncated<F>(PhantomData<F>);
+		impl<F: FindAuthor<u32>> FindAuthor<H160> for FindAuthorTruncated<F> {
+			fn find_author<'a, I>(digests: I) -> Option<H160>
+			where
+				I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+			{
+				if let Some(author_index) = F::find_author(digests) {
+					let authority_id =
+						pallet_aura::Authorities::<Runtime>::get()[author_index as usize].clone();
+					return Some(H160::from_slice(&authority_id.to_raw_vec()[4..24]));
+				}
+				None
+			}
+		}
+
+		/// And ipmlementation of Frontier's AddressMapping trait for Accounts.
+		/// This is basically identical to Frontier's own IdentityAddressMapping, but it works for
+		/// any type that is Into<H160> like AccountId20 for example.
+		pub struct IntoAddressMapping;
+
+		impl<T: From<H160>> pallet_evm::AddressMapping<T> for IntoAddressMapping {
+			fn into_account_id(address: H160) -> T {
+				address.into()
+			}
+		}
+
+		const BLOCK_GAS_LIMIT: u64 = 75_000_000;
+		const MAX_POV_SIZE: u64 = 5 * 1024 * 1024;
+
+		/// The precompiles, reverting the calls paused in `pallet_transaction_pauser` at every
+		/// call frame.
+		pub type Precompiles = pallet_transaction_pauser::PausedEvmCallPrecompiles<
+			Runtime,
+			FrontierPrecompiles<Runtime>,
+		>;
+
+		parameter_types! {
+			pub const ChainId: u64 = $constants::CHAIN_ID;
+			pub BlockGasLimit: U256 = U256::from(BLOCK_GAS_LIMIT);
+			pub const GasLimitPovSizeRatio: u64 = BLOCK_GAS_LIMIT.saturating_div(MAX_POV_SIZE);
+			pub PrecompilesValue: Precompiles = Precompiles::new(FrontierPrecompiles::<_>::new());
+			pub WeightPerGas: Weight = Weight::from_parts(weight_per_gas(BLOCK_GAS_LIMIT, NORMAL_DISPATCH_RATIO, MILLISECS_PER_BLOCK), 0);
+			pub SuicideQuickClearLimit: u32 = 0;
+			pub const EvmBaseFeesSplit: FeeSplit = $constants::EVM_BASE_FEES_SPLIT;
+		}
+
+		impl pallet_evm::Config for Runtime {
+			type FeeCalculator = BaseFee;
+			type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+			type BlockHashMapping = pallet_ethereum::EthereumBlockHashMapping<Self>;
+			type CallOrigin = EnsureAddressRoot<AccountId>;
+			type WeightPerGas = WeightPerGas;
+			type WithdrawOrigin = EnsureAddressNever<AccountId>;
+			type AddressMapping = IntoAddressMapping;
+			type Currency = Balances;
+			type RuntimeEvent = RuntimeEvent;
+			type Runner = pallet_transaction_pauser::PausedEvmCallRunner<
+				Self,
+				pallet_evm::runner::stack::Runner<Self>,
+			>;
+			type PrecompilesType = Precompiles;
+			type PrecompilesValue = PrecompilesValue;
+			type ChainId = ChainId;
+			type BlockGasLimit = BlockGasLimit;
+			type OnChargeTransaction =
+				EVMCurrencyAdapter<Balances, SplitFees<Runtime, EvmBaseFeesSplit>>;
+			type OnCreate = ();
+			type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
+			type Timestamp = Timestamp;
+			type WeightInfo = pallet_evm::weights::SubstrateWeight<Self>;
+			type SuicideQuickClearLimit = SuicideQuickClearLimit;
+			type FindAuthor = FindAuthorTruncated<Aura>;
+		}
+
+		parameter_types! {
+			pub const PostBlockAndTxnHashes: PostLogContent = PostLogContent::BlockAndTxnHashes;
+		}
+
+		impl pallet_ethereum::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type StateRoot = pallet_ethereum::IntermediateStateRoot<Self>;
+			type PostLogContent = PostBlockAndTxnHashes;
+			type ExtraDataLength = ConstU32<30>;
+		}
+
+		frame_support::parameter_types! {
+			pub BoundDivision: U256 = U256::from(1024);
+		}
+
+		impl pallet_dynamic_fee::Config for Runtime {
+			type MinGasPriceBoundDivisor = BoundDivision;
+		}
+
+		frame_support::parameter_types! {
+			pub IsActive: bool = true;
+			pub DefaultBaseFeePerGas: U256 = U256::from(1_000_000_000);
+			pub DefaultElasticity: Permill = Permill::from_parts(125_000);
+		}
+
+		pub struct BaseFeeThreshold;
+		impl pallet_base_fee::BaseFeeThreshold for BaseFeeThreshold {
+			fn lower() -> Permill {
+				Permill::zero()
+			}
+			fn ideal() -> Permill {
+				Permill::from_parts(500_000)
+			}
+			fn upper() -> Permill {
+				Permill::from_parts(1_000_000)
+			}
+		}
+
+		parameter_types! {
+			pub MessageQueueServiceWeight: Weight = Perbill::from_percent(35) * RuntimeBlockWeights::get().max_block;
+		}
+
+		impl pallet_message_queue::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type WeightInfo = ();
+			#[cfg(feature = "runtime-benchmarks")]
+			type MessageProcessor = pallet_message_queue::mock_helpers::NoopMessageProcessor<
+				cumulus_primitives_core::AggregateMessageOrigin,
+			>;
+			#[cfg(not(feature = "runtime-benchmarks"))]
+			type MessageProcessor = xcm_builder::ProcessXcmMessage<
+				AggregateMessageOrigin,
+				xcm_executor::XcmExecutor<xcm_config::XcmConfig>,
+				RuntimeCall,
+			>;
+			type Size = u32;
+			// The XCMP queue pallet is only ever able to handle the `Sibling(ParaId)` origin:
+			type QueueChangeHandler = NarrowOriginToSibling<XcmpQueue>;
+			type QueuePausedQuery = NarrowOriginToSibling<XcmpQueue>;
+			type HeapSize = sp_core::ConstU32<{ 64 * 1024 }>;
+			type MaxStale = sp_core::ConstU32<8>;
+			type ServiceWeight = MessageQueueServiceWeight;
+			type IdleMaxServiceWeight = ();
+		}
+
+		impl pallet_base_fee::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type Threshold = BaseFeeThreshold;
+			type DefaultBaseFeePerGas = DefaultBaseFeePerGas;
+			type DefaultElasticity = DefaultElasticity;
+		}
+
+		parameter_types! {
+			pub const QuantumPortalUnsignedPriority: TransactionPriority = TransactionPriority::MAX / 2;
+			pub const QuantumPortalUnsignedLongevity: TransactionLongevity = 64;
+		}
+
+		impl pallet_quantum_portal::Config for Runtime {
+			type RuntimeCall = RuntimeCall;
+			type RuntimeEvent = RuntimeEvent;
+			type Timestamp = Timestamp;
+			type AccountIdToAddress = sp_runtime::traits::ConvertInto;
+			type UnsignedPriority = QuantumPortalUnsignedPriority;
+			type UnsignedLongevity = QuantumPortalUnsignedLongevity;
+			type FinalizerOrigin = FinalizerAdminOrigin;
+		}
+
+		impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+		where
+			RuntimeCall: From<LocalCall>,
+		{
+			fn create_transaction<
+				C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>,
+			>(
+				call: RuntimeCall,
+				public: <Signature as sp_runtime::traits::Verify>::Signer,
+				account: AccountId,
+				index: Index,
+			) -> Option<(
+				RuntimeCall,
+				<UncheckedExtrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload,
+			)> {
+				let period = BlockHashCount::get() as u64;
+				let current_block = System::block_number() as u64;
+				let tip = 0;
+				let extra: SignedExtra = (
+					frame_system::CheckNonZeroSender::<Runtime>::new(),
+					frame_system::CheckSpecVersion::<Runtime>::new(),
+					frame_system::CheckTxVersion::<Runtime>::new(),
+					frame_system::CheckGenesis::<Runtime>::new(),
+					frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(
+						period,
+						current_block,
+					)),
+					frame_system::CheckNonce::<Runtime>::from(index),
+					frame_system::CheckWeight::<Runtime>::new(),
+					pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+				);
+
+				let raw_payload = SignedPayload::new(call, extra)
+					.map_err(|e| {
+						log::warn!("Unable to create signed payload: {:?}", e);
+					})
+					.ok()?;
+				let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+				let (call, extra, _) = raw_payload.deconstruct();
+				Some((call, (account, signature, extra)))
+			}
+		}
+
+		impl frame_system::offchain::SigningTypes for Runtime {
+			type Public = <Signature as sp_runtime::traits::Verify>::Signer;
+			type Signature = Signature;
+		}
+
+		impl pallet_ethereum_xcm::Config for Runtime {
+			type ValidatedTransaction = pallet_ethereum::ValidatedTransaction<Self>;
+			type XcmEthereumOrigin = pallet_ethereum_xcm::EnsureXcmEthereumTransaction;
+			type ReservedXcmpWeight = ReservedXcmpWeight;
+			type EnsureProxy = EthereumXcmEnsureProxy;
+		}
+
+		/// Allows the proxies of an account to transact through XCM on its behalf, as long as they
+		/// are `Any` proxies without delay.
+		pub struct EthereumXcmEnsureProxy;
+		impl EnsureProxy<AccountId> for EthereumXcmEnsureProxy {
+			fn ensure_ok(delegator: AccountId, delegatee: AccountId) -> Result<(), &'static str> {
+				// the EVM implicitly contains an Any proxy, so only Any proxies are allowed
+				let def = pallet_proxy::Pallet::<Runtime>::find_proxy(
+					&delegator,
+					&delegatee,
+					Some(ProxyType::Any),
+				)
+				.map_err(|_| "proxy error: expected `ProxyType::Any`")?;
+				// the call is executed right away, so only proxies without delay are allowed
+				frame_support::ensure!(def.delay == 0, "proxy delay is non-zero");
+				Ok(())
+			}
+		}
+
+		impl pallet_utility::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type RuntimeCall = RuntimeCall;
+			type PalletsOrigin = OriginCaller;
+			type WeightInfo = ();
+		}
+
+		/// The types of proxies an account can delegate its calls to.
+		#[derive(
+			Copy,
+			Clone,
+			Eq,
+			PartialEq,
+			Ord,
+			PartialOrd,
+			Encode,
+			Decode,
+			RuntimeDebug,
+			MaxEncodedLen,
+			scale_info::TypeInfo,
+			Default,
+		)]
+		pub enum ProxyType {
+			/// All calls.
+			#[default]
+			Any = 0,
+			/// Balance transfers.
+			Balances = 1,
+			/// Governance calls, as defined by the `GovernanceCalls` of the runtime.
+			Governance = 2,
+			/// Quantum Portal finalizer operations: the submission of signatures.
+			Finalizer = 3,
+		}
+
+		impl InstanceFilter<RuntimeCall> for ProxyType {
+			fn filter(&self, call: &RuntimeCall) -> bool {
+				// batched calls are filtered one by one
+				if matches!(call, RuntimeCall::Utility(..)) {
+					return true;
+				}
+				match self {
+					ProxyType::Any => true,
+					ProxyType::Balances => matches!(call, RuntimeCall::Balances(..)),
+					ProxyType::Governance => GovernanceCalls::contains(call),
+					ProxyType::Finalizer => matches!(
+						call,
+						RuntimeCall::QuantumPortal(
+							pallet_quantum_portal::Call::submit_signature { .. }
+						)
+					),
+				}
+			}
+
+			fn is_superset(&self, o: &Self) -> bool {
+				self == &ProxyType::Any || self == o
+			}
+		}
+
+		parameter_types! {
+			pub const ProxyDepositBase: Balance = UNIT;
+			pub const ProxyDepositFactor: Balance = 10 * MILLIUNIT;
+			pub const AnnouncementDepositBase: Balance = UNIT;
+			pub const AnnouncementDepositFactor: Balance = 20 * MILLIUNIT;
+		}
+
+		impl pallet_proxy::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type RuntimeCall = RuntimeCall;
+			type Currency = Balances;
+			type ProxyType = ProxyType;
+			type ProxyDepositBase = ProxyDepositBase;
+			type ProxyDepositFactor = ProxyDepositFactor;
+			type MaxProxies = ConstU32<32>;
+			type WeightInfo = ();
+			type MaxPending = ConstU32<32>;
+			type CallHasher = BlakeTwo256;
+			type AnnouncementDepositBase = AnnouncementDepositBase;
+			type AnnouncementDepositFactor = AnnouncementDepositFactor;
+		}
+
+		parameter_types! {
+			pub const MultisigDepositBase: Balance = UNIT;
+			pub const MultisigDepositFactor: Balance = 10 * MILLIUNIT;
+		}
+
+		impl pallet_multisig::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type RuntimeCall = RuntimeCall;
+			type Currency = Balances;
+			type DepositBase = MultisigDepositBase;
+			type DepositFactor = MultisigDepositFactor;
+			type MaxSignatories = ConstU32<100>;
+			type WeightInfo = ();
+		}
+
+		impl pallet_transaction_pauser::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type RuntimeCall = RuntimeCall;
+			type UpdateOrigin = PauserOrigin;
+			type UnpausablePallets = UnpausablePallets;
+			type WeightInfo = ();
+		}
+
+		impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+		where
+			RuntimeCall: From<C>,
+		{
+			type OverarchingCall = RuntimeCall;
+			type Extrinsic = UncheckedExtrinsic;
+		}
+
+		/// Filters the calls paused in the transaction pauser, the core calls always being allowed.
+		pub struct FerrumCallFilter;
+		impl Contains<RuntimeCall> for FerrumCallFilter {
+			fn contains(call: &RuntimeCall) -> bool {
+				let is_core_call = matches!(
+					call,
+					RuntimeCall::System(_) |
+						RuntimeCall::Timestamp(_) |
+						RuntimeCall::ParachainSystem(_)
+				);
+				if is_core_call {
+					// always allow core call
+					return true;
+				}
+
+				let is_paused =
+					pallet_transaction_pauser::PausedTransactionFilter::<Runtime>::contains(call);
+				if is_paused {
+					// no paused call
+					return false;
+				}
+
+				// if let RuntimeCall::PolkadotXcm(xcm_method) = call {
+				// 	match xcm_method {
+				// 		pallet_xcm::Call::send { .. } |
+				// 		pallet_xcm::Call::execute { .. } |
+				// 		pallet_xcm::Call::teleport_assets { .. } |
+				// 		pallet_xcm::Call::reserve_transfer_assets { .. } |
+				// 		pallet_xcm::Call::limited_reserve_transfer_assets { .. } |
+				// 		pallet_xcm::Call::limited_teleport_assets { .. } => return false,
+				// 		pallet_xcm::Call::force_xcm_version { .. } |
+				// 		pallet_xcm::Call::force_default_xcm_version { .. } |
+				// 		pallet_xcm::Call::force_subscribe_version_notify { .. } |
+				// 		pallet_xcm::Call::force_suspension { .. } |
+				// 		pallet_xcm::Call::force_unsubscribe_version_notify { .. } => return true,
+				// 		pallet_xcm::Call::__Ignore { .. } => {
+				// 			unimplemented!()
+				// 		},
+				// 	}
+				// }
+
+				true
+			}
+		}
+
+		#[derive(Clone)]
+		pub struct TransactionConverter;
+		impl fp_rpc::ConvertTransaction<UncheckedExtrinsic> for TransactionConverter {
+			fn convert_transaction(
+				&self,
+				transaction: pallet_ethereum::Transaction,
+			) -> UncheckedExtrinsic {
+				UncheckedExtrinsic::new_unsigned(
+					pallet_ethereum::Call::<Runtime>::transact { transaction }.into(),
+				)
+			}
+		}
+
+		impl fp_rpc::ConvertTransaction<opaque::UncheckedExtrinsic> for TransactionConverter {
+			fn convert_transaction(
+				&self,
+				transaction: pallet_ethereum::Transaction,
+			) -> opaque::UncheckedExtrinsic {
+				let extrinsic = UncheckedExtrinsic::new_unsigned(
+					pallet_ethereum::Call::<Runtime>::transact { transaction }.into(),
+				);
+				let encoded = extrinsic.encode();
+				opaque::UncheckedExtrinsic::decode(&mut &encoded[..])
+					.expect("Encoded extrinsic is always valid")
+			}
+		}
+	};
+}
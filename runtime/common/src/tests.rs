@@ -0,0 +1,89 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Unit tests of the fee handlers.
+#![cfg(test)]
+use crate::{
+	impls::{DealWithFees, FeeSplit, SplitFees},
+	mock::*,
+};
+use frame_support::traits::{Currency, OnUnbalanced};
+use sp_runtime::Percent;
+
+fn treasury_balance() -> Balance {
+	Balances::free_balance(Treasury::account_id())
+}
+
+#[test]
+fn fee_split_author_gets_the_rest() {
+	assert_eq!(FeeSplit::new(50, 0).author(), Percent::from_percent(50));
+	assert_eq!(FeeSplit::new(50, 50).author(), Percent::zero());
+	assert_eq!(FeeSplit::new(20, 30).author(), Percent::from_percent(50));
+}
+
+#[test]
+#[should_panic(expected = "fee split shares exceed 100%")]
+fn fee_split_rejects_shares_over_100_percent() {
+	FeeSplit::new(60, 50);
+}
+
+#[test]
+fn transaction_fees_are_split_and_tips_go_to_author() {
+	new_test_ext().execute_with(|| {
+		let issuance = Balances::total_issuance();
+		let fees = Balances::issue(1_000);
+		let tips = Balances::issue(100);
+
+		<DealWithFees<Runtime, TransactionFeesSplit> as OnUnbalanced<_>>::on_unbalanceds(
+			vec![fees, tips].into_iter(),
+		);
+
+		assert_eq!(treasury_balance(), 500);
+		assert_eq!(Balances::free_balance(AUTHOR), 600);
+		// nothing is burnt
+		assert_eq!(Balances::total_issuance(), issuance + 1_100);
+	});
+}
+
+#[test]
+fn evm_base_fees_are_split_and_burnt() {
+	new_test_ext().execute_with(|| {
+		let issuance = Balances::total_issuance();
+
+		let fees = Balances::issue(1_000);
+		<SplitFees<Runtime, EvmBaseFeesSplit> as OnUnbalanced<_>>::on_unbalanced(fees);
+
+		assert_eq!(treasury_balance(), 500);
+		assert_eq!(Balances::free_balance(AUTHOR), 0);
+		// the burnt half leaves the total issuance
+		assert_eq!(Balances::total_issuance(), issuance + 500);
+	});
+}
+
+#[test]
+fn rounding_remainder_goes_to_author() {
+	new_test_ext().execute_with(|| {
+		let issuance = Balances::total_issuance();
+
+		// 50% of 3 is rounded down for both the treasury and the burn
+		let fees = Balances::issue(3);
+		<SplitFees<Runtime, EvmBaseFeesSplit> as OnUnbalanced<_>>::on_unbalanced(fees);
+
+		assert_eq!(treasury_balance(), 1);
+		assert_eq!(Balances::free_balance(AUTHOR), 1);
+		assert_eq!(Balances::total_issuance(), issuance + 2);
+	});
+}
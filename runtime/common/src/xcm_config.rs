@@ -0,0 +1,331 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+/// Implements the XCM configuration of the Ferrum runtimes.
+///
+/// It is expanded in the `xcm_config` module of a runtime crate, by [`impl_runtime_common`].
+#[macro_export]
+macro_rules! impl_xcm_config {
+	() => {
+		use crate::{
+			governance::GeneralAdminOrigin, AccountId, AllPalletsWithSystem, Balance, Balances,
+			ForeignAssets, ParachainInfo, ParachainSystem, PolkadotXcm, Runtime, RuntimeCall,
+			RuntimeEvent, RuntimeOrigin, WeightToFee, XcmpQueue,
+		};
+		use $crate::impls::ToAuthor;
+		use frame_support::{
+			dispatch::{DispatchErrorWithPostInfo, PostDispatchInfo},
+			parameter_types,
+			traits::{ConstU32, Contains, Everything, Nothing, OriginTrait},
+			weights::Weight,
+			PalletId,
+		};
+		use pallet_asset_registry::{
+			LocationToAssetId, RegisteredReserveAssets, UnitsPerSecondTrader,
+		};
+		use pallet_xcm::XcmPassthrough;
+		use polkadot_parachain_primitives::primitives::Sibling;
+		use sp_runtime::traits::{AccountIdConversion, Dispatchable, Get, TryConvert};
+		use xcm::latest::{prelude::*, NetworkId};
+		use xcm_builder::{
+			AccountId32Aliases, AccountKey20Aliases, AllowExplicitUnpaidExecutionFrom,
+			AllowTopLevelPaidExecutionFrom, ConvertedConcreteId, DenyReserveTransferToRelayChain,
+			DenyThenTry, DescribeAllTerminal, DescribeFamily, EnsureXcmOrigin, FixedWeightBounds,
+			FrameTransactionalProcessor, FungibleAdapter, FungiblesAdapter, HashedDescription,
+			IsConcrete, JustTry, NoChecking, ParentAsSuperuser, ParentIsPreset, RelayChainAsNative,
+			SiblingParachainAsNative, SiblingParachainConvertsVia, SignedAccountId32AsNative,
+			SignedAccountKey20AsNative, SignedToAccountId32, SovereignSignedViaLocation,
+			TakeRevenue, TakeWeightCredit, TrailingSetTopicAsId, UsingComponents,
+			WithComputedOrigin, WithUniqueTopic,
+		};
+		use xcm_executor::{
+			traits::{CallDispatcher, TransactAsset},
+			XcmExecutor,
+		};
+
+		parameter_types! {
+			pub const RelayLocation: Location = Location::parent();
+			pub const RelayNetwork: NetworkId = NetworkId::Kusama;
+			pub RelayChainOrigin: RuntimeOrigin = cumulus_pallet_xcm::Origin::Relay.into();
+			// For the real deployment, it is recommended to set `RelayNetwork` according to the relay chain
+			// and prepend `UniversalLocation` with `GlobalConsensus(RelayNetwork::get())`.
+			pub UniversalLocation: InteriorLocation = Parachain(ParachainInfo::parachain_id().into()).into();
+			// The location of FRM, the native token.
+			pub SelfLocation: Location = Location::here();
+			pub CheckingAccount: AccountId = PolkadotXcm::check_account();
+			pub const XcmFeesPalletId: PalletId = PalletId(*b"frm/xcmf");
+		}
+
+		/// Type for specifying how a `Location` can be converted into an `AccountId`. This is used
+		/// when determining ownership of accounts for asset transacting and when attempting to use
+		/// XCM `Transact` in order to determine the dispatch Origin.
+		pub type LocationToAccountId = (
+			// The parent (Relay-chain) origin converts to the default `AccountId`.
+			ParentIsPreset<AccountId>,
+			// Sibling parachain origins convert to AccountId via the `ParaId::into`.
+			SiblingParachainConvertsVia<
+				polkadot_parachain_primitives::primitives::Sibling,
+				AccountId,
+			>,
+			// If we receive a Location of type AccountKey20, just generate a native account
+			AccountKey20Aliases<RelayNetwork, AccountId>,
+			// Generate remote accounts according to polkadot standards
+			HashedDescription<AccountId, DescribeFamily<DescribeAllTerminal>>,
+		);
+
+		/// Means for transacting the native token on this chain.
+		pub type LocalAssetTransactor = FungibleAdapter<
+			// Use this currency:
+			Balances,
+			// Use this currency when it is a fungible asset matching the given location or name:
+			IsConcrete<SelfLocation>,
+			// Do a simple punn to convert an AccountId32 Location into a native chain account ID:
+			LocationToAccountId,
+			// Our chain's account ID type (we can't get away without mentioning it explicitly):
+			AccountId,
+			// We don't track any teleports.
+			(),
+		>;
+
+		/// Means for transacting the foreign assets registered in the asset registry.
+		pub type ForeignAssetsTransactor = FungiblesAdapter<
+			// Use this fungibles implementation:
+			ForeignAssets,
+			// Use this asset when its location is registered in the asset registry:
+			ConvertedConcreteId<crate::AssetId, Balance, LocationToAssetId<Runtime>, JustTry>,
+			// Convert a Location into a native chain account ID:
+			LocationToAccountId,
+			// Our chain's account ID type (we can't get away without mentioning it explicitly):
+			AccountId,
+			// We don't track any teleports of foreign assets.
+			NoChecking,
+			// The account used for teleports checking, unused.
+			CheckingAccount,
+		>;
+
+		/// Means for transacting assets on this chain.
+		pub type AssetTransactors = (LocalAssetTransactor, ForeignAssetsTransactor);
+
+		/// This is the type we use to convert an (incoming) XCM origin into a local `Origin`
+		/// instance, ready for dispatching a transaction with Xcm's `Transact`. There is an
+		/// `OriginKind` which can biases the kind of local `Origin` it will become.
+		pub type XcmOriginToTransactDispatchOrigin = (
+			// Sovereign account converter; this attempts to derive an `AccountId` from the origin
+			// location using `LocationToAccountId` and then turn that into the usual `Signed`
+			// origin. Useful for foreign chains who want to have a local sovereign account on
+			// this chain which they control.
+			SovereignSignedViaLocation<LocationToAccountId, RuntimeOrigin>,
+			// Native converter for Relay-chain (Parent) location; will converts to a `Relay`
+			// origin when recognised.
+			RelayChainAsNative<RelayChainOrigin, RuntimeOrigin>,
+			// Native converter for sibling Parachains; will convert to a `SiblingPara` origin when
+			// recognised.
+			SiblingParachainAsNative<cumulus_pallet_xcm::Origin, RuntimeOrigin>,
+			// Superuser converter for the Relay-chain (Parent) location. This will allow it to
+			// issue a transaction from the Root origin.
+			ParentAsSuperuser<RuntimeOrigin>,
+			// Xcm origins can be represented natively under the Xcm pallet's Xcm origin.
+			pallet_xcm::XcmPassthrough<RuntimeOrigin>,
+			SignedAccountKey20AsNative<RelayNetwork, RuntimeOrigin>,
+		);
+
+		parameter_types! {
+			// One XCM operation is 1_000_000_000 weight - almost certainly a conservative estimate.
+			pub UnitWeightCost: Weight = Weight::from_parts(1_000_000_000, 64 * 1024);
+			pub const MaxInstructions: u32 = 100;
+			pub const MaxAssetsIntoHolding: u32 = 64;
+		}
+
+		pub struct ParentOrParentsExecutivePlurality;
+		impl Contains<Location> for ParentOrParentsExecutivePlurality {
+			fn contains(location: &Location) -> bool {
+				matches!(
+					location.unpack(),
+					(1, []) | (1, [Plurality { id: BodyId::Executive, .. }])
+				)
+			}
+		}
+
+		pub type Barrier = TrailingSetTopicAsId<
+			DenyThenTry<
+				DenyReserveTransferToRelayChain,
+				(
+					TakeWeightCredit,
+					WithComputedOrigin<
+						(
+							AllowTopLevelPaidExecutionFrom<Everything>,
+							AllowExplicitUnpaidExecutionFrom<ParentOrParentsExecutivePlurality>,
+							// ^^^ Parent and its exec plurality get free execution
+						),
+						UniversalLocation,
+						ConstU32<8>,
+					>,
+				),
+			>,
+		>;
+
+		/// Dispatches the calls of XCM `Transact`. The `EthereumXcm` calls of a signed origin are
+		/// given the Ethereum transaction origin of the signer, so they are executed in the EVM as
+		/// sent by it.
+		pub struct FerrumCall;
+		impl CallDispatcher<RuntimeCall> for FerrumCall {
+			fn dispatch(
+				call: RuntimeCall,
+				origin: RuntimeOrigin,
+			) -> Result<PostDispatchInfo, DispatchErrorWithPostInfo<PostDispatchInfo>> {
+				if let Ok(frame_system::RawOrigin::Signed(account_id)) =
+					frame_system::RawOrigin::<AccountId>::try_from(origin.caller().clone())
+				{
+					if let RuntimeCall::EthereumXcm(
+						pallet_ethereum_xcm::Call::transact { .. } |
+						pallet_ethereum_xcm::Call::transact_through_proxy { .. },
+					) = call
+					{
+						return RuntimeCall::dispatch(
+							call,
+							pallet_ethereum_xcm::RawOrigin::XcmEthereumTransaction(
+								account_id.into(),
+							)
+							.into(),
+						);
+					}
+				}
+				RuntimeCall::dispatch(call, origin)
+			}
+		}
+
+		/// Deposits the xcm execution fees paid in foreign assets to the xcm fees account.
+		pub struct XcmFeesToAccount;
+		impl TakeRevenue for XcmFeesToAccount {
+			fn take_revenue(revenue: Asset) {
+				let account: AccountId = XcmFeesPalletId::get().into_account_truncating();
+				let beneficiary: Location =
+					AccountKey20 { network: None, key: account.into() }.into();
+				if let Err(error) = AssetTransactors::deposit_asset(&revenue, &beneficiary, None) {
+					log::warn!(target: "xcm::fees", "Failed to deposit xcm fees {:?}: {:?}", revenue, error);
+				}
+			}
+		}
+
+		/// The xcm execution fees are paid in FRM, at the rate of the transaction fees, or in any
+		/// asset with units per second set in the asset registry.
+		pub type Trader = (
+			UsingComponents<WeightToFee, SelfLocation, AccountId, Balances, ToAuthor<Runtime>>,
+			UnitsPerSecondTrader<Runtime, XcmFeesToAccount>,
+		);
+
+		pub struct XcmConfig;
+		impl xcm_executor::Config for XcmConfig {
+			type RuntimeCall = RuntimeCall;
+			type XcmSender = XcmRouter;
+			// How to withdraw and deposit an asset.
+			type AssetTransactor = AssetTransactors;
+			type OriginConverter = XcmOriginToTransactDispatchOrigin;
+			type IsReserve = RegisteredReserveAssets<Runtime>;
+			type IsTeleporter = (); // Teleporting is disabled.
+			type UniversalLocation = UniversalLocation;
+			type Barrier = Barrier;
+			type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+			type Trader = Trader;
+			type ResponseHandler = PolkadotXcm;
+			type AssetTrap = PolkadotXcm;
+			type AssetClaims = PolkadotXcm;
+			type SubscriptionService = PolkadotXcm;
+			type PalletInstancesInfo = AllPalletsWithSystem;
+			type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+			type AssetLocker = ();
+			type AssetExchanger = ();
+			type FeeManager = ();
+			type MessageExporter = ();
+			type UniversalAliases = Nothing;
+			type CallDispatcher = FerrumCall;
+			type SafeCallFilter = Everything;
+			type Aliasers = Nothing;
+			type TransactionalProcessor = FrameTransactionalProcessor;
+			type HrmpNewChannelOpenRequestHandler = ();
+			type HrmpChannelAcceptedHandler = ();
+			type HrmpChannelClosingHandler = ();
+		}
+
+		// Convert a local Origin (i.e., a signed 20 byte account Origin)  to a Multilocation
+		pub struct SignedToAccountId20<Origin, AccountId, Network>(
+			sp_std::marker::PhantomData<(Origin, AccountId, Network)>,
+		);
+		impl<Origin: OriginTrait + Clone, AccountId: Into<[u8; 20]>, Network: Get<NetworkId>>
+			TryConvert<Origin, Location> for SignedToAccountId20<Origin, AccountId, Network>
+		where
+			Origin::PalletsOrigin: From<frame_system::RawOrigin<AccountId>>
+				+ TryInto<frame_system::RawOrigin<AccountId>, Error = Origin::PalletsOrigin>,
+		{
+			fn try_convert(o: Origin) -> Result<Location, Origin> {
+				o.try_with_caller(|caller| match caller.try_into() {
+					Ok(frame_system::RawOrigin::Signed(who)) =>
+						Ok(AccountKey20 { key: who.into(), network: Some(Network::get()) }.into()),
+					Ok(other) => Err(other.into()),
+					Err(other) => Err(other),
+				})
+			}
+		}
+
+		// Converts a Signed Local Origin into a Location
+		pub type LocalOriginToLocation =
+			SignedToAccountId20<RuntimeOrigin, AccountId, RelayNetwork>;
+
+		/// The means for routing XCM messages which are not for local execution into the right
+		/// message queues.
+		pub type XcmRouter = WithUniqueTopic<(
+			// Two routers - use UMP to communicate with the relay chain:
+			cumulus_primitives_utility::ParentAsUmp<ParachainSystem, (), ()>,
+			// ..and XCMP to communicate with the sibling chains.
+			XcmpQueue,
+		)>;
+
+		impl pallet_xcm::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type SendXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+			type XcmRouter = XcmRouter;
+			type ExecuteXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+			type XcmExecuteFilter = Nothing;
+			// ^ Disable dispatchable execute on the XCM pallet.
+			// Needs to be `Everything` for local testing.
+			type XcmExecutor = XcmExecutor<XcmConfig>;
+			type XcmTeleportFilter = Everything;
+			type XcmReserveTransferFilter = Everything;
+			type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+			type UniversalLocation = UniversalLocation;
+			type RuntimeOrigin = RuntimeOrigin;
+			type RuntimeCall = RuntimeCall;
+
+			const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
+			// ^ Override for AdvertisedXcmVersion default
+			type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+			type Currency = Balances;
+			type CurrencyMatcher = ();
+			type TrustedLockers = ();
+			type SovereignAccountOf = LocationToAccountId;
+			type MaxLockers = ConstU32<8>;
+			type WeightInfo = pallet_xcm::TestWeightInfo;
+			type AdminOrigin = GeneralAdminOrigin;
+			type MaxRemoteLockConsumers = ConstU32<0>;
+			type RemoteLockConsumerIdentifier = ();
+		}
+
+		impl cumulus_pallet_xcm::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type XcmExecutor = XcmExecutor<XcmConfig>;
+		}
+	};
+}
@@ -0,0 +1,13 @@
+//! Constants of the Ferrum Kusama network, parameterizing the configuration shared by the
+//! Ferrum runtimes.
+
+use ferrum_runtime_common::impls::FeeSplit;
+
+/// The EVM chain id.
+pub const CHAIN_ID: u64 = 26000;
+
+/// Split of the substrate transaction fees, tips going entirely to the collator.
+pub const TRANSACTION_FEES_SPLIT: FeeSplit = FeeSplit::new(50, 0);
+
+/// Split of the EVM base fees, priority fees going entirely to the collator.
+pub const EVM_BASE_FEES_SPLIT: FeeSplit = FeeSplit::new(50, 50);
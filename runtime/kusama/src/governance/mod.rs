@@ -0,0 +1,215 @@
+//! On-chain governance of the runtime.
+//!
+//! Referenda (`pallet_referenda`) are voted with conviction (`pallet_conviction_voting`) on the
+//! tracks of [`tracks`], each of them dispatching either root or one of the custom [`origins`].
+//! The technical committee (`pallet_collective`) whitelists the calls (`pallet_whitelist`) to be
+//! fast-tracked on the `whitelisted_caller` track, and can pause transactions in an emergency.
+//! Runtime upgrades are authorized on the `runtime_upgrade` track with
+//! [`origins::Call::authorize_upgrade`], then applied by anyone with
+//! `System::apply_authorized_upgrade`.
+//!
+//! # Removing sudo
+//!
+//! Every admin origin still accepts root, so `pallet_sudo` keeps working while the governance is
+//! being bootstrapped. Sudo is then removed by a runtime upgrade dropping `Sudo` from
+//! `construct_runtime!` and adding [`crate::migrations::RemoveSudo`] to the `Migrations`, after
+//! which root is only reachable through the `root` and `whitelisted_caller` tracks.
+
+pub mod origins;
+mod tracks;
+
+pub use origins::{
+	FinalizerAdmin, GeneralAdmin, Origin as CustomOrigin, PauseTransactions, RuntimeUpgrade,
+	Treasurer, WhitelistedCaller,
+};
+pub use tracks::TracksInfo;
+
+use crate::{
+	AccountId, Balance, Balances, BlockNumber, OriginCaller, Preimage, Referenda, Runtime,
+	RuntimeBlockWeights, RuntimeCall, RuntimeEvent, RuntimeHoldReason, RuntimeOrigin, Scheduler,
+	Treasury, DAYS, MILLIUNIT, UNIT,
+};
+use frame_support::{
+	parameter_types,
+	traits::{
+		fungible::HoldConsideration, tokens::currency::ActiveIssuanceOf, ConstU32, Contains,
+		EitherOf, EitherOfDiverse, EqualPrivilegeOnly, LinearStoragePrice,
+	},
+	weights::Weight,
+};
+use frame_system::{EnsureRoot, EnsureRootWithSuccess, EnsureSigned, EnsureWithSuccess};
+use sp_runtime::Perbill;
+
+/// The technical committee.
+pub type TechnicalCollective = pallet_collective::Instance1;
+
+/// At least two thirds of the technical committee.
+pub type TechnicalCommitteeTwoThirds =
+	pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 2, 3>;
+
+/// Origin of the general admin operations: asset and XCM management, collator selection.
+pub type GeneralAdminOrigin = EitherOf<EnsureRoot<AccountId>, GeneralAdmin>;
+
+/// Origin allowed to pause and unpause transactions, which the technical committee can also do
+/// in an emergency.
+pub type PauserOrigin = EitherOfDiverse<
+	EitherOf<EnsureRoot<AccountId>, PauseTransactions>,
+	TechnicalCommitteeTwoThirds,
+>;
+
+/// Origin allowed to manage the Quantum Portal finalizers.
+pub type FinalizerAdminOrigin = EitherOf<EnsureRoot<AccountId>, FinalizerAdmin>;
+
+/// Origin allowed to authorize runtime upgrades.
+pub type RuntimeUpgradeOrigin = EitherOf<EnsureRoot<AccountId>, RuntimeUpgrade>;
+
+parameter_types! {
+	pub const MaxBalance: Balance = Balance::MAX;
+	pub const MaxTreasurerSpend: Balance = 1_000_000 * UNIT;
+	/// Pallets whose calls may not be paused, the governance being needed to lift a pause.
+	pub const UnpausablePallets: &'static [&'static str] = &[
+		"System",
+		"Sudo",
+		"Scheduler",
+		"Preimage",
+		"ConvictionVoting",
+		"Referenda",
+		"Origins",
+		"Whitelist",
+		"TechnicalCommittee",
+	];
+}
+
+/// Origin allowed to approve and reject treasury spend proposals.
+pub type TreasurerOrigin = EitherOf<EnsureRoot<AccountId>, Treasurer>;
+
+/// Origin allowed to spend from the treasury, up to the returned amount.
+pub type TreasurySpendOrigin = EitherOf<
+	EnsureRootWithSuccess<AccountId, MaxBalance>,
+	EnsureWithSuccess<Treasurer, AccountId, MaxTreasurerSpend>,
+>;
+
+/// Calls of the governance, which the governance proxies are allowed to make.
+pub struct GovernanceCalls;
+impl Contains<RuntimeCall> for GovernanceCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::Referenda(..) |
+				RuntimeCall::ConvictionVoting(..) |
+				RuntimeCall::Preimage(..) |
+				RuntimeCall::Whitelist(..) |
+				RuntimeCall::TechnicalCommittee(..) |
+				RuntimeCall::Treasury(..)
+		)
+	}
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * RuntimeBlockWeights::get().max_block;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeEvent = RuntimeEvent;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = ConstU32<50>;
+	type WeightInfo = ();
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = 5 * UNIT;
+	pub const PreimageByteDeposit: Balance = MILLIUNIT;
+	pub const PreimageHoldReason: RuntimeHoldReason = RuntimeHoldReason::Preimage(pallet_preimage::HoldReason::Preimage);
+}
+
+impl pallet_preimage::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<AccountId>;
+	type Consideration = HoldConsideration<
+		AccountId,
+		Balances,
+		PreimageHoldReason,
+		LinearStoragePrice<PreimageBaseDeposit, PreimageByteDeposit, Balance>,
+	>;
+}
+
+parameter_types! {
+	pub const VoteLockingPeriod: BlockNumber = 7 * DAYS;
+}
+
+impl pallet_conviction_voting::Config for Runtime {
+	type WeightInfo = ();
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type Polls = Referenda;
+	type MaxTurnout = ActiveIssuanceOf<Balances, AccountId>;
+	type MaxVotes = ConstU32<512>;
+	type VoteLockingPeriod = VoteLockingPeriod;
+}
+
+parameter_types! {
+	pub const AlarmInterval: BlockNumber = 1;
+	pub const SubmissionDeposit: Balance = 100 * UNIT;
+	pub const UndecidingTimeout: BlockNumber = 14 * DAYS;
+}
+
+impl pallet_referenda::Config for Runtime {
+	type WeightInfo = ();
+	type RuntimeCall = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type Scheduler = Scheduler;
+	type Currency = Balances;
+	type SubmitOrigin = EnsureSigned<AccountId>;
+	type CancelOrigin = EitherOfDiverse<EnsureRoot<AccountId>, TechnicalCommitteeTwoThirds>;
+	type KillOrigin = EnsureRoot<AccountId>;
+	type Slash = Treasury;
+	type Votes = pallet_conviction_voting::VotesOf<Runtime>;
+	type Tally = pallet_conviction_voting::TallyOf<Runtime>;
+	type SubmissionDeposit = SubmissionDeposit;
+	type MaxQueued = ConstU32<100>;
+	type UndecidingTimeout = UndecidingTimeout;
+	type AlarmInterval = AlarmInterval;
+	type Tracks = TracksInfo;
+	type Preimages = Preimage;
+}
+
+impl origins::pallet_custom_origins::Config for Runtime {
+	type RuntimeUpgradeOrigin = RuntimeUpgradeOrigin;
+}
+
+impl pallet_whitelist::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type WhitelistOrigin = EitherOfDiverse<EnsureRoot<AccountId>, TechnicalCommitteeTwoThirds>;
+	type DispatchWhitelistedOrigin = EitherOf<EnsureRoot<AccountId>, WhitelistedCaller>;
+	type Preimages = Preimage;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const TechnicalMotionDuration: BlockNumber = 3 * DAYS;
+	pub const TechnicalMaxProposals: u32 = 100;
+	pub const TechnicalMaxMembers: u32 = 100;
+	pub MaxProposalWeight: Weight = Perbill::from_percent(50) * RuntimeBlockWeights::get().max_block;
+}
+
+impl pallet_collective::Config<TechnicalCollective> for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Proposal = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type MotionDuration = TechnicalMotionDuration;
+	type MaxProposals = TechnicalMaxProposals;
+	type MaxMembers = TechnicalMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = ();
+	type SetMembersOrigin = GeneralAdminOrigin;
+	type MaxProposalWeight = MaxProposalWeight;
+}
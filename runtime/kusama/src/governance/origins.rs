@@ -0,0 +1,80 @@
+//! Custom origins of the referenda tracks.
+
+pub use pallet_custom_origins::*;
+
+#[frame_support::pallet]
+pub mod pallet_custom_origins {
+	use frame_support::pallet_prelude::*;
+	use frame_system::{pallet_prelude::*, WeightInfo};
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Origin allowed to authorize runtime upgrades.
+		type RuntimeUpgradeOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The origins dispatched by the referenda of the custom tracks.
+	#[derive(PartialEq, Eq, Clone, MaxEncodedLen, Encode, Decode, TypeInfo, RuntimeDebug)]
+	#[pallet::origin]
+	pub enum Origin {
+		/// Origin able to dispatch a call whitelisted by the technical committee.
+		WhitelistedCaller,
+		/// Origin for the general admin operations: asset and XCM management, collator selection.
+		GeneralAdmin,
+		/// Origin able to pause and unpause transactions.
+		PauseTransactions,
+		/// Origin able to manage the Quantum Portal finalizers.
+		FinalizerAdmin,
+		/// Origin able to approve treasury spends, such as the funding of the Quantum Portal
+		/// miners and finalizers.
+		Treasurer,
+		/// Origin able to authorize runtime upgrades.
+		RuntimeUpgrade,
+	}
+
+	macro_rules! decl_unit_ensures {
+		( $($name:ident),* $(,)? ) => {
+			$(
+				#[doc = concat!("Ensures the `", stringify!($name), "` origin.")]
+				pub struct $name;
+				impl<O: Into<Result<Origin, O>> + From<Origin>> EnsureOrigin<O> for $name {
+					type Success = ();
+					fn try_origin(o: O) -> Result<Self::Success, O> {
+						o.into().and_then(|o| match o {
+							Origin::$name => Ok(()),
+							r => Err(O::from(r)),
+						})
+					}
+					#[cfg(feature = "runtime-benchmarks")]
+					fn try_successful_origin() -> Result<O, ()> {
+						Ok(O::from(Origin::$name))
+					}
+				}
+			)*
+		};
+	}
+	decl_unit_ensures!(
+		WhitelistedCaller,
+		GeneralAdmin,
+		PauseTransactions,
+		FinalizerAdmin,
+		Treasurer,
+		RuntimeUpgrade,
+	);
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Authorizes an upgrade to the runtime code of the given hash, which anyone can then
+		/// apply with `System::apply_authorized_upgrade`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::SystemWeightInfo::authorize_upgrade())]
+		pub fn authorize_upgrade(origin: OriginFor<T>, code_hash: T::Hash) -> DispatchResult {
+			T::RuntimeUpgradeOrigin::ensure_origin(origin)?;
+			frame_system::Pallet::<T>::do_authorize_upgrade(code_hash, true);
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,174 @@
+//! Tracks of the referenda.
+//!
+//! - `root`: any call requiring the root origin;
+//! - `whitelisted_caller`: calls whitelisted by the technical committee, dispatched as root;
+//! - `general_admin`: asset and XCM management, collator selection;
+//! - `pause_transactions`: pausing and unpausing transactions;
+//! - `finalizer_admin`: management of the Quantum Portal finalizers;
+//! - `treasurer`: treasury spends;
+//! - `runtime_upgrade`: authorization of runtime upgrades.
+
+use super::origins;
+use crate::{Balance, BlockNumber, RuntimeOrigin, DAYS, HOURS, MINUTES, UNIT};
+use pallet_referenda::Curve;
+use sp_runtime::FixedI64;
+
+const fn percent(x: i32) -> FixedI64 {
+	FixedI64::from_rational(x as u128, 100)
+}
+
+const APP_ROOT: Curve = Curve::make_reciprocal(4, 14, percent(80), percent(50), percent(100));
+const SUP_ROOT: Curve = Curve::make_linear(14, 14, percent(0), percent(50));
+const APP_WHITELISTED_CALLER: Curve =
+	Curve::make_reciprocal(1, 14, percent(96), percent(50), percent(100));
+const SUP_WHITELISTED_CALLER: Curve =
+	Curve::make_reciprocal(1, 14, percent(20), percent(1), percent(50));
+const APP_GENERAL_ADMIN: Curve =
+	Curve::make_reciprocal(4, 14, percent(80), percent(50), percent(100));
+const SUP_GENERAL_ADMIN: Curve =
+	Curve::make_reciprocal(7, 14, percent(10), percent(0), percent(50));
+const APP_PAUSE_TRANSACTIONS: Curve =
+	Curve::make_reciprocal(1, 14, percent(80), percent(50), percent(100));
+const SUP_PAUSE_TRANSACTIONS: Curve =
+	Curve::make_reciprocal(1, 14, percent(5), percent(0), percent(50));
+const APP_FINALIZER_ADMIN: Curve =
+	Curve::make_reciprocal(4, 14, percent(80), percent(50), percent(100));
+const SUP_FINALIZER_ADMIN: Curve =
+	Curve::make_reciprocal(7, 14, percent(10), percent(0), percent(50));
+const APP_TREASURER: Curve = Curve::make_reciprocal(4, 14, percent(80), percent(50), percent(100));
+const SUP_TREASURER: Curve = Curve::make_linear(14, 14, percent(0), percent(50));
+const APP_RUNTIME_UPGRADE: Curve =
+	Curve::make_reciprocal(4, 14, percent(80), percent(50), percent(100));
+const SUP_RUNTIME_UPGRADE: Curve = Curve::make_linear(14, 14, percent(0), percent(50));
+
+const TRACKS_DATA: [(u16, pallet_referenda::TrackInfo<Balance, BlockNumber>); 7] = [
+	(
+		0,
+		pallet_referenda::TrackInfo {
+			name: "root",
+			max_deciding: 5,
+			decision_deposit: 100_000 * UNIT,
+			prepare_period: DAYS,
+			decision_period: 14 * DAYS,
+			confirm_period: DAYS,
+			min_enactment_period: DAYS,
+			min_approval: APP_ROOT,
+			min_support: SUP_ROOT,
+		},
+	),
+	(
+		1,
+		pallet_referenda::TrackInfo {
+			name: "whitelisted_caller",
+			max_deciding: 100,
+			decision_deposit: 10_000 * UNIT,
+			prepare_period: 10 * MINUTES,
+			decision_period: 14 * DAYS,
+			confirm_period: 10 * MINUTES,
+			min_enactment_period: 30 * MINUTES,
+			min_approval: APP_WHITELISTED_CALLER,
+			min_support: SUP_WHITELISTED_CALLER,
+		},
+	),
+	(
+		2,
+		pallet_referenda::TrackInfo {
+			name: "general_admin",
+			max_deciding: 10,
+			decision_deposit: 500 * UNIT,
+			prepare_period: HOURS,
+			decision_period: 14 * DAYS,
+			confirm_period: DAYS,
+			min_enactment_period: DAYS,
+			min_approval: APP_GENERAL_ADMIN,
+			min_support: SUP_GENERAL_ADMIN,
+		},
+	),
+	(
+		3,
+		pallet_referenda::TrackInfo {
+			name: "pause_transactions",
+			max_deciding: 20,
+			decision_deposit: 1_000 * UNIT,
+			prepare_period: 10 * MINUTES,
+			decision_period: 14 * DAYS,
+			confirm_period: HOURS,
+			min_enactment_period: 10 * MINUTES,
+			min_approval: APP_PAUSE_TRANSACTIONS,
+			min_support: SUP_PAUSE_TRANSACTIONS,
+		},
+	),
+	(
+		4,
+		pallet_referenda::TrackInfo {
+			name: "finalizer_admin",
+			max_deciding: 10,
+			decision_deposit: 500 * UNIT,
+			prepare_period: HOURS,
+			decision_period: 14 * DAYS,
+			confirm_period: DAYS,
+			min_enactment_period: DAYS,
+			min_approval: APP_FINALIZER_ADMIN,
+			min_support: SUP_FINALIZER_ADMIN,
+		},
+	),
+	(
+		5,
+		pallet_referenda::TrackInfo {
+			name: "treasurer",
+			max_deciding: 10,
+			decision_deposit: 1_000 * UNIT,
+			prepare_period: HOURS,
+			decision_period: 14 * DAYS,
+			confirm_period: DAYS,
+			min_enactment_period: DAYS,
+			min_approval: APP_TREASURER,
+			min_support: SUP_TREASURER,
+		},
+	),
+	(
+		6,
+		pallet_referenda::TrackInfo {
+			name: "runtime_upgrade",
+			max_deciding: 5,
+			decision_deposit: 100_000 * UNIT,
+			prepare_period: DAYS,
+			decision_period: 14 * DAYS,
+			confirm_period: DAYS,
+			min_enactment_period: DAYS,
+			min_approval: APP_RUNTIME_UPGRADE,
+			min_support: SUP_RUNTIME_UPGRADE,
+		},
+	),
+];
+
+pub struct TracksInfo;
+impl pallet_referenda::TracksInfo<Balance, BlockNumber> for TracksInfo {
+	type Id = u16;
+	type RuntimeOrigin = <RuntimeOrigin as frame_support::traits::OriginTrait>::PalletsOrigin;
+
+	fn tracks() -> &'static [(Self::Id, pallet_referenda::TrackInfo<Balance, BlockNumber>)] {
+		&TRACKS_DATA[..]
+	}
+
+	fn track_for(id: &Self::RuntimeOrigin) -> Result<Self::Id, ()> {
+		if let Ok(system_origin) = frame_system::RawOrigin::try_from(id.clone()) {
+			match system_origin {
+				frame_system::RawOrigin::Root => Ok(0),
+				_ => Err(()),
+			}
+		} else if let Ok(custom_origin) = origins::Origin::try_from(id.clone()) {
+			match custom_origin {
+				origins::Origin::WhitelistedCaller => Ok(1),
+				origins::Origin::GeneralAdmin => Ok(2),
+				origins::Origin::PauseTransactions => Ok(3),
+				origins::Origin::FinalizerAdmin => Ok(4),
+				origins::Origin::Treasurer => Ok(5),
+				origins::Origin::RuntimeUpgrade => Ok(6),
+			}
+		} else {
+			Err(())
+		}
+	}
+}
+pallet_referenda::impl_tracksinfo_get!(TracksInfo, Balance, BlockNumber);
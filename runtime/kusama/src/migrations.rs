@@ -0,0 +1,15 @@
+//! Migrations run on runtime upgrade.
+
+use crate::Runtime;
+use frame_support::{parameter_types, weights::constants::RocksDbWeight};
+
+parameter_types! {
+	pub const SudoPalletName: &'static str = "Sudo";
+}
+
+/// Removes the storage of `pallet_sudo`. To be added to the [`Migrations`] of the runtime upgrade
+/// dropping `Sudo` from `construct_runtime!`, once the governance has taken over.
+pub type RemoveSudo = frame_support::migrations::RemovePallet<SudoPalletName, RocksDbWeight>;
+
+/// Migrations run on runtime upgrade.
+pub type Migrations = (pallet_transaction_pauser::migrations::v1::MigrateToV1<Runtime>,);
@@ -0,0 +1,101 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+#![cfg(test)]
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::traits::Dispatchable;
+
+const ALICE: [u8; 20] = [0xAA; 20];
+const BOB: [u8; 20] = [0xBB; 20];
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::<Runtime>::default()
+		.build_storage()
+		.expect("Frame system builds valid default genesis config");
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn pause(pallet_name: &[u8]) -> DispatchResult {
+	TransactionPauser::pause_transaction(
+		RuntimeOrigin::root(),
+		pallet_name.to_vec(),
+		None,
+		None,
+		vec![],
+	)
+}
+
+fn is_filtered(call: &RuntimeCall) -> bool {
+	!<Runtime as frame_system::Config>::BaseCallFilter::contains(call)
+}
+
+#[test]
+fn paused_calls_fail_to_dispatch() {
+	new_test_ext().execute_with(|| {
+		let transfer = RuntimeCall::Balances(pallet_balances::Call::transfer_allow_death {
+			dest: BOB.into(),
+			value: 0,
+		});
+		assert!(!is_filtered(&transfer));
+
+		assert_ok!(pause(b"Balances"));
+		assert!(is_filtered(&transfer));
+		assert_noop!(
+			transfer.dispatch(RuntimeOrigin::signed(ALICE.into())),
+			frame_system::Error::<Runtime>::CallFiltered
+		);
+	});
+}
+
+#[test]
+fn core_calls_are_never_filtered() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(pause(b"Timestamp"));
+		assert_ok!(pause(b"ParachainSystem"));
+
+		assert!(!is_filtered(&RuntimeCall::Timestamp(pallet_timestamp::Call::set { now: 0 })));
+		assert!(!is_filtered(&RuntimeCall::ParachainSystem(
+			cumulus_pallet_parachain_system::Call::sudo_send_upward_message { message: vec![] }
+		)));
+		assert_noop!(
+			pause(b"System"),
+			pallet_transaction_pauser::Error::<Runtime>::InvalidPalletName
+		);
+		assert_ok!(RuntimeCall::System(frame_system::Call::remark { remark: vec![] })
+			.dispatch(RuntimeOrigin::signed(ALICE.into())));
+	});
+}
+
+#[test]
+fn governance_calls_still_dispatch() {
+	new_test_ext().execute_with(|| {
+		for pallet_name in UnpausablePallets::get() {
+			assert_noop!(
+				pause(pallet_name.as_bytes()),
+				pallet_transaction_pauser::Error::<Runtime>::InvalidPalletName
+			);
+		}
+
+		let unlock = RuntimeCall::ConvictionVoting(pallet_conviction_voting::Call::unlock {
+			class: 0,
+			target: ALICE.into(),
+		});
+		assert!(!is_filtered(&unlock));
+		assert_ok!(unlock.dispatch(RuntimeOrigin::signed(ALICE.into())));
+	});
+}
@@ -0,0 +1,34 @@
+//! Origins of the privileged operations of the testnet, which is governed by `pallet_sudo`.
+
+use crate::{AccountId, Balance, RuntimeCall};
+use frame_support::{parameter_types, traits::Contains};
+use frame_system::{EnsureRoot, EnsureRootWithSuccess};
+
+/// Origin of the general admin operations: asset and XCM management, collator selection.
+pub type GeneralAdminOrigin = EnsureRoot<AccountId>;
+
+/// Origin allowed to pause and unpause transactions.
+pub type PauserOrigin = EnsureRoot<AccountId>;
+
+/// Origin allowed to manage the Quantum Portal finalizers.
+pub type FinalizerAdminOrigin = EnsureRoot<AccountId>;
+
+parameter_types! {
+	pub const MaxBalance: Balance = Balance::MAX;
+	/// Pallets whose calls may not be paused, `Sudo` being needed to lift a pause.
+	pub const UnpausablePallets: &'static [&'static str] = &["System", "Sudo"];
+}
+
+/// Origin allowed to approve and reject treasury spend proposals.
+pub type TreasurerOrigin = EnsureRoot<AccountId>;
+
+/// Origin allowed to spend from the treasury, up to the returned amount.
+pub type TreasurySpendOrigin = EnsureRootWithSuccess<AccountId, MaxBalance>;
+
+/// Calls of the governance, which the governance proxies are allowed to make.
+pub struct GovernanceCalls;
+impl Contains<RuntimeCall> for GovernanceCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(call, RuntimeCall::Treasury(..))
+	}
+}
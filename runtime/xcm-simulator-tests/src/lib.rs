@@ -0,0 +1,235 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! XCM tests of the Ferrum runtimes, on an `xcm-simulator` network made of a mock relay chain,
+//! the Kusama runtime as Ferrum and the testnet runtime as a sibling parachain.
+//!
+//! The parachains execute the messages they receive with their own `XcmConfig`. The messages sent
+//! by a parachain to a sibling are queued in its `XcmpQueue`, and delivered with [`deliver_xcmp`].
+use codec::{Decode, Encode};
+use cumulus_primitives_core::XcmpMessageSource;
+use frame_support::weights::Weight;
+use polkadot_parachain_primitives::primitives::XcmpMessageFormat;
+use sp_runtime::{AccountId32, BuildStorage};
+use std::{iter, marker::PhantomData};
+use xcm::{latest::prelude::*, VersionedXcm};
+use xcm_executor::XcmExecutor;
+use xcm_simulator::{
+	decl_test_network, decl_test_parachain, decl_test_relay_chain, DmpMessageHandlerT, ParaId,
+	RelayBlockNumber, TestExt, XcmpMessageHandlerT,
+};
+
+mod relay_chain;
+#[cfg(test)]
+mod tests;
+
+pub const FERRUM_PARA_ID: u32 = 2000;
+pub const SIBLING_PARA_ID: u32 = 2001;
+
+pub const RELAY_ALICE: AccountId32 = AccountId32::new([1u8; 32]);
+pub const ALICE: [u8; 20] = [0xAA; 20];
+pub const BOB: [u8; 20] = [0xBB; 20];
+
+pub const INITIAL_BALANCE: u128 = 1_000 * ferrum_primitives::UNIT;
+
+/// Executes the messages received by a parachain with the executor of `XcmConfig`, skipping its
+/// message queues.
+pub struct XcmMessageHandler<XcmConfig>(PhantomData<XcmConfig>);
+
+impl<XcmConfig: xcm_executor::Config> XcmMessageHandler<XcmConfig> {
+	fn execute(origin: Location, data: VersionedXcm<XcmConfig::RuntimeCall>, max_weight: Weight) {
+		let mut id = data.using_encoded(sp_io::hashing::blake2_256);
+		if let Ok(xcm) = Xcm::try_from(data) {
+			let _ = XcmExecutor::<XcmConfig>::prepare_and_execute(
+				origin,
+				xcm,
+				&mut id,
+				max_weight,
+				Weight::zero(),
+			);
+		}
+	}
+}
+
+impl<XcmConfig: xcm_executor::Config> XcmpMessageHandlerT for XcmMessageHandler<XcmConfig> {
+	fn handle_xcmp_messages<'a, I: Iterator<Item = (ParaId, RelayBlockNumber, &'a [u8])>>(
+		iter: I,
+		max_weight: Weight,
+	) -> Weight {
+		for (sender, _sent_at, mut data) in iter {
+			let format = XcmpMessageFormat::decode(&mut data)
+				.expect("The xcmp queue encodes the message format; qed");
+			assert_eq!(format, XcmpMessageFormat::ConcatenatedVersionedXcm);
+
+			while !data.is_empty() {
+				let xcm = VersionedXcm::<XcmConfig::RuntimeCall>::decode(&mut data)
+					.expect("The xcmp queue concatenates versioned xcms; qed");
+				Self::execute(Location::new(1, [Parachain(sender.into())]), xcm, max_weight);
+			}
+		}
+		max_weight
+	}
+}
+
+impl<XcmConfig: xcm_executor::Config> DmpMessageHandlerT for XcmMessageHandler<XcmConfig> {
+	fn handle_dmp_messages(
+		iter: impl Iterator<Item = (RelayBlockNumber, Vec<u8>)>,
+		max_weight: Weight,
+	) -> Weight {
+		for (_sent_at, data) in iter {
+			if let Ok(xcm) = VersionedXcm::<XcmConfig::RuntimeCall>::decode(&mut &data[..]) {
+				Self::execute(Location::parent(), xcm, max_weight);
+			}
+		}
+		max_weight
+	}
+}
+
+decl_test_parachain! {
+	pub struct Ferrum {
+		Runtime = ferrum_runtime::Runtime,
+		XcmpMessageHandler = XcmMessageHandler<ferrum_runtime::xcm_config::XcmConfig>,
+		DmpMessageHandler = XcmMessageHandler<ferrum_runtime::xcm_config::XcmConfig>,
+		new_ext = ferrum_ext(),
+	}
+}
+
+decl_test_parachain! {
+	pub struct Sibling {
+		Runtime = ferrum_testnet_runtime::Runtime,
+		XcmpMessageHandler = XcmMessageHandler<ferrum_testnet_runtime::xcm_config::XcmConfig>,
+		DmpMessageHandler = XcmMessageHandler<ferrum_testnet_runtime::xcm_config::XcmConfig>,
+		new_ext = sibling_ext(),
+	}
+}
+
+decl_test_relay_chain! {
+	pub struct Relay {
+		Runtime = relay_chain::Runtime,
+		RuntimeCall = relay_chain::RuntimeCall,
+		RuntimeEvent = relay_chain::RuntimeEvent,
+		XcmConfig = relay_chain::XcmConfig,
+		MessageQueue = relay_chain::MessageQueue,
+		System = relay_chain::System,
+		new_ext = relay_ext(),
+	}
+}
+
+decl_test_network! {
+	pub struct MockNet {
+		relay_chain = Relay,
+		parachains = vec![
+			(2000, Ferrum),
+			(2001, Sibling),
+		],
+	}
+}
+
+/// Builds the genesis storage shared by the parachain runtimes.
+fn para_storage<Runtime>(para_id: u32) -> sp_runtime::Storage
+where
+	Runtime: pallet_balances::Config<Balance = u128> + parachain_info::Config + pallet_xcm::Config,
+	Runtime::AccountId: From<[u8; 20]>,
+{
+	let mut t = frame_system::GenesisConfig::<Runtime>::default()
+		.build_storage()
+		.expect("Frame system builds valid default genesis config");
+
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(ALICE.into(), INITIAL_BALANCE), (BOB.into(), INITIAL_BALANCE)],
+	}
+	.assimilate_storage(&mut t)
+	.expect("Pallet balances storage can be assimilated");
+
+	parachain_info::GenesisConfig::<Runtime> { parachain_id: para_id.into(), ..Default::default() }
+		.assimilate_storage(&mut t)
+		.expect("Parachain info storage can be assimilated");
+
+	pallet_xcm::GenesisConfig::<Runtime> {
+		safe_xcm_version: Some(XCM_VERSION),
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.expect("Pallet xcm storage can be assimilated");
+
+	t
+}
+
+pub fn ferrum_ext() -> sp_io::TestExternalities {
+	use ferrum_runtime::{ParachainSystem, System};
+
+	let mut ext =
+		sp_io::TestExternalities::new(para_storage::<ferrum_runtime::Runtime>(FERRUM_PARA_ID));
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		ParachainSystem::open_outbound_hrmp_channel_for_benchmarks_or_tests(SIBLING_PARA_ID.into());
+	});
+	ext
+}
+
+pub fn sibling_ext() -> sp_io::TestExternalities {
+	use ferrum_testnet_runtime::{ParachainSystem, System};
+
+	let mut ext = sp_io::TestExternalities::new(para_storage::<ferrum_testnet_runtime::Runtime>(
+		SIBLING_PARA_ID,
+	));
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		ParachainSystem::open_outbound_hrmp_channel_for_benchmarks_or_tests(FERRUM_PARA_ID.into());
+	});
+	ext
+}
+
+pub fn relay_ext() -> sp_io::TestExternalities {
+	use relay_chain::{Runtime, System};
+
+	let mut t = frame_system::GenesisConfig::<Runtime>::default()
+		.build_storage()
+		.expect("Frame system builds valid default genesis config");
+
+	pallet_balances::GenesisConfig::<Runtime> { balances: vec![(RELAY_ALICE, INITIAL_BALANCE)] }
+		.assimilate_storage(&mut t)
+		.expect("Pallet balances storage can be assimilated");
+
+	pallet_xcm::GenesisConfig::<Runtime> {
+		safe_xcm_version: Some(XCM_VERSION),
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.expect("Pallet xcm storage can be assimilated");
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Delivers the messages queued in the `XcmpQueue` of `Source`, the parachain `source`, to the
+/// sibling parachains.
+pub fn deliver_xcmp<Source: TestExt, Queue: XcmpMessageSource>(source: u32) {
+	let messages = Source::execute_with(|| Queue::take_outbound_messages(usize::MAX));
+	for (recipient, data) in messages {
+		let message = (ParaId::from(source), 1, &data[..]);
+		match u32::from(recipient) {
+			FERRUM_PARA_ID => {
+				Ferrum::handle_xcmp_messages(iter::once(message), Weight::MAX);
+			},
+			SIBLING_PARA_ID => {
+				Sibling::handle_xcmp_messages(iter::once(message), Weight::MAX);
+			},
+			_ => panic!("no parachain {recipient:?} on the network"),
+		}
+	}
+}
@@ -0,0 +1,217 @@
+// Copyright 2019-2024 Ferrum Inc.
+// This file is part of Ferrum.
+
+// Ferrum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ferrum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ferrum.  If not, see <http://www.gnu.org/licenses/>.
+use super::*;
+use ferrum_primitives::{AccountId, UNIT};
+use frame_support::assert_ok;
+use pallet_asset_registry::AssetMetadata;
+use sp_runtime::traits::AccountIdConversion;
+use xcm_executor::traits::ConvertLocation;
+
+const KSM: u128 = 1_000_000_000_000;
+/// Id of KSM in the foreign assets of Ferrum.
+const KSM_ASSET_ID: u128 = 1;
+/// Id of FRM in the foreign assets of the sibling.
+const FRM_ASSET_ID: u128 = 1;
+/// One unit of asset per picosecond of execution.
+const UNITS_PER_SECOND: u128 = 1_000_000_000_000;
+
+fn ferrum_location() -> Location {
+	Location::new(1, [Parachain(FERRUM_PARA_ID)])
+}
+
+fn account_location(key: [u8; 20]) -> Location {
+	Location::new(0, [AccountKey20 { network: None, key }])
+}
+
+fn xcm_fees_account() -> AccountId {
+	ferrum_runtime::xcm_config::XcmFeesPalletId::get().into_account_truncating()
+}
+
+fn register_ksm_on_ferrum(units_per_second: Option<u128>) {
+	use ferrum_runtime::{AssetRegistry, RuntimeOrigin};
+
+	Ferrum::execute_with(|| {
+		assert_ok!(AssetRegistry::register_foreign_asset(
+			RuntimeOrigin::root(),
+			Box::new(Location::parent().into()),
+			KSM_ASSET_ID,
+			AssetMetadata { name: b"Kusama".to_vec(), symbol: b"KSM".to_vec(), decimals: 12 },
+			1,
+			true
+		));
+		if let Some(units_per_second) = units_per_second {
+			assert_ok!(AssetRegistry::set_units_per_second(
+				RuntimeOrigin::root(),
+				Box::new(Location::parent().into()),
+				units_per_second
+			));
+		}
+	});
+}
+
+fn register_frm_on_sibling() {
+	use ferrum_testnet_runtime::{AssetRegistry, RuntimeOrigin};
+
+	Sibling::execute_with(|| {
+		assert_ok!(AssetRegistry::register_foreign_asset(
+			RuntimeOrigin::root(),
+			Box::new(ferrum_location().into()),
+			FRM_ASSET_ID,
+			AssetMetadata { name: b"Ferrum".to_vec(), symbol: b"FRM".to_vec(), decimals: 18 },
+			1,
+			true
+		));
+		assert_ok!(AssetRegistry::set_units_per_second(
+			RuntimeOrigin::root(),
+			Box::new(ferrum_location().into()),
+			UNITS_PER_SECOND
+		));
+	});
+}
+
+fn reserve_transfer_ksm_to_ferrum(amount: u128) {
+	use relay_chain::{RuntimeOrigin, XcmPallet};
+
+	Relay::execute_with(|| {
+		assert_ok!(XcmPallet::limited_reserve_transfer_assets(
+			RuntimeOrigin::signed(RELAY_ALICE),
+			Box::new(Location::new(0, [Parachain(FERRUM_PARA_ID)]).into()),
+			Box::new(account_location(ALICE).into()),
+			Box::new(Asset::from((Here, amount)).into()),
+			0,
+			WeightLimit::Unlimited,
+		));
+	});
+}
+
+#[test]
+fn registered_ksm_is_received_from_the_relay_chain() {
+	MockNet::reset();
+	register_ksm_on_ferrum(Some(UNITS_PER_SECOND));
+
+	reserve_transfer_ksm_to_ferrum(KSM);
+
+	Relay::execute_with(|| {
+		let ferrum_sovereign: relay_chain::AccountId =
+			ParaId::from(FERRUM_PARA_ID).into_account_truncating();
+		assert_eq!(relay_chain::Balances::free_balance(RELAY_ALICE), INITIAL_BALANCE - KSM);
+		assert_eq!(relay_chain::Balances::free_balance(ferrum_sovereign), KSM);
+	});
+
+	Ferrum::execute_with(|| {
+		use ferrum_runtime::ForeignAssets;
+
+		let received = ForeignAssets::balance(KSM_ASSET_ID, AccountId::from(ALICE));
+		let fees = ForeignAssets::balance(KSM_ASSET_ID, xcm_fees_account());
+		assert!(fees > 0);
+		assert_eq!(received + fees, KSM);
+	});
+}
+
+#[test]
+fn unregistered_ksm_is_rejected() {
+	MockNet::reset();
+
+	reserve_transfer_ksm_to_ferrum(KSM);
+
+	Ferrum::execute_with(|| {
+		use ferrum_runtime::ForeignAssets;
+
+		assert_eq!(ForeignAssets::balance(KSM_ASSET_ID, AccountId::from(ALICE)), 0);
+		assert_eq!(ForeignAssets::balance(KSM_ASSET_ID, xcm_fees_account()), 0);
+	});
+}
+
+#[test]
+fn fees_cannot_be_paid_in_assets_without_units_per_second() {
+	MockNet::reset();
+	register_ksm_on_ferrum(None);
+
+	reserve_transfer_ksm_to_ferrum(KSM);
+
+	Ferrum::execute_with(|| {
+		use ferrum_runtime::{ForeignAssets, RuntimeEvent, System};
+
+		assert_eq!(ForeignAssets::balance(KSM_ASSET_ID, AccountId::from(ALICE)), 0);
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::PolkadotXcm(pallet_xcm::Event::AssetsTrapped { .. })
+		)));
+	});
+}
+
+#[test]
+fn frm_is_reserve_transferred_to_sibling_and_back() {
+	MockNet::reset();
+	register_frm_on_sibling();
+
+	let amount = 100 * UNIT;
+	let sibling_sovereign = ferrum_runtime::xcm_config::LocationToAccountId::convert_location(
+		&Location::new(1, [Parachain(SIBLING_PARA_ID)]),
+	)
+	.expect("sibling parachains have a sovereign account");
+
+	Ferrum::execute_with(|| {
+		use ferrum_runtime::{Balances, PolkadotXcm, RuntimeOrigin};
+
+		assert_ok!(PolkadotXcm::limited_reserve_transfer_assets(
+			RuntimeOrigin::signed(ALICE.into()),
+			Box::new(Location::new(1, [Parachain(SIBLING_PARA_ID)]).into()),
+			Box::new(account_location(BOB).into()),
+			Box::new(Asset::from((Here, amount)).into()),
+			0,
+			WeightLimit::Unlimited,
+		));
+
+		assert_eq!(Balances::free_balance(AccountId::from(ALICE)), INITIAL_BALANCE - amount);
+		assert_eq!(Balances::free_balance(&sibling_sovereign), amount);
+	});
+	deliver_xcmp::<Ferrum, ferrum_runtime::XcmpQueue>(FERRUM_PARA_ID);
+
+	let received = Sibling::execute_with(|| {
+		use ferrum_testnet_runtime::ForeignAssets;
+
+		let received = ForeignAssets::balance(FRM_ASSET_ID, AccountId::from(BOB));
+		assert!(received > 0 && received < amount);
+		received
+	});
+
+	let back = received / 2;
+	Sibling::execute_with(|| {
+		use ferrum_testnet_runtime::{ForeignAssets, PolkadotXcm, RuntimeOrigin};
+
+		assert_ok!(PolkadotXcm::limited_reserve_transfer_assets(
+			RuntimeOrigin::signed(BOB.into()),
+			Box::new(ferrum_location().into()),
+			Box::new(account_location(ALICE).into()),
+			Box::new(Asset::from((ferrum_location(), back)).into()),
+			0,
+			WeightLimit::Unlimited,
+		));
+
+		assert_eq!(ForeignAssets::balance(FRM_ASSET_ID, AccountId::from(BOB)), received - back);
+	});
+	deliver_xcmp::<Sibling, ferrum_testnet_runtime::XcmpQueue>(SIBLING_PARA_ID);
+
+	Ferrum::execute_with(|| {
+		use ferrum_runtime::Balances;
+
+		assert_eq!(Balances::free_balance(&sibling_sovereign), amount - back);
+		// The xcm execution fees are paid in FRM
+		let alice = Balances::free_balance(AccountId::from(ALICE));
+		assert!(alice > INITIAL_BALANCE - amount && alice < INITIAL_BALANCE - amount + back);
+	});
+}